@@ -2,7 +2,7 @@ extern crate polydraw;
 
 use std::usize;
 
-use polydraw::{Application, Renderer, Frame};
+use polydraw::{Application, Renderer, Frame, FrameClock};
 use polydraw::draw::RGB;
 use polydraw::geom::point::Point;
 use polydraw::raster::{Scene, Segment, Circle, EdgeType, EdgeSrc, Poly, Rasterizer};
@@ -52,6 +52,10 @@ impl SceneRenderer {
          Circle::new(9, 4),  // 1
       ];
 
+      let curves = vec![];
+
+      let ellipses = vec![];
+
       let edges = vec![
          EdgeSrc::new(EdgeType::LVT, 2, usize::MAX),
          EdgeSrc::new(EdgeType::CTR, 7, 1),
@@ -83,9 +87,14 @@ impl SceneRenderer {
          points: points,
          segments: segments,
          circles: circles,
+         curves: curves,
+         ellipses: ellipses,
          edges: edges,
          polys: polys,
          colors: colors,
+         clips: Vec::new(),
+         groups: Vec::new(),
+         paints: Vec::new(),
       }
    }
 
@@ -104,7 +113,7 @@ impl SceneRenderer {
 }
 
 impl Renderer for SceneRenderer {
-   fn render(&mut self, frame: &mut Frame) {
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
       frame.clear();
 
       let mut scene = self.create_scene();
@@ -4,7 +4,7 @@ use std::cmp::{Ordering, min, max};
 use std::i64;
 
 use polydraw::geom::point::Point;
-use polydraw::{Application, Renderer, Frame};
+use polydraw::{Application, Renderer, Frame, FrameClock};
 use polydraw::raster::{Scene, Rasterizer, EdgeType, Poly, create_default_vec};
 use polydraw::num::NumberOps;
 
@@ -228,6 +228,10 @@ impl ClipRenderer {
 
       let circles = vec![];
 
+      let curves = vec![];
+
+      let ellipses = vec![];
+
       let edges = vec![];
 
       let polys = vec![];
@@ -238,9 +242,14 @@ impl ClipRenderer {
          points: points,
          segments: segments,
          circles: circles,
+         curves: curves,
+         ellipses: ellipses,
          edges: edges,
          polys: polys,
          colors: colors,
+         clips: Vec::new(),
+         groups: Vec::new(),
+         paints: Vec::new(),
       }
    }
 
@@ -842,7 +851,7 @@ impl ClipRenderer {
 }
 
 impl Renderer for ClipRenderer {
-   fn render(&mut self, frame: &mut Frame) {
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
       frame.clear();
 
       self.transfer_sections();
@@ -0,0 +1,122 @@
+extern crate polydraw;
+
+use std::usize;
+
+use polydraw::{Application, Renderer, Frame, FrameClock};
+use polydraw::draw::RGB;
+use polydraw::geom::point::Point;
+use polydraw::raster::{Scene, Segment, Curve, EdgeType, EdgeSrc, Poly, Rasterizer};
+
+struct SceneRenderer {
+   rasterizer: Rasterizer,
+   div_per_pixel: i64,
+}
+
+impl SceneRenderer {
+   fn new() -> Self {
+      SceneRenderer {
+         rasterizer: Rasterizer::new(),
+         div_per_pixel: 1000,
+      }
+   }
+
+   fn create_scene(&self) -> Scene {
+      let points = vec![
+         Point::new(0, 0),   // 0
+         Point::new(12, 0),  // 1
+         Point::new(0, 12),  // 2
+         Point::new(12, 12), // 3
+         Point::new(4, 0),   // 4
+         Point::new(12, 8),  // 5
+         Point::new(2, 12),  // 6
+         Point::new(4, 6),   // 7
+         Point::new(6, 8),   // 8
+      ];
+
+      let segments = vec![
+         Segment::new(0, 2), // a 0
+         Segment::new(2, 3), // b 1
+         Segment::new(0, 3), // c 2
+         Segment::new(5, 3), // d 3
+         Segment::new(4, 5), // e 4
+         Segment::new(0, 4), // f 5
+         Segment::new(1, 5), // g 6
+         Segment::new(4, 1), // h 7
+      ];
+
+      let curves = vec![
+         Curve::quadratic(6), // 0
+         Curve::new(7, 8),    // 1
+      ];
+
+      let ellipses = vec![];
+
+      let edges = vec![
+         EdgeSrc::new(EdgeType::LVT, 0, usize::MAX),
+         EdgeSrc::new(EdgeType::LHR, 1, usize::MAX),
+         EdgeSrc::new_curve(EdgeType::QBL, 2, 0),
+         EdgeSrc::new_curve(EdgeType::QTR, 2, 0),
+         EdgeSrc::new(EdgeType::LVB, 3, usize::MAX),
+         EdgeSrc::new_curve(EdgeType::BBL, 4, 1),
+         EdgeSrc::new(EdgeType::LHL, 5, usize::MAX),
+         EdgeSrc::new_curve(EdgeType::BTR, 4, 1),
+         EdgeSrc::new(EdgeType::LVB, 6, usize::MAX),
+         EdgeSrc::new(EdgeType::LHL, 7, usize::MAX),
+      ];
+
+      let polys = vec![
+         Poly::new(0, 3, 0),
+         Poly::new(3, 7, 1),
+         Poly::new(7, 10, 2),
+      ];
+
+      let colors = vec![
+         RGB::new(194, 243, 137),
+         RGB::new(154, 222, 76),
+         RGB::new(172, 58, 162),
+      ];
+
+      Scene {
+         points: points,
+         segments: segments,
+         circles: vec![],
+         curves: curves,
+         ellipses: ellipses,
+         edges: edges,
+         polys: polys,
+         colors: colors,
+         clips: Vec::new(),
+         groups: Vec::new(),
+         paints: Vec::new(),
+      }
+   }
+
+   fn scale_scene(&self, scene: &mut Scene) {
+      let scale = 50_000;
+
+      for point in &mut scene.points {
+         point.x = scale * point.x;
+         point.y = scale * point.y;
+      }
+   }
+}
+
+impl Renderer for SceneRenderer {
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
+      frame.clear();
+
+      let mut scene = self.create_scene();
+      self.scale_scene(&mut scene);
+
+      self.rasterizer.render(&scene, frame, self.div_per_pixel);
+   }
+}
+
+fn main() {
+   let mut renderer = SceneRenderer::new();
+
+   Application::new()
+      .renderer(&mut renderer)
+      .title("Curves")
+      .run();
+}
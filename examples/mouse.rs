@@ -2,7 +2,7 @@ extern crate polydraw;
 
 use std::cmp::{min, max};
 
-use polydraw::{Application, Renderer, Frame};
+use polydraw::{Application, Renderer, Frame, FrameClock};
 use polydraw::draw::RGB;
 
 struct MouseRenderer {
@@ -20,7 +20,7 @@ impl MouseRenderer {
 }
 
 impl Renderer for MouseRenderer {
-   fn render(&mut self, frame: &mut Frame) {
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
       frame.clear();
 
       let color = RGB::new(33, 168, 222);
@@ -0,0 +1,51 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+
+// System allocator counting the heap allocations of the process, reallocations
+// included. Installed by the application with
+//
+//    #[global_allocator]
+//    static ALLOCATOR: CountingAllocator = CountingAllocator;
+//
+// so that the count difference over some frames shows whether rendering
+// allocates at all
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+   #[inline]
+   unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+
+      System.alloc(layout)
+   }
+
+   #[inline]
+   unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+
+      System.alloc_zeroed(layout)
+   }
+
+   #[inline]
+   unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+      ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+
+      System.realloc(ptr, layout, new_size)
+   }
+
+   #[inline]
+   unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      System.dealloc(ptr, layout)
+   }
+}
+
+
+// Allocations so far, always 0 unless CountingAllocator is the global allocator
+#[inline]
+pub fn allocation_count() -> usize {
+   ALLOCATIONS.load(Ordering::Relaxed)
+}
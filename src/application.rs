@@ -10,11 +10,18 @@ pub use os::linux::display::LinuxDisplay as OsDisplay;
 
 use super::renderer::Renderer;
 use super::event_loop::EventLoop;
+use super::event::WindowId;
+use super::clipboard::{Clipboard, Selection};
+use super::pointer::Pointer;
+use super::monitor::Monitor;
+use super::error::{RuntimeError, ErrorKind, VoidResult};
 
 pub struct Application {
    pub os_application: OsApplication,
-   pub initial_width: u32,
-   pub initial_height: u32,
+   pub clipboard: Option<Clipboard>,
+   pub initial_sizes: Vec<(u32, u32)>,
+   pub target_fps: Option<u32>,
+   pub vsync: bool,
 }
 
 use super::creator::ApplicationCreator;
@@ -31,32 +38,76 @@ impl Application {
       ApplicationCreator::new(display)
    }
 
+   pub fn with_display(display: OsDisplay) -> Self {
+      let os_application = match OsApplication::new(display) {
+         Ok(os_application) => os_application,
+         Err(e) => {
+            panic!(e.description);
+         }
+      };
+
+      // Windows work without a clipboard, its calls report it missing
+      let clipboard = Clipboard::new(&os_application.display).ok();
+
+      Application {
+         os_application: os_application,
+         clipboard: clipboard,
+         initial_sizes: Vec::new(),
+         target_fps: None,
+         vsync: true,
+      }
+   }
+
    pub fn create(
       display: OsDisplay,
       title: &str,
       x: i32, y: i32,
       width: u32, height: u32
    ) -> Self {
-      let os_application = match OsApplication::new(
-         display, title, x, y, width, height
+      let mut application = Application::with_display(display);
+
+      application.create_window(title, x, y, width, height);
+
+      application
+   }
+
+   pub fn create_window(
+      &mut self,
+      title: &str,
+      x: i32, y: i32,
+      width: u32, height: u32
+   ) -> WindowId {
+      let window_id = match self.os_application.create_window(
+         title, x, y, width, height
       ) {
-         Ok(os_application) => os_application,
+         Ok(window_id) => window_id,
          Err(e) => {
             panic!(e.description);
          }
       };
 
-      Application {
-         os_application: os_application,
-         initial_width: width,
-         initial_height: height,
-      }
+      self.initial_sizes.push((width, height));
+
+      window_id
    }
 
    pub fn run(&mut self, renderer: &mut Renderer) {
-      let event_loop = EventLoop::new(&self.os_application);
+      self.run_windows(&mut [renderer])
+   }
+
+   pub fn run_windows(&mut self, renderers: &mut [&mut Renderer]) {
+      if renderers.len() != self.initial_sizes.len() {
+         panic!(
+            "Expected {} renderers, one per window, got {}",
+            self.initial_sizes.len(), renderers.len()
+         );
+      }
+
+      let mut event_loop = EventLoop::new(
+         &mut self.os_application, self.clipboard.as_ref(), self.target_fps, self.vsync
+      );
 
-      match event_loop.run(renderer, self.initial_width, self.initial_height) {
+      match event_loop.run(renderers, &self.initial_sizes) {
          Ok(_) => {},
          Err(e) => {
             panic!(e.description);
@@ -64,7 +115,59 @@ impl Application {
       }
    }
 
+   pub fn set_target_fps(&mut self, target_fps: Option<u32>) {
+      self.target_fps = target_fps;
+   }
+
+   pub fn set_vsync(&mut self, vsync: bool) {
+      self.vsync = vsync;
+   }
+
    pub fn screen_size(&self) -> (u32, u32) {
       self.os_application.screen_size()
    }
+
+   pub fn monitors(&self) -> Vec<Monitor> {
+      self.os_application.monitors().to_vec()
+   }
+
+   pub fn scale_factor(&self, window_id: WindowId) -> f64 {
+      match self.os_application.scale_factor(window_id) {
+         Ok(scale_factor) => scale_factor,
+         Err(e) => {
+            panic!(e.description);
+         }
+      }
+   }
+
+   pub fn clipboard(&self) -> Option<Clipboard> {
+      self.clipboard.clone()
+   }
+
+   pub fn clipboard_text(&self, selection: Selection) -> Result<Option<String>, RuntimeError> {
+      try!(self.available_clipboard()).text(selection)
+   }
+
+   pub fn set_clipboard_text(&self, selection: Selection, text: &str) -> VoidResult {
+      try!(self.available_clipboard()).set_text(selection, text)
+   }
+
+   fn available_clipboard(&self) -> Result<&Clipboard, RuntimeError> {
+      match self.clipboard {
+         Some(ref clipboard) => Ok(clipboard),
+         None => Err(RuntimeError::new(
+            ErrorKind::Other,
+            "Clipboard is not available".to_string()
+         ))
+      }
+   }
+
+   pub fn pointer(&self, window_id: WindowId) -> Pointer {
+      match self.os_application.pointer(window_id) {
+         Ok(os_pointer) => Pointer::new(os_pointer),
+         Err(e) => {
+            panic!(e.description);
+         }
+      }
+   }
 }
@@ -0,0 +1,46 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[cfg(target_os = "windows")]
+pub use os::windows::clipboard::WindowsClipboard as OsClipboard;
+#[cfg(target_os = "linux")]
+pub use os::linux::clipboard::LinuxClipboard as OsClipboard;
+
+use application::OsDisplay;
+use error::{RuntimeError, VoidResult};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Selection {
+   Clipboard,
+   Primary,
+}
+
+#[derive(Clone)]
+pub struct Clipboard {
+   pub os_clipboard: Rc<RefCell<OsClipboard>>,
+}
+
+impl Clipboard {
+   pub fn new(display: &OsDisplay) -> Result<Self, RuntimeError> {
+      let os_clipboard = try!(OsClipboard::new(display));
+
+      Ok(Clipboard {
+         os_clipboard: Rc::new(RefCell::new(os_clipboard)),
+      })
+   }
+
+   #[inline]
+   pub fn text(&self, selection: Selection) -> Result<Option<String>, RuntimeError> {
+      self.os_clipboard.borrow_mut().text(selection)
+   }
+
+   #[inline]
+   pub fn set_text(&self, selection: Selection, text: &str) -> VoidResult {
+      self.os_clipboard.borrow_mut().set_text(selection, text)
+   }
+
+   #[inline]
+   pub fn process_events(&self) -> VoidResult {
+      self.os_clipboard.borrow_mut().process_events()
+   }
+}
@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const STATS_WINDOW: usize = 120;
+
+#[derive(Clone, Copy, Debug)]
+struct FrameSample {
+   frame_time: f64,
+   render_time: f64,
+   present_time: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+   samples: VecDeque<FrameSample>,
+   dropped_frames: u64,
+   dropped_in_window: VecDeque<u64>,
+}
+
+impl FrameStats {
+   pub fn new() -> Self {
+      FrameStats {
+         samples: VecDeque::with_capacity(STATS_WINDOW),
+         dropped_frames: 0,
+         dropped_in_window: VecDeque::with_capacity(STATS_WINDOW),
+      }
+   }
+
+   pub fn push(&mut self, frame_time: f64, render_time: f64, present_time: f64, dropped: u64) {
+      if self.samples.len() == STATS_WINDOW {
+         self.samples.pop_front();
+         self.dropped_in_window.pop_front();
+      }
+
+      self.samples.push_back(FrameSample {
+         frame_time: frame_time,
+         render_time: render_time,
+         present_time: present_time,
+      });
+
+      self.dropped_in_window.push_back(dropped);
+      self.dropped_frames += dropped;
+   }
+
+   #[inline]
+   pub fn render_time(&self) -> f64 {
+      self.average(|sample| sample.render_time)
+   }
+
+   #[inline]
+   pub fn present_time(&self) -> f64 {
+      self.average(|sample| sample.present_time)
+   }
+
+   #[inline]
+   pub fn frame_time(&self) -> f64 {
+      self.average(|sample| sample.frame_time)
+   }
+
+   pub fn max_render_time(&self) -> f64 {
+      self.samples.iter().fold(0., |max, sample| {
+         if sample.render_time > max { sample.render_time } else { max }
+      })
+   }
+
+   pub fn fps(&self) -> f64 {
+      let frame_time = self.frame_time();
+
+      if frame_time > 0. {
+         1. / frame_time
+      } else {
+         0.
+      }
+   }
+
+   #[inline]
+   pub fn dropped_frames(&self) -> u64 {
+      self.dropped_frames
+   }
+
+   #[inline]
+   pub fn recently_dropped_frames(&self) -> u64 {
+      self.dropped_in_window.iter().sum()
+   }
+
+   fn average<F>(&self, value: F) -> f64 where F: Fn(&FrameSample) -> f64 {
+      if self.samples.is_empty() {
+         return 0.;
+      }
+
+      self.samples.iter().map(value).sum::<f64>() / self.samples.len() as f64
+   }
+}
+
+#[derive(Clone, Debug)]
+pub struct FrameClock {
+   pub frame: u64,
+   pub delta: f64,
+   pub elapsed: f64,
+   pub stats: FrameStats,
+   start: Instant,
+   last: Option<Instant>,
+}
+
+impl FrameClock {
+   pub fn new(start: Instant) -> Self {
+      FrameClock {
+         frame: 0,
+         delta: 0.,
+         elapsed: 0.,
+         stats: FrameStats::new(),
+         start: start,
+         last: None,
+      }
+   }
+
+   pub fn tick(&mut self, now: Instant) {
+      if let Some(last) = self.last {
+         self.frame += 1;
+         self.delta = seconds(now.duration_since(last));
+      }
+
+      self.elapsed = seconds(now.duration_since(self.start));
+      self.last = Some(now);
+   }
+}
+
+#[inline]
+pub fn seconds(duration: Duration) -> f64 {
+   duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1e-9
+}
+
+pub fn dropped_frames(delta: f64, interval: Option<f64>) -> u64 {
+   let interval = match interval {
+      Some(interval) if interval > 0. => interval,
+      _ => return 0
+   };
+
+   let frames = (delta / interval).round() as u64;
+
+   if frames > 1 {
+      frames - 1
+   } else {
+      0
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn test_dropped_frames() {
+      let interval = Some(1. / 60.);
+
+      assert_eq!(dropped_frames(1. / 60., interval), 0);
+      assert_eq!(dropped_frames(1.2 / 60., interval), 0);
+      assert_eq!(dropped_frames(2. / 60., interval), 1);
+      assert_eq!(dropped_frames(3.9 / 60., interval), 3);
+      assert_eq!(dropped_frames(1., None), 0);
+   }
+
+   #[test]
+   fn test_frame_stats_window() {
+      let mut stats = FrameStats::new();
+
+      for _ in 0..STATS_WINDOW {
+         stats.push(0.5, 0.25, 0.125, 1);
+      }
+
+      stats.push(1., 0.25, 0.125, 0);
+
+      assert_eq!(stats.dropped_frames(), STATS_WINDOW as u64);
+      assert_eq!(stats.recently_dropped_frames(), STATS_WINDOW as u64 - 1);
+      assert_eq!(stats.render_time(), 0.25);
+      assert_eq!(stats.present_time(), 0.125);
+      assert!(stats.frame_time() > 0.5);
+   }
+}
@@ -0,0 +1,569 @@
+use std::io;
+use std::io::Read;
+use std::fs::File;
+
+use draw::RGB;
+
+use super::space::{Matrix, RgbSpace, Xyz, D50, D65, multiply, concat, invert, bradford};
+
+
+const HEADER_SIZE: usize = 128;
+
+// Entries of the tables mapping linear values to 8-bit device values
+const ENCODE_SIZE: usize = 4096;
+
+const IDENTITY: Matrix = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+
+
+// Tone reproduction curve from device values to linear light, both in the
+// 0 to 1 range
+#[derive(Debug, PartialEq, Clone)]
+pub enum Curve {
+   Gamma(f64),
+   Table(Vec<f64>),
+   // Parameters g, a, b, c, d, e and f of the ICC parametric curves, the
+   // value being (a * x + b) ^ g + e from d on and c * x + f below
+   Parametric([f64; 7]),
+}
+
+impl Curve {
+   #[inline]
+   pub fn srgb() -> Self {
+      Curve::Parametric([2.4, 1. / 1.055, 0.055 / 1.055, 1. / 12.92, 0.04045, 0., 0.])
+   }
+
+   pub fn eval(&self, x: f64) -> f64 {
+      match *self {
+         Curve::Gamma(gamma) => x.max(0.).powf(gamma),
+         Curve::Table(ref table) => {
+            let position = x.max(0.).min(1.) * (table.len() - 1) as f64;
+
+            let index = (position.floor() as usize).min(table.len() - 2);
+
+            let t = position - index as f64;
+
+            table[index] + (table[index + 1] - table[index]) * t
+         },
+         Curve::Parametric(p) => {
+            if x >= p[4] {
+               (p[1] * x + p[2]).max(0.).powf(p[0]) + p[5]
+            } else {
+               p[3] * x + p[6]
+            }
+         },
+      }
+   }
+
+   // Device value giving the linear `y`, the curves being increasing
+   pub fn invert(&self, y: f64) -> f64 {
+      if let Curve::Gamma(gamma) = *self {
+         return y.max(0.).powf(1. / gamma);
+      }
+
+      let mut low = 0.;
+      let mut high = 1.;
+
+      for _ in 0..32 {
+         let middle = (low + high) / 2.;
+
+         if self.eval(middle) < y {
+            low = middle;
+         } else {
+            high = middle;
+         }
+      }
+
+      (low + high) / 2.
+   }
+}
+
+
+// Matrix/TRC profile of an RGB device, like the ones of displays and the
+// wide gamut working spaces
+#[derive(Debug, PartialEq, Clone)]
+pub struct Profile {
+   // Linear device values to XYZ in the D50 connection space
+   pub matrix: Matrix,
+   pub curves: [Curve; 3],
+}
+
+impl Profile {
+   #[inline]
+   pub fn new(matrix: Matrix, curves: [Curve; 3]) -> Self {
+      Profile {
+         matrix: matrix,
+         curves: curves,
+      }
+   }
+
+   pub fn from_space(space: RgbSpace) -> Self {
+      let curve = match space {
+         RgbSpace::Srgb | RgbSpace::DisplayP3 => Curve::srgb(),
+         RgbSpace::LinearSrgb => Curve::Gamma(1.),
+         RgbSpace::AdobeRgb => Curve::Gamma(563. / 256.),
+      };
+
+      Profile::new(
+         concat(&bradford(D65, D50), &space.to_xyz_matrix()),
+         [curve.clone(), curve.clone(), curve],
+      )
+   }
+
+   #[inline]
+   pub fn srgb() -> Self {
+      Profile::from_space(RgbSpace::Srgb)
+   }
+
+   pub fn from_file(filename: &str) -> io::Result<Self> {
+      let mut data = Vec::new();
+
+      try!(try!(File::open(filename)).read_to_end(&mut data));
+
+      Profile::parse(&data)
+   }
+
+   // ICC v2 or v4 display or working space profile with the colorant and
+   // TRC tags
+   pub fn parse(data: &[u8]) -> io::Result<Self> {
+      if data.len() < HEADER_SIZE + 4 || &data[36..40] != b"acsp" {
+         return Err(invalid("Not an ICC profile"));
+      }
+
+      if data[8] != 2 && data[8] != 4 {
+         return Err(invalid("Unsupported ICC profile version"));
+      }
+
+      if &data[16..20] != b"RGB " || &data[20..24] != b"XYZ " {
+         return Err(invalid("Only RGB profiles with an XYZ connection space are supported"));
+      }
+
+      let count = try!(read_u32(data, HEADER_SIZE)) as usize;
+
+      let mut tags = Vec::new();
+
+      for i in 0..count {
+         let entry = HEADER_SIZE + 4 + i * 12;
+
+         let offset = try!(read_u32(data, entry + 4)) as usize;
+         let size = try!(read_u32(data, entry + 8)) as usize;
+
+         if offset + size > data.len() {
+            return Err(invalid("ICC tag out of the profile data"));
+         }
+
+         tags.push((&data[entry..entry + 4], &data[offset..offset + size]));
+      }
+
+      let find = |signature: &[u8]| {
+         match tags.iter().find(|&&(tag, _)| tag == signature) {
+            Some(&(_, tag_data)) => Ok(tag_data),
+            None => Err(invalid("Missing ICC colorant or TRC tag")),
+         }
+      };
+
+      let columns = [
+         try!(parse_xyz(try!(find(b"rXYZ")))),
+         try!(parse_xyz(try!(find(b"gXYZ")))),
+         try!(parse_xyz(try!(find(b"bXYZ")))),
+      ];
+
+      let mut matrix = [[0.; 3]; 3];
+
+      for row in 0..3 {
+         for column in 0..3 {
+            matrix[row][column] = columns[column][row];
+         }
+      }
+
+      let curves = [
+         try!(parse_curve(try!(find(b"rTRC")))),
+         try!(parse_curve(try!(find(b"gTRC")))),
+         try!(parse_curve(try!(find(b"bTRC")))),
+      ];
+
+      Ok(Profile::new(matrix, curves))
+   }
+
+   // XYZ relative to D65 of the device values
+   pub fn to_xyz(&self, rgb: [f64; 3]) -> Xyz {
+      let linear = [self.curves[0].eval(rgb[0]), self.curves[1].eval(rgb[1]), self.curves[2].eval(rgb[2])];
+
+      Xyz::from_array(multiply(&bradford(D50, D65), multiply(&self.matrix, linear)))
+   }
+
+   pub fn from_xyz(&self, xyz: &Xyz) -> [f64; 3] {
+      let pcs = multiply(&bradford(D65, D50), xyz.to_array());
+
+      let linear = multiply(&invert(&self.matrix).unwrap_or(IDENTITY), pcs);
+
+      [self.curves[0].invert(linear[0]), self.curves[1].invert(linear[1]), self.curves[2].invert(linear[2])]
+   }
+}
+
+
+// Relative colorimetric conversion of 8-bit device values between two
+// profiles, with the curves sampled in tables
+pub struct ColorTransform {
+   to_linear: [Vec<f32>; 3],
+   matrix: [[f32; 3]; 3],
+   from_linear: [Vec<u8>; 3],
+}
+
+impl ColorTransform {
+   pub fn new(source: &Profile, destination: &Profile) -> Self {
+      let matrix = concat(&invert(&destination.matrix).unwrap_or(IDENTITY), &source.matrix);
+
+      let mut matrix_f32 = [[0.; 3]; 3];
+
+      for row in 0..3 {
+         for column in 0..3 {
+            matrix_f32[row][column] = matrix[row][column] as f32;
+         }
+      }
+
+      let to_linear = |curve: &Curve| {
+         (0..256).map(|value| curve.eval(value as f64 / 255.) as f32).collect()
+      };
+
+      let from_linear = |curve: &Curve| {
+         (0..ENCODE_SIZE).map(|index| {
+            let value = curve.invert(index as f64 / (ENCODE_SIZE - 1) as f64);
+
+            (value.max(0.).min(1.) * 255.).round() as u8
+         }).collect()
+      };
+
+      ColorTransform {
+         to_linear: [
+            to_linear(&source.curves[0]), to_linear(&source.curves[1]), to_linear(&source.curves[2])
+         ],
+         matrix: matrix_f32,
+         from_linear: [
+            from_linear(&destination.curves[0]),
+            from_linear(&destination.curves[1]),
+            from_linear(&destination.curves[2]),
+         ],
+      }
+   }
+
+   #[inline]
+   pub fn apply(&self, color: &RGB) -> RGB {
+      let converted = self.convert([color.r, color.g, color.b]);
+
+      RGB::new(converted[0], converted[1], converted[2])
+   }
+
+   // RGBA pixels with premultiplied alpha, converted in place
+   pub fn apply_pixels(&self, pixels: &mut [u8]) {
+      for pixel in pixels.chunks_mut(4) {
+         let alpha = pixel[3];
+
+         if alpha == 0 {
+            continue;
+         }
+
+         if alpha == 255 {
+            let converted = self.convert([pixel[0], pixel[1], pixel[2]]);
+
+            pixel[..3].copy_from_slice(&converted);
+
+            continue;
+         }
+
+         let unpremultiply = |value: u8| {
+            ((value as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8
+         };
+
+         let converted = self.convert([
+            unpremultiply(pixel[0]), unpremultiply(pixel[1]), unpremultiply(pixel[2])
+         ]);
+
+         for channel in 0..3 {
+            pixel[channel] = ((converted[channel] as u32 * alpha as u32 + 127) / 255) as u8;
+         }
+      }
+   }
+
+   #[inline]
+   fn convert(&self, values: [u8; 3]) -> [u8; 3] {
+      let linear = [
+         self.to_linear[0][values[0] as usize],
+         self.to_linear[1][values[1] as usize],
+         self.to_linear[2][values[2] as usize],
+      ];
+
+      let m = &self.matrix;
+
+      let mut converted = [0; 3];
+
+      for channel in 0..3 {
+         let value = m[channel][0] * linear[0] + m[channel][1] * linear[1] + m[channel][2] * linear[2];
+
+         let index = (value.max(0.).min(1.) * (ENCODE_SIZE - 1) as f32).round() as usize;
+
+         converted[channel] = self.from_linear[channel][index];
+      }
+
+      converted
+   }
+}
+
+
+#[inline]
+fn invalid(description: &str) -> io::Error {
+   io::Error::new(io::ErrorKind::InvalidData, description)
+}
+
+
+#[inline]
+fn read_u16(data: &[u8], offset: usize) -> io::Result<u16> {
+   if offset + 2 > data.len() {
+      return Err(invalid("Truncated ICC profile"));
+   }
+
+   Ok((data[offset] as u16) << 8 | data[offset + 1] as u16)
+}
+
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> io::Result<u32> {
+   if offset + 4 > data.len() {
+      return Err(invalid("Truncated ICC profile"));
+   }
+
+   Ok(
+      (data[offset] as u32) << 24 | (data[offset + 1] as u32) << 16 |
+      (data[offset + 2] as u32) << 8 | data[offset + 3] as u32
+   )
+}
+
+
+#[inline]
+fn read_s15_fixed16(data: &[u8], offset: usize) -> io::Result<f64> {
+   Ok(try!(read_u32(data, offset)) as i32 as f64 / 65536.)
+}
+
+
+fn parse_xyz(tag: &[u8]) -> io::Result<[f64; 3]> {
+   if tag.len() < 20 || &tag[0..4] != b"XYZ " {
+      return Err(invalid("Invalid ICC XYZ tag"));
+   }
+
+   Ok([
+      try!(read_s15_fixed16(tag, 8)),
+      try!(read_s15_fixed16(tag, 12)),
+      try!(read_s15_fixed16(tag, 16)),
+   ])
+}
+
+
+fn parse_curve(tag: &[u8]) -> io::Result<Curve> {
+   if tag.len() < 12 {
+      return Err(invalid("Invalid ICC curve tag"));
+   }
+
+   match &tag[0..4] {
+      b"curv" => {
+         let count = try!(read_u32(tag, 8)) as usize;
+
+         match count {
+            0 => Ok(Curve::Gamma(1.)),
+            1 => Ok(Curve::Gamma(try!(read_u16(tag, 12)) as f64 / 256.)),
+            _ => {
+               let mut table = Vec::with_capacity(count);
+
+               for i in 0..count {
+                  table.push(try!(read_u16(tag, 12 + i * 2)) as f64 / 65535.);
+               }
+
+               Ok(Curve::Table(table))
+            },
+         }
+      },
+      b"para" => {
+         let function = try!(read_u16(tag, 8));
+
+         let count = match function {
+            0 => 1,
+            1 => 3,
+            2 => 4,
+            3 => 5,
+            4 => 7,
+            _ => return Err(invalid("Unknown ICC parametric curve")),
+         };
+
+         let mut params = [0.; 7];
+
+         for i in 0..count {
+            params[i] = try!(read_s15_fixed16(tag, 12 + i * 4));
+         }
+
+         let [g, a, b, c, d, e, f] = params;
+
+         // The first two types start at -b / a
+         if (function == 1 || function == 2) && a == 0. {
+            return Err(invalid("Invalid ICC parametric curve"));
+         }
+
+         // Everything in the form of the fifth function type
+         let params = match function {
+            0 => [g, 1., 0., 0., 0., 0., 0.],
+            1 => [g, a, b, 0., -b / a, 0., 0.],
+            2 => [g, a, b, 0., -b / a, c, c],
+            3 => [g, a, b, c, d, 0., 0.],
+            _ => [g, a, b, c, d, e, f],
+         };
+
+         Ok(Curve::Parametric(params))
+      },
+      _ => Err(invalid("Unknown ICC curve type")),
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn push_u32(data: &mut Vec<u8>, value: u32) {
+      data.extend_from_slice(&[(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8]);
+   }
+
+   fn push_s15_fixed16(data: &mut Vec<u8>, value: f64) {
+      push_u32(data, (value * 65536.).round() as i32 as u32);
+   }
+
+   // Version 4 profile of `profile`, whose curves are parametric
+   fn write_profile(profile: &Profile) -> Vec<u8> {
+      let mut tags = Vec::new();
+
+      for column in 0..3 {
+         let mut tag = b"XYZ \0\0\0\0".to_vec();
+
+         for row in 0..3 {
+            push_s15_fixed16(&mut tag, profile.matrix[row][column]);
+         }
+
+         tags.push(tag);
+      }
+
+      for curve in profile.curves.iter() {
+         let mut tag = b"para\0\0\0\0\0\x04\0\0".to_vec();
+
+         if let Curve::Parametric(params) = *curve {
+            for param in params.iter() {
+               push_s15_fixed16(&mut tag, *param);
+            }
+         }
+
+         tags.push(tag);
+      }
+
+      let signatures = [b"rXYZ", b"gXYZ", b"bXYZ", b"rTRC", b"gTRC", b"bTRC"];
+
+      let mut data = vec![0; HEADER_SIZE];
+
+      data[8] = 4;
+      data[16..20].copy_from_slice(b"RGB ");
+      data[20..24].copy_from_slice(b"XYZ ");
+      data[36..40].copy_from_slice(b"acsp");
+
+      push_u32(&mut data, tags.len() as u32);
+
+      let mut offset = HEADER_SIZE + 4 + tags.len() * 12;
+
+      for (signature, tag) in signatures.iter().zip(tags.iter()) {
+         data.extend_from_slice(*signature);
+         push_u32(&mut data, offset as u32);
+         push_u32(&mut data, tag.len() as u32);
+
+         offset += tag.len();
+      }
+
+      for tag in tags.iter() {
+         data.extend_from_slice(tag);
+      }
+
+      data
+   }
+
+   #[test]
+   fn test_parse() {
+      let srgb = Profile::srgb();
+
+      let parsed = Profile::parse(&write_profile(&srgb)).unwrap();
+
+      for row in 0..3 {
+         for column in 0..3 {
+            assert!((parsed.matrix[row][column] - srgb.matrix[row][column]).abs() < 1e-4);
+         }
+      }
+
+      for value in 0..256 {
+         let x = value as f64 / 255.;
+
+         assert!((parsed.curves[0].eval(x) - srgb.curves[0].eval(x)).abs() < 1e-4);
+      }
+
+      assert!(Profile::parse(&[0; 200]).is_err());
+   }
+
+   #[test]
+   fn test_curves() {
+      let table = Curve::Table(vec![0., 0.25, 1.]);
+
+      assert_eq!(table.eval(0.25), 0.125);
+      assert!((table.invert(0.625) - 0.75).abs() < 1e-6);
+
+      let gamma = Curve::Gamma(2.2);
+
+      assert!((gamma.invert(gamma.eval(0.3)) - 0.3).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_zero_slope() {
+      let curve = |function: u8, params: &[f64]| {
+         let mut tag = b"para\0\0\0\0\0".to_vec();
+
+         tag.extend_from_slice(&[function, 0, 0]);
+
+         for param in params.iter() {
+            push_s15_fixed16(&mut tag, *param);
+         }
+
+         parse_curve(&tag)
+      };
+
+      assert!(curve(1, &[2.2, 0., 0.5]).is_err());
+      assert!(curve(2, &[2.2, 0., 0.5, 0.1]).is_err());
+
+      match curve(1, &[2.2, 2., -0.5]) {
+         Ok(Curve::Parametric(params)) => assert_eq!(params[4], 0.25),
+         _ => panic!("Parametric curve expected"),
+      }
+   }
+
+   #[test]
+   fn test_transform() {
+      let srgb = Profile::srgb();
+
+      let identity = ColorTransform::new(&srgb, &srgb);
+
+      for value in 0..256 {
+         let color = RGB::new(value as u8, 255 - value as u8, value as u8 / 2);
+
+         assert_eq!(identity.apply(&color), color);
+      }
+
+      let p3 = ColorTransform::new(&srgb, &Profile::from_space(RgbSpace::DisplayP3));
+
+      // 0.9175, 0.2003 and 0.1387 in Display P3
+      assert_eq!(p3.apply(&RGB::new(255, 0, 0)), RGB::new(234, 51, 35));
+
+      let mut pixels = [255, 0, 0, 255, 128, 0, 0, 128, 0, 0, 0, 0];
+
+      p3.apply_pixels(&mut pixels);
+
+      assert_eq!(pixels, [234, 51, 35, 255, 117, 26, 18, 128, 0, 0, 0, 0]);
+   }
+}
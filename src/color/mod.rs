@@ -0,0 +1,5 @@
+pub mod space;
+pub mod icc;
+
+pub use self::space::{RgbSpace, Xyz, Lab, Lch, Oklab, Oklch, hsl_to_rgb, hsv_to_rgb};
+pub use self::icc::{Curve, Profile, ColorTransform};
@@ -0,0 +1,530 @@
+use std::f64::consts::PI;
+
+use draw::RGB;
+use srgb::{srgb_to_linear, linear_to_srgb};
+
+
+pub type Matrix = [[f64; 3]; 3];
+
+// Reference whites, with Y normalized to one
+pub const D65: [f64; 3] = [0.3127 / 0.3290, 1., (1. - 0.3127 - 0.3290) / 0.3290];
+pub const D50: [f64; 3] = [0.3457 / 0.3585, 1., (1. - 0.3457 - 0.3585) / 0.3585];
+
+const BRADFORD: Matrix = [
+   [0.8951, 0.2664, -0.1614],
+   [-0.7502, 1.7135, 0.0367],
+   [0.0389, -0.0685, 1.0296],
+];
+
+const SRGB_TO_XYZ: Matrix = [
+   [0.41239079926595934, 0.357584339383878, 0.1804807884018343],
+   [0.21263900587151027, 0.715168678767756, 0.07219231536073371],
+   [0.01933081871559182, 0.11919477979462598, 0.9505321522496607],
+];
+
+const DISPLAY_P3_TO_XYZ: Matrix = [
+   [0.4865709486482162, 0.26566769316909306, 0.1982172852343625],
+   [0.2289745640697488, 0.6917385218365064, 0.079286914093745],
+   [0., 0.04511338185890264, 1.043944368900976],
+];
+
+const ADOBE_RGB_TO_XYZ: Matrix = [
+   [0.5766690429101305, 0.1855582379065463, 0.1882286462349947],
+   [0.29734497525053605, 0.6273635662554661, 0.07529145849399788],
+   [0.02703136138641234, 0.07068885253582723, 0.9913375368376388],
+];
+
+const ADOBE_RGB_GAMMA: f64 = 563. / 256.;
+
+const XYZ_TO_LMS: Matrix = [
+   [0.8190224379967030, 0.3619062600528904, -0.1288737815209879],
+   [0.0329836539323885, 0.9292868615863434, 0.0361446663506424],
+   [0.0481771893596242, 0.2642395317527308, 0.6335478284694309],
+];
+
+const LMS_TO_OKLAB: Matrix = [
+   [0.2104542683093140, 0.7936177747023054, -0.0040720430116193],
+   [1.9779985324311684, -2.4285922420485799, 0.4505937096174110],
+   [0.0259040424655478, 0.7827717124575296, -0.8086757549230774],
+];
+
+const LAB_EPSILON: f64 = 216. / 24389.;
+const LAB_KAPPA: f64 = 24389. / 27.;
+
+
+#[inline]
+pub fn multiply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+   [
+      m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+      m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+      m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+   ]
+}
+
+
+// `a` applied after `b`
+pub fn concat(a: &Matrix, b: &Matrix) -> Matrix {
+   let mut m = [[0.; 3]; 3];
+
+   for row in 0..3 {
+      for column in 0..3 {
+         for k in 0..3 {
+            m[row][column] += a[row][k] * b[k][column];
+         }
+      }
+   }
+
+   m
+}
+
+
+pub fn invert(m: &Matrix) -> Option<Matrix> {
+   let adjugate = [
+      [
+         m[1][1] * m[2][2] - m[1][2] * m[2][1],
+         m[0][2] * m[2][1] - m[0][1] * m[2][2],
+         m[0][1] * m[1][2] - m[0][2] * m[1][1],
+      ],
+      [
+         m[1][2] * m[2][0] - m[1][0] * m[2][2],
+         m[0][0] * m[2][2] - m[0][2] * m[2][0],
+         m[0][2] * m[1][0] - m[0][0] * m[1][2],
+      ],
+      [
+         m[1][0] * m[2][1] - m[1][1] * m[2][0],
+         m[0][1] * m[2][0] - m[0][0] * m[2][1],
+         m[0][0] * m[1][1] - m[0][1] * m[1][0],
+      ],
+   ];
+
+   let determinant = m[0][0] * adjugate[0][0] + m[0][1] * adjugate[1][0] + m[0][2] * adjugate[2][0];
+
+   if determinant.abs() < 1e-12 {
+      return None;
+   }
+
+   let mut inverse = adjugate;
+
+   for row in inverse.iter_mut() {
+      for value in row.iter_mut() {
+         *value /= determinant;
+      }
+   }
+
+   Some(inverse)
+}
+
+
+// Bradford chromatic adaptation of XYZ values from one white to another
+pub fn bradford(from: [f64; 3], to: [f64; 3]) -> Matrix {
+   let cone_from = multiply(&BRADFORD, from);
+   let cone_to = multiply(&BRADFORD, to);
+
+   let scale = [
+      [cone_to[0] / cone_from[0], 0., 0.],
+      [0., cone_to[1] / cone_from[1], 0.],
+      [0., 0., cone_to[2] / cone_from[2]],
+   ];
+
+   concat(&invert(&BRADFORD).unwrap(), &concat(&scale, &BRADFORD))
+}
+
+
+// RGB spaces with D65 white, channels in the 0 to 1 range
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RgbSpace {
+   Srgb,
+   LinearSrgb,
+   DisplayP3,
+   AdobeRgb,
+}
+
+impl RgbSpace {
+   pub fn from_name(name: &str) -> Option<Self> {
+      match name {
+         "srgb" => Some(RgbSpace::Srgb),
+         "srgb-linear" => Some(RgbSpace::LinearSrgb),
+         "display-p3" => Some(RgbSpace::DisplayP3),
+         "a98-rgb" => Some(RgbSpace::AdobeRgb),
+         _ => None,
+      }
+   }
+
+   #[inline]
+   pub fn to_xyz_matrix(&self) -> Matrix {
+      match *self {
+         RgbSpace::Srgb | RgbSpace::LinearSrgb => SRGB_TO_XYZ,
+         RgbSpace::DisplayP3 => DISPLAY_P3_TO_XYZ,
+         RgbSpace::AdobeRgb => ADOBE_RGB_TO_XYZ,
+      }
+   }
+
+   #[inline]
+   pub fn to_linear(&self, value: f64) -> f64 {
+      match *self {
+         RgbSpace::Srgb | RgbSpace::DisplayP3 => value.signum() * srgb_to_linear(value.abs()),
+         RgbSpace::LinearSrgb => value,
+         RgbSpace::AdobeRgb => value.signum() * value.abs().powf(ADOBE_RGB_GAMMA),
+      }
+   }
+
+   #[inline]
+   pub fn from_linear(&self, value: f64) -> f64 {
+      match *self {
+         RgbSpace::Srgb | RgbSpace::DisplayP3 => value.signum() * linear_to_srgb(value.abs()),
+         RgbSpace::LinearSrgb => value,
+         RgbSpace::AdobeRgb => value.signum() * value.abs().powf(1. / ADOBE_RGB_GAMMA),
+      }
+   }
+
+   pub fn to_xyz(&self, rgb: [f64; 3]) -> Xyz {
+      let linear = [self.to_linear(rgb[0]), self.to_linear(rgb[1]), self.to_linear(rgb[2])];
+
+      Xyz::from_array(multiply(&self.to_xyz_matrix(), linear))
+   }
+
+   // Channels can fall outside the 0 to 1 range for colors out of the gamut
+   pub fn from_xyz(&self, xyz: &Xyz) -> [f64; 3] {
+      let linear = multiply(&invert(&self.to_xyz_matrix()).unwrap(), xyz.to_array());
+
+      [self.from_linear(linear[0]), self.from_linear(linear[1]), self.from_linear(linear[2])]
+   }
+}
+
+
+// CIE XYZ relative to D65
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Xyz {
+   pub x: f64,
+   pub y: f64,
+   pub z: f64,
+}
+
+impl Xyz {
+   #[inline]
+   pub fn new(x: f64, y: f64, z: f64) -> Self {
+      Xyz {
+         x: x,
+         y: y,
+         z: z,
+      }
+   }
+
+   #[inline]
+   pub fn from_array(xyz: [f64; 3]) -> Self {
+      Xyz::new(xyz[0], xyz[1], xyz[2])
+   }
+
+   #[inline]
+   pub fn to_array(&self) -> [f64; 3] {
+      [self.x, self.y, self.z]
+   }
+
+   #[inline]
+   pub fn from_rgb(color: &RGB) -> Self {
+      RgbSpace::Srgb.to_xyz(from_rgb8(color))
+   }
+
+   #[inline]
+   pub fn to_rgb(&self) -> RGB {
+      to_rgb8(RgbSpace::Srgb.from_xyz(self))
+   }
+
+   // Lab is relative to D50 like the ICC connection space
+   pub fn to_lab(&self) -> Lab {
+      let xyz = multiply(&bradford(D65, D50), self.to_array());
+
+      let f = |t: f64| if t > LAB_EPSILON {
+         t.cbrt()
+      } else {
+         (LAB_KAPPA * t + 16.) / 116.
+      };
+
+      let fx = f(xyz[0] / D50[0]);
+      let fy = f(xyz[1] / D50[1]);
+      let fz = f(xyz[2] / D50[2]);
+
+      Lab::new(116. * fy - 16., 500. * (fx - fy), 200. * (fy - fz))
+   }
+
+   pub fn to_oklab(&self) -> Oklab {
+      let lms = multiply(&XYZ_TO_LMS, self.to_array());
+
+      let lms = [lms[0].cbrt(), lms[1].cbrt(), lms[2].cbrt()];
+
+      let lab = multiply(&LMS_TO_OKLAB, lms);
+
+      Oklab::new(lab[0], lab[1], lab[2])
+   }
+}
+
+
+// CIE Lab with D50 white, L from 0 to 100
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Lab {
+   pub l: f64,
+   pub a: f64,
+   pub b: f64,
+}
+
+impl Lab {
+   #[inline]
+   pub fn new(l: f64, a: f64, b: f64) -> Self {
+      Lab {
+         l: l,
+         a: a,
+         b: b,
+      }
+   }
+
+   pub fn to_xyz(&self) -> Xyz {
+      let fy = (self.l + 16.) / 116.;
+      let fx = self.a / 500. + fy;
+      let fz = fy - self.b / 200.;
+
+      let f_inverse = |f: f64| if f * f * f > LAB_EPSILON {
+         f * f * f
+      } else {
+         (116. * f - 16.) / LAB_KAPPA
+      };
+
+      let y = if self.l > LAB_KAPPA * LAB_EPSILON {
+         fy * fy * fy
+      } else {
+         self.l / LAB_KAPPA
+      };
+
+      let xyz = [f_inverse(fx) * D50[0], y * D50[1], f_inverse(fz) * D50[2]];
+
+      Xyz::from_array(multiply(&bradford(D50, D65), xyz))
+   }
+
+   #[inline]
+   pub fn to_lch(&self) -> Lch {
+      let (c, h) = to_polar(self.a, self.b);
+
+      Lch::new(self.l, c, h)
+   }
+}
+
+
+// Polar CIE Lab, hue in degrees
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Lch {
+   pub l: f64,
+   pub c: f64,
+   pub h: f64,
+}
+
+impl Lch {
+   #[inline]
+   pub fn new(l: f64, c: f64, h: f64) -> Self {
+      Lch {
+         l: l,
+         c: c,
+         h: h,
+      }
+   }
+
+   #[inline]
+   pub fn to_lab(&self) -> Lab {
+      let (a, b) = from_polar(self.c, self.h);
+
+      Lab::new(self.l, a, b)
+   }
+}
+
+
+// Perceptual Oklab space, L from 0 to 1
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Oklab {
+   pub l: f64,
+   pub a: f64,
+   pub b: f64,
+}
+
+impl Oklab {
+   #[inline]
+   pub fn new(l: f64, a: f64, b: f64) -> Self {
+      Oklab {
+         l: l,
+         a: a,
+         b: b,
+      }
+   }
+
+   pub fn to_xyz(&self) -> Xyz {
+      let lms = multiply(&invert(&LMS_TO_OKLAB).unwrap(), [self.l, self.a, self.b]);
+
+      let lms = [lms[0] * lms[0] * lms[0], lms[1] * lms[1] * lms[1], lms[2] * lms[2] * lms[2]];
+
+      Xyz::from_array(multiply(&invert(&XYZ_TO_LMS).unwrap(), lms))
+   }
+
+   #[inline]
+   pub fn to_oklch(&self) -> Oklch {
+      let (c, h) = to_polar(self.a, self.b);
+
+      Oklch::new(self.l, c, h)
+   }
+
+   // Linear interpolation towards `other` by `t`
+   #[inline]
+   pub fn mix(&self, other: &Oklab, t: f64) -> Oklab {
+      Oklab::new(
+         self.l + (other.l - self.l) * t,
+         self.a + (other.a - self.a) * t,
+         self.b + (other.b - self.b) * t,
+      )
+   }
+}
+
+
+// Polar Oklab, hue in degrees
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Oklch {
+   pub l: f64,
+   pub c: f64,
+   pub h: f64,
+}
+
+impl Oklch {
+   #[inline]
+   pub fn new(l: f64, c: f64, h: f64) -> Self {
+      Oklch {
+         l: l,
+         c: c,
+         h: h,
+      }
+   }
+
+   #[inline]
+   pub fn to_oklab(&self) -> Oklab {
+      let (a, b) = from_polar(self.c, self.h);
+
+      Oklab::new(self.l, a, b)
+   }
+}
+
+
+// sRGB channels of hue `h` in degrees, saturation and lightness from 0 to 1
+pub fn hsl_to_rgb(h: f64, s: f64, l: f64) -> [f64; 3] {
+   let channel = |n: f64| {
+      let k = (n + h / 30.) % 12.;
+      let a = s * l.min(1. - l);
+
+      l - a * (k - 3.).min(9. - k).min(1.).max(-1.)
+   };
+
+   [channel(0.), channel(8.), channel(4.)]
+}
+
+
+// sRGB channels of hue `h` in degrees, saturation and value from 0 to 1
+pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> [f64; 3] {
+   let channel = |n: f64| {
+      let k = (n + h / 60.) % 6.;
+
+      v - v * s * k.min(4. - k).min(1.).max(0.)
+   };
+
+   [channel(5.), channel(3.), channel(1.)]
+}
+
+
+#[inline]
+pub fn from_rgb8(color: &RGB) -> [f64; 3] {
+   [color.r as f64 / 255., color.g as f64 / 255., color.b as f64 / 255.]
+}
+
+
+// Channels get clipped to the 0 to 1 range
+#[inline]
+pub fn to_rgb8(rgb: [f64; 3]) -> RGB {
+   let channel = |value: f64| (value.max(0.).min(1.) * 255.).round() as u8;
+
+   RGB::new(channel(rgb[0]), channel(rgb[1]), channel(rgb[2]))
+}
+
+
+#[inline]
+fn to_polar(a: f64, b: f64) -> (f64, f64) {
+   let h = b.atan2(a) * 180. / PI;
+
+   ((a * a + b * b).sqrt(), if h < 0. { h + 360. } else { h })
+}
+
+
+#[inline]
+fn from_polar(c: f64, h: f64) -> (f64, f64) {
+   let h = h * PI / 180.;
+
+   (c * h.cos(), c * h.sin())
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn assert_near(a: &[f64], b: &[f64], tolerance: f64) {
+      for (a, b) in a.iter().zip(b.iter()) {
+         assert!((a - b).abs() < tolerance, "{:?} != {:?}", a, b);
+      }
+   }
+
+   #[test]
+   fn test_white_point() {
+      let white = RgbSpace::Srgb.to_xyz([1., 1., 1.]);
+
+      assert_near(&white.to_array(), &D65, 1e-4);
+
+      let lab = white.to_lab();
+
+      assert_near(&[lab.l, lab.a, lab.b], &[100., 0., 0.], 1e-3);
+
+      let oklab = white.to_oklab();
+
+      assert_near(&[oklab.l, oklab.a, oklab.b], &[1., 0., 0.], 1e-4);
+   }
+
+   #[test]
+   fn test_known_values() {
+      let red = RgbSpace::Srgb.to_xyz([1., 0., 0.]);
+
+      let lab = red.to_lab();
+
+      assert_near(&[lab.l, lab.a, lab.b], &[54.29, 80.80, 69.89], 0.05);
+
+      let oklch = red.to_oklab().to_oklch();
+
+      assert_near(&[oklch.l, oklch.c, oklch.h], &[0.6280, 0.2577, 29.23], 0.01);
+
+      // sRGB red is inside the wider gamuts
+      let p3 = RgbSpace::DisplayP3.from_xyz(&red);
+
+      assert_near(&p3, &[0.9175, 0.2003, 0.1387], 1e-3);
+
+      let adobe = RgbSpace::AdobeRgb.from_xyz(&red);
+
+      assert_near(&adobe, &[0.8590, 0., 0.], 1e-3);
+   }
+
+   #[test]
+   fn test_round_trips() {
+      let xyz = RgbSpace::Srgb.to_xyz([0.2, 0.5, 0.8]);
+
+      assert_near(&xyz.to_lab().to_lch().to_lab().to_xyz().to_array(), &xyz.to_array(), 1e-9);
+      assert_near(&xyz.to_oklab().to_oklch().to_oklab().to_xyz().to_array(), &xyz.to_array(), 1e-9);
+
+      assert_near(&RgbSpace::LinearSrgb.from_xyz(&xyz), &[0.0331, 0.2140, 0.6038], 1e-4);
+   }
+
+   #[test]
+   fn test_hsl_hsv() {
+      assert_near(&hsl_to_rgb(120., 1., 0.5), &[0., 1., 0.], 1e-9);
+      assert_near(&hsl_to_rgb(210., 0.5, 0.25), &[0.125, 0.25, 0.375], 1e-9);
+      assert_near(&hsv_to_rgb(0., 1., 1.), &[1., 0., 0.], 1e-9);
+      assert_near(&hsv_to_rgb(240., 0.5, 0.8), &[0.4, 0.4, 0.8], 1e-9);
+   }
+}
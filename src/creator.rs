@@ -1,8 +1,8 @@
 pub use super::application::{OsDisplay, Application};
 pub use super::renderer::{Renderer, NullRenderer};
+pub use super::monitor::{Monitor, primary_monitor};
 
-pub struct ApplicationCreator<'a> {
-   display: OsDisplay,
+pub struct WindowCreator<'a> {
    renderer: Option<&'a mut Renderer>,
    title: Option<&'a str>,
    x: Option<i32>,
@@ -11,10 +11,9 @@ pub struct ApplicationCreator<'a> {
    height: Option<u32>,
 }
 
-impl<'a> ApplicationCreator<'a> {
-   pub fn new(display: OsDisplay) -> Self {
-      ApplicationCreator {
-         display: display,
+impl<'a> WindowCreator<'a> {
+   pub fn new() -> Self {
+      WindowCreator {
          renderer: None,
          title: None,
          x: None,
@@ -46,41 +45,125 @@ impl<'a> ApplicationCreator<'a> {
       self
    }
 
-   pub fn run(self) {
-      let mut null_renderer = NullRenderer;
-
-      let renderer: &mut Renderer = match self.renderer {
-         Some(mut renderer) => renderer,
-         None => &mut null_renderer as &mut Renderer
-      };
-
-      let title = match self.title {
-         Some(title) => title,
-         None => "PolyDraw"
-      };
-
-      let (screen_width, screen_height) = self.display.screen_size();
-
+   fn geometry(&self, monitor: &Monitor) -> (i32, i32, u32, u32) {
+      // Requested sizes are in logical pixels
       let width = match self.width {
-         Some(width) => width,
-         None => 3 * screen_width / 4
+         Some(width) => (width as f64 * monitor.scale_factor) as u32,
+         None => 3 * monitor.width / 4
       };
 
       let height = match self.height {
-         Some(height) => height,
-         None => 3 * screen_height / 4
+         Some(height) => (height as f64 * monitor.scale_factor) as u32,
+         None => 3 * monitor.height / 4
       };
 
       let x = match self.x {
          Some(x) => x,
-         None => ((screen_width - width) / 2) as i32
+         None => monitor.x + (monitor.width.saturating_sub(width) / 2) as i32
       };
 
       let y = match self.y {
          Some(y) => y,
-         None => ((screen_height - height) / 2) as i32
+         None => monitor.y + (monitor.height.saturating_sub(height) / 2) as i32
+      };
+
+      (x, y, width, height)
+   }
+}
+
+pub struct ApplicationCreator<'a> {
+   display: OsDisplay,
+   main_window: WindowCreator<'a>,
+   extra_windows: Vec<WindowCreator<'a>>,
+   target_fps: Option<u32>,
+   vsync: bool,
+}
+
+impl<'a> ApplicationCreator<'a> {
+   pub fn new(display: OsDisplay) -> Self {
+      ApplicationCreator {
+         display: display,
+         main_window: WindowCreator::new(),
+         extra_windows: Vec::new(),
+         target_fps: None,
+         vsync: true,
+      }
+   }
+
+   pub fn renderer(mut self, renderer: &'a mut Renderer) -> Self {
+      self.main_window = self.main_window.renderer(renderer);
+      self
+   }
+
+   pub fn title(mut self, title: &'a str) -> Self {
+      self.main_window = self.main_window.title(title);
+      self
+   }
+
+   pub fn size(mut self, width: u32, height: u32) -> Self {
+      self.main_window = self.main_window.size(width, height);
+      self
+   }
+
+   pub fn position(mut self, x: i32, y: i32) -> Self {
+      self.main_window = self.main_window.position(x, y);
+      self
+   }
+
+   pub fn window(mut self, window: WindowCreator<'a>) -> Self {
+      self.extra_windows.push(window);
+      self
+   }
+
+   pub fn target_fps(mut self, target_fps: u32) -> Self {
+      self.target_fps = Some(target_fps);
+      self
+   }
+
+   pub fn vsync(mut self, vsync: bool) -> Self {
+      self.vsync = vsync;
+      self
+   }
+
+   pub fn run(self) {
+      let monitors = self.display.monitors();
+
+      let monitor = match primary_monitor(&monitors) {
+         Some(monitor) => monitor.clone(),
+         None => {
+            panic!("No monitors found");
+         }
       };
 
-      Application::create(self.display, title, x, y, width, height).run(renderer)
+      let mut windows = vec![self.main_window];
+      windows.extend(self.extra_windows);
+
+      let mut application = Application::with_display(self.display);
+
+      application.set_target_fps(self.target_fps);
+      application.set_vsync(self.vsync);
+
+      let mut null_renderers: Vec<NullRenderer> = windows.iter().map(|_| NullRenderer).collect();
+      let mut null_renderers = null_renderers.iter_mut();
+
+      let mut renderers: Vec<&mut Renderer> = Vec::with_capacity(windows.len());
+
+      for window in windows {
+         let title = match window.title {
+            Some(title) => title,
+            None => "PolyDraw"
+         };
+
+         let (x, y, width, height) = window.geometry(&monitor);
+
+         application.create_window(title, x, y, width, height);
+
+         renderers.push(match window.renderer {
+            Some(renderer) => renderer,
+            None => null_renderers.next().unwrap() as &mut Renderer
+         });
+      }
+
+      application.run_windows(&mut renderers)
    }
 }
@@ -0,0 +1,687 @@
+use std::sync::{Arc, Barrier};
+use std::cmp::min;
+
+use draw::RGB;
+use srgb::SrgbTables;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Morphology {
+   Dilate,
+   Erode,
+}
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct DropShadow {
+   pub dx: f64,
+   pub dy: f64,
+   pub std_dev: f64,
+   pub color: RGB,
+   pub opacity: f64,
+}
+
+
+// Lengths are in frame pixels. The color matrix is applied row by row to the
+// straight RGBA values in 0..1 like the SVG feColorMatrix
+#[derive(Debug, Clone, Copy)]
+pub enum FilterOp {
+   Blur(f64, f64),
+   Offset(f64, f64),
+   ColorMatrix([f64; 20]),
+   Morphology(Morphology, f64, f64),
+   DropShadow(DropShadow),
+}
+
+impl FilterOp {
+   // Same op with its lengths multiplied by `factor`
+   pub fn scale(&self, factor: f64) -> Self {
+      match *self {
+         FilterOp::Blur(x, y) => FilterOp::Blur(x * factor, y * factor),
+         FilterOp::Offset(dx, dy) => FilterOp::Offset(dx * factor, dy * factor),
+         FilterOp::ColorMatrix(matrix) => FilterOp::ColorMatrix(matrix),
+         FilterOp::Morphology(op, rx, ry) => FilterOp::Morphology(op, rx * factor, ry * factor),
+         FilterOp::DropShadow(shadow) => FilterOp::DropShadow(
+            DropShadow {
+               dx: shadow.dx * factor,
+               dy: shadow.dy * factor,
+               std_dev: shadow.std_dev * factor,
+               color: shadow.color,
+               opacity: shadow.opacity,
+            }
+         ),
+      }
+   }
+}
+
+
+// Renders the scene polys from `start` to `end` in their own layer, which
+// gets passed through `ops` before being composited
+#[derive(Debug, Clone)]
+pub struct Filter {
+   pub ops: Arc<Vec<FilterOp>>,
+   pub start: usize,
+   pub end: usize,
+}
+
+impl Filter {
+   #[inline]
+   pub fn new(ops: Vec<FilterOp>, start: usize) -> Self {
+      Filter {
+         ops: Arc::new(ops),
+         start: start,
+         end: start,
+      }
+   }
+}
+
+
+// Pixel rows from `y` to `y_end` of the full frame layers one zone thread
+// filters. All the zone threads of a frame share the barrier
+pub struct FilterRows<'a> {
+   pub width: usize,
+   pub height: usize,
+   pub y: usize,
+   pub y_end: usize,
+   pub barrier: &'a Barrier,
+   // Blending in linear light, the layers holding 16-bit linear channels
+   pub srgb: Option<&'a SrgbTables>,
+}
+
+
+impl<'a> FilterRows<'a> {
+   #[inline]
+   fn wide(&self) -> bool {
+      self.srgb.is_some()
+   }
+}
+
+
+type Pixel = [f32; 4];
+
+
+// Line buffers of the filter passes, kept by every zone thread across frames
+pub struct FilterScratch {
+   src_line: Vec<Pixel>,
+   dst_line: Vec<Pixel>,
+   line: LineScratch,
+}
+
+struct LineScratch {
+   tmp: Vec<Pixel>,
+   weights: Vec<f64>,
+}
+
+impl FilterScratch {
+   #[inline]
+   pub fn new() -> Self {
+      FilterScratch {
+         src_line: Vec::new(),
+         dst_line: Vec::new(),
+         line: LineScratch {
+            tmp: Vec::new(),
+            weights: Vec::new(),
+         },
+      }
+   }
+}
+
+impl Default for FilterScratch {
+   #[inline]
+   fn default() -> FilterScratch {
+      FilterScratch::new()
+   }
+}
+
+
+// Runs `ops` on the premultiplied RGBA layer in `layers[0]`, the other two
+// layers are scratch space. Passes reading rows of other zones are fenced by
+// the barrier on both sides, so every zone thread has to call this with the
+// same ops. The result ends up in `layers[0]`
+pub fn apply_filter(ops: &[FilterOp], layers: &mut [*mut u8; 3], rows: &FilterRows, scratch: &mut FilterScratch) {
+   for op in ops.iter() {
+      match *op {
+         FilterOp::Blur(std_dev_x, std_dev_y) => {
+            blur(layers[0], layers[1], std_dev_x, std_dev_y, rows, scratch);
+         },
+         FilterOp::Offset(dx, dy) => {
+            offset(layers[0], layers[1], dx, dy, rows);
+
+            layers.swap(0, 1);
+         },
+         FilterOp::ColorMatrix(ref matrix) => {
+            color_matrix(layers[0], matrix, rows);
+         },
+         FilterOp::Morphology(op, radius_x, radius_y) => {
+            let radius_x = radius_x.max(0.).round() as usize;
+            let radius_y = radius_y.max(0.).round() as usize;
+
+            horizontal_pass(layers[0], layers[1], rows, scratch, |src, dst, _| morphology_line(src, dst, op, radius_x));
+
+            vertical_pass(layers[1], layers[0], radius_y, rows, scratch, |src, dst, _| morphology_line(src, dst, op, radius_y));
+         },
+         FilterOp::DropShadow(ref shadow) => {
+            drop_shadow(layers, shadow, rows, scratch);
+         },
+      }
+   }
+}
+
+
+#[inline]
+fn blur(src: *mut u8, tmp: *mut u8, std_dev_x: f64, std_dev_y: f64, rows: &FilterRows, scratch: &mut FilterScratch) {
+   horizontal_pass(src, tmp, rows, scratch, |src, dst, line| blur_line(src, dst, std_dev_x, line));
+
+   vertical_pass(tmp, src, blur_extent(std_dev_y), rows, scratch, |src, dst, line| blur_line(src, dst, std_dev_y, line));
+}
+
+
+fn offset(src: *mut u8, dst: *mut u8, dx: f64, dy: f64, rows: &FilterRows) {
+   let dx = dx.round() as i64;
+   let dy = dy.round() as i64;
+
+   rows.barrier.wait();
+
+   for y in rows.y..rows.y_end {
+      let src_y = y as i64 - dy;
+
+      for x in 0..rows.width {
+         let src_x = x as i64 - dx;
+
+         let pixel = if src_x >= 0 && src_x < rows.width as i64 && src_y >= 0 && src_y < rows.height as i64 {
+            load(src, (src_y as usize) * rows.width + src_x as usize, rows.wide())
+         } else {
+            [0.; 4]
+         };
+
+         store(dst, y * rows.width + x, &pixel, rows.wide());
+      }
+   }
+
+   rows.barrier.wait();
+}
+
+
+fn color_matrix(layer: *mut u8, matrix: &[f64; 20], rows: &FilterRows) {
+   for i in rows.y * rows.width..rows.y_end * rows.width {
+      let pixel = load(layer, i, rows.wide());
+
+      let alpha = pixel[3] as f64 / 255.;
+
+      let mut straight = [0.; 4];
+      if alpha > 0. {
+         for channel in 0..3 {
+            straight[channel] = pixel[channel] as f64 / 255. / alpha;
+         }
+      }
+      straight[3] = alpha;
+
+      let mut result = [0.; 4];
+      for row in 0..4 {
+         let m = &matrix[row * 5..row * 5 + 5];
+
+         let value = m[0] * straight[0] + m[1] * straight[1] + m[2] * straight[2] + m[3] * straight[3] + m[4];
+
+         result[row] = value.max(0.).min(1.);
+      }
+
+      let alpha = result[3];
+
+      store(layer, i, &[
+         (result[0] * alpha * 255.) as f32,
+         (result[1] * alpha * 255.) as f32,
+         (result[2] * alpha * 255.) as f32,
+         (alpha * 255.) as f32,
+      ], rows.wide());
+   }
+}
+
+
+// The shadow is the alpha of the layer tinted, offset and blurred, with the
+// layer composited over it
+fn drop_shadow(layers: &mut [*mut u8; 3], shadow: &DropShadow, rows: &FilterRows, scratch: &mut FilterScratch) {
+   let opacity = shadow.opacity.max(0.).min(1.) as f32;
+   let channel = |value: u8| match rows.srgb {
+      Some(srgb) => srgb.linear(value) as f32 / 257.,
+      None => value as f32,
+   };
+
+   let color = [channel(shadow.color.r), channel(shadow.color.g), channel(shadow.color.b)];
+
+   for i in rows.y * rows.width..rows.y_end * rows.width {
+      let alpha = load(layers[0], i, rows.wide())[3] / 255. * opacity;
+
+      store(layers[1], i, &[color[0] * alpha, color[1] * alpha, color[2] * alpha, alpha * 255.], rows.wide());
+   }
+
+   offset(layers[1], layers[2], shadow.dx, shadow.dy, rows);
+
+   blur(layers[2], layers[1], shadow.std_dev, shadow.std_dev, rows, scratch);
+
+   for i in rows.y * rows.width..rows.y_end * rows.width {
+      let source = load(layers[0], i, rows.wide());
+      let backdrop = load(layers[2], i, rows.wide());
+
+      let remaining = 1. - source[3] / 255.;
+
+      let mut pixel = [0.; 4];
+      for channel in 0..4 {
+         pixel[channel] = source[channel] + backdrop[channel] * remaining;
+      }
+
+      store(layers[0], i, &pixel, rows.wide());
+   }
+}
+
+
+// Runs `line_op` on the zone rows of `src` writing to the same rows of `dst`,
+// other zones are not touched so no synchronization is needed
+fn horizontal_pass<F>(src: *mut u8, dst: *mut u8, rows: &FilterRows, scratch: &mut FilterScratch, mut line_op: F)
+   where F: FnMut(&[Pixel], &mut [Pixel], &mut LineScratch)
+{
+   let FilterScratch { ref mut src_line, ref mut dst_line, ref mut line } = *scratch;
+
+   src_line.resize(rows.width, [0.; 4]);
+   dst_line.resize(rows.width, [0.; 4]);
+
+   for y in rows.y..rows.y_end {
+      let start = y * rows.width;
+
+      for x in 0..rows.width {
+         src_line[x] = load(src, start + x, rows.wide());
+      }
+
+      line_op(src_line, dst_line, line);
+
+      for x in 0..rows.width {
+         store(dst, start + x, &dst_line[x], rows.wide());
+      }
+   }
+}
+
+
+// Runs `line_op` on the columns of `src` extended by `margin` rows into the
+// neighbour zones, writing the zone rows of `dst`
+fn vertical_pass<F>(src: *mut u8, dst: *mut u8, margin: usize, rows: &FilterRows, scratch: &mut FilterScratch, mut line_op: F)
+   where F: FnMut(&[Pixel], &mut [Pixel], &mut LineScratch)
+{
+   let start = rows.y.saturating_sub(margin);
+   let end = min(rows.height, rows.y_end + margin);
+
+   let FilterScratch { ref mut src_line, ref mut dst_line, ref mut line } = *scratch;
+
+   src_line.resize(end - start, [0.; 4]);
+   dst_line.resize(end - start, [0.; 4]);
+
+   rows.barrier.wait();
+
+   for x in 0..rows.width {
+      for y in start..end {
+         src_line[y - start] = load(src, y * rows.width + x, rows.wide());
+      }
+
+      line_op(src_line, dst_line, line);
+
+      for y in rows.y..rows.y_end {
+         store(dst, y * rows.width + x, &dst_line[y - start], rows.wide());
+      }
+   }
+
+   rows.barrier.wait();
+}
+
+
+// Distance in pixels a blur line reads past each output value
+fn blur_extent(std_dev: f64) -> usize {
+   if std_dev <= 0. {
+      0
+   } else if std_dev < 2. {
+      (3. * std_dev).ceil() as usize
+   } else {
+      box_sizes(std_dev).iter().map(|size| size / 2).sum()
+   }
+}
+
+
+// Gaussian blur of the line with transparent values past its ends, large
+// deviations get approximated with three box blurs
+fn blur_line(src: &[Pixel], dst: &mut [Pixel], std_dev: f64, line: &mut LineScratch) {
+   if std_dev <= 0. {
+      dst.copy_from_slice(src);
+   } else if std_dev < 2. {
+      gaussian_line(src, dst, std_dev, &mut line.weights);
+   } else {
+      let sizes = box_sizes(std_dev);
+
+      let tmp = &mut line.tmp;
+
+      tmp.clear();
+      tmp.extend_from_slice(src);
+
+      box_line(tmp, dst, sizes[0] / 2);
+      box_line(dst, tmp, sizes[1] / 2);
+      box_line(tmp, dst, sizes[2] / 2);
+   }
+}
+
+
+fn gaussian_line(src: &[Pixel], dst: &mut [Pixel], std_dev: f64, weights: &mut Vec<f64>) {
+   let radius = (3. * std_dev).ceil() as usize;
+
+   weights.clear();
+   for i in 0..2 * radius + 1 {
+      let d = i as f64 - radius as f64;
+
+      weights.push((-d * d / (2. * std_dev * std_dev)).exp());
+   }
+
+   let total: f64 = weights.iter().sum();
+
+   for weight in weights.iter_mut() {
+      *weight /= total;
+   }
+
+   let len = src.len();
+
+   for x in 0..len {
+      let mut sum = [0.; 4];
+
+      let first = x.saturating_sub(radius);
+      let last = min(len, x + radius + 1);
+
+      for i in first..last {
+         let weight = weights[i + radius - x] as f32;
+
+         for channel in 0..4 {
+            sum[channel] += src[i][channel] * weight;
+         }
+      }
+
+      dst[x] = sum;
+   }
+}
+
+
+// Odd sizes of three successive box blurs matching a gaussian blur
+fn box_sizes(std_dev: f64) -> [usize; 3] {
+   let variance = 12. * std_dev * std_dev;
+
+   let mut lower = ((variance / 3. + 1.).sqrt()).floor() as i64;
+   if lower % 2 == 0 {
+      lower -= 1;
+   }
+
+   let upper = lower + 2;
+
+   let lower_count = ((variance - 3. * (lower * lower) as f64 - 12. * lower as f64 - 9.) / (-4. * lower as f64 - 4.)).round() as i64;
+
+   let mut sizes = [upper as usize; 3];
+   for i in 0..3 {
+      if (i as i64) < lower_count {
+         sizes[i] = lower as usize;
+      }
+   }
+
+   sizes
+}
+
+
+fn box_line(src: &[Pixel], dst: &mut [Pixel], radius: usize) {
+   let len = src.len();
+   let scale = 1. / (2 * radius + 1) as f32;
+
+   let mut sum = [0.; 4];
+
+   for pixel in src[..min(radius, len)].iter() {
+      for channel in 0..4 {
+         sum[channel] += pixel[channel];
+      }
+   }
+
+   for x in 0..len {
+      if x + radius < len {
+         for channel in 0..4 {
+            sum[channel] += src[x + radius][channel];
+         }
+      }
+
+      for channel in 0..4 {
+         dst[x][channel] = sum[channel] * scale;
+      }
+
+      if x >= radius {
+         for channel in 0..4 {
+            sum[channel] -= src[x - radius][channel];
+         }
+      }
+   }
+}
+
+
+// Per channel maximum or minimum over the window clipped to the line
+fn morphology_line(src: &[Pixel], dst: &mut [Pixel], op: Morphology, radius: usize) {
+   let len = src.len();
+
+   for x in 0..len {
+      let mut value = src[x];
+
+      for pixel in src[x.saturating_sub(radius)..min(len, x + radius + 1)].iter() {
+         for channel in 0..4 {
+            value[channel] = match op {
+               Morphology::Dilate => value[channel].max(pixel[channel]),
+               Morphology::Erode => value[channel].min(pixel[channel]),
+            };
+         }
+      }
+
+      dst[x] = value;
+   }
+}
+
+
+// Pixel in the 0 to 255 range, 16-bit linear layers getting scaled down
+#[inline]
+fn load(layer: *mut u8, index: usize, wide: bool) -> Pixel {
+   unsafe {
+      if wide {
+         let ptr = (layer as *mut u16).offset((index * 4) as isize);
+
+         [
+            *ptr as f32 / 257., *ptr.offset(1) as f32 / 257.,
+            *ptr.offset(2) as f32 / 257., *ptr.offset(3) as f32 / 257.,
+         ]
+      } else {
+         let ptr = layer.offset((index * 4) as isize);
+
+         [*ptr as f32, *ptr.offset(1) as f32, *ptr.offset(2) as f32, *ptr.offset(3) as f32]
+      }
+   }
+}
+
+
+#[inline]
+fn store(layer: *mut u8, index: usize, pixel: &Pixel, wide: bool) {
+   unsafe {
+      if wide {
+         let ptr = (layer as *mut u16).offset((index * 4) as isize);
+
+         for channel in 0..4 {
+            *ptr.offset(channel as isize) = (pixel[channel] * 257.).round().max(0.).min(65535.) as u16;
+         }
+      } else {
+         let ptr = layer.offset((index * 4) as isize);
+
+         for channel in 0..4 {
+            *ptr.offset(channel as isize) = pixel[channel].round().max(0.).min(255.) as u8;
+         }
+      }
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use std::thread;
+
+   const SIZE: usize = 32;
+
+   fn layers() -> Vec<Vec<u8>> {
+      vec![vec![0; SIZE * SIZE * 4]; 3]
+   }
+
+   fn fill_square(layer: &mut Vec<u8>, x0: usize, y0: usize, size: usize) {
+      for y in y0..y0 + size {
+         for x in x0..x0 + size {
+            let i = (y * SIZE + x) * 4;
+
+            layer[i] = 255;
+            layer[i + 3] = 255;
+         }
+      }
+   }
+
+   fn alpha(layer: &Vec<u8>, x: usize, y: usize) -> u8 {
+      layer[(y * SIZE + x) * 4 + 3]
+   }
+
+   fn run(layers: &mut Vec<Vec<u8>>, ops: &[FilterOp]) {
+      let barrier = Barrier::new(1);
+
+      let rows = FilterRows {
+         width: SIZE,
+         height: SIZE,
+         y: 0,
+         y_end: SIZE,
+         barrier: &barrier,
+         srgb: None,
+      };
+
+      let mut ptrs = [layers[0].as_mut_ptr(), layers[1].as_mut_ptr(), layers[2].as_mut_ptr()];
+
+      apply_filter(ops, &mut ptrs, &rows, &mut FilterScratch::new());
+
+      let result = unsafe { ::std::slice::from_raw_parts(ptrs[0], SIZE * SIZE * 4).to_vec() };
+
+      layers[0] = result;
+   }
+
+   #[test]
+   fn test_blur() {
+      for &std_dev in [1., 4.].iter() {
+         let mut layers = layers();
+
+         fill_square(&mut layers[0], 12, 12, 8);
+
+         run(&mut layers, &[FilterOp::Blur(std_dev, std_dev)]);
+
+         let total: u32 = (0..SIZE * SIZE).map(|i| layers[0][i * 4 + 3] as u32).sum();
+
+         assert!((total as i64 - 64 * 255).abs() < 64 * 255 / 50);
+
+         assert!(alpha(&layers[0], 11, 16) > 0);
+         assert!(alpha(&layers[0], 16, 16) < 255 || std_dev < 2.);
+         assert!(alpha(&layers[0], 11, 16) < alpha(&layers[0], 12, 16));
+      }
+   }
+
+   #[test]
+   fn test_offset_and_morphology() {
+      let mut layers = layers();
+
+      fill_square(&mut layers[0], 4, 4, 4);
+
+      run(&mut layers, &[FilterOp::Offset(10., 2.), FilterOp::Morphology(Morphology::Dilate, 1., 0.)]);
+
+      assert_eq!(alpha(&layers[0], 4, 4), 0);
+      assert_eq!(alpha(&layers[0], 13, 6), 255);
+      assert_eq!(alpha(&layers[0], 18, 9), 255);
+      assert_eq!(alpha(&layers[0], 13, 5), 0);
+
+      run(&mut layers, &[FilterOp::Morphology(Morphology::Erode, 1., 1.)]);
+
+      assert_eq!(alpha(&layers[0], 13, 7), 0);
+      assert_eq!(alpha(&layers[0], 15, 7), 255);
+   }
+
+   #[test]
+   fn test_color_matrix_and_shadow() {
+      let mut layers = layers();
+
+      fill_square(&mut layers[0], 4, 4, 4);
+
+      // Swaps red and green, halves the alpha
+      let matrix = [
+         0., 1., 0., 0., 0.,
+         1., 0., 0., 0., 0.,
+         0., 0., 1., 0., 0.,
+         0., 0., 0., 0.5, 0.,
+      ];
+
+      run(&mut layers, &[FilterOp::ColorMatrix(matrix)]);
+
+      assert_eq!(&layers[0][(5 * SIZE + 5) * 4..(5 * SIZE + 5) * 4 + 4], &[0, 128, 0, 128]);
+
+      let shadow = DropShadow {
+         dx: 8.,
+         dy: 0.,
+         std_dev: 0.,
+         color: RGB::new(0, 0, 255),
+         opacity: 1.,
+      };
+
+      run(&mut layers, &[FilterOp::DropShadow(shadow)]);
+
+      assert_eq!(&layers[0][(5 * SIZE + 13) * 4..(5 * SIZE + 13) * 4 + 4], &[0, 0, 128, 128]);
+      assert_eq!(&layers[0][(5 * SIZE + 5) * 4..(5 * SIZE + 5) * 4 + 4], &[0, 128, 0, 128]);
+   }
+
+   #[test]
+   fn test_zone_threads() {
+      let ops = vec![
+         FilterOp::Blur(3., 1.),
+         FilterOp::Offset(0., 5.),
+         FilterOp::Morphology(Morphology::Erode, 0., 2.),
+      ];
+
+      let mut expected = layers();
+      fill_square(&mut expected[0], 6, 6, 14);
+
+      let mut zoned = expected.clone();
+
+      run(&mut expected, &ops);
+
+      let zones = 4;
+      let barrier = Arc::new(Barrier::new(zones));
+      let ptrs = [zoned[0].as_mut_ptr() as usize, zoned[1].as_mut_ptr() as usize, zoned[2].as_mut_ptr() as usize];
+
+      let handles = (0..zones).map(|zone| {
+         let barrier = barrier.clone();
+         let ops = ops.clone();
+
+         thread::spawn(move || {
+            let rows = FilterRows {
+               width: SIZE,
+               height: SIZE,
+               y: zone * SIZE / zones,
+               y_end: (zone + 1) * SIZE / zones,
+               barrier: &barrier,
+               srgb: None,
+            };
+
+            let mut layers = [ptrs[0] as *mut u8, ptrs[1] as *mut u8, ptrs[2] as *mut u8];
+
+            apply_filter(&ops, &mut layers, &rows, &mut FilterScratch::new());
+
+            layers[0] as usize
+         })
+      }).collect::<Vec<_>>();
+
+      let results = handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>();
+
+      let result = unsafe { ::std::slice::from_raw_parts(results[0] as *mut u8, SIZE * SIZE * 4) };
+
+      assert_eq!(result, &expected[0][..]);
+   }
+}
@@ -0,0 +1,73 @@
+use draw::BlendMode;
+
+use super::mask::Mask;
+use super::filter::Filter;
+
+
+// Renders the scene polys from `start` to `end` isolated in their own layer,
+// composited with a single opacity and blend mode
+#[derive(Debug, Clone)]
+pub struct Group {
+   pub opacity: f64,
+   pub blend: BlendMode,
+   pub start: usize,
+   pub end: usize,
+}
+
+impl Group {
+   #[inline]
+   pub fn new(opacity: f64, blend: BlendMode, start: usize) -> Self {
+      Group {
+         opacity: opacity,
+         blend: blend,
+         start: start,
+         end: start,
+      }
+   }
+}
+
+
+// Offscreen layers of the scene in the order they were opened
+#[derive(Debug, Clone)]
+pub enum Layer {
+   Group(Group),
+   Mask(Mask),
+   Filter(Filter),
+}
+
+impl Layer {
+   #[inline]
+   pub fn start(&self) -> usize {
+      match *self {
+         Layer::Group(ref group) => group.start,
+         Layer::Mask(ref mask) => mask.start,
+         Layer::Filter(ref filter) => filter.start,
+      }
+   }
+
+   #[inline]
+   pub fn end(&self) -> usize {
+      match *self {
+         Layer::Group(ref group) => group.end,
+         Layer::Mask(ref mask) => mask.end,
+         Layer::Filter(ref filter) => filter.end,
+      }
+   }
+
+   #[inline]
+   pub fn set_end(&mut self, end: usize) {
+      match *self {
+         Layer::Group(ref mut group) => group.end = end,
+         Layer::Mask(ref mut mask) => mask.end = end,
+         Layer::Filter(ref mut filter) => filter.end = end,
+      }
+   }
+
+   #[inline]
+   pub fn is_filter(&self) -> bool {
+      match *self {
+         Layer::Filter(_) => true,
+         _ => false,
+      }
+   }
+}
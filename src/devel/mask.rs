@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use super::Poly;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskMode {
+   Alpha,
+   Luminance,
+}
+
+
+// RGBA pixels with straight alpha, placed at `x` and `y` in frame pixels
+#[derive(Debug, Clone)]
+pub struct MaskImage {
+   pub x: i64,
+   pub y: i64,
+   pub width: usize,
+   pub height: usize,
+   pub pixels: Vec<u8>,
+}
+
+impl MaskImage {
+   #[inline]
+   pub fn new(x: i64, y: i64, width: usize, height: usize, pixels: Vec<u8>) -> Self {
+      assert_eq!(pixels.len(), width * height * 4);
+
+      MaskImage {
+         x: x,
+         y: y,
+         width: width,
+         height: height,
+         pixels: pixels,
+      }
+   }
+}
+
+
+#[derive(Debug, Clone)]
+pub enum MaskSource {
+   Polys(Vec<Box<Poly>>),
+   Image(Arc<MaskImage>),
+}
+
+
+// Modulates the coverage of the scene polys from `start` to `end`
+#[derive(Debug, Clone)]
+pub struct Mask {
+   pub mode: MaskMode,
+   pub source: MaskSource,
+   pub start: usize,
+   pub end: usize,
+}
+
+impl Mask {
+   #[inline]
+   pub fn new(mode: MaskMode, source: MaskSource, start: usize) -> Self {
+      Mask {
+         mode: mode,
+         source: source,
+         start: start,
+         end: start,
+      }
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use draw::RGB;
+   use data::IntPoint;
+   use image::Image;
+   use devel::{Scene, DevelRenderer, SUBDIVISIONS};
+
+   fn rect(x1: i64, y1: i64, x2: i64, y2: i64, color: RGB) -> Box<Poly> {
+      let s = SUBDIVISIONS;
+
+      Box::new(Poly::new(vec![vec![
+         IntPoint::new(x1 * s, y1 * s),
+         IntPoint::new(x2 * s, y1 * s),
+         IntPoint::new(x2 * s, y2 * s),
+         IntPoint::new(x1 * s, y2 * s),
+      ]], color))
+   }
+
+   // Red square over the 16 by 16 frame through the mask, the frame is
+   // split in zones of a few rows so the masks cross them
+   fn render(mode: MaskMode, source: MaskSource) -> Image {
+      let mut scene = Scene::new();
+
+      scene.begin_mask(mode, source);
+      scene.push(rect(0, 0, 16, 16, RGB::new(255, 0, 0)));
+      scene.end_mask();
+
+      let mut renderer = DevelRenderer::new(scene);
+
+      renderer.set_workers(3);
+
+      renderer.render_image(16, 16)
+   }
+
+   // Mask image from `x`, `y` in frame pixels, opaque `color` on the left
+   // `width` columns and transparent on the rest
+   fn mask_image(x: i64, y: i64, width: usize, height: usize, color: [u8; 3]) -> MaskSource {
+      let mut pixels = Vec::new();
+
+      for _ in 0..height {
+         for column in 0..2 * width {
+            if column < width {
+               pixels.extend_from_slice(&[color[0], color[1], color[2], 255]);
+            } else {
+               pixels.extend_from_slice(&[0, 0, 0, 0]);
+            }
+         }
+      }
+
+      MaskSource::Image(Arc::new(MaskImage::new(x, y, 2 * width, height, pixels)))
+   }
+
+   #[test]
+   fn test_alpha_polys() {
+      // Mask poly sticking out above and left of the frame
+      let image = render(MaskMode::Alpha, MaskSource::Polys(vec![
+         rect(-4, -4, 6, 11, RGB::new(0, 0, 0)),
+      ]));
+
+      assert_eq!(image.pixel(2, 2), [255, 0, 0, 255]);
+      assert_eq!(image.pixel(5, 10)[3], 255);
+      assert_eq!(image.pixel(6, 10)[3], 0);
+      assert_eq!(image.pixel(5, 11)[3], 0);
+      assert_eq!(image.pixel(12, 14)[3], 0);
+   }
+
+   #[test]
+   fn test_luminance_polys() {
+      // Black hides the content like no mask at all, white shows it
+      let image = render(MaskMode::Luminance, MaskSource::Polys(vec![
+         rect(0, 0, 8, 20, RGB::new(255, 255, 255)),
+         rect(8, 0, 12, 20, RGB::new(0, 0, 0)),
+         rect(12, 0, 16, 20, RGB::new(0, 255, 0)),
+      ]));
+
+      assert_eq!(image.pixel(3, 15), [255, 0, 0, 255]);
+      assert_eq!(image.pixel(9, 3)[3], 0);
+
+      // Green is most of the luminance
+      let green = image.pixel(14, 8)[3];
+
+      assert!(green > 170 && green < 200, "{}", green);
+   }
+
+   #[test]
+   fn test_alpha_image() {
+      // Taller than the frame and starting left of it
+      let image = render(MaskMode::Alpha, mask_image(-3, -5, 9, 30, [0, 0, 0]));
+
+      for y in 0..16 {
+         assert_eq!(image.pixel(2, y), [255, 0, 0, 255]);
+         assert_eq!(image.pixel(5, y)[3], 255);
+         assert_eq!(image.pixel(6, y)[3], 0);
+         assert_eq!(image.pixel(15, y)[3], 0);
+      }
+   }
+
+   #[test]
+   fn test_luminance_image() {
+      // Below the top rows and past the bottom of the frame
+      let image = render(MaskMode::Luminance, mask_image(4, 5, 6, 20, [255, 255, 255]));
+
+      assert_eq!(image.pixel(6, 4)[3], 0);
+      assert_eq!(image.pixel(6, 5), [255, 0, 0, 255]);
+      assert_eq!(image.pixel(9, 15)[3], 255);
+      assert_eq!(image.pixel(10, 15)[3], 0);
+      assert_eq!(image.pixel(3, 10)[3], 0);
+
+      // Black pixels of the image hide the content
+      let image = render(MaskMode::Luminance, mask_image(0, 0, 16, 16, [0, 0, 0]));
+
+      assert_eq!(image.pixel(8, 8)[3], 0);
+   }
+}
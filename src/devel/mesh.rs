@@ -0,0 +1,661 @@
+use draw::RGB;
+use data::{IntPoint, FloatPoint};
+use geom::transform::Transform;
+use image::{Image, ImagePaint, RepeatMode, ImageFilter};
+
+use super::Poly;
+
+
+// Upper bound of the subdivisions along each side of a patch
+pub const MAX_DIVISIONS: usize = 256;
+
+// Upper bound of the width and height of the color image of a mesh, larger
+// meshes get coarser pixels
+pub const MAX_IMAGE_SIZE: usize = 4096;
+
+const NEWTON_STEPS: usize = 8;
+
+
+// Bicubic patch like the PDF shading types 6 and 7. `points[i][j]` is the
+// tensor-product control net with `i` along u and `j` along v, the corner
+// colors are in the PDF order c00, c03, c33, c30
+#[derive(Debug, Clone)]
+pub struct Patch {
+   pub points: [[FloatPoint; 4]; 4],
+   pub colors: [RGB; 4],
+}
+
+impl Patch {
+   #[inline]
+   pub fn tensor(points: [[FloatPoint; 4]; 4], colors: [RGB; 4]) -> Self {
+      Patch {
+         points: points,
+         colors: colors,
+      }
+   }
+
+   // Coons patch from the 12 boundary points in the PDF order: up the u = 0
+   // side from p00 to p03, along v = 1 to p33, down u = 1 to p30 and back
+   // along v = 0 ending with p10. The interior points are the ones that make
+   // the tensor patch equal to the Coons surface
+   pub fn coons(boundary: &[FloatPoint; 12], colors: [RGB; 4]) -> Self {
+      let b = boundary;
+
+      let mut p = [[FloatPoint::default(); 4]; 4];
+
+      p[0] = [b[0], b[1], b[2], b[3]];
+      p[1][3] = b[4];
+      p[2][3] = b[5];
+      p[3] = [b[9], b[8], b[7], b[6]];
+      p[2][0] = b[10];
+      p[1][0] = b[11];
+
+      let interior = |c: FloatPoint, n: [FloatPoint; 2], f: [FloatPoint; 2], s: [FloatPoint; 2], o: FloatPoint| {
+         (c * -4. + (n[0] + n[1]) * 6. - (f[0] + f[1]) * 2. + (s[0] + s[1]) * 3. - o) / 9.
+      };
+
+      p[1][1] = interior(p[0][0], [p[0][1], p[1][0]], [p[0][3], p[3][0]], [p[3][1], p[1][3]], p[3][3]);
+      p[1][2] = interior(p[0][3], [p[0][2], p[1][3]], [p[0][0], p[3][3]], [p[3][2], p[1][0]], p[3][0]);
+      p[2][2] = interior(p[3][3], [p[3][2], p[2][3]], [p[3][0], p[0][3]], [p[2][0], p[0][2]], p[0][0]);
+      p[2][1] = interior(p[3][0], [p[3][1], p[2][0]], [p[3][3], p[0][0]], [p[0][1], p[2][3]], p[0][3]);
+
+      Patch::tensor(p, colors)
+   }
+
+   // Coons patch with straight sides between the corners p00, p03, p33 and
+   // p30, which reduces to a bilinear quad
+   pub fn quad(corners: &[FloatPoint; 4], colors: [RGB; 4]) -> Self {
+      let mut boundary = [FloatPoint::default(); 12];
+
+      for side in 0..4 {
+         let start = corners[side];
+         let end = corners[(side + 1) % 4];
+
+         boundary[3 * side] = start;
+         boundary[3 * side + 1] = start + (end - start) * (1. / 3.);
+         boundary[3 * side + 2] = start + (end - start) * (2. / 3.);
+      }
+
+      Patch::coons(&boundary, colors)
+   }
+
+   pub fn point(&self, u: f64, v: f64) -> FloatPoint {
+      let bu = bernstein(u);
+      let bv = bernstein(v);
+
+      let mut point = FloatPoint::default();
+
+      for i in 0..4 {
+         for j in 0..4 {
+            point = point + self.points[i][j] * (bu[i] * bv[j]);
+         }
+      }
+
+      point
+   }
+
+   // Bilinear interpolation of the corner colors
+   pub fn color(&self, u: f64, v: f64) -> RGB {
+      let c = &self.colors;
+
+      let weights = [(1. - u) * (1. - v), (1. - u) * v, u * v, u * (1. - v)];
+
+      let channel = |get: &Fn(&RGB) -> u8| {
+         let mut sum = 0.;
+
+         for k in 0..4 {
+            sum += get(&c[k]) as f64 * weights[k];
+         }
+
+         sum.round().max(0.).min(255.) as u8
+      };
+
+      RGB::new(channel(&|c| c.r), channel(&|c| c.g), channel(&|c| c.b))
+   }
+
+   #[inline]
+   pub fn transform(&mut self, transform: &Transform) {
+      for row in self.points.iter_mut() {
+         for point in row.iter_mut() {
+            *point = transform.apply(*point);
+         }
+      }
+   }
+
+   // Boundary flattened into segments no longer than about `step` along
+   // the control net
+   pub fn outline(&self, step: f64) -> Vec<IntPoint> {
+      let (columns, rows) = self.divisions(step);
+
+      let mut contour = Vec::with_capacity(2 * (columns + rows));
+
+      for k in 0..columns {
+         contour.push(self.point(k as f64 / columns as f64, 0.).as_int());
+      }
+
+      for k in 0..rows {
+         contour.push(self.point(1., k as f64 / rows as f64).as_int());
+      }
+
+      for k in 0..columns {
+         contour.push(self.point(1. - k as f64 / columns as f64, 1.).as_int());
+      }
+
+      for k in 0..rows {
+         contour.push(self.point(0., 1. - k as f64 / rows as f64).as_int());
+      }
+
+      contour
+   }
+
+   // Colors the pixels whose center is inside the patch with the color at
+   // the parameters of the center. Cells of about a pixel are searched with
+   // increasing v then u so the later parts of folded patches end up on top
+   fn fill(&self, pixels: &mut Pixels) {
+      let size = pixels.size;
+
+      let (columns, rows) = self.divisions(size);
+
+      let du = 1. / columns as f64;
+      let dv = 1. / rows as f64;
+
+      for row in 0..rows {
+         let v0 = row as f64 * dv;
+         let v1 = v0 + dv;
+
+         for column in 0..columns {
+            let u0 = column as f64 * du;
+            let u1 = u0 + du;
+
+            let corners = [
+               self.point(u0, v0), self.point(u1, v0), self.point(u1, v1), self.point(u0, v1)
+            ];
+
+            let mut min = corners[0];
+            let mut max = corners[0];
+
+            for corner in corners.iter() {
+               min = FloatPoint::new(min.x.min(corner.x), min.y.min(corner.y));
+               max = FloatPoint::new(max.x.max(corner.x), max.y.max(corner.y));
+            }
+
+            // Cells bulge a little past their corners
+            let (x_start, x_end) = pixels.columns(min.x - size, max.x + size);
+            let (y_start, y_end) = pixels.rows(min.y - size, max.y + size);
+
+            for y in y_start..y_end {
+               for x in x_start..x_end {
+                  let center = pixels.center(x, y);
+
+                  let (u, v) = match self.locate(center, (u0 + u1) / 2., (v0 + v1) / 2., size * 1e-3) {
+                     Some(found) => found,
+                     None => continue,
+                  };
+
+                  if u < u0 - 1e-9 || u > u1 + 1e-9 || v < v0 - 1e-9 || v > v1 + 1e-9 {
+                     continue;
+                  }
+
+                  pixels.set(x, y, self.color(u.max(0.).min(1.), v.max(0.).min(1.)));
+               }
+            }
+         }
+      }
+   }
+
+   // Parameters of `target` found by Newton's method from `u` and `v`
+   fn locate(&self, target: FloatPoint, u: f64, v: f64, tolerance: f64) -> Option<(f64, f64)> {
+      let mut u = u;
+      let mut v = v;
+
+      for _ in 0..NEWTON_STEPS {
+         let error = self.point(u, v) - target;
+
+         if error.x.abs() <= tolerance && error.y.abs() <= tolerance {
+            return Some((u, v));
+         }
+
+         let (pu, pv) = self.derivatives(u, v);
+
+         let determinant = pu.x * pv.y - pu.y * pv.x;
+
+         if determinant.abs() < 1e-12 {
+            return None;
+         }
+
+         u -= (error.x * pv.y - error.y * pv.x) / determinant;
+         v -= (pu.x * error.y - pu.y * error.x) / determinant;
+      }
+
+      let error = self.point(u, v) - target;
+
+      if error.x.abs() <= tolerance && error.y.abs() <= tolerance {
+         Some((u, v))
+      } else {
+         None
+      }
+   }
+
+   // Partial derivatives along u and v
+   fn derivatives(&self, u: f64, v: f64) -> (FloatPoint, FloatPoint) {
+      let bu = bernstein(u);
+      let bv = bernstein(v);
+      let du = bernstein_derivative(u);
+      let dv = bernstein_derivative(v);
+
+      let mut pu = FloatPoint::default();
+      let mut pv = FloatPoint::default();
+
+      for i in 0..4 {
+         for j in 0..4 {
+            pu = pu + self.points[i][j] * (du[i] * bv[j]);
+            pv = pv + self.points[i][j] * (bu[i] * dv[j]);
+         }
+      }
+
+      (pu, pv)
+   }
+
+   // Longest control net polyline along u and along v over `step`
+   fn divisions(&self, step: f64) -> (usize, usize) {
+      let p = &self.points;
+
+      let mut u_length: f64 = 0.;
+      let mut v_length: f64 = 0.;
+
+      for k in 0..4 {
+         let mut u_sum = 0.;
+         let mut v_sum = 0.;
+
+         for l in 0..3 {
+            u_sum += distance(p[l][k], p[l + 1][k]);
+            v_sum += distance(p[k][l], p[k][l + 1]);
+         }
+
+         u_length = u_length.max(u_sum);
+         v_length = v_length.max(v_sum);
+      }
+
+      let count = |length: f64| {
+         ((length / step).ceil() as usize).max(1).min(MAX_DIVISIONS)
+      };
+
+      (count(u_length), count(v_length))
+   }
+}
+
+
+// Patches drawn in order, like the SVG2 mesh gradient or a PDF patch mesh
+// shading
+#[derive(Debug, Clone)]
+pub struct Mesh {
+   pub patches: Vec<Patch>,
+}
+
+impl Mesh {
+   #[inline]
+   pub fn new(patches: Vec<Patch>) -> Self {
+      Mesh {
+         patches: patches,
+      }
+   }
+
+   // Grid of `columns` by `rows` straight sided patches sharing the vertex
+   // `points` and their `colors`, both given row by row with `columns + 1`
+   // entries per row
+   pub fn grid(columns: usize, rows: usize, points: &[FloatPoint], colors: &[RGB]) -> Option<Self> {
+      let count = (columns + 1) * (rows + 1);
+
+      if columns == 0 || rows == 0 || points.len() != count || colors.len() != count {
+         return None;
+      }
+
+      let mut patches = Vec::with_capacity(columns * rows);
+
+      for row in 0..rows {
+         for column in 0..columns {
+            let top = row * (columns + 1) + column;
+            let bottom = top + columns + 1;
+
+            // u goes along the columns and v along the rows
+            let indices = [top, bottom, bottom + 1, top + 1];
+
+            let corners = [
+               points[indices[0]], points[indices[1]], points[indices[2]], points[indices[3]]
+            ];
+
+            let corner_colors = [
+               colors[indices[0]], colors[indices[1]], colors[indices[2]], colors[indices[3]]
+            ];
+
+            patches.push(Patch::quad(&corners, corner_colors));
+         }
+      }
+
+      Some(Mesh::new(patches))
+   }
+
+   #[inline]
+   pub fn transform(&mut self, transform: &Transform) {
+      for patch in self.patches.iter_mut() {
+         patch.transform(transform);
+      }
+   }
+
+   // Single poly covering the patches, painted with the colors evaluated at
+   // the centers of pixels of `pixel_size` and interpolated in between, so
+   // no part of the mesh gets blended twice. None without patches
+   pub fn poly(&self, pixel_size: f64) -> Option<Poly> {
+      let mut points = self.patches.iter().flat_map(|patch| patch.points.iter().flat_map(|row| row.iter()));
+
+      let first = match points.next() {
+         Some(first) => *first,
+         None => return None,
+      };
+
+      // The patches stay inside the bounds of their control nets
+      let (min, max) = points.fold((first, first), |(min, max), point| {
+         (
+            FloatPoint::new(min.x.min(point.x), min.y.min(point.y)),
+            FloatPoint::new(max.x.max(point.x), max.y.max(point.y)),
+         )
+      });
+
+      let extent = (max.x - min.x).max(max.y - min.y);
+
+      let size = pixel_size.max(extent / (MAX_IMAGE_SIZE - 2) as f64);
+
+      // A pixel of margin so the edges interpolate between colored pixels
+      let x = (min.x / size).floor() - 1.;
+      let y = (min.y / size).floor() - 1.;
+
+      let width = ((max.x / size).ceil() + 1. - x).max(1.) as usize;
+      let height = ((max.y / size).ceil() + 1. - y).max(1.) as usize;
+
+      let mut pixels = Pixels::new(x * size, y * size, size, width, height);
+
+      let mut outline: Option<Poly> = None;
+
+      for patch in self.patches.iter() {
+         patch.fill(&mut pixels);
+
+         let poly = Poly::new(vec![patch.outline(size)], RGB::default());
+
+         outline = Some(match outline {
+            Some(outline) => outline.union(&poly),
+            None => poly,
+         });
+      }
+
+      pixels.extend();
+
+      let transform = Transform::scale(size, size).then(&Transform::translate(x * size, y * size));
+
+      let image = Image::new(width, height, pixels.pixels);
+
+      let paint = match ImagePaint::new(image, transform, RepeatMode::None, ImageFilter::Bilinear) {
+         Some(paint) => paint,
+         None => return None,
+      };
+
+      outline.map(|outline| outline.with_paint(paint))
+   }
+}
+
+
+// Straight alpha RGBA pixels of `size` from `x` and `y`, transparent where
+// no patch has been found
+struct Pixels {
+   x: f64,
+   y: f64,
+   size: f64,
+   width: usize,
+   height: usize,
+   pixels: Vec<u8>,
+}
+
+impl Pixels {
+   #[inline]
+   fn new(x: f64, y: f64, size: f64, width: usize, height: usize) -> Self {
+      Pixels {
+         x: x,
+         y: y,
+         size: size,
+         width: width,
+         height: height,
+         pixels: vec![0; width * height * 4],
+      }
+   }
+
+   #[inline]
+   fn center(&self, x: usize, y: usize) -> FloatPoint {
+      FloatPoint::new(self.x + (x as f64 + 0.5) * self.size, self.y + (y as f64 + 0.5) * self.size)
+   }
+
+   // Columns whose center is between `start` and `end`
+   #[inline]
+   fn columns(&self, start: f64, end: f64) -> (usize, usize) {
+      span(start, end, self.x, self.size, self.width)
+   }
+
+   #[inline]
+   fn rows(&self, start: f64, end: f64) -> (usize, usize) {
+      span(start, end, self.y, self.size, self.height)
+   }
+
+   #[inline]
+   fn set(&mut self, x: usize, y: usize, color: RGB) {
+      let index = (y * self.width + x) * 4;
+
+      self.pixels[index] = color.r;
+      self.pixels[index + 1] = color.g;
+      self.pixels[index + 2] = color.b;
+      self.pixels[index + 3] = 255;
+   }
+
+   // Transparent pixels next to colored ones take their average color, the
+   // outline covering a bit more than the pixel centers found inside
+   fn extend(&mut self) {
+      let source = self.pixels.clone();
+
+      for y in 0..self.height {
+         for x in 0..self.width {
+            let index = (y * self.width + x) * 4;
+
+            if source[index + 3] != 0 {
+               continue;
+            }
+
+            let mut sum = [0u32; 3];
+            let mut count = 0;
+
+            for ny in y.saturating_sub(1)..(y + 2).min(self.height) {
+               for nx in x.saturating_sub(1)..(x + 2).min(self.width) {
+                  let neighbour = (ny * self.width + nx) * 4;
+
+                  if source[neighbour + 3] == 0 {
+                     continue;
+                  }
+
+                  for channel in 0..3 {
+                     sum[channel] += source[neighbour + channel] as u32;
+                  }
+
+                  count += 1;
+               }
+            }
+
+            if count > 0 {
+               for channel in 0..3 {
+                  self.pixels[index + channel] = ((sum[channel] + count / 2) / count) as u8;
+               }
+
+               self.pixels[index + 3] = 255;
+            }
+         }
+      }
+   }
+}
+
+
+#[inline]
+fn span(start: f64, end: f64, origin: f64, size: f64, count: usize) -> (usize, usize) {
+   let first = ((start - origin) / size - 0.5).ceil().max(0.);
+   let last = ((end - origin) / size - 0.5).floor() + 1.;
+
+   (first.min(count as f64) as usize, last.max(0.).min(count as f64) as usize)
+}
+
+
+#[inline]
+fn bernstein(t: f64) -> [f64; 4] {
+   let s = 1. - t;
+
+   [s * s * s, 3. * t * s * s, 3. * t * t * s, t * t * t]
+}
+
+
+#[inline]
+fn bernstein_derivative(t: f64) -> [f64; 4] {
+   let s = 1. - t;
+
+   [-3. * s * s, 3. * s * (s - 2. * t), 3. * t * (2. * s - t), 3. * t * t]
+}
+
+
+#[inline]
+fn distance(a: FloatPoint, b: FloatPoint) -> f64 {
+   ((b.x - a.x) * (b.x - a.x) + (b.y - a.y) * (b.y - a.y)).sqrt()
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use draw::BlendMode;
+   use devel::{Scene, DevelRenderer, SUBDIVISIONS};
+
+   fn corners(size: f64) -> [FloatPoint; 4] {
+      [
+         FloatPoint::new(0., 0.),
+         FloatPoint::new(0., size),
+         FloatPoint::new(size, size),
+         FloatPoint::new(size, 0.),
+      ]
+   }
+
+   #[test]
+   fn test_coons_interior() {
+      let colors = [RGB::default(); 4];
+
+      let patch = Patch::quad(&corners(3.), colors);
+
+      // A bilinear square has its control net on the thirds
+      for i in 0..4 {
+         for j in 0..4 {
+            let p = patch.points[i][j];
+
+            assert!((p.x - i as f64).abs() < 1e-9 && (p.y - j as f64).abs() < 1e-9);
+         }
+      }
+
+      let point = patch.point(0.25, 0.5);
+
+      assert!((point.x - 0.75).abs() < 1e-9 && (point.y - 1.5).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_render_gradient() {
+      let s = SUBDIVISIONS as f64;
+
+      let colors = [
+         RGB::new(255, 0, 0),
+         RGB::new(0, 255, 0),
+         RGB::new(0, 0, 255),
+         RGB::new(255, 255, 255),
+      ];
+
+      let patch = Patch::quad(&corners(32. * s), colors);
+
+      // Same gradient split into a 2 by 2 grid
+      let mut points = Vec::new();
+      let mut grid_colors = Vec::new();
+
+      for row in 0..3 {
+         for column in 0..3 {
+            points.push(FloatPoint::new(column as f64 * 16. * s, row as f64 * 16. * s));
+            grid_colors.push(patch.color(column as f64 / 2., row as f64 / 2.));
+         }
+      }
+
+      let mesh = Mesh::grid(2, 2, &points, &grid_colors).unwrap();
+
+      assert_eq!(mesh.patches.len(), 4);
+
+      let mut scene = Scene::new();
+
+      scene.push(Box::new(mesh.poly(s).unwrap()));
+
+      let image = DevelRenderer::new(scene).render_image(32, 32);
+
+      // Fully covered without seams between the quads
+      for y in 0..32 {
+         for x in 0..32 {
+            assert_eq!(image.pixel(x, y)[3], 255);
+         }
+      }
+
+      // Colors interpolated at the pixel centers
+      let near = |x: usize, y: usize| {
+         let expected = patch.color((x as f64 + 0.5) / 32., (y as f64 + 0.5) / 32.);
+         let actual = image.pixel(x, y);
+
+         (actual[0] as i32 - expected.r as i32).abs() <= 3 &&
+            (actual[1] as i32 - expected.g as i32).abs() <= 3 &&
+            (actual[2] as i32 - expected.b as i32).abs() <= 3
+      };
+
+      for y in 0..32 {
+         for x in 0..32 {
+            assert!(near(x, y), "{} {} {:?}", x, y, image.pixel(x, y));
+         }
+      }
+   }
+
+   #[test]
+   fn test_group_opacity() {
+      let s = SUBDIVISIONS as f64;
+
+      let color = RGB::new(200, 0, 0);
+
+      let mesh = Mesh::new(vec![Patch::quad(&corners(16. * s), [color; 4])]);
+
+      let flat = Poly::new(vec![corners(16. * s).iter().map(|point| point.as_int()).collect()], color);
+
+      let render = |poly: Poly| {
+         let mut scene = Scene::new();
+
+         scene.begin_group(0.5, BlendMode::Normal);
+         scene.push(Box::new(poly));
+         scene.end_group();
+
+         DevelRenderer::new(scene).render_image(16, 16)
+      };
+
+      let expected = render(flat);
+      let actual = render(mesh.poly(s).unwrap());
+
+      // Every pixel gets blended once like with a single flat poly
+      for y in 0..16 {
+         for x in 0..16 {
+            let a = actual.pixel(x, y);
+            let b = expected.pixel(x, y);
+
+            let close = (0..4).all(|k| (a[k] as i32 - b[k] as i32).abs() <= 1);
+
+            assert!(close, "{} {} {:?} {:?}", x, y, a, b);
+         }
+      }
+   }
+}
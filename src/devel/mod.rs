@@ -1,8 +1,22 @@
 pub mod poly;
 pub mod scene;
+pub mod mask;
+pub mod layer;
+pub mod filter;
+pub mod pattern;
+pub mod mesh;
 pub mod renderer;
+pub mod sampling;
+pub mod simd;
 pub mod ppm;
 
 pub use self::poly::Poly;
 pub use self::scene::Scene;
+pub use self::mask::{Mask, MaskMode, MaskSource, MaskImage};
+pub use self::layer::{Layer, Group};
+pub use self::filter::{Filter, FilterOp, Morphology, DropShadow};
+pub use self::pattern::Pattern;
+pub use self::mesh::{Patch, Mesh};
 pub use self::renderer::{DevelRenderer, SUBDIVISIONS};
+pub use self::sampling::Sampling;
+pub use self::simd::Simd;
@@ -0,0 +1,116 @@
+use geom::transform::Transform;
+use image::{ImagePaint, RepeatMode, ImageFilter};
+
+use super::Scene;
+use super::renderer::DevelRenderer;
+
+
+// Scene repeated infinitely like the SVG pattern element. The tile rectangle
+// from `x`, `y` with `width` and `height` is in the scene units of the
+// renderer painting it, `transform` maps it to the filled outlines
+#[derive(Clone)]
+pub struct Pattern {
+   pub scene: Scene,
+   pub x: f64,
+   pub y: f64,
+   pub width: f64,
+   pub height: f64,
+   pub transform: Transform,
+}
+
+impl Pattern {
+   #[inline]
+   pub fn new(scene: Scene, x: f64, y: f64, width: f64, height: f64, transform: Transform) -> Self {
+      Pattern {
+         scene: scene,
+         x: x,
+         y: y,
+         width: width,
+         height: height,
+         transform: transform,
+      }
+   }
+
+   // Repeating paint sampling the tile, rendered by `renderer` at the
+   // resolution the transform asks for. None when the transform can't be
+   // inverted
+   pub fn paint(&self, renderer: &mut DevelRenderer) -> Option<ImagePaint> {
+      if self.transform.invert().is_none() {
+         return None;
+      }
+
+      let t = &self.transform;
+
+      let scale = (t.xx * t.xx + t.yx * t.yx).sqrt().max((t.xy * t.xy + t.yy * t.yy).sqrt());
+
+      let subdivisions = renderer.subdivisions() as f64;
+
+      let pixel_width = (self.width * scale / subdivisions).ceil().max(1.);
+      let pixel_height = (self.height * scale / subdivisions).ceil().max(1.);
+
+      // The tile gets stretched to whole pixels so the repeats meet exactly
+      let tile_transform = Transform::translate(-self.x, -self.y).then(
+         &Transform::scale(pixel_width * subdivisions / self.width, pixel_height * subdivisions / self.height)
+      );
+
+      let mut scene = self.scene.clone();
+
+      scene.transform(&tile_transform);
+
+      renderer.set_scene(scene);
+
+      let image = renderer.render_image(pixel_width as u32, pixel_height as u32);
+
+      let image_transform = Transform::scale(self.width / pixel_width, self.height / pixel_height)
+         .then(&Transform::translate(self.x, self.y))
+         .then(&self.transform);
+
+      ImagePaint::new(image, image_transform, RepeatMode::Repeat, ImageFilter::Mipmap)
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use draw::RGB;
+   use data::IntPoint;
+   use devel::{Poly, SUBDIVISIONS};
+
+   fn square(x: i64, y: i64, size: i64, color: RGB) -> Box<Poly> {
+      let s = SUBDIVISIONS;
+
+      Box::new(Poly::new(vec![vec![
+         IntPoint::new(x * s, y * s),
+         IntPoint::new((x + size) * s, y * s),
+         IntPoint::new((x + size) * s, (y + size) * s),
+         IntPoint::new(x * s, (y + size) * s),
+      ]], color))
+   }
+
+   #[test]
+   fn test_checkerboard() {
+      let mut scene = Scene::new();
+
+      scene.push(square(0, 0, 4, RGB::new(255, 0, 0)));
+      scene.push(square(4, 4, 4, RGB::new(255, 0, 0)));
+
+      let s = SUBDIVISIONS as f64;
+
+      let pattern = Pattern::new(scene, 0., 0., 8. * s, 8. * s, Transform::translate(2. * s, 0.));
+
+      let paint = pattern.paint(&mut DevelRenderer::new(Scene::new())).unwrap();
+
+      assert_eq!(paint.image.width, 8);
+      assert_eq!(paint.image.height, 8);
+
+      let sample = |x: f64, y: f64| paint.sample(x * s, y * s, s);
+
+      // Red on the tile diagonal, transparent off it, repeating every 8 pixels
+      assert_eq!(sample(3.5, 1.5), [255., 0., 0., 255.]);
+      assert_eq!(sample(7.5, 1.5), [0., 0., 0., 0.]);
+      assert_eq!(sample(3.5 + 16., 1.5 - 24.), [255., 0., 0., 255.]);
+      assert_eq!(sample(7.5 - 40., 5.5), [255., 0., 0., 255.]);
+   }
+}
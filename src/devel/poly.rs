@@ -1,10 +1,16 @@
-use data::IntPoint;
+use data::{IntPoint, FloatPoint};
 use draw::RGB;
+use geom::path::Path;
+use geom::flatten::Flattener;
+use geom::transform::Transform;
+use geom::boolean::{BooleanOp, boolean};
+use image::ImagePaint;
 
 #[derive(Debug, Clone)]
 pub struct Poly {
    pub contours: Vec<Vec<IntPoint>>,
    pub color: RGB,
+   pub paint: Option<ImagePaint>,
 }
 
 impl Poly {
@@ -13,6 +19,83 @@ impl Poly {
       Poly {
          contours: contours,
          color: color,
+         paint: None,
       }
    }
+
+   // Fills with the image instead of the color, the paint transform is in
+   // the same subdivided units as the contours
+   #[inline]
+   pub fn with_paint(mut self, paint: ImagePaint) -> Self {
+      self.paint = Some(paint);
+      self
+   }
+
+   pub fn from_path(path: &Path, flattener: &Flattener, color: RGB) -> Self {
+      let mut contours = Vec::new();
+
+      for contour in path.flatten(flattener) {
+         if contour.len() < 3 {
+            continue;
+         }
+
+         contours.push(contour.iter().map(|point| point.as_int()).collect());
+      }
+
+      Poly::new(contours, color)
+   }
+
+   // Maps the contours and the paint, both being in subdivided units. The
+   // paint is dropped by transforms that can't be inverted
+   pub fn transform(&mut self, transform: &Transform) {
+      for contour in self.contours.iter_mut() {
+         for point in contour.iter_mut() {
+            *point = transform.apply(point.as_float()).as_int();
+         }
+      }
+
+      self.paint = self.paint.as_ref().and_then(|paint| paint.then(transform));
+   }
+
+   // Result keeps the color and paint of `self`
+   pub fn boolean(&self, op: BooleanOp, other: &Poly) -> Self {
+      let result = boolean(op, &float_contours(&self.contours), &float_contours(&other.contours));
+
+      let contours = result.iter().map(
+         |contour| contour.iter().map(|point| point.as_int()).collect()
+      ).collect();
+
+      Poly {
+         contours: contours,
+         color: self.color,
+         paint: self.paint.clone(),
+      }
+   }
+
+   #[inline]
+   pub fn union(&self, other: &Poly) -> Self {
+      self.boolean(BooleanOp::Union, other)
+   }
+
+   #[inline]
+   pub fn intersection(&self, other: &Poly) -> Self {
+      self.boolean(BooleanOp::Intersection, other)
+   }
+
+   #[inline]
+   pub fn difference(&self, other: &Poly) -> Self {
+      self.boolean(BooleanOp::Difference, other)
+   }
+
+   #[inline]
+   pub fn xor(&self, other: &Poly) -> Self {
+      self.boolean(BooleanOp::Xor, other)
+   }
+}
+
+
+fn float_contours(contours: &[Vec<IntPoint>]) -> Vec<Vec<FloatPoint>> {
+   contours.iter().map(
+      |contour| contour.iter().map(|point| point.as_float()).collect()
+   ).collect()
 }
@@ -1,5 +1,5 @@
 use std::io;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::fs::File;
 use std::slice;
@@ -25,3 +25,71 @@ pub fn write_ppm(filename: &str, width: usize, height: usize, mut buffer: *const
 
    Ok(())
 }
+
+
+// Binary P6 files with a maximum value of 255, returned as width, height and
+// opaque RGBA pixels
+pub fn read_ppm(filename: &str) -> io::Result<(usize, usize, Vec<u8>)> {
+   let mut file = try!(File::open(&Path::new(filename)));
+
+   let mut data = Vec::new();
+
+   try!(file.read_to_end(&mut data));
+
+   let mut pos = 0;
+   let mut header = Vec::new();
+
+   while header.len() < 4 {
+      while pos < data.len() && (data[pos] as char).is_whitespace() {
+         pos += 1;
+      }
+
+      if pos < data.len() && data[pos] == b'#' {
+         while pos < data.len() && data[pos] != b'\n' {
+            pos += 1;
+         }
+         continue;
+      }
+
+      let start = pos;
+
+      while pos < data.len() && !(data[pos] as char).is_whitespace() {
+         pos += 1;
+      }
+
+      if start == pos {
+         return Err(invalid_ppm());
+      }
+
+      header.push(String::from_utf8_lossy(&data[start..pos]).into_owned());
+   }
+
+   // Single whitespace before the raster
+   pos += 1;
+
+   if header[0] != "P6" {
+      return Err(invalid_ppm());
+   }
+
+   let width = try!(header[1].parse::<usize>().map_err(|_| invalid_ppm()));
+   let height = try!(header[2].parse::<usize>().map_err(|_| invalid_ppm()));
+
+   if header[3] != "255" || data.len() < pos + width * height * 3 {
+      return Err(invalid_ppm());
+   }
+
+   let mut pixels = Vec::with_capacity(width * height * 4);
+
+   for rgb in data[pos..pos + width * height * 3].chunks(3) {
+      pixels.extend_from_slice(rgb);
+      pixels.push(255);
+   }
+
+   Ok((width, height, pixels))
+}
+
+
+#[inline]
+fn invalid_ppm() -> io::Error {
+   io::Error::new(io::ErrorKind::InvalidData, "Invalid PPM file")
+}
@@ -1,29 +1,44 @@
 use std::marker::Send;
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::thread;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::thread::{self, JoinHandle};
 use std::ptr;
+use std::slice;
 use std::usize;
 use std::cmp::{min, max};
 use std::u32;
 use std::mem::replace;
+use std::ops::Range;
+use std::sync::{Arc, Barrier, Mutex};
 
 use renderer::Renderer;
 use frame::Frame;
-use draw::RGB;
+use clock::FrameClock;
+use draw::{RGB, BlendMode, Blending, Subpixel, SUBPIXEL_FILTER};
 use data::{IntPoint, min_max, min_max_by_x};
+use image::{Image, ImagePaint};
+use srgb::SrgbTables;
+use color::{Profile, ColorTransform};
 
-use super::Scene;
+use super::{Scene, Poly};
+use super::sampling::{Sampling, SampleGrid, MAX_SAMPLES};
+use super::simd::{self, Simd};
+use super::mask::{MaskMode, MaskSource, MaskImage};
+use super::layer::Layer;
+use super::filter::{FilterOp, FilterRows, FilterScratch, apply_filter};
 
 
+// Scene coordinates are in fractions of a pixel, one per sample of the grid
+// the edges get rasterized on. This is their count with the default sampling
 pub const SUBDIVISIONS: i64 = 4;
-pub const SUBDIVISIONS_U: usize = SUBDIVISIONS as usize;
-pub const SUBDIVISIONS_X2: i64 = SUBDIVISIONS * SUBDIVISIONS;
 
-//pub const ALPHAS: [u16; 10] = [1, 30, 58, 86, 114, 143, 171, 199, 228, 256];
-pub const ALPHAS: [u16; 17] = [1, 17, 33, 49, 65, 81, 97, 113, 128, 144, 160, 176, 192, 208, 224, 240, 256];
 
+// Tiles of the frame per worker thread, the workers taking the next tile off
+// a shared queue when done with one
+const TILES_PER_WORKER: i64 = 4;
 
-pub const ZONE_COUNT: i64 = 8;
+// Layers for every nesting level of groups: content, mask and two filter
+// scratch layers
+const LAYERS_PER_DEPTH: usize = 4;
 
 
 #[derive(PartialEq, Clone, Copy, Debug)]
@@ -116,9 +131,9 @@ struct ZoneSplitter {
 
 impl ZoneSplitter {
    #[inline]
-   fn new(aliased_height: i64, frame_height: i64, zone_count: i64) -> Self {
+   fn new(aliased_height: i64, frame_height: i64, zone_count: i64, samples: i64) -> Self {
       let pixel_height = 1 + (frame_height - 1) / zone_count;
-      let zone_height = pixel_height * SUBDIVISIONS;
+      let zone_height = pixel_height * samples;
       let zone_count = 1 + (frame_height - 1) / pixel_height;
 
       ZoneSplitter {
@@ -232,17 +247,6 @@ fn h_intersect(p: IntPoint, slope: f64, y: i64) -> i64 {
 }
 
 
-fn create_edges_vec(zone_count: i64) -> Vec<Vec<Edge>> {
-   let mut edges = Vec::new();
-
-   for _ in 0..zone_count {
-      edges.push(Vec::new());
-   }
-
-   edges
-}
-
-
 fn clean_edges_vec(edges: &mut Vec<Vec<Edge>>) {
    for list in edges.iter_mut() {
       list.clear();
@@ -341,7 +345,7 @@ fn build_edges(
 }
 
 
-fn move_from_start(zone_edges: &mut Vec<Edge>, start: usize, offset: usize, end: usize) {
+fn move_from_start(zone_edges: &mut [Edge], start: usize, offset: usize, end: usize) {
    let count = end.wrapping_sub(start).wrapping_sub(offset);
 
    unsafe {
@@ -353,26 +357,37 @@ fn move_from_start(zone_edges: &mut Vec<Edge>, start: usize, offset: usize, end:
 
 
 #[inline]
-fn aliased_alpha(mut ptr: *const u8, next_row_offset: isize) -> u16 {
-   let mut sum: u8 = 0;
+fn aliased_alpha(mut ptr: *const u8, next_row_offset: isize, grid: &SampleGrid) -> u16 {
+   let mut sum: usize = 0;
 
-   for _ in 0..SUBDIVISIONS_U {
-      for _ in 0..SUBDIVISIONS_U {
-         unsafe {
-            sum = sum.wrapping_add(*ptr);
-            ptr = ptr.offset(1);
+   match grid.columns {
+      Some(columns) => {
+         let row_offset = next_row_offset.wrapping_add(grid.size as isize);
+
+         for &column in columns.iter() {
+            unsafe {
+               sum = sum.wrapping_add(*ptr.offset(column as isize) as usize);
+               ptr = ptr.offset(row_offset);
+            }
          }
-      }
+      },
+      None => {
+         for _ in 0..grid.size {
+            for _ in 0..grid.size {
+               unsafe {
+                  sum = sum.wrapping_add(*ptr as usize);
+                  ptr = ptr.offset(1);
+               }
+            }
 
-      unsafe {
-         ptr = ptr.offset(next_row_offset);
-      }
+            unsafe {
+               ptr = ptr.offset(next_row_offset);
+            }
+         }
+      },
    }
 
-   // (255 * sum as u16 / SUBDIVISIONS_X2 as u16) + 1
-   unsafe {
-      *ALPHAS.get_unchecked(sum as usize)
-   }
+   grid.alpha(sum)
 }
 
 
@@ -411,6 +426,40 @@ fn blend(v1: u16, v2: u16, a1: u16) -> u8 {
    ).wrapping_shr(8) as u8).wrapping_sub(1)
 }
 
+
+// Same as blend_alpha_pixel on a linear layer, `color` being linear
+#[inline]
+unsafe fn blend_linear_pixel(mut ptr: *mut u16, a: u16, color: &[u16; 3]) -> *mut u16 {
+   if a == 1 {
+      return ptr.offset(4);
+   }
+
+   let coverage = (a - 1) as u32;
+   let remaining = 255 - coverage;
+
+   for channel in 0..3 {
+      *ptr = ((color[channel] as u32 * coverage + *ptr as u32 * remaining + 127) / 255) as u16;
+
+      ptr = ptr.offset(1);
+   }
+
+   *ptr = ((65535 * coverage + *ptr as u32 * remaining + 127) / 255) as u16;
+
+   ptr.offset(1)
+}
+
+
+// Bytes per pixel of the layers. Blending in linear light they hold 16-bit
+// premultiplied linear channels, which get encoded and dithered only when
+// written to the surface
+#[inline]
+fn pixel_bytes(srgb: Option<&SrgbTables>) -> usize {
+   match srgb {
+      Some(_) => 8,
+      None => 4,
+   }
+}
+
 /*
 
 #[inline]
@@ -450,8 +499,14 @@ fn write_to_surface(
    mut dst_ptr: *mut u8,
    width: usize,
    y: i64,
-   y_end: i64
+   y_end: i64,
+   srgb: Option<&SrgbTables>,
 ) {
+   if let Some(srgb) = srgb {
+      write_linear_to_surface(src_ptr as *const u16, dst_ptr, width, y as usize, y_end as usize, srgb);
+      return;
+   }
+
    let width = width as isize;
    let y = y as isize;
    let y_end = y_end as isize;
@@ -471,6 +526,48 @@ fn write_to_surface(
 }
 
 
+// Linear layer rows encoded to premultiplied sRGB, with the ordered dither
+// keeping the gradients free of banding. Stays scalar, a table lookup and a
+// division per channel leave little to the vector units
+fn write_linear_to_surface(
+   src_ptr: *const u16,
+   dst_ptr: *mut u8,
+   width: usize,
+   y: usize,
+   y_end: usize,
+   srgb: &SrgbTables,
+) {
+   for row in y..y_end {
+      for x in 0..width {
+         let index = row.wrapping_mul(width).wrapping_add(x).wrapping_mul(4);
+
+         unsafe {
+            let src = src_ptr.offset(index as isize);
+            let dst = dst_ptr.offset(index as isize);
+
+            let alpha = *src.offset(3) as u32;
+
+            for channel in 0..3 {
+               let value = *src.offset(channel) as u32;
+
+               *dst.offset(channel) = if alpha == 65535 {
+                  srgb.srgb_dithered(value as u16, x, row)
+               } else if alpha == 0 {
+                  0
+               } else {
+                  let straight = min(value * 65535 / alpha, 65535) as u16;
+
+                  ((srgb.srgb_dithered(straight, x, row) as u32 * alpha + 32767) / 65535) as u8
+               };
+            }
+
+            *dst.offset(3) = ((alpha * 255 + 32767) / 65535) as u8;
+         }
+      }
+   }
+}
+
+
 #[inline]
 fn supersample(
    ptr: *mut u8,
@@ -482,15 +579,23 @@ fn supersample(
    y: i64,
    frame_width: usize,
    color: RGB,
+   srgb: Option<&SrgbTables>,
+   subpixel: Subpixel,
+   grid: &SampleGrid,
+   simd: Simd,
 ) {
-   let x_start = start.wrapping_div(SUBDIVISIONS_U);
-   let x_start_max = start_max.wrapping_add(SUBDIVISIONS_U).
-      wrapping_sub(1).wrapping_div(SUBDIVISIONS_U);
+   let n = grid.size;
 
-   let x_end = end.wrapping_add(SUBDIVISIONS_U).wrapping_sub(1).wrapping_div(SUBDIVISIONS_U);
-   let x_end_min = end_min.wrapping_div(SUBDIVISIONS_U);
+   let x_start = start.wrapping_div(n);
+   let x_start_max = start_max.wrapping_add(n).
+      wrapping_sub(1).wrapping_div(n);
 
-   let y = y.wrapping_div(SUBDIVISIONS).wrapping_sub(1) as usize;
+   let x_end = end.wrapping_add(n).wrapping_sub(1).wrapping_div(n);
+   let x_end_min = end_min.wrapping_div(n);
+
+   let y = y.wrapping_div(n as i64).wrapping_sub(1) as usize;
+
+   let bytes = pixel_bytes(srgb);
 
    let mut color_u32: u32 = 255_u32;
    color_u32 |= (color.b as u32).wrapping_shl(8_u32);
@@ -502,59 +607,210 @@ fn supersample(
    let g = (color.g as u16).wrapping_add(1);
    let b = (color.b as u16).wrapping_add(1);
 
-   // (frame width - 1) * SUBDIVISIONS_U
-   let next_row_offset = frame_width.wrapping_sub(1).wrapping_mul(SUBDIVISIONS_U) as isize;
+   let linear = srgb.map(|srgb| [srgb.linear(color.r), srgb.linear(color.g), srgb.linear(color.b)]);
+
+   let fill = |ptr: *mut u8, len: usize| {
+      match linear {
+         Some(ref color) => fill_linear(ptr as *mut u16, len, color),
+         None => fill_solid(ptr as *mut u32, len, color_u32, simd),
+      }
+   };
+
+   if subpixel != Subpixel::None {
+      // The filter spreads the coverage over a third of a pixel further on
+      // each side
+      let first = x_start.wrapping_sub(1).min(x_start);
+      let last = min(x_end.wrapping_add(1), frame_width);
+
+      if first >= last {
+         return;
+      }
+
+      let solid_start = x_start_max.wrapping_add(1);
+      let solid_end = x_end_min.wrapping_sub(1);
+
+      if x_start_max < x_end_min && solid_start < solid_end {
+         blend_subpixel_stripe(ptr, aliased_ptr, first, solid_start, y, frame_width, color, linear, subpixel, grid);
+
+         let solid_ptr = unsafe {
+            ptr.offset(y.wrapping_mul(frame_width).wrapping_add(solid_start).wrapping_mul(bytes) as isize)
+         };
+
+         fill(solid_ptr, solid_end - solid_start);
+
+         blend_subpixel_stripe(ptr, aliased_ptr, solid_end, last, y, frame_width, color, linear, subpixel, grid);
+      } else {
+         blend_subpixel_stripe(ptr, aliased_ptr, first, last, y, frame_width, color, linear, subpixel, grid);
+      }
+
+      return;
+   }
+
+   // (frame width - 1) * samples per row
+   let next_row_offset = frame_width.wrapping_sub(1).wrapping_mul(n) as isize;
 
    let mut ptr = unsafe {
       ptr.offset(
-         y.wrapping_mul(frame_width).wrapping_add(x_start).wrapping_mul(4) as isize
+         y.wrapping_mul(frame_width).wrapping_add(x_start).wrapping_mul(bytes) as isize
       )
    };
 
    if x_start_max < x_end_min {
       let src_ptr = unsafe {
          aliased_ptr.offset(
-            (x_start.wrapping_mul(SUBDIVISIONS_U)) as isize
+            (x_start.wrapping_mul(n)) as isize
          )
       };
 
       ptr = blend_alpha_stripe(
-         ptr, src_ptr, x_start_max - x_start, next_row_offset, r, g, b
+         ptr, src_ptr, x_start_max - x_start, next_row_offset, r, g, b, linear, grid, simd
       );
 
-      let mut ptr = ptr as *mut u32;
+      let len = x_end_min.wrapping_sub(x_start_max);
 
-      for _ in 0..x_end_min.wrapping_sub(x_start_max) {
-         unsafe {
-            *ptr = color_u32;
-            ptr = ptr.offset(1);
-         }
-      }
-      let ptr = ptr as *mut u8;
+      fill(ptr, len);
+
+      let ptr = unsafe { ptr.offset(len.wrapping_mul(bytes) as isize) };
 
       let src_ptr = unsafe {
          aliased_ptr.offset(
-            (x_end_min.wrapping_mul(SUBDIVISIONS_U)) as isize
+            (x_end_min.wrapping_mul(n)) as isize
          )
       };
 
       blend_alpha_stripe(
-         ptr, src_ptr, x_end - x_end_min, next_row_offset, r, g, b
+         ptr, src_ptr, x_end - x_end_min, next_row_offset, r, g, b, linear, grid, simd
       );
    } else {
       let src_ptr = unsafe {
          aliased_ptr.offset(
-            (x_start.wrapping_mul(SUBDIVISIONS_U)) as isize
+            (x_start.wrapping_mul(n)) as isize
          )
       };
 
       blend_alpha_stripe(
-         ptr, src_ptr, x_end - x_start, next_row_offset, r, g, b
+         ptr, src_ptr, x_end - x_start, next_row_offset, r, g, b, linear, grid, simd
       );
    }
 }
 
 
+#[inline]
+fn fill_solid(mut ptr: *mut u32, len: usize, color: u32, simd: Simd) {
+   unsafe {
+      let done = simd::fill(simd, ptr, len, color);
+
+      ptr = ptr.offset(done as isize);
+
+      for _ in done..len {
+         *ptr = color;
+         ptr = ptr.offset(1);
+      }
+   }
+}
+
+
+#[inline]
+fn fill_linear(mut ptr: *mut u16, len: usize, color: &[u16; 3]) {
+   unsafe {
+      for _ in 0..len {
+         *ptr = color[0];
+         *ptr.offset(1) = color[1];
+         *ptr.offset(2) = color[2];
+         *ptr.offset(3) = 65535;
+
+         ptr = ptr.offset(4);
+      }
+   }
+}
+
+
+// Image fills get sampled at every pixel center and blended with the
+// coverage over the premultiplied layer
+fn supersample_image(
+   ptr: *mut u8,
+   aliased_ptr: *const u8,
+   start: usize,
+   end: usize,
+   y: i64,
+   frame_width: usize,
+   paint: &ImagePaint,
+   srgb: Option<&SrgbTables>,
+   grid: &SampleGrid,
+) {
+   let n = grid.size;
+
+   let x_start = start.wrapping_div(n);
+   let x_end = end.wrapping_add(n).wrapping_sub(1).wrapping_div(n);
+
+   let y = y.wrapping_div(n as i64).wrapping_sub(1) as usize;
+
+   let next_row_offset = frame_width.wrapping_sub(1).wrapping_mul(n) as isize;
+
+   let pixel_size = n as f64;
+   let center_y = (y as f64 + 0.5) * pixel_size;
+
+   for x in x_start..x_end {
+      let a = unsafe {
+         aliased_alpha(aliased_ptr.offset(x.wrapping_mul(n) as isize), next_row_offset, grid)
+      };
+
+      if a == 1 {
+         continue;
+      }
+
+      let coverage = (a - 1) as f32 / 255.;
+
+      let sample = paint.sample((x as f64 + 0.5) * pixel_size, center_y, pixel_size);
+
+      let alpha = sample[3] / 255.;
+      let remaining = 1. - alpha * coverage;
+
+      let index = y.wrapping_mul(frame_width).wrapping_add(x).wrapping_mul(4);
+
+      unsafe {
+         match srgb {
+            Some(srgb) => {
+               let dst = (ptr as *mut u16).offset(index as isize);
+
+               // Samples are premultiplied encoded values
+               let mut source = [0.; 4];
+
+               if alpha > 0. {
+                  for channel in 0..3 {
+                     source[channel] = srgb.linear((sample[channel] / alpha).round().min(255.) as u8) as f32 * alpha;
+                  }
+               }
+
+               source[3] = alpha * 65535.;
+
+               for channel in 0..4 {
+                  let channel_ptr = dst.offset(channel as isize);
+
+                  let value = source[channel] * coverage + *channel_ptr as f32 * remaining;
+
+                  *channel_ptr = value.round().min(65535.) as u16;
+               }
+            },
+            None => {
+               let dst = ptr.offset(index as isize);
+
+               for channel in 0..4 {
+                  let channel_ptr = dst.offset(channel as isize);
+
+                  let value = sample[channel] * coverage + *channel_ptr as f32 * remaining;
+
+                  *channel_ptr = value.round().min(255.) as u8;
+               }
+            },
+         }
+      }
+   }
+}
+
+
+// Pixels of a stripe blended with their coverage, in linear light with the
+// linear color when given
 #[inline]
 fn blend_alpha_stripe(
    mut dst_ptr: *mut u8,
@@ -564,12 +820,38 @@ fn blend_alpha_stripe(
    r: u16,
    g: u16,
    b: u16,
+   linear: Option<[u16; 3]>,
+   grid: &SampleGrid,
+   simd: Simd,
 ) -> *mut u8 {
    unsafe {
-      for _ in 0..len {
-         let a = aliased_alpha(src_ptr, next_row_offset);
-         dst_ptr = blend_alpha_pixel(dst_ptr, a, r, g, b);
-         src_ptr = src_ptr.offset(SUBDIVISIONS as isize);
+      match linear {
+         Some(color) => {
+            let done = simd::blend_linear_stripe(simd, dst_ptr as *mut u16, src_ptr, len, next_row_offset, color, grid);
+
+            let mut linear_ptr = (dst_ptr as *mut u16).offset(done.wrapping_mul(4) as isize);
+            src_ptr = src_ptr.offset(done.wrapping_mul(grid.size) as isize);
+
+            for _ in done..len {
+               let a = aliased_alpha(src_ptr, next_row_offset, grid);
+               linear_ptr = blend_linear_pixel(linear_ptr, a, &color);
+               src_ptr = src_ptr.offset(grid.size as isize);
+            }
+
+            dst_ptr = linear_ptr as *mut u8;
+         },
+         None => {
+            let done = simd::blend_stripe(simd, dst_ptr, src_ptr, len, next_row_offset, [r, g, b], grid);
+
+            dst_ptr = dst_ptr.offset(done.wrapping_mul(4) as isize);
+            src_ptr = src_ptr.offset(done.wrapping_mul(grid.size) as isize);
+
+            for _ in done..len {
+               let a = aliased_alpha(src_ptr, next_row_offset, grid);
+               dst_ptr = blend_alpha_pixel(dst_ptr, a, r, g, b);
+               src_ptr = src_ptr.offset(grid.size as isize);
+            }
+         },
       }
    }
 
@@ -577,13 +859,149 @@ fn blend_alpha_stripe(
 }
 
 
+// Coverage of the thirds of pixel `x`, filtered with the neighbouring thirds
+// and ordered as the red, green and blue channels, from 0 to 255
+#[inline]
+fn subpixel_coverage(
+   aliased_ptr: *const u8,
+   x: usize,
+   frame_width: usize,
+   subpixel: Subpixel,
+   grid: &SampleGrid,
+) -> [u32; 3] {
+   let n = grid.size;
+
+   let aliased_width = frame_width.wrapping_mul(n);
+
+   // Sample columns of the pixel and the two next to it
+   let mut columns = [0_u32; 3 * MAX_SAMPLES];
+
+   for (i, column) in columns[..3 * n].iter_mut().enumerate() {
+      let aliased_x = (x.wrapping_mul(n) + i).wrapping_sub(n);
+
+      if aliased_x >= aliased_width {
+         continue;
+      }
+
+      for row in 0..n {
+         if grid.is_sampled(row, i % n) {
+            *column += unsafe {
+               *aliased_ptr.offset(row.wrapping_mul(aliased_width).wrapping_add(aliased_x) as isize)
+            } as u32;
+         }
+      }
+   }
+
+   // A third spans n / 3 columns, overlaps measured in thirds of a column
+   let mut thirds = [0_u32; 9];
+
+   for (k, third) in thirds.iter_mut().enumerate() {
+      let start = k * n;
+      let end = start + n;
+
+      for (i, column) in columns[..3 * n].iter().enumerate() {
+         let overlap = min(end, 3 * i + 3).saturating_sub(max(start, 3 * i));
+
+         *third += column * overlap as u32;
+      }
+   }
+
+   let rows_per_column = if grid.columns.is_some() { 1 } else { n as u32 };
+
+   let filter_sum: u32 = SUBPIXEL_FILTER.iter().sum();
+   let full = n as u32 * rows_per_column * filter_sum;
+
+   let mut coverage = [0; 3];
+
+   for (channel, third) in subpixel.thirds().iter().enumerate() {
+      let center = 3 + third;
+
+      let mut sum = 0;
+
+      for (tap, weight) in SUBPIXEL_FILTER.iter().enumerate() {
+         sum += thirds[center + tap - 2] * weight;
+      }
+
+      coverage[channel] = min((sum * 255 + full / 2) / full, 255);
+   }
+
+   coverage
+}
+
+
+// Pixels from `x` to `x_end` of row `y` blended with the coverage of each
+// channel
+fn blend_subpixel_stripe(
+   ptr: *mut u8,
+   aliased_ptr: *const u8,
+   x: usize,
+   x_end: usize,
+   y: usize,
+   frame_width: usize,
+   color: RGB,
+   linear: Option<[u16; 3]>,
+   subpixel: Subpixel,
+   grid: &SampleGrid,
+) {
+   let (channels, full) = match linear {
+      Some(color) => ([color[0] as u32, color[1] as u32, color[2] as u32], 65535),
+      None => ([color.r as u32, color.g as u32, color.b as u32], 255),
+   };
+
+   for x in x..x_end {
+      let coverage = subpixel_coverage(aliased_ptr, x, frame_width, subpixel, grid);
+
+      if coverage == [0; 3] {
+         continue;
+      }
+
+      let index = y.wrapping_mul(frame_width).wrapping_add(x).wrapping_mul(4) as isize;
+
+      let mut values = [0; 4];
+
+      unsafe {
+         for channel in 0..4 {
+            values[channel] = match linear {
+               Some(_) => *(ptr as *mut u16).offset(index + channel as isize) as u32,
+               None => *ptr.offset(index + channel as isize) as u32,
+            };
+         }
+      }
+
+      for channel in 0..3 {
+         let a = coverage[channel];
+
+         values[channel] = (channels[channel] * a + values[channel] * (255 - a) + 127) / 255;
+      }
+
+      let a = (coverage[0] + coverage[1] + coverage[2] + 1) / 3;
+
+      values[3] = (full * a + values[3] * (255 - a) + 127) / 255;
+
+      unsafe {
+         for channel in 0..4 {
+            match linear {
+               Some(_) => *(ptr as *mut u16).offset(index + channel as isize) = values[channel] as u16,
+               None => *ptr.offset(index + channel as isize) = values[channel] as u8,
+            }
+         }
+      }
+   }
+}
+
+
 fn rasterize_edges(
-   mut zone_edges: &mut Vec<Edge>,
+   mut zone_edges: &mut [Edge],
    color: RGB,
+   paint: Option<&ImagePaint>,
    aliased_ptr: *mut u8,
    ptr: *mut u8,
    y_end: i64,
    frame_width: usize,
+   srgb: Option<&SrgbTables>,
+   subpixel: Subpixel,
+   grid: &SampleGrid,
+   simd: Simd,
 ) {
    let mut active_start = 0;
    let mut active_end = 0;
@@ -594,7 +1012,9 @@ fn rasterize_edges(
    let mut aliased_end = usize::MIN;
    let mut aliased_end_min = usize::MAX;
 
-   let aliased_width = frame_width * SUBDIVISIONS_U;
+   let samples = grid.size as i64;
+
+   let aliased_width = frame_width * grid.size;
 
    let zone_edges_len = zone_edges.len();
 
@@ -666,7 +1086,7 @@ fn rasterize_edges(
          aliased_end_min = min(right_x, aliased_end_min);
 
          let len = right_x.wrapping_sub(left_x);
-         let start = ((y % SUBDIVISIONS) as usize).wrapping_mul(aliased_width);
+         let start = ((y % samples) as usize).wrapping_mul(aliased_width);
 
          unsafe {
             let vp = aliased_ptr.offset((start.wrapping_add(left_x)) as isize);
@@ -676,20 +1096,37 @@ fn rasterize_edges(
 
       y = y.wrapping_add(1);
 
-      if y % SUBDIVISIONS == 0 {
-         supersample(
-            ptr,
-            aliased_ptr,
-            aliased_start,
-            aliased_start_max,
-            aliased_end,
-            aliased_end_min,
-            y,
-            frame_width,
-            color
-         );
-
-         let len = aliased_width.wrapping_mul(SUBDIVISIONS_U);
+      if y % samples == 0 {
+         match paint {
+            Some(paint) => supersample_image(
+               ptr,
+               aliased_ptr,
+               aliased_start,
+               aliased_end,
+               y,
+               frame_width,
+               paint,
+               srgb,
+               grid
+            ),
+            None => supersample(
+               ptr,
+               aliased_ptr,
+               aliased_start,
+               aliased_start_max,
+               aliased_end,
+               aliased_end_min,
+               y,
+               frame_width,
+               color,
+               srgb,
+               subpixel,
+               grid,
+               simd
+            ),
+         }
+
+         let len = aliased_width.wrapping_mul(grid.size);
          unsafe {
             ptr::write_bytes(aliased_ptr, 0, len);
          }
@@ -707,119 +1144,790 @@ fn rasterize_edges(
 }
 
 
-fn thread_rasterize(thread_rx: Receiver<ThreadInput>, thread_tx: Sender<usize>) {
-   let mut aliased = Vec::new();
-
-   loop {
-      let input = match thread_rx.recv() {
-         Ok(input) => input,
-         Err(_) => break,
-      };
+fn push_poly_items(
+   tiles: &mut [Tile],
+   edges: &mut Vec<Vec<Edge>>,
+   poly: &Poly,
+   left_vertical: &mut Vec<(i64, i64)>,
+   right_vertical: &mut Vec<(i64, i64)>,
+   aliased_width: i64,
+   splitter: &ZoneSplitter
+) {
+   clean_edges_vec(edges);
 
-      match input {
-         ThreadInput::Render(input) => {
-            let RenderInput {
-               mut zone_polys,
-               layer_ptr,
-               frame_ptr,
-               y,
-               y_end,
-               frame_width,
-            } = input;
+   build_edges(
+      edges,
+      &poly.contours,
+      left_vertical,
+      right_vertical,
+      aliased_width,
+      splitter
+   );
 
-            let aliased_len = (frame_width as usize) * (SUBDIVISIONS_X2 as usize);
-            if aliased.len() != aliased_len {
-               aliased.resize(aliased_len, 0);
-            }
+   sort_edges(edges);
 
-            let aliased_ptr = aliased.as_mut_ptr();
+   for zone in 0..splitter.zone_count as usize {
+      unsafe {
+         if edges.get_unchecked(zone).len() > 0 {
+            let tile = tiles.get_unchecked_mut(zone);
 
-            for &mut (ref mut zone_edges, color) in zone_polys.iter_mut() {
-               rasterize_edges(
-                  zone_edges,
-                  color,
-                  aliased_ptr,
-                  layer_ptr,
-                  y_end,
-                  frame_width,
-               );
-            }
+            // Moving the edges over keeps the capacity of both
+            let start = tile.edges.len();
+            tile.edges.append(edges.get_unchecked_mut(zone));
 
-            let y = y.wrapping_div(SUBDIVISIONS);
-            let y_end = y_end.wrapping_div(SUBDIVISIONS);
-            let bytes_width = (frame_width).wrapping_mul(4);
+            let range = start..tile.edges.len();
 
-            write_to_surface(
-               layer_ptr,
-               frame_ptr,
-               frame_width,
-               y,
-               y_end,
-            );
+            let item = match poly.paint {
+               Some(ref paint) => ZoneItem::ImagePoly(range, paint.clone()),
+               None => ZoneItem::Poly(range, poly.color),
+            };
 
-            unsafe {
+            tile.items.push(item);
+         }
+      }
+   }
+}
+
+
+#[inline]
+fn push_all_zones<F>(tiles: &mut [Tile], item: F) where F: Fn() -> ZoneItem {
+   for tile in tiles.iter_mut() {
+      tile.items.push(item());
+   }
+}
+
+
+// Mask images are composited over the mask layer, rows from `y` to `y_end`
+fn draw_mask_image(
+   image: &MaskImage,
+   layer_ptr: *mut u8,
+   y: i64,
+   y_end: i64,
+   frame_width: usize,
+   srgb: Option<&SrgbTables>,
+) {
+   let row_start = max(y, image.y);
+   let row_end = min(y_end, image.y + image.height as i64);
+
+   let col_start = max(0, image.x);
+   let col_end = min(frame_width as i64, image.x + image.width as i64);
+
+   for row in row_start..row_end {
+      for col in col_start..col_end {
+         let src = (((row - image.y) as usize) * image.width + (col - image.x) as usize) * 4;
+         let dst = ((row as usize) * frame_width + col as usize) * 4;
+
+         let a = image.pixels[src + 3] as u32;
+
+         unsafe {
+            match srgb {
+               Some(srgb) => {
+                  let ptr = (layer_ptr as *mut u16).offset(dst as isize);
+
+                  for channel in 0..4 {
+                     let value = if channel == 3 { 65535 } else { srgb.linear(image.pixels[src + channel]) as u32 };
+
+                     let ptr = ptr.offset(channel as isize);
+
+                     *ptr = ((value * a + (*ptr as u32) * (255 - a)) / 255) as u16;
+                  }
+               },
+               None => {
+                  let ptr = layer_ptr.offset(dst as isize);
+
+                  for channel in 0..4 {
+                     let value = if channel == 3 { 255 } else { image.pixels[src + channel] as u32 };
+
+                     let ptr = ptr.offset(channel as isize);
+
+                     *ptr = ((value * a + (*ptr as u32) * (255 - a)) / 255) as u8;
+                  }
+               },
+            }
+         }
+      }
+   }
+}
+
+
+// Composites the premultiplied content layer over the target, scaled by the
+// mask alpha or luminance, and clears both group layers for the next frame
+fn composite_masked(
+   content_ptr: *mut u8,
+   mask_ptr: *mut u8,
+   target_ptr: *mut u8,
+   mode: MaskMode,
+   y: i64,
+   y_end: i64,
+   frame_width: usize,
+   srgb: Option<&SrgbTables>,
+) {
+   let start = (y as usize) * frame_width;
+   let end = (y_end as usize) * frame_width;
+
+   for pixel in start..end {
+      unsafe {
+         match srgb {
+            Some(srgb) => {
+               let mask = (mask_ptr as *const u16).offset((pixel * 4) as isize);
+
+               // Luminance of the encoded values like on the other layers
+               let factor = match mode {
+                  MaskMode::Alpha => *mask.offset(3) as f64 / 65535.,
+                  MaskMode::Luminance => {
+                     let luminance = 54 * (srgb.srgb(*mask) as u32) +
+                        183 * (srgb.srgb(*mask.offset(1)) as u32) +
+                        19 * (srgb.srgb(*mask.offset(2)) as u32);
+
+                     (luminance >> 8) as f64 / 255.
+                  },
+               };
+
+               let content = read_pixel(content_ptr, pixel, true);
+               let target = read_pixel(target_ptr, pixel, true);
+
+               let remaining = 1. - content[3] * factor;
+
+               let mut result = [0.; 4];
+
+               for channel in 0..4 {
+                  result[channel] = content[channel] * factor + target[channel] * remaining;
+               }
+
+               write_pixel(target_ptr, pixel, &result, true);
+            },
+            None => {
+               let i = pixel * 4;
+
+               let mask = mask_ptr.offset(i as isize);
+
+               let factor = match mode {
+                  MaskMode::Alpha => *mask.offset(3) as u32,
+                  MaskMode::Luminance => {
+                     (54 * (*mask as u32) + 183 * (*mask.offset(1) as u32) + 19 * (*mask.offset(2) as u32)) >> 8
+                  },
+               };
+
+               let content = content_ptr.offset(i as isize);
+               let target = target_ptr.offset(i as isize);
+
+               let alpha = (*content.offset(3) as u32) * factor / 255;
+
+               for channel in 0..4 {
+                  let src = (*content.offset(channel) as u32) * factor / 255;
+                  let dst = *target.offset(channel) as u32;
+
+                  *target.offset(channel) = (src + dst * (255 - alpha) / 255) as u8;
+               }
+            },
+         }
+      }
+   }
+
+   let bytes = pixel_bytes(srgb);
+
+   unsafe {
+      ptr::write_bytes(content_ptr.offset((start * bytes) as isize), 0, (end - start) * bytes);
+      ptr::write_bytes(mask_ptr.offset((start * bytes) as isize), 0, (end - start) * bytes);
+   }
+}
+
+
+// Composites the premultiplied group layer over the target with `opacity`
+// and `blend`, and clears the group layer for the next frame
+fn composite_group(
+   content_ptr: *mut u8,
+   target_ptr: *mut u8,
+   opacity: u8,
+   blend: BlendMode,
+   y: i64,
+   y_end: i64,
+   frame_width: usize,
+   srgb: Option<&SrgbTables>,
+) {
+   let start = (y as usize) * frame_width;
+   let end = (y_end as usize) * frame_width;
+
+   let opacity = opacity as f64 / 255.;
+
+   let wide = srgb.is_some();
+
+   for pixel in start..end {
+      unsafe {
+         let content = read_pixel(content_ptr, pixel, wide);
+
+         let source_alpha = content[3] * opacity;
+
+         if source_alpha > 0. {
+            let target = read_pixel(target_ptr, pixel, wide);
+
+            let backdrop_alpha = target[3];
+
+            let mut result = [0.; 4];
+
+            for channel in 0..3 {
+               let source = content[channel] * opacity;
+               let backdrop = target[channel];
+
+               result[channel] = if blend == BlendMode::Normal || backdrop_alpha == 0. {
+                  source + backdrop * (1. - source_alpha)
+               } else {
+                  let blended = blend.blend(backdrop / backdrop_alpha, source / source_alpha);
+
+                  source * (1. - backdrop_alpha) + backdrop * (1. - source_alpha) +
+                     source_alpha * backdrop_alpha * blended
+               };
+            }
+
+            result[3] = source_alpha + backdrop_alpha * (1. - source_alpha);
+
+            write_pixel(target_ptr, pixel, &result, wide);
+         }
+      }
+   }
+
+   let bytes = pixel_bytes(srgb);
+
+   unsafe {
+      ptr::write_bytes(content_ptr.offset((start * bytes) as isize), 0, (end - start) * bytes);
+   }
+}
+
+
+// Premultiplied pixel `index` in the 0 to 1 range, from a 16-bit linear layer
+// when `wide`
+#[inline]
+unsafe fn read_pixel(layer_ptr: *const u8, index: usize, wide: bool) -> [f64; 4] {
+   let mut pixel = [0.; 4];
+
+   for channel in 0..4 {
+      let offset = (index * 4 + channel) as isize;
+
+      pixel[channel] = if wide {
+         *(layer_ptr as *const u16).offset(offset) as f64 / 65535.
+      } else {
+         *layer_ptr.offset(offset) as f64 / 255.
+      };
+   }
+
+   pixel
+}
+
+
+#[inline]
+unsafe fn write_pixel(layer_ptr: *mut u8, index: usize, pixel: &[f64; 4], wide: bool) {
+   for channel in 0..4 {
+      let offset = (index * 4 + channel) as isize;
+
+      let value = pixel[channel].max(0.).min(1.);
+
+      if wide {
+         *(layer_ptr as *mut u16).offset(offset) = (value * 65535.).round() as u16;
+      } else {
+         *layer_ptr.offset(offset) = (value * 255.).round() as u8;
+      }
+   }
+}
+
+
+#[inline]
+fn clear_rows(layer_ptr: *mut u8, y: i64, y_end: i64, frame_width: usize, bytes: usize) {
+   let start = (y as usize) * frame_width * bytes;
+   let end = (y_end as usize) * frame_width * bytes;
+
+   unsafe {
+      ptr::write_bytes(layer_ptr.offset(start as isize), 0, end - start);
+   }
+}
+
+
+fn thread_rasterize(thread_rx: Arc<Mutex<Receiver<ThreadInput>>>, thread_tx: SyncSender<ThreadOutput>) {
+   let mut aliased = Vec::new();
+   let mut scratch = FilterScratch::new();
+
+   loop {
+      let input = match thread_rx.lock() {
+         Ok(rx) => rx.recv(),
+         Err(_) => break,
+      };
+
+      let input = match input {
+         Ok(input) => input,
+         Err(_) => break,
+      };
+
+      match input {
+         ThreadInput::Render(input) => {
+            let RenderInput {
+               zone,
+               mut tile,
+               layer_ptr,
+               frame_ptr,
+               y,
+               y_end,
+               frame_width,
+               filtered,
+               srgb,
+               transform,
+               subpixel,
+               grid,
+               simd,
+            } = input;
+
+            let srgb = srgb.as_ref().map(|srgb| &**srgb);
+
+            let samples = grid.size as i64;
+
+            let aliased_len = (frame_width as usize) * grid.size * grid.size;
+            if aliased.len() != aliased_len {
+               aliased.resize(aliased_len, 0);
+            }
+
+            let aliased_ptr = aliased.as_mut_ptr();
+
+            let px_y = y.wrapping_div(samples);
+            let px_y_end = y_end.wrapping_div(samples);
+
+            let Tile {
+               ref mut items,
+               ref mut edges,
+               ref group_layers,
+               ref mut targets,
+               ref mut next,
+               ref mut paused,
+            } = tile;
+
+            if *paused {
+               // Back from the filter the tile stopped at, its result goes
+               // into the enclosing target
+               let depth = targets.len() - 1;
+
+               composite_group(
+                  filtered,
+                  *targets.last().unwrap(),
+                  255,
+                  BlendMode::Normal,
+                  px_y,
+                  px_y_end,
+                  frame_width,
+                  srgb,
+               );
+
+               for &index in [0, 2, 3].iter() {
+                  let layer = group_layers[depth * LAYERS_PER_DEPTH + index];
+
+                  if layer != filtered {
+                     clear_rows(layer, px_y, px_y_end, frame_width, pixel_bytes(srgb));
+                  }
+               }
+
+               *paused = false;
+               *next += 1;
+            } else {
+               // Layer the items currently render to, one more for every open group
+               targets.clear();
+               targets.push(layer_ptr);
+
+               *next = 0;
+            }
+
+            for item in items[*next..].iter() {
+               let target = *targets.last().unwrap();
+
+               match *item {
+                  ZoneItem::Poly(ref range, color) => {
+                     rasterize_edges(
+                        &mut edges[range.clone()],
+                        color,
+                        None,
+                        aliased_ptr,
+                        target,
+                        y_end,
+                        frame_width,
+                        srgb,
+                        subpixel,
+                        &grid,
+                        simd,
+                     );
+                  },
+                  ZoneItem::ImagePoly(ref range, ref paint) => {
+                     rasterize_edges(
+                        &mut edges[range.clone()],
+                        RGB::default(),
+                        Some(paint),
+                        aliased_ptr,
+                        target,
+                        y_end,
+                        frame_width,
+                        srgb,
+                        subpixel,
+                        &grid,
+                        simd,
+                     );
+                  },
+                  ZoneItem::BeginGroup => {
+                     let depth = targets.len() - 1;
+
+                     targets.push(group_layers[depth * LAYERS_PER_DEPTH]);
+                  },
+                  ZoneItem::BeginMask => {
+                     let depth = targets.len() - 2;
+
+                     *targets.last_mut().unwrap() = group_layers[depth * LAYERS_PER_DEPTH + 1];
+                  },
+                  ZoneItem::MaskImage(ref image) => {
+                     draw_mask_image(image, target, px_y, px_y_end, frame_width, srgb);
+                  },
+                  ZoneItem::EndGroup(opacity, blend) => {
+                     targets.pop();
+
+                     let depth = targets.len() - 1;
+
+                     composite_group(
+                        group_layers[depth * LAYERS_PER_DEPTH],
+                        *targets.last().unwrap(),
+                        opacity,
+                        blend,
+                        px_y,
+                        px_y_end,
+                        frame_width,
+                        srgb,
+                     );
+                  },
+                  ZoneItem::EndFilter(_) => {
+                     // The filter reads the rows of the other tiles, so it
+                     // waits for all of them to get here
+                     targets.pop();
+
+                     *paused = true;
+
+                     break;
+                  },
+                  ZoneItem::EndMask(mode) => {
+                     targets.pop();
+
+                     let depth = targets.len() - 1;
+
+                     composite_masked(
+                        group_layers[depth * LAYERS_PER_DEPTH],
+                        group_layers[depth * LAYERS_PER_DEPTH + 1],
+                        *targets.last().unwrap(),
+                        mode,
+                        px_y,
+                        px_y_end,
+                        frame_width,
+                        srgb,
+                     );
+                  },
+               }
+
+               *next += 1;
+            }
+
+            if *paused {
+               if let Err(_) = thread_tx.send(ThreadOutput::Tile(zone, tile)) {
+                  break;
+               }
+
+               continue;
+            }
+
+            let y = y.wrapping_div(samples);
+            let y_end = y_end.wrapping_div(samples);
+
+            write_to_surface(
+               layer_ptr,
+               frame_ptr,
+               frame_width,
+               y,
+               y_end,
+               srgb,
+            );
+
+            if let Some(transform) = transform {
+               let frame_bytes_width = frame_width.wrapping_mul(4);
+
+               let rows = unsafe {
+                  slice::from_raw_parts_mut(
+                     frame_ptr.offset((y as isize).wrapping_mul(frame_bytes_width as isize)),
+                     ((y_end - y) as usize).wrapping_mul(frame_bytes_width),
+                  )
+               };
+
+               transform.apply_pixels(rows);
+            }
+
+            let bytes_width = frame_width.wrapping_mul(pixel_bytes(srgb));
+
+            unsafe {
                ptr::write_bytes(
                   layer_ptr.offset((y as isize).wrapping_mul(bytes_width as isize)),
                   0,
                   ((y_end - y) as usize).wrapping_mul(bytes_width),
                );
             }
+
+            // Drops the paints, the buffers go back for the next frame
+            items.clear();
+
+            if let Err(_) = thread_tx.send(ThreadOutput::Tile(zone, tile)) {
+               break;
+            }
          },
+         ThreadInput::Filter(input) => {
+            let FilterInput {
+               ops,
+               mut layers,
+               y,
+               y_end,
+               frame_width,
+               frame_height,
+               barrier,
+               srgb,
+            } = input;
+
+            apply_filter(
+               &ops,
+               &mut layers,
+               &FilterRows {
+                  width: frame_width,
+                  height: frame_height,
+                  y: y,
+                  y_end: y_end,
+                  barrier: &barrier,
+                  srgb: srgb.as_ref().map(|srgb| &**srgb),
+               },
+               &mut scratch,
+            );
+
+            if let Err(_) = thread_tx.send(ThreadOutput::Filtered(layers[0])) {
+               break;
+            }
+         },
+         ThreadInput::Stop => break,
       }
+   }
+}
+
+
+// Groups and filters render to their own layer and masks to the next one, the
+// layers get composited into the enclosing target on EndGroup, EndMask or
+// EndFilter
+enum ZoneItem {
+   Poly(Range<usize>, RGB),
+   ImagePoly(Range<usize>, ImagePaint),
+   BeginGroup,
+   BeginMask,
+   MaskImage(Arc<MaskImage>),
+   EndMask(MaskMode),
+   EndGroup(u8, BlendMode),
+   EndFilter(Arc<Vec<FilterOp>>),
+}
 
-      if let Err(_) = thread_tx.send(0) {
-         break;
+
+// Buffers of a zone, kept across frames and lent to the thread rendering it.
+// Items refer to their edges by range in the edge arena. A tile reaching the
+// end of a filter gets `paused` there with its `targets` and the `next` item,
+// and resumes once every tile got there and the filter ran
+struct Tile {
+   items: Vec<ZoneItem>,
+   edges: Vec<Edge>,
+   group_layers: Vec<*mut u8>,
+   targets: Vec<*mut u8>,
+   next: usize,
+   paused: bool,
+}
+
+impl Tile {
+   #[inline]
+   fn new() -> Self {
+      Tile {
+         items: Vec::new(),
+         edges: Vec::new(),
+         group_layers: Vec::new(),
+         targets: Vec::new(),
+         next: 0,
+         paused: false,
       }
    }
+
+   // Filter the tile is paused at
+   #[inline]
+   fn paused_filter(&self) -> Option<Arc<Vec<FilterOp>>> {
+      if !self.paused {
+         return None;
+      }
+
+      match self.items.get(self.next) {
+         Some(&ZoneItem::EndFilter(ref ops)) => Some(ops.clone()),
+         _ => None,
+      }
+   }
+}
+
+unsafe impl Send for Tile {
 }
 
 
 struct RenderInput {
-   zone_polys: Vec<(Vec<Edge>, RGB)>,
+   zone: usize,
+   tile: Tile,
    layer_ptr: *mut u8,
    frame_ptr: *mut u8,
    y: i64,
    y_end: i64,
    frame_width: usize,
+   // Result layer of the filter a paused tile resumes from
+   filtered: *mut u8,
+   srgb: Option<Arc<SrgbTables>>,
+   transform: Option<Arc<ColorTransform>>,
+   subpixel: Subpixel,
+   grid: Arc<SampleGrid>,
+   simd: Simd,
 }
 
 unsafe impl Send for RenderInput {
 }
 
 
+// Pixel rows from `y` to `y_end` of a filter run over the full frame layers,
+// every band of the frame on its own thread
+struct FilterInput {
+   ops: Arc<Vec<FilterOp>>,
+   layers: [*mut u8; 3],
+   y: usize,
+   y_end: usize,
+   frame_width: usize,
+   frame_height: usize,
+   barrier: Arc<Barrier>,
+   srgb: Option<Arc<SrgbTables>>,
+}
+
+unsafe impl Send for FilterInput {
+}
+
+
 enum ThreadInput {
    Render(RenderInput),
+   Filter(FilterInput),
+   Stop,
 }
 
 
-struct ThreadChannels {
-   render_tx_vec: Vec<Sender<ThreadInput>>,
-   result_rx: Receiver<usize>,
+// Tiles come back with their zone, filter bands with the layer holding the
+// result
+enum ThreadOutput {
+   Tile(usize, Tile),
+   Filtered(*mut u8),
+}
+
+unsafe impl Send for ThreadOutput {
+}
+
+
+// Worker threads sharing the queue of tiles, stopped and joined on drop. The
+// bounded channels hold all the tiles of a frame without allocating
+struct ThreadPool {
+   render_tx: SyncSender<ThreadInput>,
+   result_rx: Receiver<ThreadOutput>,
+   handles: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+   fn new(workers: usize) -> Self {
+      let capacity = workers * TILES_PER_WORKER as usize;
+
+      let (render_tx, render_rx) = sync_channel(capacity);
+      let (result_tx, result_rx) = sync_channel(capacity);
+
+      let render_rx = Arc::new(Mutex::new(render_rx));
+
+      let mut handles = Vec::new();
+
+      for _ in 0..workers {
+         let thread_rx = render_rx.clone();
+         let thread_tx = result_tx.clone();
+
+         handles.push(thread::spawn(move|| {
+            thread_rasterize(thread_rx, thread_tx);
+         }));
+      }
+
+      ThreadPool {
+         render_tx: render_tx,
+         result_rx: result_rx,
+         handles: handles,
+      }
+   }
+
+   #[inline]
+   fn workers(&self) -> usize {
+      self.handles.len()
+   }
+}
+
+impl Drop for ThreadPool {
+   fn drop(&mut self) {
+      // Every worker takes a single Stop off the queue
+      for _ in 0..self.handles.len() {
+         let _ = self.render_tx.send(ThreadInput::Stop);
+      }
+
+      for handle in self.handles.drain(..) {
+         let _ = handle.join();
+      }
+   }
+}
+
+
+// Worker count matching the cores available to the process
+pub fn default_workers() -> usize {
+   thread::available_parallelism().map(|count| count.get()).unwrap_or(1)
 }
 
 
 pub struct DevelRenderer {
    scene: Scene,
    layer: Vec<u8>,
+   group_layers: Vec<Vec<u8>>,
    left_vertical: Vec<(i64, i64)>,
    right_vertical: Vec<(i64, i64)>,
    edges: Vec<Vec<Edge>>,
-   channels: ThreadChannels,
+   tiles: Vec<Tile>,
+   barrier: Arc<Barrier>,
+   barrier_count: usize,
+   pool: ThreadPool,
+   blending: Blending,
+   srgb: Arc<SrgbTables>,
+   output_profile: Option<Profile>,
+   transform: Option<(Arc<Profile>, Arc<ColorTransform>)>,
+   subpixel: Subpixel,
+   grid: Arc<SampleGrid>,
+   simd: Simd,
 }
 
 
 impl DevelRenderer {
    #[inline]
    pub fn new(scene: Scene) -> Self {
-      let channels = Self::start_render_threads();
-
       DevelRenderer {
          scene: scene,
          layer: Vec::new(),
+         group_layers: Vec::new(),
          left_vertical: Vec::new(),
          right_vertical: Vec::new(),
-         edges: create_edges_vec(ZONE_COUNT),
-         channels: channels,
+         edges: Vec::new(),
+         tiles: Vec::new(),
+         barrier: Arc::new(Barrier::new(1)),
+         barrier_count: 1,
+         pool: ThreadPool::new(default_workers()),
+         blending: Blending::default(),
+         srgb: Arc::new(SrgbTables::new()),
+         output_profile: None,
+         transform: None,
+         subpixel: Subpixel::default(),
+         grid: Arc::new(SampleGrid::default()),
+         simd: Simd::detect(),
       }
    }
 
@@ -829,26 +1937,87 @@ impl DevelRenderer {
    }
 
    #[inline]
-   fn start_render_threads() -> ThreadChannels {
-      let (result_tx, result_rx) = channel();
+   pub fn set_blending(&mut self, blending: Blending) {
+      self.blending = blending;
+   }
 
-      let mut render_tx_vec = Vec::new();
+   // Number of render threads, restarting them. Defaults to the available
+   // cores
+   pub fn set_workers(&mut self, workers: usize) {
+      let workers = max(workers, 1);
 
-      for _ in 0..ZONE_COUNT as usize {
-         let (render_tx, render_rx) = channel();
+      if workers != self.pool.workers() {
+         self.pool = ThreadPool::new(workers);
+      }
+   }
 
-         render_tx_vec.push(render_tx);
+   #[inline]
+   pub fn workers(&self) -> usize {
+      self.pool.workers()
+   }
 
-         let thread_tx = result_tx.clone();
+   // Anti-aliasing level, which sets the scene units to `subdivisions` per
+   // pixel
+   #[inline]
+   pub fn set_sampling(&mut self, sampling: Sampling) {
+      self.grid = Arc::new(SampleGrid::new(sampling));
+   }
 
-         thread::spawn(move|| {
-            thread_rasterize(render_rx, thread_tx);
-         });
+   #[inline]
+   pub fn subdivisions(&self) -> i64 {
+      self.grid.size as i64
+   }
+
+   // Instruction set of the rasterizer loops, the detected one by default.
+   // Levels the CPU lacks fall back to the scalar code
+   #[inline]
+   pub fn set_simd(&mut self, simd: Simd) {
+      self.simd = if simd.is_supported() { simd } else { Simd::Scalar };
+   }
+
+   // LCD subpixel anti-aliasing of the solid color fills, for the frames
+   // shown on a screen with that subpixel order
+   #[inline]
+   pub fn set_subpixel(&mut self, subpixel: Subpixel) {
+      self.subpixel = subpixel;
+   }
+
+   // Scene colors get converted from the scene profile to `profile` when
+   // written to the frame, None leaves them untouched
+   #[inline]
+   pub fn set_output_profile(&mut self, profile: Option<Profile>) {
+      self.output_profile = profile;
+      self.transform = None;
+   }
+
+   fn color_transform(&mut self) -> Option<Arc<ColorTransform>> {
+      let output = match self.output_profile {
+         Some(ref output) => output,
+         None => return None,
+      };
+
+      if *output == *self.scene.profile {
+         return None;
       }
 
-      ThreadChannels {
-         render_tx_vec: render_tx_vec,
-         result_rx: result_rx,
+      if let Some((ref source, ref transform)) = self.transform {
+         if *source == self.scene.profile {
+            return Some(transform.clone());
+         }
+      }
+
+      let transform = Arc::new(ColorTransform::new(&self.scene.profile, output));
+
+      self.transform = Some((self.scene.profile.clone(), transform.clone()));
+
+      Some(transform)
+   }
+
+   #[inline]
+   fn linear_tables(&self) -> Option<Arc<SrgbTables>> {
+      match self.blending {
+         Blending::Linear => Some(self.srgb.clone()),
+         Blending::Srgb => None,
       }
    }
 
@@ -856,7 +2025,9 @@ impl DevelRenderer {
    fn check_resize(&mut self, width: u32, height: u32) -> bool {
       let mut resized = false;
 
-      let layer_len = 4 * (width as usize * height as usize);
+      let bytes = pixel_bytes(self.linear_tables().as_ref().map(|srgb| &**srgb));
+
+      let layer_len = bytes * (width as usize * height as usize);
       if self.layer.len() != layer_len {
          self.layer.resize(layer_len, 0);
          resized = true;
@@ -865,97 +2036,624 @@ impl DevelRenderer {
       resized
    }
 
-   #[inline]
-   #[allow(unused_must_use)]
-   fn wait_threads(&self, splitter: &ZoneSplitter) {
-      for _ in 0..splitter.zone_count {
-         self.channels.result_rx.recv();
+   fn check_tiles(&mut self, zone_count: usize) {
+      while self.edges.len() < zone_count {
+         self.edges.push(Vec::new());
+      }
+
+      while self.tiles.len() < zone_count {
+         self.tiles.push(Tile::new());
+      }
+
+      for tile in self.tiles.iter_mut() {
+         tile.items.clear();
+         tile.edges.clear();
+         tile.paused = false;
       }
    }
-}
 
-impl Renderer for DevelRenderer {
-   #[inline]
-   fn init(&mut self, width: u32, height: u32) {
-      let _ = self.check_resize(width, height);
+   fn check_group_layers(&mut self, depth: usize) {
+      let layer_len = self.layer.len();
+
+      while self.group_layers.len() < depth * LAYERS_PER_DEPTH {
+         self.group_layers.push(vec![0; layer_len]);
+      }
+
+      for layer in self.group_layers.iter_mut() {
+         if layer.len() != layer_len {
+            layer.resize(layer_len, 0);
+         }
+      }
    }
 
-   fn render(&mut self, frame: &mut Frame) {
-      let _ = self.check_resize(frame.width, frame.height);
+   // Renders the scene offscreen, for pattern tiles and the like
+   pub fn render_image(&mut self, width: u32, height: u32) -> Image {
+      let mut pixels = vec![0; 4 * width as usize * height as usize];
 
-      let frame_width = frame.width as usize;
-      let frame_height = frame.height as usize;
+      // Tiles get resampled, so no subpixel anti-aliasing
+      self.render_pixels(pixels.as_mut_ptr(), width, height, None, Subpixel::None);
 
-      let aliased_width = frame.width as i64 * SUBDIVISIONS;
-      let aliased_height = frame.height as i64 * SUBDIVISIONS;
+      Image::from_premultiplied(width as usize, height as usize, pixels)
+   }
 
-      let splitter = ZoneSplitter::new(aliased_height, frame_height as i64, ZONE_COUNT);
+   // Writes premultiplied RGBA rows to `frame_ptr`
+   fn render_pixels(
+      &mut self,
+      frame_ptr: *mut u8,
+      width: u32,
+      height: u32,
+      transform: Option<Arc<ColorTransform>>,
+      subpixel: Subpixel
+   ) {
+      let _ = self.check_resize(width, height);
 
-      let mut all_zones_polys = Vec::new();
+      let frame_width = width as usize;
+      let frame_height = height as usize;
 
-      for _ in 0..splitter.zone_count {
-         all_zones_polys.push(Vec::new());
-      }
+      let samples = self.grid.size as i64;
 
-      for poly in self.scene.polys.iter() {
-         clean_edges_vec(&mut self.edges);
+      let aliased_width = width as i64 * samples;
+      let aliased_height = height as i64 * samples;
 
-         build_edges(
-            &mut self.edges,
-            &poly.contours,
-            &mut self.left_vertical,
-            &mut self.right_vertical,
-            aliased_width,
-            &splitter
-         );
+      let tile_count = self.pool.workers() as i64 * TILES_PER_WORKER;
 
-         sort_edges(&mut self.edges);
+      let splitter = ZoneSplitter::new(aliased_height, frame_height as i64, tile_count, samples);
 
-         for zone in 0..splitter.zone_count as usize {
-            unsafe {
-               if self.edges.get_unchecked(zone).len() > 0 {
-                  let zone_edges = replace(self.edges.get_unchecked_mut(zone), Vec::new());
+      let zone_count = splitter.zone_count as usize;
 
-                  all_zones_polys.get_unchecked_mut(zone).push((zone_edges, poly.color));
-               }
+      self.check_tiles(zone_count);
+
+      let tiles = &mut self.tiles[..zone_count];
+
+      let mut depth = 0;
+      let mut max_depth = 0;
+
+      for index in 0..self.scene.polys.len() + 1 {
+         // Inner layers end first
+         for layer in self.scene.layers.iter().rev() {
+            if layer.end() != index || layer.start() == layer.end() {
+               continue;
             }
+
+            match *layer {
+               Layer::Group(ref group) => {
+                  let opacity = (group.opacity.max(0.).min(1.) * 255.).round() as u8;
+
+                  push_all_zones(tiles, || ZoneItem::EndGroup(opacity, group.blend));
+               },
+               Layer::Mask(ref mask) => {
+                  push_all_zones(tiles, || ZoneItem::BeginMask);
+
+                  match mask.source {
+                     MaskSource::Polys(ref polys) => {
+                        for poly in polys.iter() {
+                           push_poly_items(
+                              tiles,
+                              &mut self.edges,
+                              poly,
+                              &mut self.left_vertical,
+                              &mut self.right_vertical,
+                              aliased_width,
+                              &splitter
+                           );
+                        }
+                     },
+                     MaskSource::Image(ref image) => {
+                        push_all_zones(tiles, || ZoneItem::MaskImage(image.clone()));
+                     },
+                  }
+
+                  push_all_zones(tiles, || ZoneItem::EndMask(mask.mode));
+               },
+               Layer::Filter(ref filter) => {
+                  push_all_zones(tiles, || ZoneItem::EndFilter(filter.ops.clone()));
+               },
+            }
+
+            depth -= 1;
+         }
+
+         for layer in self.scene.layers.iter() {
+            if layer.start() != index || layer.start() == layer.end() {
+               continue;
+            }
+
+            push_all_zones(tiles, || ZoneItem::BeginGroup);
+
+            depth += 1;
+            max_depth = max(depth, max_depth);
+         }
+
+         if let Some(poly) = self.scene.polys.get(index) {
+            push_poly_items(
+               tiles,
+               &mut self.edges,
+               poly,
+               &mut self.left_vertical,
+               &mut self.right_vertical,
+               aliased_width,
+               &splitter
+            );
          }
       }
 
-      let layer_ptr = self.layer.as_mut_ptr();
-      let mut y = 0;
+      self.check_group_layers(max_depth);
 
-      let frame_ptr = frame.ptr_mut();
+      for tile in self.tiles[..zone_count].iter_mut() {
+         tile.group_layers.clear();
+         tile.group_layers.extend(self.group_layers.iter_mut().map(|layer| layer.as_mut_ptr()));
+      }
 
-      for zone in 0..splitter.zone_count as usize {
-         let render_tx = unsafe {
-            self.channels.render_tx_vec.get_unchecked(zone)
+      let srgb = self.linear_tables();
+
+      let mut filtered = ptr::null_mut();
+
+      // The tiles pause at every filter, run in bands of rows once they all
+      // got there
+      loop {
+         if !self.render_tiles(&splitter, frame_ptr, frame_width, filtered, &srgb, &transform, subpixel) {
+            return;
+         }
+
+         let ops = match self.tiles[..zone_count].first().and_then(|tile| tile.paused_filter()) {
+            Some(ops) => ops,
+            None => break,
          };
 
-         let zone_polys = unsafe {
-            replace(all_zones_polys.get_unchecked_mut(zone), Vec::new())
+         let depth = self.tiles[0].targets.len() - 1;
+
+         filtered = match self.filter_bands(ops, depth, frame_width, frame_height, &srgb) {
+            Some(layer) => layer,
+            None => return,
+         };
+      }
+   }
+
+   // Sends every tile to the workers, false when they are gone
+   fn render_tiles(
+      &mut self,
+      splitter: &ZoneSplitter,
+      frame_ptr: *mut u8,
+      frame_width: usize,
+      filtered: *mut u8,
+      srgb: &Option<Arc<SrgbTables>>,
+      transform: &Option<Arc<ColorTransform>>,
+      subpixel: Subpixel
+   ) -> bool {
+      let zone_count = splitter.zone_count as usize;
+
+      let layer_ptr = self.layer.as_mut_ptr();
+      let mut y = 0;
+
+      for zone in 0..zone_count {
+         let tile = unsafe {
+            replace(self.tiles.get_unchecked_mut(zone), Tile::new())
          };
 
          let y_end = splitter.zone_y_end(zone as i64);
 
-         if let Err(_) = render_tx.send(
+         if let Err(_) = self.pool.render_tx.send(
             ThreadInput::Render(
                RenderInput {
-                  zone_polys: zone_polys,
+                  zone: zone,
+                  tile: tile,
                   layer_ptr: layer_ptr,
                   frame_ptr: frame_ptr,
                   y: y,
                   y_end: y_end,
                   frame_width: frame_width,
+                  filtered: filtered,
+                  srgb: srgb.clone(),
+                  transform: transform.clone(),
+                  subpixel: subpixel,
+                  grid: self.grid.clone(),
+                  simd: self.simd,
                }
             )
          ) {
-            return;
+            return false;
          }
 
          y = y_end;
       }
 
-      self.wait_threads(&splitter);
+      self.wait_threads(zone_count);
+
+      true
+   }
+
+   #[inline]
+   fn wait_threads(&mut self, zone_count: usize) {
+      for _ in 0..zone_count {
+         if let Ok(ThreadOutput::Tile(zone, tile)) = self.pool.result_rx.recv() {
+            self.tiles[zone] = tile;
+         }
+      }
+   }
+
+   // Runs the filter of the group layers at `depth` over bands of rows, one
+   // per worker so they all wait on the barrier at once. Returns the layer
+   // with the result
+   fn filter_bands(
+      &mut self,
+      ops: Arc<Vec<FilterOp>>,
+      depth: usize,
+      frame_width: usize,
+      frame_height: usize,
+      srgb: &Option<Arc<SrgbTables>>
+   ) -> Option<*mut u8> {
+      let layers = [
+         self.group_layers[depth * LAYERS_PER_DEPTH].as_mut_ptr(),
+         self.group_layers[depth * LAYERS_PER_DEPTH + 2].as_mut_ptr(),
+         self.group_layers[depth * LAYERS_PER_DEPTH + 3].as_mut_ptr(),
+      ];
+
+      let bands = min(self.pool.workers(), max(frame_height, 1));
+
+      if self.barrier_count != bands {
+         self.barrier = Arc::new(Barrier::new(bands));
+         self.barrier_count = bands;
+      }
+
+      for band in 0..bands {
+         if let Err(_) = self.pool.render_tx.send(
+            ThreadInput::Filter(
+               FilterInput {
+                  ops: ops.clone(),
+                  layers: layers,
+                  y: frame_height * band / bands,
+                  y_end: frame_height * (band + 1) / bands,
+                  frame_width: frame_width,
+                  frame_height: frame_height,
+                  barrier: self.barrier.clone(),
+                  srgb: srgb.clone(),
+               }
+            )
+         ) {
+            return None;
+         }
+      }
+
+      let mut result = layers[0];
+
+      for _ in 0..bands {
+         if let Ok(ThreadOutput::Filtered(layer)) = self.pool.result_rx.recv() {
+            result = layer;
+         }
+      }
+
+      Some(result)
+   }
+}
+
+impl Renderer for DevelRenderer {
+   #[inline]
+   fn init(&mut self, width: u32, height: u32) {
+      let _ = self.check_resize(width, height);
+   }
+
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
+      let frame_ptr = frame.ptr_mut();
+
+      let transform = self.color_transform();
+
+      let subpixel = self.subpixel;
+
+      self.render_pixels(frame_ptr, frame.width, frame.height, transform, subpixel);
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn test_subpixel_coverage() {
+      let grid = SampleGrid::new(Sampling::Grid(4));
+
+      let frame_width = 3;
+      let aliased_width = frame_width * grid.size;
+
+      // Middle pixel fully covered
+      let mut aliased = vec![0; aliased_width * grid.size];
+
+      for row in 0..grid.size {
+         for x in grid.size..2 * grid.size {
+            aliased[row * aliased_width + x] = 1;
+         }
+      }
+
+      let coverage = |x: usize, subpixel: Subpixel| {
+         subpixel_coverage(aliased.as_ptr(), x, frame_width, subpixel, &grid)
+      };
+
+      assert_eq!(coverage(1, Subpixel::Rgb), [170, 198, 170]);
+
+      // The filter spills onto the channels next to the covered pixel
+      assert_eq!(coverage(0, Subpixel::Rgb), [0, 28, 85]);
+      assert_eq!(coverage(2, Subpixel::Rgb), [85, 28, 0]);
+
+      assert_eq!(coverage(0, Subpixel::Bgr), [85, 28, 0]);
+   }
+
+   // Pseudo random bytes below `range`
+   fn noise(seed: &mut u32, len: usize, range: u32) -> Vec<u8> {
+      (0..len).map(|_| {
+         *seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+
+         ((*seed >> 16) % range) as u8
+      }).collect()
+   }
+
+   fn vector_levels() -> Vec<Simd> {
+      [Simd::Sse2, Simd::Avx2, Simd::Neon].iter().cloned().filter(|simd| simd.is_supported()).collect()
+   }
+
+   #[test]
+   fn test_simd_stripes() {
+      let samplings = [
+         Sampling::Grid(1), Sampling::Grid(2), Sampling::Grid(4), Sampling::Grid(8), Sampling::Grid(16),
+         Sampling::RotatedGrid(2), Sampling::RotatedGrid(4), Sampling::RotatedGrid(8), Sampling::RotatedGrid(16),
+      ];
+
+      let frame_width = 21;
+
+      let mut seed = 7;
+
+      for sampling in samplings.iter() {
+         let grid = SampleGrid::new(*sampling);
+         let n = grid.size;
+
+         let next_row_offset = ((frame_width - 1) * n) as isize;
+
+         for len in 0..frame_width {
+            // Solid runs as well as edges
+            let range = if len % 3 == 0 { 1 } else { 2 };
+            let aliased = noise(&mut seed, frame_width * n * n, range).iter().map(|&value| 1 - value).collect::<Vec<_>>();
+            let pixels = noise(&mut seed, frame_width * 4, 256);
+            let color = noise(&mut seed, 3, 256);
+
+            let (r, g, b) = (color[0] as u16 + 1, color[1] as u16 + 1, color[2] as u16 + 1);
+
+            let stripe = |simd: Simd| {
+               let mut result = pixels.clone();
+
+               let end = blend_alpha_stripe(
+                  result.as_mut_ptr(), aliased.as_ptr(), len, next_row_offset, r, g, b, None, &grid, simd
+               );
+
+               assert_eq!(end, unsafe { result.as_mut_ptr().offset((len * 4) as isize) });
+
+               result
+            };
+
+            // Linear layers of 16-bit channels
+            let wide = noise(&mut seed, frame_width * 8, 256).chunks(2).map(
+               |bytes| (bytes[0] as u16) << 8 | bytes[1] as u16
+            ).collect::<Vec<_>>();
+
+            let linear = [(r - 1) * 257, (g - 1) * 251, (b - 1) * 257];
+
+            let linear_stripe = |simd: Simd| {
+               let mut result = wide.clone();
+
+               let ptr = result.as_mut_ptr() as *mut u8;
+
+               let end = blend_alpha_stripe(
+                  ptr, aliased.as_ptr(), len, next_row_offset, r, g, b, Some(linear), &grid, simd
+               );
+
+               assert_eq!(end, unsafe { ptr.offset((len * 8) as isize) });
+
+               result
+            };
+
+            let expected = stripe(Simd::Scalar);
+            let expected_linear = linear_stripe(Simd::Scalar);
+
+            for &simd in vector_levels().iter() {
+               assert!(stripe(simd) == expected, "{:?} {:?} {}", simd, sampling, len);
+               assert!(linear_stripe(simd) == expected_linear, "{:?} {:?} {} linear", simd, sampling, len);
+
+               let mut filled = vec![0_u32; len + 1];
+
+               fill_solid(filled.as_mut_ptr(), len, 0x01020304, simd);
+
+               assert!(filled[..len].iter().all(|&value| value == 0x01020304));
+               assert_eq!(filled[len], 0);
+            }
+         }
+      }
+   }
+
+   #[test]
+   fn test_simd_render() {
+      // Same triangles in the units of every grid
+      let scene = |s: i64| {
+         let mut seed = 11;
+
+         let mut scene = Scene::new();
+
+         for i in 0..60 {
+            let points = noise(&mut seed, 6, 70 * s as u32);
+            let color = noise(&mut seed, 3, 256);
+
+            let contour = (0..3).map(
+               |j| IntPoint::new(points[2 * j] as i64 + i, points[2 * j + 1] as i64)
+            ).collect();
+
+            scene.push(Box::new(Poly::new(vec![contour], RGB::new(color[0], color[1], color[2]))));
+         }
+
+         scene
+      };
+
+      let mut renderer = DevelRenderer::new(Scene::new());
+
+      let render = |renderer: &mut DevelRenderer| {
+         let mut pixels = vec![0; 4 * 90 * 70];
+
+         renderer.render_pixels(pixels.as_mut_ptr(), 90, 70, None, Subpixel::None);
+
+         pixels
+      };
+
+      let samplings = [
+         Sampling::Grid(2), Sampling::Grid(4), Sampling::Grid(8), Sampling::Grid(16),
+         Sampling::RotatedGrid(2), Sampling::RotatedGrid(4), Sampling::RotatedGrid(8), Sampling::RotatedGrid(16),
+      ];
+
+      for &blending in [Blending::Srgb, Blending::Linear].iter() {
+         renderer.set_blending(blending);
+
+         for &sampling in samplings.iter() {
+            renderer.set_sampling(sampling);
+
+            let s = renderer.subdivisions();
+
+            renderer.set_scene(scene(s));
+
+            renderer.set_simd(Simd::Scalar);
+
+            let expected = render(&mut renderer);
+
+            for &simd in vector_levels().iter() {
+               renderer.set_simd(simd);
+
+               assert!(render(&mut renderer) == expected, "{:?} {:?} {:?}", simd, sampling, blending);
+            }
+         }
+      }
+   }
+
+   #[test]
+   fn test_linear_blending() {
+      let s = SUBDIVISIONS;
+
+      let square = vec![
+         IntPoint::new(0, 0), IntPoint::new(16 * s, 0), IntPoint::new(16 * s, 16 * s), IntPoint::new(0, 16 * s)
+      ];
+
+      let triangle = vec![IntPoint::new(s, 2 * s), IntPoint::new(15 * s, s), IntPoint::new(7 * s, 15 * s)];
+
+      let render = |scene: Scene, blending: Blending| {
+         let mut renderer = DevelRenderer::new(scene);
+
+         renderer.set_blending(blending);
+
+         let mut pixels = vec![0; 4 * 16 * 16];
+
+         renderer.render_pixels(pixels.as_mut_ptr(), 16, 16, None, Subpixel::None);
+
+         pixels
+      };
+
+      // Half of white over black, in linear light encoding to 187.5
+      let mut scene = Scene::new();
+
+      scene.push(Box::new(Poly::new(vec![square.clone()], RGB::new(0, 0, 0))));
+      scene.begin_group(0.5, BlendMode::Normal);
+      scene.push(Box::new(Poly::new(vec![square.clone()], RGB::new(255, 255, 255))));
+      scene.end_group();
+
+      let encoded = render(scene.clone(), Blending::Srgb);
+
+      assert!(encoded.chunks(4).all(|pixel| pixel == [128, 128, 128, 255]));
+
+      let linear = render(scene, Blending::Linear);
+
+      // Dithered once on the way out, so both neighbours show up
+      assert!(linear.chunks(4).all(|pixel| pixel[0] == 187 || pixel[0] == 188));
+      assert!(linear.chunks(4).any(|pixel| pixel[0] == 187));
+      assert!(linear.chunks(4).any(|pixel| pixel[0] == 188));
+
+      // Layers keep the full precision, an opaque group changes nothing
+      let scene = |grouped: bool| {
+         let mut scene = Scene::new();
+
+         scene.push(Box::new(Poly::new(vec![square.clone()], RGB::new(90, 140, 30))));
+
+         if grouped {
+            scene.begin_group(1., BlendMode::Normal);
+         }
+
+         scene.push(Box::new(Poly::new(vec![triangle.clone()], RGB::new(250, 20, 200))));
+
+         if grouped {
+            scene.end_group();
+         }
+
+         scene
+      };
+
+      let expected = render(scene(false), Blending::Linear);
+
+      assert!(render(scene(true), Blending::Linear) == expected);
+   }
+
+   #[test]
+   fn test_workers() {
+      let s = SUBDIVISIONS;
+
+      let square = |x: i64, y: i64, size: i64| vec![
+         IntPoint::new(x * s, y * s),
+         IntPoint::new((x + size) * s, y * s),
+         IntPoint::new((x + size) * s, (y + size) * s),
+         IntPoint::new(x * s, (y + size) * s),
+      ];
+
+      let render = |renderer: &mut DevelRenderer| {
+         let mut pixels = vec![0; 4 * 32 * 37];
+
+         renderer.render_pixels(pixels.as_mut_ptr(), 32, 37, None, Subpixel::None);
+
+         pixels
+      };
+
+      // Unfiltered, filtered and with a filter nested in a group of the
+      // filtered content
+      for &filters in [0, 1, 2].iter() {
+         let mut scene = Scene::new();
+
+         scene.push(Box::new(Poly::new(vec![square(3, 2, 20)], RGB::new(255, 0, 0))));
+
+         if filters > 0 {
+            scene.begin_filter(vec![FilterOp::Blur(2., 2.)]);
+         }
+
+         scene.push(Box::new(Poly::new(vec![square(10, 12, 9)], RGB::new(0, 0, 255))));
+
+         if filters > 1 {
+            scene.begin_group(0.8, BlendMode::Normal);
+            scene.begin_filter(vec![FilterOp::Offset(3., -2.), FilterOp::Blur(1., 0.)]);
+            scene.push(Box::new(Poly::new(vec![square(5, 20, 6)], RGB::new(0, 255, 0))));
+            scene.end_filter();
+            scene.end_group();
+         }
+
+         if filters > 0 {
+            scene.end_filter();
+         }
+
+         let mut renderer = DevelRenderer::new(scene);
+
+         renderer.set_workers(1);
+
+         let expected = render(&mut renderer);
+
+         assert!(expected.iter().any(|&value| value != 0));
+
+         // The filters wait for every tile, not just the ones on a worker
+         assert!(renderer.tiles.len() > renderer.workers());
+
+         // Tiles split differently, same pixels
+         for &workers in [2, 3, 16].iter() {
+            renderer.set_workers(workers);
+
+            assert_eq!(renderer.workers(), workers);
+            assert!(render(&mut renderer) == expected, "{} workers {} filters", workers, filters);
+         }
+      }
    }
 }
@@ -0,0 +1,232 @@
+// Largest number of samples along each axis of a pixel
+pub const MAX_SAMPLES: usize = 16;
+
+// Sampled column of every row of the sparse patterns, no two samples sharing
+// a row, a column or a diagonal
+const ROTATED_1: [usize; 1] = [0];
+const ROTATED_2: [usize; 2] = [1, 0];
+const ROTATED_4: [usize; 4] = [1, 3, 0, 2];
+const ROTATED_8: [usize; 8] = [3, 6, 2, 7, 1, 4, 0, 5];
+const ROTATED_16: [usize; 16] = [3, 10, 8, 1, 15, 13, 6, 2, 7, 14, 12, 5, 9, 4, 0, 11];
+
+
+// Anti-aliasing level of the devel renderer. The sizes are rounded up to a
+// power of two no larger than MAX_SAMPLES
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Sampling {
+   // `n` by `n` samples per pixel
+   Grid(usize),
+   // `n` samples per pixel on an `n` by `n` grid, one in every row and
+   // column, which resolves near vertical and near horizontal edges about
+   // as finely as the full grid
+   RotatedGrid(usize),
+}
+
+impl Default for Sampling {
+   fn default() -> Sampling {
+      Sampling::Grid(4)
+   }
+}
+
+
+// Grid the edges are rasterized on, with the samples of a pixel that count
+// towards its coverage
+pub struct SampleGrid {
+   pub size: usize,
+   pub columns: Option<&'static [usize]>,
+   alphas: Vec<u16>,
+   // Sampled columns of every row repeated over 16 aliased bytes, for the
+   // vector code
+   masks: [[u8; 16]; MAX_SAMPLES],
+}
+
+impl SampleGrid {
+   pub fn new(sampling: Sampling) -> Self {
+      let (size, sparse) = match sampling {
+         Sampling::Grid(size) => (size, false),
+         Sampling::RotatedGrid(size) => (size, true),
+      };
+
+      let size = size.max(1).next_power_of_two().min(MAX_SAMPLES);
+
+      let columns = if sparse {
+         Some(match size {
+            1 => &ROTATED_1[..],
+            2 => &ROTATED_2[..],
+            4 => &ROTATED_4[..],
+            8 => &ROTATED_8[..],
+            _ => &ROTATED_16[..],
+         })
+      } else {
+         None
+      };
+
+      let count = if sparse { size } else { size * size };
+
+      // 255 * covered / count + 1, the blend factors of blend_alpha_pixel
+      let alphas = (0..count + 1).map(
+         |covered| ((255 * covered + (count - 1) / 2) / count + 1) as u16
+      ).collect();
+
+      let mut masks = [[0; 16]; MAX_SAMPLES];
+
+      for (row, mask) in masks[..size].iter_mut().enumerate() {
+         for (i, byte) in mask.iter_mut().enumerate() {
+            if columns.map_or(true, |columns| columns[row] == i % size) {
+               *byte = 0xff;
+            }
+         }
+      }
+
+      SampleGrid {
+         size: size,
+         columns: columns,
+         alphas: alphas,
+         masks: masks,
+      }
+   }
+
+   // Blend factor of `covered` samples
+   #[inline]
+   pub fn alpha(&self, covered: usize) -> u16 {
+      unsafe {
+         *self.alphas.get_unchecked(covered)
+      }
+   }
+
+   #[inline]
+   pub fn is_sampled(&self, row: usize, column: usize) -> bool {
+      match self.columns {
+         Some(columns) => columns[row] == column,
+         None => true,
+      }
+   }
+
+   #[inline]
+   pub fn mask(&self, row: usize) -> &[u8; 16] {
+      &self.masks[row]
+   }
+}
+
+impl Default for SampleGrid {
+   #[inline]
+   fn default() -> SampleGrid {
+      SampleGrid::new(Sampling::default())
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use data::IntPoint;
+   use draw::RGB;
+   use devel::{Scene, Poly, DevelRenderer};
+
+   #[test]
+   fn test_grids() {
+      let grid = SampleGrid::new(Sampling::Grid(4));
+
+      // Same factors as the former fixed 4 by 4 table
+      let alphas = (0..17).map(|covered| grid.alpha(covered)).collect::<Vec<_>>();
+
+      assert_eq!(
+         alphas,
+         vec![1, 17, 33, 49, 65, 81, 97, 113, 128, 144, 160, 176, 192, 208, 224, 240, 256]
+      );
+
+      assert_eq!(SampleGrid::new(Sampling::Grid(1)).alpha(1), 256);
+
+      assert_eq!(SampleGrid::new(Sampling::Grid(5)).size, 8);
+      assert_eq!(SampleGrid::new(Sampling::Grid(64)).size, 16);
+   }
+
+   #[test]
+   fn test_rotated_patterns() {
+      for &size in [1, 2, 4, 8, 16].iter() {
+         let grid = SampleGrid::new(Sampling::RotatedGrid(size));
+
+         assert_eq!(grid.alpha(size), 256);
+
+         // One sample in every row and every column
+         for column in 0..size {
+            assert_eq!((0..size).filter(|&row| grid.is_sampled(row, column)).count(), 1);
+         }
+
+         for row in 0..size {
+            for i in 0..16 {
+               assert_eq!(grid.mask(row)[i] == 0xff, grid.is_sampled(row, i % size));
+            }
+         }
+      }
+   }
+
+   #[test]
+   fn test_render_levels() {
+      let samplings = [
+         Sampling::Grid(2), Sampling::Grid(4), Sampling::Grid(16),
+         Sampling::RotatedGrid(4), Sampling::RotatedGrid(8),
+      ];
+
+      for sampling in samplings.iter() {
+         let mut renderer = DevelRenderer::new(Scene::new());
+
+         renderer.set_sampling(*sampling);
+
+         let s = renderer.subdivisions();
+
+         // Ends halfway through the eleventh column
+         let contour = vec![
+            IntPoint::new(0, 0),
+            IntPoint::new(21 * s / 2, 0),
+            IntPoint::new(21 * s / 2, 4 * s),
+            IntPoint::new(0, 4 * s),
+         ];
+
+         let mut scene = Scene::new();
+
+         scene.push(Box::new(Poly::new(vec![contour], RGB::new(255, 255, 255))));
+
+         renderer.set_scene(scene);
+
+         let image = renderer.render_image(16, 4);
+
+         let alpha = |x: usize| image.pixel(x, 1)[3];
+
+         assert_eq!(alpha(9), 255);
+         assert!((alpha(10) as f32 - 127.5).abs() <= 1., "{:?} {}", sampling, alpha(10));
+         assert_eq!(alpha(11), 0);
+      }
+   }
+
+   #[test]
+   fn test_fine_edges() {
+      let mut renderer = DevelRenderer::new(Scene::new());
+
+      renderer.set_sampling(Sampling::Grid(16));
+
+      let s = renderer.subdivisions();
+
+      // A sixteenth of a pixel into the eleventh column, finer than the
+      // default grid resolves
+      let contour = vec![
+         IntPoint::new(0, 0),
+         IntPoint::new(10 * s + 1, 0),
+         IntPoint::new(10 * s + 1, 4 * s),
+         IntPoint::new(0, 4 * s),
+      ];
+
+      let mut scene = Scene::new();
+
+      scene.push(Box::new(Poly::new(vec![contour], RGB::new(255, 255, 255))));
+
+      renderer.set_scene(scene);
+
+      let image = renderer.render_image(16, 4);
+
+      let alpha = image.pixel(10, 1)[3];
+
+      assert!((alpha as f32 - 255. / 16.).abs() <= 1., "{}", alpha);
+   }
+}
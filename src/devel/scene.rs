@@ -1,7 +1,23 @@
+use std::sync::Arc;
+
+use draw::BlendMode;
+use color::Profile;
+use geom::transform::Transform;
+
 use super::Poly;
+use super::mask::{Mask, MaskMode, MaskSource};
+use super::layer::{Layer, Group};
+use super::filter::{Filter, FilterOp};
 
+#[derive(Clone)]
 pub struct Scene {
    pub polys: Vec<Box<Poly>>,
+   pub clips: Vec<Poly>,
+   pub layers: Vec<Layer>,
+   // Profile of the scene colors, converted to the output profile of the
+   // renderer when composited to the frame
+   pub profile: Arc<Profile>,
+   open_layers: Vec<usize>,
 }
 
 impl Scene {
@@ -9,11 +25,177 @@ impl Scene {
    pub fn new() -> Self {
       Scene {
          polys: Vec::new(),
+         clips: Vec::new(),
+         layers: Vec::new(),
+         profile: Arc::new(Profile::srgb()),
+         open_layers: Vec::new(),
       }
    }
 
+   #[inline]
+   pub fn set_profile(&mut self, profile: Profile) {
+      self.profile = Arc::new(profile);
+   }
+
+   // Clipping is applied to the outlines when pushed, so the clip edges get
+   // anti-aliased the same way as the poly edges
    #[inline]
    pub fn push(&mut self, poly: Box<Poly>) {
-      self.polys.push(poly);
+      let poly = match self.clips.last() {
+         Some(clip) => Box::new(poly.intersection(clip)),
+         None => poly,
+      };
+
+      if poly.contours.len() > 0 {
+         self.polys.push(poly);
+      }
+   }
+
+   // Polys pushed until the matching pop_clip are clipped by `clip`. Nested
+   // clips intersect with the enclosing ones
+   pub fn push_clip(&mut self, clip: &Poly) {
+      let clip = match self.clips.last() {
+         Some(outer) => outer.intersection(clip),
+         None => clip.clone(),
+      };
+
+      self.clips.push(clip);
+   }
+
+   #[inline]
+   pub fn pop_clip(&mut self) {
+      self.clips.pop();
+   }
+
+   // Polys pushed until the matching end_mask are rendered as a group, which
+   // gets composited with its coverage modulated by `source`
+   #[inline]
+   pub fn begin_mask(&mut self, mode: MaskMode, source: MaskSource) {
+      let mask = Mask::new(mode, source, self.polys.len());
+
+      self.open_layer(Layer::Mask(mask));
+   }
+
+   #[inline]
+   pub fn end_mask(&mut self) {
+      self.close_layer();
+   }
+
+   // Polys pushed until the matching end_group are composited together
+   #[inline]
+   pub fn begin_group(&mut self, opacity: f64, blend: BlendMode) {
+      let group = Group::new(opacity, blend, self.polys.len());
+
+      self.open_layer(Layer::Group(group));
+   }
+
+   #[inline]
+   pub fn end_group(&mut self) {
+      self.close_layer();
+   }
+
+   // Polys pushed until the matching end_filter are passed through `ops`
+   // together before being composited
+   #[inline]
+   pub fn begin_filter(&mut self, ops: Vec<FilterOp>) {
+      let filter = Filter::new(ops, self.polys.len());
+
+      self.open_layer(Layer::Filter(filter));
+   }
+
+   #[inline]
+   pub fn end_filter(&mut self) {
+      self.close_layer();
+   }
+
+   // Maps the polys, clips and mask polys. Filter lengths get scaled by the
+   // average scale of the transform, mask images are left in place
+   pub fn transform(&mut self, transform: &Transform) {
+      for poly in self.polys.iter_mut() {
+         poly.transform(transform);
+      }
+
+      for clip in self.clips.iter_mut() {
+         clip.transform(transform);
+      }
+
+      let scale = transform.determinant().abs().sqrt();
+
+      for layer in self.layers.iter_mut() {
+         match *layer {
+            Layer::Mask(ref mut mask) => {
+               if let MaskSource::Polys(ref mut polys) = mask.source {
+                  for poly in polys.iter_mut() {
+                     poly.transform(transform);
+                  }
+               }
+            },
+            Layer::Filter(ref mut filter) => {
+               filter.ops = Arc::new(filter.ops.iter().map(|op| op.scale(scale)).collect());
+            },
+            Layer::Group(_) => {},
+         }
+      }
+   }
+
+   #[inline]
+   fn open_layer(&mut self, layer: Layer) {
+      self.open_layers.push(self.layers.len());
+
+      self.layers.push(layer);
+   }
+
+   #[inline]
+   fn close_layer(&mut self) {
+      if let Some(index) = self.open_layers.pop() {
+         self.layers[index].set_end(self.polys.len());
+      }
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use draw::RGB;
+   use data::IntPoint;
+   use devel::{DevelRenderer, SUBDIVISIONS};
+
+   fn poly(points: &[(i64, i64)], color: RGB) -> Poly {
+      let s = SUBDIVISIONS;
+
+      Poly::new(vec![points.iter().map(|&(x, y)| IntPoint::new(x * s, y * s)).collect()], color)
+   }
+
+   #[test]
+   fn test_clip_render() {
+      let mut scene = Scene::new();
+
+      // Triangle below the diagonal, nested with a square that cuts its tip
+      scene.push_clip(&poly(&[(0, 0), (20, 0), (20, 20)], RGB::default()));
+      scene.push_clip(&poly(&[(4, 0), (20, 0), (20, 20), (4, 20)], RGB::default()));
+
+      scene.push(Box::new(poly(&[(0, 0), (20, 0), (20, 20), (0, 20)], RGB::new(255, 0, 0))));
+
+      scene.pop_clip();
+      scene.pop_clip();
+
+      // Left alone once the clips are popped
+      scene.push(Box::new(poly(&[(0, 16), (4, 16), (4, 20), (0, 20)], RGB::new(0, 0, 255))));
+
+      assert_eq!(scene.clips.len(), 0);
+
+      let image = DevelRenderer::new(scene).render_image(20, 20);
+
+      assert_eq!(image.pixel(15, 5), [255, 0, 0, 255]);
+      assert_eq!(image.pixel(5, 15), [0, 0, 0, 0]);
+      assert_eq!(image.pixel(2, 1), [0, 0, 0, 0]);
+      assert_eq!(image.pixel(2, 18), [0, 0, 255, 255]);
+
+      // The diagonal clip edge gets anti-aliased like any poly edge
+      let edge = image.pixel(10, 10);
+
+      assert!(edge[3] > 64 && edge[3] < 192, "{:?}", edge);
    }
 }
@@ -0,0 +1,793 @@
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+use super::sampling::SampleGrid;
+
+
+// Instruction set of the inner loops of the devel renderer. The vector code
+// produces the same bytes as the scalar code
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Simd {
+   Scalar,
+   Sse2,
+   Avx2,
+   Neon,
+}
+
+impl Simd {
+   // Widest instruction set of the CPU
+   pub fn detect() -> Simd {
+      for &simd in [Simd::Avx2, Simd::Sse2, Simd::Neon].iter() {
+         if simd.is_supported() {
+            return simd;
+         }
+      }
+
+      Simd::Scalar
+   }
+
+   pub fn is_supported(&self) -> bool {
+      match *self {
+         Simd::Scalar => true,
+         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+         Simd::Sse2 => is_x86_feature_detected!("sse2"),
+         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+         Simd::Avx2 => is_x86_feature_detected!("avx2"),
+         #[cfg(target_arch = "aarch64")]
+         Simd::Neon => ::std::arch::is_aarch64_feature_detected!("neon"),
+         _ => false,
+      }
+   }
+}
+
+impl Default for Simd {
+   #[inline]
+   fn default() -> Simd {
+      Simd::detect()
+   }
+}
+
+
+// Writes `color` to the first pixels of the `len` pixel run, as many as the
+// vector width allows, and returns their count. The scalar code does the rest
+#[inline]
+pub unsafe fn fill(simd: Simd, dst: *mut u32, len: usize, color: u32) -> usize {
+   match simd {
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Sse2 => fill_sse2(dst, len, color),
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Avx2 => fill_avx2(dst, len, color),
+      #[cfg(target_arch = "aarch64")]
+      Simd::Neon => fill_neon(dst, len, color),
+      _ => 0,
+   }
+}
+
+
+// Blends `color` over the first pixels of the `len` pixel stripe with their
+// coverage in the aliased rows from `src`, like blend_alpha_pixel, and returns
+// their count. `color` holds the channels plus one, as blend expects them
+#[inline]
+pub unsafe fn blend_stripe(
+   simd: Simd,
+   dst: *mut u8,
+   src: *const u8,
+   len: usize,
+   next_row_offset: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let row_stride = next_row_offset.wrapping_add(grid.size as isize);
+
+   match simd {
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Sse2 => blend_stripe_sse2(dst, src, len, row_stride, color, grid),
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Avx2 => blend_stripe_avx2(dst, src, len, row_stride, color, grid),
+      #[cfg(target_arch = "aarch64")]
+      Simd::Neon => blend_stripe_neon(dst, src, len, row_stride, color, grid),
+      _ => 0,
+   }
+}
+
+
+// Same as blend_stripe on a 16-bit linear layer, like blend_linear_pixel
+#[inline]
+pub unsafe fn blend_linear_stripe(
+   simd: Simd,
+   dst: *mut u16,
+   src: *const u8,
+   len: usize,
+   next_row_offset: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let row_stride = next_row_offset.wrapping_add(grid.size as isize);
+
+   match simd {
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Sse2 => blend_linear_stripe_sse2(dst, src, len, row_stride, color, grid),
+      #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+      Simd::Avx2 => blend_linear_stripe_avx2(dst, src, len, row_stride, color, grid),
+      #[cfg(target_arch = "aarch64")]
+      Simd::Neon => blend_linear_stripe_neon(dst, src, len, row_stride, color, grid),
+      _ => 0,
+   }
+}
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn fill_sse2(dst: *mut u32, len: usize, color: u32) -> usize {
+   let value = _mm_set1_epi32(color as i32);
+
+   let count = len / 4;
+
+   for i in 0..count {
+      _mm_storeu_si128(dst.offset((i * 4) as isize) as *mut __m128i, value);
+   }
+
+   count * 4
+}
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn fill_avx2(dst: *mut u32, len: usize, color: u32) -> usize {
+   let value = _mm256_set1_epi32(color as i32);
+
+   let count = len / 8;
+
+   for i in 0..count {
+      _mm256_storeu_si256(dst.offset((i * 8) as isize) as *mut __m256i, value);
+   }
+
+   count * 8
+}
+
+
+// Samples of row `row`, only the sampled column of every pixel kept on the
+// sparse grids
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn sampled_sse2(samples: __m128i, row: usize, grid: &SampleGrid) -> __m128i {
+   match grid.columns {
+      Some(_) => _mm_and_si128(samples, _mm_loadu_si128(grid.mask(row).as_ptr() as *const __m128i)),
+      None => samples,
+   }
+}
+
+
+// Covered samples of the four pixels from `src`, the rows being `row_stride`
+// apart. Samples are 0 or 1, so the byte sums of up to 16 rows don't overflow
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn coverage_sse2(src: *const u8, row_stride: isize, grid: &SampleGrid) -> [usize; 4] {
+   let zero = _mm_setzero_si128();
+
+   let row_ptr = |row: usize| src.offset(row as isize * row_stride) as *const __m128i;
+
+   match grid.size {
+      2 => {
+         let mut sum = zero;
+
+         for row in 0..2 {
+            sum = _mm_add_epi8(sum, sampled_sse2(_mm_loadl_epi64(row_ptr(row)), row, grid));
+         }
+
+         let pairs = _mm_and_si128(_mm_add_epi16(sum, _mm_srli_epi16(sum, 8)), _mm_set1_epi16(0xff));
+
+         let mut counts = [0_u16; 8];
+         _mm_storeu_si128(counts.as_mut_ptr() as *mut __m128i, pairs);
+
+         [counts[0] as usize, counts[1] as usize, counts[2] as usize, counts[3] as usize]
+      },
+      4 => {
+         let mut sum = zero;
+
+         for row in 0..4 {
+            sum = _mm_add_epi8(sum, sampled_sse2(_mm_loadu_si128(row_ptr(row)), row, grid));
+         }
+
+         let sum = _mm_add_epi32(sum, _mm_srli_epi32(sum, 8));
+         let quads = _mm_and_si128(_mm_add_epi32(sum, _mm_srli_epi32(sum, 16)), _mm_set1_epi32(0xff));
+
+         let mut counts = [0_u32; 4];
+         _mm_storeu_si128(counts.as_mut_ptr() as *mut __m128i, quads);
+
+         [counts[0] as usize, counts[1] as usize, counts[2] as usize, counts[3] as usize]
+      },
+      8 => {
+         let mut first = zero;
+         let mut second = zero;
+
+         for row in 0..8 {
+            first = _mm_add_epi8(first, sampled_sse2(_mm_loadu_si128(row_ptr(row)), row, grid));
+            second = _mm_add_epi8(second, sampled_sse2(_mm_loadu_si128(row_ptr(row).offset(1)), row, grid));
+         }
+
+         let mut counts = [0_u64; 4];
+         _mm_storeu_si128(counts.as_mut_ptr() as *mut __m128i, _mm_sad_epu8(first, zero));
+         _mm_storeu_si128(counts.as_mut_ptr().offset(2) as *mut __m128i, _mm_sad_epu8(second, zero));
+
+         [counts[0] as usize, counts[1] as usize, counts[2] as usize, counts[3] as usize]
+      },
+      16 => {
+         let mut result = [0; 4];
+
+         for pixel in 0..4 {
+            let mut sum = zero;
+
+            for row in 0..16 {
+               sum = _mm_add_epi8(sum, sampled_sse2(_mm_loadu_si128(row_ptr(row).offset(pixel as isize)), row, grid));
+            }
+
+            let mut halves = [0_u64; 2];
+            _mm_storeu_si128(halves.as_mut_ptr() as *mut __m128i, _mm_sad_epu8(sum, zero));
+
+            result[pixel] = (halves[0] + halves[1]) as usize;
+         }
+
+         result
+      },
+      _ => [*src as usize, *src.offset(1) as usize, *src.offset(2) as usize, *src.offset(3) as usize],
+   }
+}
+
+
+// Blend factors of two pixels for the four channels of each
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn alpha_pair(first: u16, second: u16) -> __m128i {
+   let first = first as i16;
+   let second = second as i16;
+
+   _mm_set_epi16(second, second, second, second, first, first, first, first)
+}
+
+
+// blend of the eight channels of two pixels, the channels being 16 bit plus one
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn blend_sse2(color: __m128i, backdrop: __m128i, alpha: __m128i) -> __m128i {
+   let remaining = _mm_sub_epi16(_mm_set1_epi16(256), alpha);
+
+   _mm_srli_epi16(_mm_add_epi16(_mm_mullo_epi16(color, alpha), _mm_mullo_epi16(backdrop, remaining)), 8)
+}
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn blend_stripe_sse2(
+   mut dst: *mut u8,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let color = _mm_set_epi16(
+      256, color[2] as i16, color[1] as i16, color[0] as i16,
+      256, color[2] as i16, color[1] as i16, color[0] as i16
+   );
+
+   let zero = _mm_setzero_si128();
+   let one = _mm_set1_epi16(1);
+
+   let count = len / 4;
+
+   for _ in 0..count {
+      let covered = coverage_sse2(src, row_stride, grid);
+
+      let a = [grid.alpha(covered[0]), grid.alpha(covered[1]), grid.alpha(covered[2]), grid.alpha(covered[3])];
+
+      let first_alpha = alpha_pair(a[0], a[1]);
+      let second_alpha = alpha_pair(a[2], a[3]);
+
+      let pixels = _mm_loadu_si128(dst as *const __m128i);
+
+      let first = blend_sse2(color, _mm_add_epi16(_mm_unpacklo_epi8(pixels, zero), one), first_alpha);
+      let second = blend_sse2(color, _mm_add_epi16(_mm_unpackhi_epi8(pixels, zero), one), second_alpha);
+
+      let blended = _mm_sub_epi8(_mm_packus_epi16(first, second), _mm_set1_epi8(1));
+
+      // Uncovered pixels are left untouched
+      let keep = _mm_packs_epi16(_mm_cmpeq_epi16(first_alpha, one), _mm_cmpeq_epi16(second_alpha, one));
+
+      let result = _mm_or_si128(_mm_and_si128(keep, pixels), _mm_andnot_si128(keep, blended));
+
+      _mm_storeu_si128(dst as *mut __m128i, result);
+
+      dst = dst.offset(16);
+      src = src.offset((4 * n) as isize);
+   }
+
+   count * 4
+}
+
+
+// Same as blend_stripe_sse2 eight pixels at a time. The 256 bit unpacks work
+// on the 128 bit halves, so the pairs of factors follow the same split
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn blend_stripe_avx2(
+   mut dst: *mut u8,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let color = _mm256_set_epi16(
+      256, color[2] as i16, color[1] as i16, color[0] as i16,
+      256, color[2] as i16, color[1] as i16, color[0] as i16,
+      256, color[2] as i16, color[1] as i16, color[0] as i16,
+      256, color[2] as i16, color[1] as i16, color[0] as i16
+   );
+
+   let zero = _mm256_setzero_si256();
+   let one = _mm256_set1_epi16(1);
+   let full = _mm256_set1_epi16(256);
+
+   let count = len / 8;
+
+   for _ in 0..count {
+      let low = coverage_sse2(src, row_stride, grid);
+      let high = coverage_sse2(src.offset((4 * n) as isize), row_stride, grid);
+
+      let a = [
+         grid.alpha(low[0]), grid.alpha(low[1]), grid.alpha(low[2]), grid.alpha(low[3]),
+         grid.alpha(high[0]), grid.alpha(high[1]), grid.alpha(high[2]), grid.alpha(high[3]),
+      ];
+
+      let first_alpha = _mm256_set_m128i(alpha_pair(a[4], a[5]), alpha_pair(a[0], a[1]));
+      let second_alpha = _mm256_set_m128i(alpha_pair(a[6], a[7]), alpha_pair(a[2], a[3]));
+
+      let pixels = _mm256_loadu_si256(dst as *const __m256i);
+
+      let first_backdrop = _mm256_add_epi16(_mm256_unpacklo_epi8(pixels, zero), one);
+      let second_backdrop = _mm256_add_epi16(_mm256_unpackhi_epi8(pixels, zero), one);
+
+      let first = _mm256_srli_epi16(_mm256_add_epi16(
+         _mm256_mullo_epi16(color, first_alpha),
+         _mm256_mullo_epi16(first_backdrop, _mm256_sub_epi16(full, first_alpha))
+      ), 8);
+
+      let second = _mm256_srli_epi16(_mm256_add_epi16(
+         _mm256_mullo_epi16(color, second_alpha),
+         _mm256_mullo_epi16(second_backdrop, _mm256_sub_epi16(full, second_alpha))
+      ), 8);
+
+      let blended = _mm256_sub_epi8(_mm256_packus_epi16(first, second), _mm256_set1_epi8(1));
+
+      let keep = _mm256_packs_epi16(
+         _mm256_cmpeq_epi16(first_alpha, one), _mm256_cmpeq_epi16(second_alpha, one)
+      );
+
+      _mm256_storeu_si256(dst as *mut __m256i, _mm256_blendv_epi8(blended, pixels, keep));
+
+      dst = dst.offset(32);
+      src = src.offset((8 * n) as isize);
+   }
+
+   count * 8
+}
+
+
+// Quotients by 255 of the 32 bit lanes below 2^24. The estimate is exact or
+// one short, which the remainder tells
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn div_255_sse2(x: __m128i) -> __m128i {
+   let estimate = _mm_srli_epi32(
+      _mm_add_epi32(x, _mm_add_epi32(_mm_srli_epi32(x, 8), _mm_srli_epi32(x, 16))), 8
+   );
+
+   let rest = _mm_sub_epi32(x, _mm_sub_epi32(_mm_slli_epi32(estimate, 8), estimate));
+
+   _mm_sub_epi32(estimate, _mm_cmpgt_epi32(rest, _mm_set1_epi32(254)))
+}
+
+
+// blend_linear_pixel of the eight channels of two pixels, `coverage` holding
+// the factor of each for its four channels
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn blend_linear_sse2(color: __m128i, backdrop: __m128i, coverage: __m128i) -> __m128i {
+   let remaining = _mm_sub_epi16(_mm_set1_epi16(255), coverage);
+
+   let source_low = _mm_mullo_epi16(color, coverage);
+   let source_high = _mm_mulhi_epu16(color, coverage);
+
+   let backdrop_low = _mm_mullo_epi16(backdrop, remaining);
+   let backdrop_high = _mm_mulhi_epu16(backdrop, remaining);
+
+   let round = _mm_set1_epi32(127);
+
+   let first = div_255_sse2(_mm_add_epi32(_mm_add_epi32(
+      _mm_unpacklo_epi16(source_low, source_high), _mm_unpacklo_epi16(backdrop_low, backdrop_high)
+   ), round));
+
+   let second = div_255_sse2(_mm_add_epi32(_mm_add_epi32(
+      _mm_unpackhi_epi16(source_low, source_high), _mm_unpackhi_epi16(backdrop_low, backdrop_high)
+   ), round));
+
+   // The pack saturates to signed values, so they go through the signed range
+   let bias = _mm_set1_epi32(32768);
+
+   _mm_xor_si128(
+      _mm_packs_epi32(_mm_sub_epi32(first, bias), _mm_sub_epi32(second, bias)),
+      _mm_set1_epi16(-32768)
+   )
+}
+
+
+// Uncovered pixels come out of the blend unchanged, no need to keep them
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn blend_linear_stripe_sse2(
+   mut dst: *mut u16,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let color = _mm_set_epi16(
+      -1, color[2] as i16, color[1] as i16, color[0] as i16,
+      -1, color[2] as i16, color[1] as i16, color[0] as i16
+   );
+
+   let count = len / 4;
+
+   for _ in 0..count {
+      let covered = coverage_sse2(src, row_stride, grid);
+
+      let a = [grid.alpha(covered[0]), grid.alpha(covered[1]), grid.alpha(covered[2]), grid.alpha(covered[3])];
+
+      let first_ptr = dst as *mut __m128i;
+      let second_ptr = dst.offset(8) as *mut __m128i;
+
+      let first = blend_linear_sse2(color, _mm_loadu_si128(first_ptr), alpha_pair(a[0] - 1, a[1] - 1));
+      let second = blend_linear_sse2(color, _mm_loadu_si128(second_ptr), alpha_pair(a[2] - 1, a[3] - 1));
+
+      _mm_storeu_si128(first_ptr, first);
+      _mm_storeu_si128(second_ptr, second);
+
+      dst = dst.offset(16);
+      src = src.offset((4 * n) as isize);
+   }
+
+   count * 4
+}
+
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn div_255_avx2(x: __m256i) -> __m256i {
+   let estimate = _mm256_srli_epi32(
+      _mm256_add_epi32(x, _mm256_add_epi32(_mm256_srli_epi32(x, 8), _mm256_srli_epi32(x, 16))), 8
+   );
+
+   let rest = _mm256_sub_epi32(x, _mm256_sub_epi32(_mm256_slli_epi32(estimate, 8), estimate));
+
+   _mm256_sub_epi32(estimate, _mm256_cmpgt_epi32(rest, _mm256_set1_epi32(254)))
+}
+
+
+// Same as blend_linear_stripe_sse2 four pixels at a time. The unpacks and the
+// pack work on the 128 bit halves, which keeps the pixels in order
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn blend_linear_stripe_avx2(
+   mut dst: *mut u16,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let color = _mm256_set_epi16(
+      -1, color[2] as i16, color[1] as i16, color[0] as i16,
+      -1, color[2] as i16, color[1] as i16, color[0] as i16,
+      -1, color[2] as i16, color[1] as i16, color[0] as i16,
+      -1, color[2] as i16, color[1] as i16, color[0] as i16
+   );
+
+   let round = _mm256_set1_epi32(127);
+
+   let count = len / 4;
+
+   for _ in 0..count {
+      let covered = coverage_sse2(src, row_stride, grid);
+
+      let a = [grid.alpha(covered[0]), grid.alpha(covered[1]), grid.alpha(covered[2]), grid.alpha(covered[3])];
+
+      let coverage = _mm256_set_m128i(alpha_pair(a[2] - 1, a[3] - 1), alpha_pair(a[0] - 1, a[1] - 1));
+      let remaining = _mm256_sub_epi16(_mm256_set1_epi16(255), coverage);
+
+      let backdrop = _mm256_loadu_si256(dst as *const __m256i);
+
+      let source_low = _mm256_mullo_epi16(color, coverage);
+      let source_high = _mm256_mulhi_epu16(color, coverage);
+
+      let backdrop_low = _mm256_mullo_epi16(backdrop, remaining);
+      let backdrop_high = _mm256_mulhi_epu16(backdrop, remaining);
+
+      let first = div_255_avx2(_mm256_add_epi32(_mm256_add_epi32(
+         _mm256_unpacklo_epi16(source_low, source_high), _mm256_unpacklo_epi16(backdrop_low, backdrop_high)
+      ), round));
+
+      let second = div_255_avx2(_mm256_add_epi32(_mm256_add_epi32(
+         _mm256_unpackhi_epi16(source_low, source_high), _mm256_unpackhi_epi16(backdrop_low, backdrop_high)
+      ), round));
+
+      _mm256_storeu_si256(dst as *mut __m256i, _mm256_packus_epi32(first, second));
+
+      dst = dst.offset(16);
+      src = src.offset((4 * n) as isize);
+   }
+
+   count * 4
+}
+
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn fill_neon(dst: *mut u32, len: usize, color: u32) -> usize {
+   let value = vdupq_n_u32(color);
+
+   let count = len / 4;
+
+   for i in 0..count {
+      vst1q_u32(dst.offset((i * 4) as isize), value);
+   }
+
+   count * 4
+}
+
+
+// Same as sampled_sse2
+#[cfg(target_arch = "aarch64")]
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn sampled_neon(samples: uint8x16_t, row: usize, grid: &SampleGrid) -> uint8x16_t {
+   match grid.columns {
+      Some(_) => vandq_u8(samples, vld1q_u8(grid.mask(row).as_ptr())),
+      None => samples,
+   }
+}
+
+
+// Covered samples of the four pixels from `src` with pairwise widening adds
+#[cfg(target_arch = "aarch64")]
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn coverage_neon(src: *const u8, row_stride: isize, grid: &SampleGrid) -> [usize; 4] {
+   let row_ptr = |row: usize| src.offset(row as isize * row_stride);
+
+   match grid.size {
+      2 => {
+         let mut sum = vdup_n_u8(0);
+
+         for row in 0..2 {
+            let samples = match grid.columns {
+               Some(_) => vand_u8(vld1_u8(row_ptr(row)), vld1_u8(grid.mask(row).as_ptr())),
+               None => vld1_u8(row_ptr(row)),
+            };
+
+            sum = vadd_u8(sum, samples);
+         }
+
+         let pairs = vpaddl_u8(sum);
+
+         [
+            vget_lane_u16::<0>(pairs) as usize, vget_lane_u16::<1>(pairs) as usize,
+            vget_lane_u16::<2>(pairs) as usize, vget_lane_u16::<3>(pairs) as usize,
+         ]
+      },
+      4 => {
+         let mut sum = vdupq_n_u8(0);
+
+         for row in 0..4 {
+            sum = vaddq_u8(sum, sampled_neon(vld1q_u8(row_ptr(row)), row, grid));
+         }
+
+         let quads = vpaddlq_u16(vpaddlq_u8(sum));
+
+         [
+            vgetq_lane_u32::<0>(quads) as usize, vgetq_lane_u32::<1>(quads) as usize,
+            vgetq_lane_u32::<2>(quads) as usize, vgetq_lane_u32::<3>(quads) as usize,
+         ]
+      },
+      8 => {
+         let mut first = vdupq_n_u8(0);
+         let mut second = vdupq_n_u8(0);
+
+         for row in 0..8 {
+            first = vaddq_u8(first, sampled_neon(vld1q_u8(row_ptr(row)), row, grid));
+            second = vaddq_u8(second, sampled_neon(vld1q_u8(row_ptr(row).offset(16)), row, grid));
+         }
+
+         let first = vpaddlq_u32(vpaddlq_u16(vpaddlq_u8(first)));
+         let second = vpaddlq_u32(vpaddlq_u16(vpaddlq_u8(second)));
+
+         [
+            vgetq_lane_u64::<0>(first) as usize, vgetq_lane_u64::<1>(first) as usize,
+            vgetq_lane_u64::<0>(second) as usize, vgetq_lane_u64::<1>(second) as usize,
+         ]
+      },
+      16 => {
+         let mut result = [0; 4];
+
+         for pixel in 0..4 {
+            let mut sum = vdupq_n_u8(0);
+
+            for row in 0..16 {
+               sum = vaddq_u8(sum, sampled_neon(vld1q_u8(row_ptr(row).offset((pixel * 16) as isize)), row, grid));
+            }
+
+            result[pixel] = vaddlvq_u8(sum) as usize;
+         }
+
+         result
+      },
+      _ => [*src as usize, *src.offset(1) as usize, *src.offset(2) as usize, *src.offset(3) as usize],
+   }
+}
+
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn blend_stripe_neon(
+   mut dst: *mut u8,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let channels = [color[0], color[1], color[2], 256, color[0], color[1], color[2], 256];
+   let color = vld1q_u16(channels.as_ptr());
+
+   let one = vdupq_n_u16(1);
+   let full = vdupq_n_u16(256);
+
+   let count = len / 4;
+
+   for _ in 0..count {
+      let covered = coverage_neon(src, row_stride, grid);
+
+      let a = [grid.alpha(covered[0]), grid.alpha(covered[1]), grid.alpha(covered[2]), grid.alpha(covered[3])];
+
+      let first_alphas = [a[0], a[0], a[0], a[0], a[1], a[1], a[1], a[1]];
+      let second_alphas = [a[2], a[2], a[2], a[2], a[3], a[3], a[3], a[3]];
+
+      let first_alpha = vld1q_u16(first_alphas.as_ptr());
+      let second_alpha = vld1q_u16(second_alphas.as_ptr());
+
+      let pixels = vld1q_u8(dst);
+
+      let first_backdrop = vaddq_u16(vmovl_u8(vget_low_u8(pixels)), one);
+      let second_backdrop = vaddq_u16(vmovl_u8(vget_high_u8(pixels)), one);
+
+      let first = vshrq_n_u16::<8>(vmlaq_u16(
+         vmulq_u16(color, first_alpha), first_backdrop, vsubq_u16(full, first_alpha)
+      ));
+
+      let second = vshrq_n_u16::<8>(vmlaq_u16(
+         vmulq_u16(color, second_alpha), second_backdrop, vsubq_u16(full, second_alpha)
+      ));
+
+      let blended = vsubq_u8(vcombine_u8(vmovn_u16(first), vmovn_u16(second)), vdupq_n_u8(1));
+
+      let keep = vcombine_u8(
+         vmovn_u16(vceqq_u16(first_alpha, one)), vmovn_u16(vceqq_u16(second_alpha, one))
+      );
+
+      vst1q_u8(dst, vbslq_u8(keep, pixels, blended));
+
+      dst = dst.offset(16);
+      src = src.offset((4 * n) as isize);
+   }
+
+   count * 4
+}
+
+
+#[cfg(target_arch = "aarch64")]
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn div_255_neon(x: uint32x4_t) -> uint32x4_t {
+   let estimate = vshrq_n_u32::<8>(vaddq_u32(x, vaddq_u32(vshrq_n_u32::<8>(x), vshrq_n_u32::<16>(x))));
+
+   let rest = vsubq_u32(x, vsubq_u32(vshlq_n_u32::<8>(estimate), estimate));
+
+   // The comparison sets all the bits, subtracting it adds one
+   vsubq_u32(estimate, vcgtq_u32(rest, vdupq_n_u32(254)))
+}
+
+
+// Same as blend_linear_sse2 with widening multiply adds
+#[cfg(target_arch = "aarch64")]
+#[inline]
+#[target_feature(enable = "neon")]
+unsafe fn blend_linear_neon(color: uint16x8_t, backdrop: uint16x8_t, coverage: uint16x8_t) -> uint16x8_t {
+   let remaining = vsubq_u16(vdupq_n_u16(255), coverage);
+
+   let round = vdupq_n_u32(127);
+
+   let first = vmlal_u16(
+      vmlal_u16(round, vget_low_u16(color), vget_low_u16(coverage)),
+      vget_low_u16(backdrop),
+      vget_low_u16(remaining)
+   );
+
+   let second = vmlal_u16(
+      vmlal_u16(round, vget_high_u16(color), vget_high_u16(coverage)),
+      vget_high_u16(backdrop),
+      vget_high_u16(remaining)
+   );
+
+   vcombine_u16(vmovn_u32(div_255_neon(first)), vmovn_u32(div_255_neon(second)))
+}
+
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "neon")]
+unsafe fn blend_linear_stripe_neon(
+   mut dst: *mut u16,
+   mut src: *const u8,
+   len: usize,
+   row_stride: isize,
+   color: [u16; 3],
+   grid: &SampleGrid,
+) -> usize {
+   let n = grid.size;
+
+   let channels = [color[0], color[1], color[2], 65535, color[0], color[1], color[2], 65535];
+   let color = vld1q_u16(channels.as_ptr());
+
+   let count = len / 4;
+
+   for _ in 0..count {
+      let covered = coverage_neon(src, row_stride, grid);
+
+      let a = [grid.alpha(covered[0]), grid.alpha(covered[1]), grid.alpha(covered[2]), grid.alpha(covered[3])];
+
+      let first_coverages = [a[0] - 1, a[0] - 1, a[0] - 1, a[0] - 1, a[1] - 1, a[1] - 1, a[1] - 1, a[1] - 1];
+      let second_coverages = [a[2] - 1, a[2] - 1, a[2] - 1, a[2] - 1, a[3] - 1, a[3] - 1, a[3] - 1, a[3] - 1];
+
+      let first = blend_linear_neon(color, vld1q_u16(dst), vld1q_u16(first_coverages.as_ptr()));
+      let second = blend_linear_neon(color, vld1q_u16(dst.offset(8)), vld1q_u16(second_coverages.as_ptr()));
+
+      vst1q_u16(dst, first);
+      vst1q_u16(dst.offset(8), second);
+
+      dst = dst.offset(16);
+      src = src.offset((4 * n) as isize);
+   }
+
+   count * 4
+}
@@ -28,3 +28,119 @@ impl fmt::Debug for RGB {
       write!(f, "(rgb! {} {} {})", self.r, self.g, self.b)
    }
 }
+
+
+// Separable blend modes of the compositing spec
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlendMode {
+   Normal,
+   Multiply,
+   Screen,
+   Overlay,
+   Darken,
+   Lighten,
+   Add,
+}
+
+impl BlendMode {
+   pub fn from_name(name: &str) -> Option<Self> {
+      match name {
+         "normal" => Some(BlendMode::Normal),
+         "multiply" => Some(BlendMode::Multiply),
+         "screen" => Some(BlendMode::Screen),
+         "overlay" => Some(BlendMode::Overlay),
+         "darken" => Some(BlendMode::Darken),
+         "lighten" => Some(BlendMode::Lighten),
+         "add" => Some(BlendMode::Add),
+         _ => None,
+      }
+   }
+
+   // Backdrop and source channels in the 0 to 1 range
+   #[inline]
+   pub fn blend(&self, backdrop: f64, source: f64) -> f64 {
+      match *self {
+         BlendMode::Normal => source,
+         BlendMode::Multiply => backdrop * source,
+         BlendMode::Screen => backdrop + source - backdrop * source,
+         BlendMode::Overlay => if backdrop <= 0.5 {
+            2. * backdrop * source
+         } else {
+            1. - 2. * (1. - backdrop) * (1. - source)
+         },
+         BlendMode::Darken => backdrop.min(source),
+         BlendMode::Lighten => backdrop.max(source),
+         BlendMode::Add => (backdrop + source).min(1.),
+      }
+   }
+
+   // Opaque `source` blended over an opaque backdrop with `opacity`
+   pub fn mix(&self, backdrop: &RGB, source: &RGB, opacity: f64) -> RGB {
+      let channel = |b: u8, s: u8| {
+         let b = b as f64 / 255.;
+         let blended = self.blend(b, s as f64 / 255.);
+
+         ((b + (blended - b) * opacity) * 255.).round() as u8
+      };
+
+      RGB::new(
+         channel(backdrop.r, source.r),
+         channel(backdrop.g, source.g),
+         channel(backdrop.b, source.b),
+      )
+   }
+}
+
+impl Default for BlendMode {
+   fn default() -> BlendMode {
+      BlendMode::Normal
+   }
+}
+
+
+// Whether the rasterizers blend coverage and layers on the sRGB encoded
+// bytes or in linear light. The encoded bytes stay the default so existing
+// scenes render as they always have
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Blending {
+   Srgb,
+   Linear,
+}
+
+impl Default for Blending {
+   fn default() -> Blending {
+      Blending::Srgb
+   }
+}
+
+
+// Horizontal order of the LCD subpixels the edges get anti-aliased for,
+// None keeping the grayscale anti-aliasing
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Subpixel {
+   None,
+   Rgb,
+   Bgr,
+}
+
+impl Subpixel {
+   // Index of the third of a pixel each of the red, green and blue
+   // channels is lit by
+   #[inline]
+   pub fn thirds(&self) -> [usize; 3] {
+      match *self {
+         Subpixel::Bgr => [2, 1, 0],
+         _ => [0, 1, 2],
+      }
+   }
+}
+
+impl Default for Subpixel {
+   fn default() -> Subpixel {
+      Subpixel::None
+   }
+}
+
+// Weights of the FIR filter over neighbouring thirds of a pixel, trading
+// some of the subpixel sharpness for fainter color fringes
+pub const SUBPIXEL_FILTER: [u32; 5] = [1, 2, 3, 2, 1];
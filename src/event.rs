@@ -1,3 +1,6 @@
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct WindowId(pub usize);
+
 #[derive(Clone, Debug)]
 pub enum Event {
    Quit,
@@ -1,38 +1,121 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
 use error::VoidResult;
 use application::OsApplication;
 use renderer::Renderer;
 use frame::Frame;
-use event::Event;
+use event::{Event, WindowId};
+use clipboard::Clipboard;
+use clock::{FrameClock, seconds, dropped_frames};
+use monitor::primary_monitor;
 
 pub struct EventLoop<'a> {
-   pub os_application: &'a OsApplication,
+   pub os_application: &'a mut OsApplication,
+   pub clipboard: Option<&'a Clipboard>,
+   pub target_fps: Option<u32>,
+   pub vsync: bool,
 }
 
 impl<'a> EventLoop<'a> {
-   pub fn new(os_application: &'a OsApplication) -> Self {
+   pub fn new(
+      os_application: &'a mut OsApplication,
+      clipboard: Option<&'a Clipboard>,
+      target_fps: Option<u32>,
+      vsync: bool,
+   ) -> Self {
       EventLoop {
          os_application: os_application,
+         clipboard: clipboard,
+         target_fps: target_fps,
+         vsync: vsync,
+      }
+   }
+
+   fn frame_interval(&self) -> Option<f64> {
+      match self.target_fps {
+         Some(fps) if fps > 0 => return Some(1. / fps as f64),
+         _ => {}
+      }
+
+      if !self.vsync {
+         return None;
+      }
+
+      match primary_monitor(self.os_application.monitors()) {
+         Some(monitor) if monitor.refresh_rate > 0. => Some(1. / monitor.refresh_rate),
+         _ => None
       }
    }
 
-   pub fn run(&self, renderer: &mut Renderer, width: u32, height: u32) -> VoidResult {
-      renderer.init(width, height);
+   pub fn run(&mut self, renderers: &mut [&mut Renderer], sizes: &[(u32, u32)]) -> VoidResult {
+      let mut frames = Vec::with_capacity(renderers.len());
+      let mut scale_factors = Vec::with_capacity(renderers.len());
+      let mut clocks = Vec::with_capacity(renderers.len());
+
+      let start = Instant::now();
+
+      let frame_interval = self.frame_interval();
+
+      // Every swap with interval 1 waits for a vblank, so only one window syncs
+      let mut vsync_index = if self.vsync { Some(0) } else { None };
+
+      for (index, renderer) in renderers.iter_mut().enumerate() {
+         let window_id = WindowId(index);
+         let (width, height) = sizes[index];
+
+         renderer.init(width, height);
+
+         let scale_factor = try!(self.os_application.scale_factor(window_id));
+
+         if scale_factor != 1. {
+            renderer.scale_factor_changed(scale_factor);
+         }
 
-      let gpu_frame = try!(self.os_application.create_gpu_frame(width, height));
+         scale_factors.push(scale_factor);
 
-      let mut frame = try!(Frame::new(width, height, gpu_frame));
+         try!(self.os_application.make_current(window_id));
 
-      let mut quit = false;
+         let swap_interval = if vsync_index == Some(index) { 1 } else { 0 };
+
+         // Not every driver lets us control the swap interval
+         let _ = self.os_application.set_swap_interval(window_id, swap_interval);
+
+         let gpu_frame = try!(self.os_application.create_gpu_frame(window_id, width, height));
+
+         frames.push(Some(try!(Frame::new(width, height, gpu_frame))));
+         clocks.push(FrameClock::new(start));
+      }
+
+      let mut next_tick = Instant::now();
 
       loop {
-         let mut new_width = frame.width;
-         let mut new_height = frame.height;
+         let mut new_sizes: Vec<(u32, u32)> = frames.iter().map(|frame| match *frame {
+            Some(ref frame) => (frame.width, frame.height),
+            None => (0, 0)
+         }).collect();
+
+         let mut configured = vec![false; frames.len()];
+
+         let mut closed = Vec::new();
+
+         if let Some(clipboard) = self.clipboard {
+            try!(clipboard.process_events());
+         }
+
+         for (window_id, event) in self.os_application.poll_events() {
+            let index = window_id.0;
+
+            if index >= renderers.len() || frames[index].is_none() || closed.contains(&index) {
+               continue;
+            }
+
+            let renderer = &mut *renderers[index];
 
-         for event in self.os_application.poll_events() {
             match event {
                Event::Resized(width, height) => {
-                  new_width = width;
-                  new_height = height;
+                  new_sizes[index] = (width, height);
+                  configured[index] = true;
                },
 
                Event::MouseMoved(x, y) => {
@@ -40,8 +123,7 @@ impl<'a> EventLoop<'a> {
                },
 
                Event::Quit => {
-                  quit = true;
-                  break
+                  closed.push(index);
                },
 
                _ => {
@@ -50,23 +132,108 @@ impl<'a> EventLoop<'a> {
             }
          }
 
-         if quit {
+         for &index in &closed {
+            try!(self.close_window(&mut frames, index));
+
+            if vsync_index == Some(index) {
+               vsync_index = frames.iter().position(|frame| frame.is_some());
+
+               if let Some(index) = vsync_index {
+                  let _ = self.os_application.set_swap_interval(WindowId(index), 1);
+               }
+            }
+         }
+
+         if frames.iter().all(|frame| frame.is_none()) {
             break
          }
 
-         if new_width != frame.width || new_height != frame.height {
-            try!(frame.resize(new_width, new_height));
-            renderer.resized(new_width, new_height);
+         for index in 0..frames.len() {
+            let window_id = WindowId(index);
+            let renderer = &mut *renderers[index];
+
+            let frame = match frames[index] {
+               Some(ref mut frame) => frame,
+               None => continue
+            };
+
+            try!(self.os_application.make_current(window_id));
+
+            if configured[index] {
+               // The window may have been moved onto a monitor with other DPI
+               let scale_factor = try!(self.os_application.scale_factor(window_id));
+
+               if scale_factor != scale_factors[index] {
+                  scale_factors[index] = scale_factor;
+                  renderer.scale_factor_changed(scale_factor);
+               }
+            }
+
+            let (new_width, new_height) = new_sizes[index];
+
+            if new_width != frame.width || new_height != frame.height {
+               try!(frame.resize(new_width, new_height));
+               renderer.resized(new_width, new_height);
+            }
+
+            let clock = &mut clocks[index];
+
+            let render_start = Instant::now();
+
+            clock.tick(render_start);
+
+            try!(frame.render(renderer, clock));
+
+            let present_start = Instant::now();
+
+            try!(self.os_application.swap_buffers(window_id));
+
+            let present_end = Instant::now();
+
+            let dropped = if clock.frame > 0 {
+               dropped_frames(clock.delta, frame_interval)
+            } else {
+               0
+            };
+
+            clock.stats.push(
+               clock.delta,
+               seconds(present_start.duration_since(render_start)),
+               seconds(present_end.duration_since(present_start)),
+               dropped
+            );
          }
 
-         try!(frame.render(renderer));
+         if let Some(fps) = self.target_fps {
+            if fps > 0 {
+               next_tick += Duration::new(0, 1_000_000_000 / fps);
 
-         try!(self.os_application.swap_buffers());
+               let now = Instant::now();
+
+               if next_tick > now {
+                  thread::sleep(next_tick.duration_since(now));
+               } else {
+                  // Running behind, do not try to catch up with a burst of frames
+                  next_tick = now;
+               }
+            }
+         }
       }
 
       Ok(())
    }
 
+   fn close_window(&mut self, frames: &mut [Option<Frame>], index: usize) -> VoidResult {
+      let window_id = WindowId(index);
+
+      // The frame holds GL objects of the window's context
+      try!(self.os_application.make_current(window_id));
+
+      frames[index] = None;
+
+      self.os_application.destroy_window(window_id)
+   }
+
    #[inline]
    fn match_more_events(&self, event: Event, renderer: &mut Renderer) {
       match event {
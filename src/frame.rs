@@ -1,6 +1,7 @@
 use error::{RuntimeError, VoidResult};
 use draw::RGB;
 use renderer::Renderer;
+use clock::FrameClock;
 
 
 pub trait GPUFrame {
@@ -12,6 +13,8 @@ pub trait GPUFrame {
 
    fn put_pixel(&mut self, x: i32, y: i32, color: &RGB, width: u32, height: u32);
 
+   fn get_pixel(&self, x: i32, y: i32, width: u32, height: u32) -> RGB;
+
    fn ptr_mut(&mut self) -> *mut u8;
 
    fn resize(&mut self, width: u32, height: u32) -> VoidResult;
@@ -51,6 +54,11 @@ impl Frame {
       self.gpu_frame.put_pixel(x, y, color, self.width, self.height);
    }
 
+   #[inline]
+   pub fn get_pixel(&self, x: i32, y: i32) -> RGB {
+      self.gpu_frame.get_pixel(x, y, self.width, self.height)
+   }
+
    #[inline]
    pub fn ptr_mut(&mut self) -> *mut u8 {
       self.gpu_frame.ptr_mut()
@@ -65,10 +73,10 @@ impl Frame {
    }
 
    #[inline]
-   pub fn render(&mut self, renderer: &mut Renderer) -> VoidResult {
+   pub fn render(&mut self, renderer: &mut Renderer, clock: &FrameClock) -> VoidResult {
       try!(self.gpu_frame.pre_render());
 
-      renderer.render(self);
+      renderer.render(self, clock);
 
       self.gpu_frame.post_render(self.width, self.height)
    }
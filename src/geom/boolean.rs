@@ -0,0 +1,957 @@
+use std::f64;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use data::FloatPoint;
+use geom::path::{Path, PathSegment, Arc, quad_piece, cubic_piece};
+use geom::flatten::Flattener;
+
+
+// Points closer than this are merged into one vertex
+pub const SNAP_DISTANCE: f64 = 1e-6;
+
+const EPSILON: f64 = 1e-9;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+   Union,
+   Intersection,
+   Difference,
+   Xor,
+}
+
+impl BooleanOp {
+   #[inline]
+   pub fn apply(&self, a: bool, b: bool) -> bool {
+      match *self {
+         BooleanOp::Union => a || b,
+         BooleanOp::Intersection => a && b,
+         BooleanOp::Difference => a && !b,
+         BooleanOp::Xor => a != b,
+      }
+   }
+}
+
+
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+   p1: FloatPoint,
+   p2: FloatPoint,
+   operand: usize,
+}
+
+
+// Sub edges with the same end points, counted per operand
+#[derive(Debug, Clone, Copy)]
+struct Group {
+   v1: usize,
+   v2: usize,
+   count: [usize; 2],
+}
+
+impl Group {
+   #[inline]
+   fn is_boundary(&self, operand: usize) -> bool {
+      self.count[operand] % 2 == 1
+   }
+}
+
+
+struct Vertices {
+   points: Vec<FloatPoint>,
+   cells: HashMap<(i64, i64), usize>,
+}
+
+impl Vertices {
+   fn new() -> Self {
+      Vertices {
+         points: Vec::new(),
+         cells: HashMap::new(),
+      }
+   }
+
+   // Points falling in the same or a neighbouring cell are merged, so that
+   // intersections computed from different edge pairs end up identical
+   fn insert(&mut self, point: FloatPoint) -> usize {
+      let cx = (point.x / SNAP_DISTANCE).round() as i64;
+      let cy = (point.y / SNAP_DISTANCE).round() as i64;
+
+      for dy in -1..2 {
+         for dx in -1..2 {
+            if let Some(&index) = self.cells.get(&(cx + dx, cy + dy)) {
+               return index;
+            }
+         }
+      }
+
+      self.points.push(point);
+
+      let index = self.points.len() - 1;
+
+      self.cells.insert((cx, cy), index);
+
+      index
+   }
+}
+
+
+// Boolean operation on two sets of contours, both interpreted with the
+// even-odd rule like devel::Poly. Outer contours of the result have the
+// orientation Rasterizer::double_area expects and holes the opposite one
+pub fn boolean(
+   op: BooleanOp, a: &[Vec<FloatPoint>], b: &[Vec<FloatPoint>]
+) -> Vec<Vec<FloatPoint>> {
+   let mut edges = Vec::new();
+
+   collect_edges(a, 0, &mut edges);
+   collect_edges(b, 1, &mut edges);
+
+   let mut vertices = Vertices::new();
+
+   let groups = split_edges(&edges, &mut vertices);
+
+   let points = &vertices.points;
+
+   let parities = ray_parities(&groups, points);
+
+   let mut outgoing = vec![Vec::new(); points.len()];
+   let mut result_edges = Vec::new();
+
+   for (index, group) in groups.iter().enumerate() {
+      if !group.is_boundary(0) && !group.is_boundary(1) {
+         continue;
+      }
+
+      let (ray_inside, ray_on_left) = parities[index];
+
+      let other_inside = [
+         ray_inside[0] != group.is_boundary(0),
+         ray_inside[1] != group.is_boundary(1),
+      ];
+
+      let ray_result = op.apply(ray_inside[0], ray_inside[1]);
+      let other_result = op.apply(other_inside[0], other_inside[1]);
+
+      if ray_result == other_result {
+         continue;
+      }
+
+      let left_result = if ray_on_left { ray_result } else { other_result };
+
+      // Filled area goes opposite to the normal, as in the raster polys
+      let (from, to) = if left_result {
+         (group.v2, group.v1)
+      } else {
+         (group.v1, group.v2)
+      };
+
+      outgoing[from].push(result_edges.len());
+      result_edges.push((from, to));
+   }
+
+   chain_contours(points, &result_edges, &outgoing)
+}
+
+
+// Flattened outline of a path operand. Every point also holds the source
+// of the chord leaving it, the segment index and parameter range of the
+// curve it was flattened from
+struct Outline<'a> {
+   segments: &'a [PathSegment],
+   starts: Vec<FloatPoint>,
+   contours: Vec<Vec<FloatPoint>>,
+   chords: Vec<Vec<Option<(usize, f64, f64)>>>,
+}
+
+impl<'a> Outline<'a> {
+   fn new(path: &'a Path, flattener: &Flattener) -> Self {
+      let segments = path.segments();
+
+      let mut starts = Vec::with_capacity(segments.len());
+
+      let mut last = FloatPoint::default();
+
+      for segment in segments.iter() {
+         starts.push(match *segment {
+            PathSegment::MoveTo(p) => p,
+            _ => last,
+         });
+
+         if let Some(end) = segment.end() {
+            last = end;
+         }
+      }
+
+      let mut contours = Vec::new();
+      let mut chords = Vec::new();
+
+      for tagged in path.flatten_params(flattener) {
+         let mut contour: Vec<FloatPoint> = Vec::with_capacity(tagged.len());
+         let mut contour_chords = Vec::with_capacity(tagged.len());
+
+         for (i, &(point, segment, t)) in tagged.iter().enumerate() {
+            // The chord into a point is part of the curve it was generated by,
+            // lines need no source
+            let chord = match segments[segment] {
+               PathSegment::QuadTo(..) | PathSegment::CubicTo(..) => {
+                  let (_, previous_segment, previous_t) = tagged[i - 1];
+
+                  let t1 = if previous_segment == segment { previous_t } else { 0. };
+
+                  Some((segment, t1, t))
+               },
+               PathSegment::ArcTo(ref arc) => {
+                  let (_, previous_segment, previous_t) = tagged[i - 1];
+
+                  let t1 = if previous_segment == segment { previous_t } else { arc.start };
+
+                  Some((segment, t1, t))
+               },
+               _ => None,
+            };
+
+            // Chords shorter than the snap distance get merged away
+            match contour.last() {
+               Some(&last) if length(point - last) <= SNAP_DISTANCE => {
+                  continue;
+               },
+               Some(_) => {
+                  *contour_chords.last_mut().unwrap() = chord;
+               },
+               None => {},
+            }
+
+            contour.push(point);
+            contour_chords.push(None);
+         }
+
+         if contour.len() > 1 && length(contour[0] - contour[contour.len() - 1]) <= SNAP_DISTANCE {
+            contour.pop();
+            contour_chords.pop();
+         }
+
+         if contour.len() > 2 {
+            contours.push(contour);
+            chords.push(contour_chords);
+         }
+      }
+
+      Outline {
+         segments: segments,
+         starts: starts,
+         contours: contours,
+         chords: chords,
+      }
+   }
+}
+
+
+// Boolean operation on two paths. The polygon operation runs on their
+// flattened outlines, after which every run of whole chords of one curve is
+// put back as that part of the curve, so only the chords cut by the other
+// operand stay straight
+pub fn boolean_path(op: BooleanOp, a: &Path, b: &Path, flattener: &Flattener) -> Path {
+   let outlines = [Outline::new(a, flattener), Outline::new(b, flattener)];
+
+   let mut sources: HashMap<(u64, u64), Vec<(usize, usize, usize)>> = HashMap::new();
+
+   for (operand, outline) in outlines.iter().enumerate() {
+      for (contour_index, contour) in outline.contours.iter().enumerate() {
+         for (index, point) in contour.iter().enumerate() {
+            sources.entry(point_key(*point)).or_insert_with(Vec::new).push(
+               (operand, contour_index, index)
+            );
+         }
+      }
+   }
+
+   // Curve source of the result edge from `p1` to `p2`, if it is a whole
+   // chord of an operand outline
+   let chord = |p1: FloatPoint, p2: FloatPoint| -> Option<(usize, usize, f64, f64)> {
+      let (sources1, sources2) = match (sources.get(&point_key(p1)), sources.get(&point_key(p2))) {
+         (Some(sources1), Some(sources2)) => (sources1, sources2),
+         _ => return None,
+      };
+
+      for &(operand1, contour1, index1) in sources1.iter() {
+         for &(operand2, contour2, index2) in sources2.iter() {
+            if operand1 != operand2 || contour1 != contour2 {
+               continue;
+            }
+
+            let ref chords = outlines[operand1].chords[contour1];
+
+            let len = chords.len();
+
+            if index2 == (index1 + 1) % len {
+               return chords[index1].map(|(segment, t1, t2)| (operand1, segment, t1, t2));
+            }
+
+            if index1 == (index2 + 1) % len {
+               return chords[index2].map(|(segment, t1, t2)| (operand1, segment, t2, t1));
+            }
+         }
+      }
+
+      None
+   };
+
+   let a_contours = &outlines[0].contours;
+   let b_contours = &outlines[1].contours;
+
+   let mut path = Path::new();
+
+   for contour in boolean(op, a_contours, b_contours) {
+      let len = contour.len();
+
+      path.push(PathSegment::MoveTo(contour[0]));
+
+      let mut i = 0;
+
+      while i < len {
+         let source = chord(contour[i], contour[(i + 1) % len]);
+
+         let (operand, segment, t1, mut t2) = match source {
+            Some(source) => source,
+            None => {
+               path.push(PathSegment::LineTo(contour[(i + 1) % len]));
+               i += 1;
+               continue;
+            }
+         };
+
+         while i + 1 < len {
+            match chord(contour[i + 1], contour[(i + 2) % len]) {
+               Some((next_operand, next_segment, next_t1, next_t2))
+                  if next_operand == operand && next_segment == segment && next_t1 == t2 => {
+                  t2 = next_t2;
+                  i += 1;
+               },
+               _ => break,
+            }
+         }
+
+         i += 1;
+
+         let end = contour[i % len];
+
+         let ref outline = outlines[operand];
+
+         let from = outline.starts[segment];
+
+         path.push(match outline.segments[segment] {
+            PathSegment::QuadTo(c, p) => {
+               let (_, c, _) = quad_piece(from, c, p, t1, t2);
+
+               PathSegment::QuadTo(c, end)
+            },
+            PathSegment::CubicTo(c1, c2, p) => {
+               let (_, c1, c2, _) = cubic_piece(from, c1, c2, p, t1, t2);
+
+               PathSegment::CubicTo(c1, c2, end)
+            },
+            PathSegment::ArcTo(ref arc) => {
+               PathSegment::ArcTo(Arc::new(arc.center, arc.x_axis, arc.y_axis, t1, t2 - t1))
+            },
+            _ => PathSegment::LineTo(end),
+         });
+      }
+
+      path.push(PathSegment::Close);
+   }
+
+   path
+}
+
+
+#[inline]
+fn point_key(point: FloatPoint) -> (u64, u64) {
+   (point.x.to_bits(), point.y.to_bits())
+}
+
+
+#[inline]
+pub fn union(a: &[Vec<FloatPoint>], b: &[Vec<FloatPoint>]) -> Vec<Vec<FloatPoint>> {
+   boolean(BooleanOp::Union, a, b)
+}
+
+
+#[inline]
+pub fn intersection(a: &[Vec<FloatPoint>], b: &[Vec<FloatPoint>]) -> Vec<Vec<FloatPoint>> {
+   boolean(BooleanOp::Intersection, a, b)
+}
+
+
+#[inline]
+pub fn difference(a: &[Vec<FloatPoint>], b: &[Vec<FloatPoint>]) -> Vec<Vec<FloatPoint>> {
+   boolean(BooleanOp::Difference, a, b)
+}
+
+
+#[inline]
+pub fn xor(a: &[Vec<FloatPoint>], b: &[Vec<FloatPoint>]) -> Vec<Vec<FloatPoint>> {
+   boolean(BooleanOp::Xor, a, b)
+}
+
+
+// Doubled signed area, positive for outer contours of boolean results
+pub fn double_area(contour: &[FloatPoint]) -> f64 {
+   let mut area = 0.;
+
+   let mut last = match contour.last() {
+      Some(point) => *point,
+      None => return 0.,
+   };
+
+   for point in contour.iter() {
+      area += (point.x - last.x) * (point.y + last.y);
+
+      last = *point;
+   }
+
+   area
+}
+
+
+// Points with NaN or infinite coordinates, which scripts can produce,
+// are left out of their contours
+fn collect_edges(contours: &[Vec<FloatPoint>], operand: usize, edges: &mut Vec<Edge>) {
+   for contour in contours.iter() {
+      let mut last = match contour.iter().rev().find(|point| is_finite(point)) {
+         Some(point) => *point,
+         None => continue,
+      };
+
+      for point in contour.iter().filter(|point| is_finite(point)) {
+         if point.x != last.x || point.y != last.y {
+            edges.push(Edge {
+               p1: last,
+               p2: *point,
+               operand: operand,
+            });
+         }
+
+         last = *point;
+      }
+   }
+}
+
+
+// Splits the edges at all intersections and overlaps and groups the
+// resulting sub edges by their end points
+fn split_edges(edges: &[Edge], vertices: &mut Vertices) -> Vec<Group> {
+   let mut splits: Vec<Vec<(f64, FloatPoint)>> = vec![Vec::new(); edges.len()];
+
+   let mut order: Vec<usize> = (0..edges.len()).collect();
+
+   order.sort_by(|&i, &j| {
+      min_x(&edges[i]).partial_cmp(&min_x(&edges[j])).unwrap_or(Ordering::Equal)
+   });
+
+   for (n, &i) in order.iter().enumerate() {
+      let max_x_i = max_x(&edges[i]) + SNAP_DISTANCE;
+
+      for &j in order[n + 1..].iter() {
+         if min_x(&edges[j]) > max_x_i {
+            break;
+         }
+
+         intersect(&edges[i], &edges[j], i, j, &mut splits);
+      }
+   }
+
+   let mut groups: Vec<Group> = Vec::new();
+   let mut group_indices = HashMap::new();
+
+   for (edge, edge_splits) in edges.iter().zip(splits.iter_mut()) {
+      edge_splits.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+      let mut last = vertices.insert(edge.p1);
+
+      let ends = edge_splits.iter().map(|&(_, point)| point).chain(Some(edge.p2));
+
+      for point in ends {
+         let current = vertices.insert(point);
+
+         if current == last {
+            continue;
+         }
+
+         let key = if last < current { (last, current) } else { (current, last) };
+
+         let index = *group_indices.entry(key).or_insert_with(|| {
+            groups.push(Group {
+               v1: key.0,
+               v2: key.1,
+               count: [0, 0],
+            });
+
+            groups.len() - 1
+         });
+
+         groups[index].count[edge.operand] += 1;
+
+         last = current;
+      }
+   }
+
+   groups
+}
+
+
+fn intersect(
+   a: &Edge, b: &Edge, ia: usize, ib: usize, splits: &mut Vec<Vec<(f64, FloatPoint)>>
+) {
+   if min_y(a) > max_y(b) + SNAP_DISTANCE || min_y(b) > max_y(a) + SNAP_DISTANCE {
+      return;
+   }
+
+   let r = a.p2 - a.p1;
+   let s = b.p2 - b.p1;
+
+   let r_len = length(r);
+   let s_len = length(s);
+
+   let denom = cross(r, s);
+
+   let ta_eps = SNAP_DISTANCE / r_len;
+   let tb_eps = SNAP_DISTANCE / s_len;
+
+   if denom.abs() <= EPSILON * r_len * s_len {
+      // Parallel edges only matter when they overlap
+      if cross(b.p1 - a.p1, r).abs() > SNAP_DISTANCE * r_len ||
+         cross(b.p2 - a.p1, r).abs() > SNAP_DISTANCE * r_len {
+         return;
+      }
+
+      for &point in [b.p1, b.p2].iter() {
+         let t = dot(point - a.p1, r) / (r_len * r_len);
+
+         if t > ta_eps && t < 1. - ta_eps {
+            splits[ia].push((t, point));
+         }
+      }
+
+      for &point in [a.p1, a.p2].iter() {
+         let t = dot(point - b.p1, s) / (s_len * s_len);
+
+         if t > tb_eps && t < 1. - tb_eps {
+            splits[ib].push((t, point));
+         }
+      }
+
+      return;
+   }
+
+   let qp = b.p1 - a.p1;
+
+   let ta = cross(qp, s) / denom;
+   let tb = cross(qp, r) / denom;
+
+   if ta < -ta_eps || ta > 1. + ta_eps || tb < -tb_eps || tb > 1. + tb_eps {
+      return;
+   }
+
+   let point = if ta <= ta_eps {
+      a.p1
+   } else if ta >= 1. - ta_eps {
+      a.p2
+   } else if tb <= tb_eps {
+      b.p1
+   } else if tb >= 1. - tb_eps {
+      b.p2
+   } else {
+      a.p1 + r * ta
+   };
+
+   if ta > ta_eps && ta < 1. - ta_eps {
+      splits[ia].push((ta, point));
+   }
+
+   if tb > tb_eps && tb < 1. - tb_eps {
+      splits[ib].push((tb, point));
+   }
+}
+
+
+// Inside state of both operands next to the middle of every boundary group,
+// found by casting a ray that avoids the group itself. Also gives whether the
+// ray leaves on the left side of the group, with left meaning the direction
+// of the normal (-dy, dx). Horizontal groups cast a vertical ray, everything
+// else a horizontal one
+fn ray_parities(groups: &[Group], points: &[FloatPoint]) -> Vec<([bool; 2], bool)> {
+   let mut parities = vec![([false, false], false); groups.len()];
+
+   let mut hori_rays = Vec::new();
+   let mut vert_rays = Vec::new();
+
+   for (index, group) in groups.iter().enumerate() {
+      if !group.is_boundary(0) && !group.is_boundary(1) {
+         continue;
+      }
+
+      let p1 = points[group.v1];
+      let p2 = points[group.v2];
+
+      if p1.y == p2.y {
+         parities[index].1 = p2.x > p1.x;
+         vert_rays.push(index);
+      } else {
+         parities[index].1 = p2.y < p1.y;
+         hori_rays.push(index);
+      }
+   }
+
+   sweep_rays(groups, points, hori_rays, false, &mut parities);
+   sweep_rays(groups, points, vert_rays, true, &mut parities);
+
+   parities
+}
+
+
+// Casts the rays in the order of their position along the sweep axis, only
+// testing them against the groups spanning that position
+fn sweep_rays(
+   groups: &[Group],
+   points: &[FloatPoint],
+   mut rays: Vec<usize>,
+   vertical: bool,
+   parities: &mut [([bool; 2], bool)]
+) {
+   // Coordinates along the sweep axis and along the ray
+   let coords = |point: FloatPoint| if vertical { (point.x, point.y) } else { (point.y, point.x) };
+
+   let mid = |index: usize| {
+      let ref group = groups[index];
+      coords((points[group.v1] + points[group.v2]) / 2.)
+   };
+
+   let span = |index: usize| {
+      let ref group = groups[index];
+      let a1 = coords(points[group.v1]).0;
+      let a2 = coords(points[group.v2]).0;
+      (a1.min(a2), a1.max(a2))
+   };
+
+   rays.sort_by(|&i, &j| mid(i).0.partial_cmp(&mid(j).0).unwrap_or(Ordering::Equal));
+
+   let mut order: Vec<usize> = (0..groups.len()).collect();
+
+   order.sort_by(|&i, &j| span(i).0.partial_cmp(&span(j).0).unwrap_or(Ordering::Equal));
+
+   let mut next = 0;
+   let mut active: Vec<usize> = Vec::new();
+
+   for &index in rays.iter() {
+      let (mid_a, mid_b) = mid(index);
+
+      while next < order.len() && span(order[next]).0 <= mid_a {
+         active.push(order[next]);
+         next += 1;
+      }
+
+      active.retain(|&other| span(other).1 > mid_a);
+
+      let mut inside = [false, false];
+
+      for &other_index in active.iter() {
+         if other_index == index {
+            continue;
+         }
+
+         let ref other = groups[other_index];
+
+         let (q1a, q1b) = coords(points[other.v1]);
+         let (q2a, q2b) = coords(points[other.v2]);
+
+         if q1b + (mid_a - q1a) * (q2b - q1b) / (q2a - q1a) > mid_b {
+            for operand in 0..2 {
+               if other.is_boundary(operand) {
+                  inside[operand] = !inside[operand];
+               }
+            }
+         }
+      }
+
+      parities[index].0 = inside;
+   }
+}
+
+
+// Links the result edges into closed contours. Where several edges leave a
+// vertex, the one turning the most towards the filled side is taken, which
+// keeps contours touching in a single point apart
+fn chain_contours(
+   points: &[FloatPoint], edges: &[(usize, usize)], outgoing: &[Vec<usize>]
+) -> Vec<Vec<FloatPoint>> {
+   let mut used = vec![false; edges.len()];
+
+   let mut contours = Vec::new();
+
+   for first in 0..edges.len() {
+      if used[first] {
+         continue;
+      }
+
+      used[first] = true;
+
+      let start = edges[first].0;
+
+      let mut contour = vec![start];
+
+      let mut from = start;
+      let mut current = edges[first].1;
+
+      while current != start {
+         contour.push(current);
+
+         let incoming = points[current] - points[from];
+
+         let mut next = None;
+         let mut next_angle = f64::MAX;
+
+         for &candidate in outgoing[current].iter() {
+            if used[candidate] {
+               continue;
+            }
+
+            let out = points[edges[candidate].1] - points[current];
+
+            let angle = cross(incoming, out).atan2(dot(incoming, out));
+
+            if angle < next_angle {
+               next = Some(candidate);
+               next_angle = angle;
+            }
+         }
+
+         match next {
+            Some(edge) => {
+               used[edge] = true;
+
+               from = current;
+               current = edges[edge].1;
+            },
+            None => break,
+         }
+      }
+
+      let contour = simplify(contour.iter().map(|&vertex| points[vertex]).collect());
+
+      if contour.len() >= 3 {
+         contours.push(contour);
+      }
+   }
+
+   contours
+}
+
+
+// Drops vertices lying on a straight line between their neighbours
+fn simplify(mut contour: Vec<FloatPoint>) -> Vec<FloatPoint> {
+   let mut i = 0;
+
+   while i < contour.len() && contour.len() >= 3 {
+      let len = contour.len();
+
+      let prev = contour[(i + len - 1) % len];
+      let next = contour[(i + 1) % len];
+
+      let d1 = contour[i] - prev;
+      let d2 = next - contour[i];
+
+      if cross(d1, d2).abs() <= EPSILON * length(d1) * length(d2) && dot(d1, d2) > 0. {
+         contour.remove(i);
+
+         if i > 0 {
+            i -= 1;
+         }
+      } else {
+         i += 1;
+      }
+   }
+
+   contour
+}
+
+
+#[inline]
+fn cross(a: FloatPoint, b: FloatPoint) -> f64 {
+   a.x * b.y - a.y * b.x
+}
+
+
+#[inline]
+fn dot(a: FloatPoint, b: FloatPoint) -> f64 {
+   a.x * b.x + a.y * b.y
+}
+
+
+#[inline]
+fn length(a: FloatPoint) -> f64 {
+   a.x.hypot(a.y)
+}
+
+
+#[inline]
+fn is_finite(point: &FloatPoint) -> bool {
+   point.x.is_finite() && point.y.is_finite()
+}
+
+
+#[inline]
+fn min_x(edge: &Edge) -> f64 {
+   edge.p1.x.min(edge.p2.x)
+}
+
+
+#[inline]
+fn max_x(edge: &Edge) -> f64 {
+   edge.p1.x.max(edge.p2.x)
+}
+
+
+#[inline]
+fn min_y(edge: &Edge) -> f64 {
+   edge.p1.y.min(edge.p2.y)
+}
+
+
+#[inline]
+fn max_y(edge: &Edge) -> f64 {
+   edge.p1.y.max(edge.p2.y)
+}
+
+
+#[cfg(test)]
+mod tests {
+   use data::FloatPoint;
+
+   use super::*;
+
+   fn rect(x1: f64, y1: f64, x2: f64, y2: f64) -> Vec<FloatPoint> {
+      vec![
+         FloatPoint::new(x1, y1),
+         FloatPoint::new(x2, y1),
+         FloatPoint::new(x2, y2),
+         FloatPoint::new(x1, y2),
+      ]
+   }
+
+   fn area(contours: &[Vec<FloatPoint>]) -> f64 {
+      contours.iter().map(|contour| double_area(contour)).sum::<f64>() / 2.
+   }
+
+   #[test]
+   fn test_overlapping_rects() {
+      let a = vec![rect(0., 0., 10., 10.)];
+      let b = vec![rect(5., 5., 15., 15.)];
+
+      let result = union(&a, &b);
+      assert_eq!(result.len(), 1);
+      assert_eq!(result[0].len(), 8);
+      assert!((area(&result) - 175.).abs() < 1e-9);
+
+      let result = intersection(&a, &b);
+      assert_eq!(result.len(), 1);
+      assert_eq!(result[0].len(), 4);
+      assert!((area(&result) - 25.).abs() < 1e-9);
+
+      assert!((area(&difference(&a, &b)) - 75.).abs() < 1e-9);
+      assert!((area(&xor(&a, &b)) - 150.).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_holes() {
+      let a = vec![rect(0., 0., 10., 10.)];
+      let b = vec![rect(3., 3., 7., 7.)];
+
+      let result = difference(&a, &b);
+      assert_eq!(result.len(), 2);
+      assert!((area(&result) - 84.).abs() < 1e-9);
+
+      // Hole contours of the operand keep the hole open
+      let ring = vec![rect(0., 0., 10., 10.), rect(3., 3., 7., 7.)];
+
+      assert!((area(&union(&ring, &vec![rect(4., 4., 6., 6.)])) - 88.).abs() < 1e-9);
+      assert!((area(&intersection(&ring, &b)) - 0.).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_coincident_edges() {
+      let a = vec![rect(0., 0., 10., 10.)];
+      let b = vec![rect(10., 0., 20., 10.)];
+
+      let result = union(&a, &b);
+      assert_eq!(result.len(), 1);
+      assert_eq!(result[0].len(), 4);
+      assert!((area(&result) - 200.).abs() < 1e-9);
+
+      assert_eq!(intersection(&a, &b).len(), 0);
+
+      let result = intersection(&a, &a);
+      assert_eq!(result.len(), 1);
+      assert!((area(&result) - 100.).abs() < 1e-9);
+
+      assert_eq!(xor(&a, &a).len(), 0);
+
+      // Touching corners stay separate contours
+      let result = union(&a, &vec![rect(10., 10., 20., 20.)]);
+      assert_eq!(result.len(), 2);
+      assert_eq!(result[0].len(), 4);
+      assert_eq!(result[1].len(), 4);
+   }
+
+   #[test]
+   fn test_self_intersection() {
+      let bowtie = vec![vec![
+         FloatPoint::new(0., 0.),
+         FloatPoint::new(10., 10.),
+         FloatPoint::new(10., 0.),
+         FloatPoint::new(0., 10.),
+      ]];
+
+      let result = union(&bowtie, &vec![]);
+      assert_eq!(result.len(), 2);
+      assert!((area(&result) - 50.).abs() < 1e-9);
+
+      for contour in result.iter() {
+         assert!(double_area(contour) > 0.);
+      }
+   }
+
+   #[test]
+   fn test_non_finite_points() {
+      let mut a = rect(0., 0., 10., 10.);
+      a.insert(2, FloatPoint::new(f64::NAN, 5.));
+      a.push(FloatPoint::new(f64::INFINITY, f64::NEG_INFINITY));
+
+      let b = vec![vec![FloatPoint::new(f64::NAN, f64::NAN); 3], rect(5., 5., 15., 15.)];
+
+      let result = union(&vec![a], &b);
+      assert_eq!(result.len(), 1);
+      assert!((area(&result) - 175.).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_many_contours() {
+      // A CAD sized clip, far too slow with a ray test against every group
+      let mut a = Vec::new();
+
+      for i in 0..100 {
+         for j in 0..100 {
+            let (x, y) = (4. * i as f64, 4. * j as f64);
+
+            a.push(rect(x, y, x + 2., y + 2.));
+         }
+      }
+
+      let b = vec![rect(1., 1., 399., 399.)];
+
+      let result = intersection(&a, &b);
+
+      assert_eq!(result.len(), 100 * 100);
+      assert!((area(&result) - 199. * 199.).abs() < 1e-6);
+   }
+}
@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+
+use data::FloatPoint;
+
+
+// Maximum distance in device pixels between a curve and its polyline
+pub const DEFAULT_TOLERANCE: f64 = 0.2;
+
+pub const MAX_STEPS: usize = 1024;
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct Flattener {
+   pub tolerance: f64,
+}
+
+impl Flattener {
+   #[inline]
+   pub fn new(tolerance: f64) -> Self {
+      Flattener {
+         tolerance: tolerance,
+      }
+   }
+
+   pub fn quadratic_steps(&self, from: FloatPoint, ctrl: FloatPoint, to: FloatPoint) -> usize {
+      let dd = length(from - ctrl * 2. + to);
+
+      self.steps(dd / (4. * self.tolerance))
+   }
+
+   pub fn cubic_steps(
+      &self, from: FloatPoint, ctrl1: FloatPoint, ctrl2: FloatPoint, to: FloatPoint
+   ) -> usize {
+      let dd1 = length(from - ctrl1 * 2. + ctrl2);
+      let dd2 = length(ctrl1 - ctrl2 * 2. + to);
+
+      self.steps(3. * dd1.max(dd2) / (4. * self.tolerance))
+   }
+
+   pub fn arc_steps(&self, radius: f64, angle: f64) -> usize {
+      let radius = radius.abs();
+
+      let min_steps = (2. * angle.abs() / PI).ceil() as usize;
+
+      let step_angle = if self.tolerance < radius {
+         2. * (1. - self.tolerance / radius).acos()
+      } else {
+         PI
+      };
+
+      let steps = if step_angle > 0. {
+         (angle.abs() / step_angle).ceil() as usize
+      } else {
+         MAX_STEPS
+      };
+
+      clamp_steps(steps.max(min_steps))
+   }
+
+   pub fn conic_to(
+      &self, contour: &mut Vec<FloatPoint>, from: FloatPoint, ctrl: FloatPoint, to: FloatPoint
+   ) {
+      let steps = self.quadratic_steps(from, ctrl, to);
+
+      for i in 1..steps {
+         let t2 = i as f64 / steps as f64;
+         let t1 = 1. - t2;
+
+         let u = on_segment(from, ctrl, t1, t2);
+         let v = on_segment(ctrl, to, t1, t2);
+
+         contour.push(on_segment(u, v, t1, t2));
+      }
+
+      contour.push(to);
+   }
+
+   pub fn cubic_to(
+      &self,
+      contour: &mut Vec<FloatPoint>,
+      from: FloatPoint,
+      ctrl1: FloatPoint,
+      ctrl2: FloatPoint,
+      to: FloatPoint
+   ) {
+      let steps = self.cubic_steps(from, ctrl1, ctrl2, to);
+
+      for i in 1..steps {
+         let t2 = i as f64 / steps as f64;
+         let t1 = 1. - t2;
+
+         let u = on_segment(from, ctrl1, t1, t2);
+         let v = on_segment(ctrl1, ctrl2, t1, t2);
+         let w = on_segment(ctrl2, to, t1, t2);
+
+         let m = on_segment(u, v, t1, t2);
+         let n = on_segment(v, w, t1, t2);
+
+         contour.push(on_segment(m, n, t1, t2));
+      }
+
+      contour.push(to);
+   }
+
+   #[inline]
+   fn steps(&self, squared: f64) -> usize {
+      if !(self.tolerance > 0.) {
+         return MAX_STEPS;
+      }
+
+      clamp_steps(squared.sqrt().ceil() as usize)
+   }
+}
+
+impl Default for Flattener {
+   fn default() -> Flattener {
+      Flattener::new(DEFAULT_TOLERANCE)
+   }
+}
+
+#[inline]
+fn clamp_steps(steps: usize) -> usize {
+   if steps < 1 {
+      1
+   } else if steps > MAX_STEPS {
+      MAX_STEPS
+   } else {
+      steps
+   }
+}
+
+#[inline]
+fn length(point: FloatPoint) -> f64 {
+   point.x.hypot(point.y)
+}
+
+#[inline]
+pub fn on_segment(pt1: FloatPoint, pt2: FloatPoint, t1: f64, t2: f64) -> FloatPoint {
+   let x = t1 * pt1.x + t2 * pt2.x;
+   let y = t1 * pt1.y + t2 * pt2.y;
+
+   FloatPoint::new(x, y)
+}
+
+
+#[cfg(test)]
+mod tests {
+   use data::FloatPoint;
+
+   use super::*;
+
+   fn cubic_at(p: &[FloatPoint; 4], t: f64) -> FloatPoint {
+      let s = 1. - t;
+
+      p[0] * (s * s * s) + p[1] * (3. * s * s * t) + p[2] * (3. * s * t * t) + p[3] * (t * t * t)
+   }
+
+   fn distance_to_polyline(point: FloatPoint, polyline: &[FloatPoint]) -> f64 {
+      let mut min = ::std::f64::MAX;
+
+      for pair in polyline.windows(2) {
+         let d = pair[1] - pair[0];
+         let len_sq = d.x * d.x + d.y * d.y;
+
+         let t = if len_sq > 0. {
+            (((point - pair[0]).x * d.x + (point - pair[0]).y * d.y) / len_sq).max(0.).min(1.)
+         } else {
+            0.
+         };
+
+         min = min.min(length(point - (pair[0] + d * t)));
+      }
+
+      min
+   }
+
+   #[test]
+   fn test_cubic_within_tolerance() {
+      let p = [
+         FloatPoint::new(0., 0.),
+         FloatPoint::new(30., 400.),
+         FloatPoint::new(700., -300.),
+         FloatPoint::new(900., 100.),
+      ];
+
+      for &tolerance in &[0.05, 0.5, 4.] {
+         let flattener = Flattener::new(tolerance);
+
+         let mut polyline = vec![p[0]];
+         flattener.cubic_to(&mut polyline, p[0], p[1], p[2], p[3]);
+
+         for i in 0..1000 {
+            let point = cubic_at(&p, i as f64 / 1000.);
+            assert!(distance_to_polyline(point, &polyline) <= tolerance);
+         }
+      }
+   }
+
+   #[test]
+   fn test_steps_follow_scale() {
+      let flattener = Flattener::new(0.25);
+
+      let from = FloatPoint::new(0., 0.);
+      let ctrl = FloatPoint::new(50., 200.);
+      let to = FloatPoint::new(100., 0.);
+
+      let steps = flattener.quadratic_steps(from, ctrl, to);
+
+      assert!(flattener.quadratic_steps(from, ctrl * 0.5, to * 0.5) < steps);
+      assert_eq!(Flattener::new(0.25 / 4.).quadratic_steps(from, ctrl, to), 2 * steps);
+      assert_eq!(flattener.quadratic_steps(from, FloatPoint::new(50., 0.), to), 1);
+   }
+
+   #[test]
+   fn test_arc_steps() {
+      let flattener = Flattener::new(0.25);
+
+      assert!(flattener.arc_steps(1000., PI) > flattener.arc_steps(10., PI));
+      assert_eq!(flattener.arc_steps(0.1, 2. * PI), 4);
+      assert_eq!(Flattener::new(0.).arc_steps(10., PI), MAX_STEPS);
+   }
+}
@@ -1 +1,5 @@
 pub mod point;
+pub mod flatten;
+pub mod transform;
+pub mod path;
+pub mod boolean;
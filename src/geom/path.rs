@@ -0,0 +1,809 @@
+use std::f64::consts::PI;
+
+use data::FloatPoint;
+use geom::flatten::{Flattener, on_segment};
+use geom::transform::Transform;
+
+
+const EPSILON: f64 = 1e-9;
+
+
+// Elliptical arc as center + x_axis * cos(angle) + y_axis * sin(angle), which
+// stays an elliptical arc under any affine transform
+#[derive(Debug, Clone, Copy)]
+pub struct Arc {
+   pub center: FloatPoint,
+   pub x_axis: FloatPoint,
+   pub y_axis: FloatPoint,
+   pub start: f64,
+   pub sweep: f64,
+}
+
+impl Arc {
+   #[inline]
+   pub fn new(
+      center: FloatPoint, x_axis: FloatPoint, y_axis: FloatPoint, start: f64, sweep: f64
+   ) -> Self {
+      Arc {
+         center: center,
+         x_axis: x_axis,
+         y_axis: y_axis,
+         start: start,
+         sweep: sweep,
+      }
+   }
+
+   pub fn ellipse(
+      center: FloatPoint, rx: f64, ry: f64, rotation: f64, start: f64, sweep: f64
+   ) -> Self {
+      let (sin, cos) = rotation.sin_cos();
+
+      Arc::new(
+         center,
+         FloatPoint::new(rx * cos, rx * sin),
+         FloatPoint::new(-ry * sin, ry * cos),
+         start,
+         sweep
+      )
+   }
+
+   #[inline]
+   pub fn point_at(&self, angle: f64) -> FloatPoint {
+      let (sin, cos) = angle.sin_cos();
+
+      self.center + self.x_axis * cos + self.y_axis * sin
+   }
+
+   #[inline]
+   pub fn start_point(&self) -> FloatPoint {
+      self.point_at(self.start)
+   }
+
+   #[inline]
+   pub fn end_point(&self) -> FloatPoint {
+      self.point_at(self.start + self.sweep)
+   }
+
+   #[inline]
+   pub fn reversed(&self) -> Self {
+      Arc::new(self.center, self.x_axis, self.y_axis, self.start + self.sweep, -self.sweep)
+   }
+
+   #[inline]
+   pub fn transformed(&self, transform: &Transform) -> Self {
+      Arc::new(
+         transform.apply(self.center),
+         transform.apply_vector(self.x_axis),
+         transform.apply_vector(self.y_axis),
+         self.start,
+         self.sweep
+      )
+   }
+
+   // Radii and rotation of the underlying ellipse, the larger radius first
+   pub fn shape(&self) -> (f64, f64, f64) {
+      let a = self.x_axis.x * self.x_axis.x + self.y_axis.x * self.y_axis.x;
+      let b = self.x_axis.x * self.x_axis.y + self.y_axis.x * self.y_axis.y;
+      let c = self.x_axis.y * self.x_axis.y + self.y_axis.y * self.y_axis.y;
+
+      let mean = 0.5 * (a + c);
+      let spread = (0.5 * (a - c)).hypot(b);
+
+      let rotation = 0.5 * (2. * b).atan2(a - c);
+
+      ((mean + spread).sqrt(), (mean - spread).max(0.).sqrt(), rotation)
+   }
+
+   // Angles inside the arc with a horizontal or vertical tangent, in the
+   // order of traversal
+   pub fn extrema(&self) -> Vec<f64> {
+      let mut angles = Vec::new();
+
+      let low = self.start.min(self.start + self.sweep);
+      let high = self.start.max(self.start + self.sweep);
+
+      for &base in &[self.y_axis.x.atan2(self.x_axis.x), self.y_axis.y.atan2(self.x_axis.y)] {
+         let mut angle = base + PI * ((low - base) / PI).floor();
+
+         while angle < high {
+            if angle > low {
+               angles.push(angle);
+            }
+
+            angle += PI;
+         }
+      }
+
+      angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+      if self.sweep < 0. {
+         angles.reverse();
+      }
+
+      angles
+   }
+}
+
+
+#[derive(Debug, Clone, Copy)]
+pub enum PathSegment {
+   MoveTo(FloatPoint),
+   LineTo(FloatPoint),
+   QuadTo(FloatPoint, FloatPoint),
+   CubicTo(FloatPoint, FloatPoint, FloatPoint),
+   ArcTo(Arc),
+   Close,
+}
+
+impl PathSegment {
+   pub fn end(&self) -> Option<FloatPoint> {
+      match *self {
+         PathSegment::MoveTo(p) | PathSegment::LineTo(p) => Some(p),
+         PathSegment::QuadTo(_, p) | PathSegment::CubicTo(_, _, p) => Some(p),
+         PathSegment::ArcTo(ref arc) => Some(arc.end_point()),
+         PathSegment::Close => None,
+      }
+   }
+
+   pub fn transformed(&self, transform: &Transform) -> Self {
+      match *self {
+         PathSegment::MoveTo(p) => PathSegment::MoveTo(transform.apply(p)),
+         PathSegment::LineTo(p) => PathSegment::LineTo(transform.apply(p)),
+         PathSegment::QuadTo(c, p) => PathSegment::QuadTo(
+            transform.apply(c), transform.apply(p)
+         ),
+         PathSegment::CubicTo(c1, c2, p) => PathSegment::CubicTo(
+            transform.apply(c1), transform.apply(c2), transform.apply(p)
+         ),
+         PathSegment::ArcTo(ref arc) => PathSegment::ArcTo(arc.transformed(transform)),
+         PathSegment::Close => PathSegment::Close,
+      }
+   }
+
+   // Same curve traversed back to `from`, the start point of the segment
+   fn reversed(&self, from: FloatPoint) -> Self {
+      match *self {
+         PathSegment::QuadTo(c, _) => PathSegment::QuadTo(c, from),
+         PathSegment::CubicTo(c1, c2, _) => PathSegment::CubicTo(c2, c1, from),
+         PathSegment::ArcTo(ref arc) => PathSegment::ArcTo(arc.reversed()),
+         _ => PathSegment::LineTo(from),
+      }
+   }
+}
+
+
+// Sequence of subpaths keeping the curve segments, every subpath starts with
+// a MoveTo and optionally ends with a Close
+#[derive(Debug, Clone)]
+pub struct Path {
+   segments: Vec<PathSegment>,
+   start: FloatPoint,
+   current: FloatPoint,
+   open: bool,
+}
+
+impl Path {
+   #[inline]
+   pub fn new() -> Self {
+      Path {
+         segments: Vec::new(),
+         start: FloatPoint::default(),
+         current: FloatPoint::default(),
+         open: false,
+      }
+   }
+
+   #[inline]
+   pub fn segments(&self) -> &[PathSegment] {
+      &self.segments
+   }
+
+   #[inline]
+   pub fn is_empty(&self) -> bool {
+      self.segments.is_empty()
+   }
+
+   #[inline]
+   pub fn current(&self) -> FloatPoint {
+      self.current
+   }
+
+   pub fn push(&mut self, segment: PathSegment) {
+      match segment {
+         PathSegment::MoveTo(p) => {
+            if let Some(&PathSegment::MoveTo(_)) = self.segments.last() {
+               self.segments.pop();
+            }
+
+            self.start = p;
+            self.current = p;
+            self.open = true;
+         },
+         PathSegment::Close => {
+            if !self.open {
+               return;
+            }
+
+            self.current = self.start;
+            self.open = false;
+         },
+         _ => {
+            if !self.open {
+               let current = self.current;
+               self.push(PathSegment::MoveTo(current));
+            }
+
+            if let PathSegment::ArcTo(ref arc) = segment {
+               let arc_start = arc.start_point();
+
+               if !coincident(arc_start, self.current) {
+                  self.segments.push(PathSegment::LineTo(arc_start));
+               }
+            }
+
+            self.current = segment.end().unwrap();
+         }
+      }
+
+      self.segments.push(segment);
+   }
+
+   pub fn extend(&mut self, other: &Path) {
+      for segment in other.segments.iter() {
+         self.push(*segment);
+      }
+   }
+
+   #[inline]
+   pub fn move_to(mut self, p: FloatPoint) -> Self {
+      self.push(PathSegment::MoveTo(p));
+      self
+   }
+
+   #[inline]
+   pub fn line_to(mut self, p: FloatPoint) -> Self {
+      self.push(PathSegment::LineTo(p));
+      self
+   }
+
+   #[inline]
+   pub fn quad_to(mut self, ctrl: FloatPoint, p: FloatPoint) -> Self {
+      self.push(PathSegment::QuadTo(ctrl, p));
+      self
+   }
+
+   #[inline]
+   pub fn cubic_to(mut self, ctrl1: FloatPoint, ctrl2: FloatPoint, p: FloatPoint) -> Self {
+      self.push(PathSegment::CubicTo(ctrl1, ctrl2, p));
+      self
+   }
+
+   // Arc of the ellipse around `center`, connected to the current point by a line
+   #[inline]
+   pub fn arc(
+      mut self, center: FloatPoint, rx: f64, ry: f64, rotation: f64, start: f64, sweep: f64
+   ) -> Self {
+      self.push(PathSegment::ArcTo(Arc::ellipse(center, rx, ry, rotation, start, sweep)));
+      self
+   }
+
+   // SVG style arc from the current point to `p`, rotation in radians
+   pub fn arc_to(
+      mut self, rx: f64, ry: f64, rotation: f64, large: bool, sweep: bool, p: FloatPoint
+   ) -> Self {
+      let from = self.current;
+
+      if coincident(from, p) {
+         return self;
+      }
+
+      let mut rx = rx.abs();
+      let mut ry = ry.abs();
+
+      if rx < EPSILON || ry < EPSILON {
+         return self.line_to(p);
+      }
+
+      let (sin, cos) = rotation.sin_cos();
+
+      let dx = 0.5 * (from.x - p.x);
+      let dy = 0.5 * (from.y - p.y);
+
+      let x1 = cos * dx + sin * dy;
+      let y1 = cos * dy - sin * dx;
+
+      let lambda = (x1 / rx).powi(2) + (y1 / ry).powi(2);
+
+      if lambda > 1. {
+         rx *= lambda.sqrt();
+         ry *= lambda.sqrt();
+      }
+
+      let num = (rx * ry).powi(2) - (rx * y1).powi(2) - (ry * x1).powi(2);
+      let den = (rx * y1).powi(2) + (ry * x1).powi(2);
+
+      let mut coef = (num / den).max(0.).sqrt();
+
+      if large == sweep {
+         coef = -coef;
+      }
+
+      let cx1 = coef * rx * y1 / ry;
+      let cy1 = -coef * ry * x1 / rx;
+
+      let center = FloatPoint::new(
+         cos * cx1 - sin * cy1 + 0.5 * (from.x + p.x),
+         sin * cx1 + cos * cy1 + 0.5 * (from.y + p.y),
+      );
+
+      let start = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+      let end = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+
+      let mut delta = end - start;
+
+      if sweep && delta < 0. {
+         delta += 2. * PI;
+      } else if !sweep && delta > 0. {
+         delta -= 2. * PI;
+      }
+
+      self.arc(center, rx, ry, rotation, start, delta)
+   }
+
+   #[inline]
+   pub fn close(mut self) -> Self {
+      self.push(PathSegment::Close);
+      self
+   }
+
+   pub fn transform(&self, transform: &Transform) -> Path {
+      Path {
+         segments: self.segments.iter().map(|s| s.transformed(transform)).collect(),
+         start: transform.apply(self.start),
+         current: transform.apply(self.current),
+         open: self.open,
+      }
+   }
+
+   #[inline]
+   pub fn translate(&self, offset: FloatPoint) -> Path {
+      self.transform(&Transform::translate(offset.x, offset.y))
+   }
+
+   #[inline]
+   pub fn scale(&self, sx: f64, sy: f64) -> Path {
+      self.transform(&Transform::scale(sx, sy))
+   }
+
+   #[inline]
+   pub fn rotate(&self, angle: f64, center: FloatPoint) -> Path {
+      self.transform(&Transform::rotate(angle, center))
+   }
+
+   pub fn reversed(&self) -> Path {
+      let mut path = Path::new();
+
+      for subpath in self.subpaths() {
+         let first = match subpath[0] {
+            PathSegment::MoveTo(p) => p,
+            _ => unreachable!(),
+         };
+
+         let closed = match subpath.last() {
+            Some(&PathSegment::Close) => true,
+            _ => false,
+         };
+
+         let body = &subpath[1..subpath.len() - closed as usize];
+
+         let mut ends = vec![first];
+
+         for segment in body.iter() {
+            ends.push(segment.end().unwrap());
+         }
+
+         let last = *ends.last().unwrap();
+
+         if closed {
+            path.push(PathSegment::MoveTo(first));
+
+            if !coincident(first, last) {
+               path.push(PathSegment::LineTo(last));
+            }
+         } else {
+            path.push(PathSegment::MoveTo(last));
+         }
+
+         for (index, segment) in body.iter().enumerate().rev() {
+            path.push(segment.reversed(ends[index]));
+         }
+
+         if closed {
+            path.push(PathSegment::Close);
+         }
+      }
+
+      path
+   }
+
+   // Smallest rectangle containing the curves, as the minimum and maximum corners
+   pub fn bounds(&self) -> Option<(FloatPoint, FloatPoint)> {
+      let mut bounds: Option<(FloatPoint, FloatPoint)> = None;
+
+      {
+         let mut include = |p: FloatPoint| {
+            bounds = Some(match bounds {
+               Some((min, max)) => (
+                  FloatPoint::new(min.x.min(p.x), min.y.min(p.y)),
+                  FloatPoint::new(max.x.max(p.x), max.y.max(p.y)),
+               ),
+               None => (p, p),
+            });
+         };
+
+         let mut last = FloatPoint::default();
+
+         for segment in self.segments.iter() {
+            match *segment {
+               PathSegment::QuadTo(c, p) => {
+                  for t in quad_extrema(last, c, p) {
+                     include(quad_piece(last, c, p, t, t).0);
+                  }
+               },
+               PathSegment::CubicTo(c1, c2, p) => {
+                  for t in cubic_extrema(last, c1, c2, p) {
+                     include(cubic_piece(last, c1, c2, p, t, t).0);
+                  }
+               },
+               PathSegment::ArcTo(ref arc) => {
+                  for angle in arc.extrema() {
+                     include(arc.point_at(angle));
+                  }
+               },
+               _ => {}
+            }
+
+            if let Some(end) = segment.end() {
+               include(end);
+               last = end;
+            }
+         }
+      }
+
+      bounds
+   }
+
+   // Polylines of the subpaths, closing them is left to the consumer
+   pub fn flatten(&self, flattener: &Flattener) -> Vec<Vec<FloatPoint>> {
+      self.flatten_params(flattener).into_iter().map(
+         |contour| contour.into_iter().map(|(point, _, _)| point).collect()
+      ).collect()
+   }
+
+   // Polylines like flatten, with the index of the segment every point was
+   // generated by and its parameter on that segment, an angle for arcs
+   pub fn flatten_params(&self, flattener: &Flattener) -> Vec<Vec<(FloatPoint, usize, f64)>> {
+      let mut contours = Vec::new();
+
+      let mut contour = Vec::new();
+      let mut points = Vec::new();
+
+      let mut last = FloatPoint::default();
+
+      for (index, segment) in self.segments.iter().enumerate() {
+         points.clear();
+
+         match *segment {
+            PathSegment::MoveTo(p) => {
+               if contour.len() > 1 {
+                  contours.push(contour);
+               }
+
+               contour = vec![(p, index, 0.)];
+            },
+            PathSegment::LineTo(p) => contour.push((p, index, 1.)),
+            PathSegment::QuadTo(c, p) => flattener.conic_to(&mut points, last, c, p),
+            PathSegment::CubicTo(c1, c2, p) => {
+               flattener.cubic_to(&mut points, last, c1, c2, p)
+            },
+            PathSegment::ArcTo(ref arc) => {
+               flatten_arc(&mut points, arc, flattener);
+
+               let steps = points.len();
+
+               for (i, point) in points.iter().enumerate() {
+                  let angle = if i + 1 == steps {
+                     arc.start + arc.sweep
+                  } else {
+                     arc.start + arc.sweep * (i + 1) as f64 / steps as f64
+                  };
+
+                  contour.push((*point, index, angle));
+               }
+
+               points.clear();
+            },
+            PathSegment::Close => {}
+         }
+
+         let steps = points.len();
+
+         for (i, point) in points.iter().enumerate() {
+            contour.push((*point, index, (i + 1) as f64 / steps as f64));
+         }
+
+         if let Some(end) = segment.end() {
+            last = end;
+         }
+      }
+
+      if contour.len() > 1 {
+         contours.push(contour);
+      }
+
+      contours
+   }
+
+   pub fn subpaths(&self) -> Vec<&[PathSegment]> {
+      let mut subpaths = Vec::new();
+
+      let mut start = 0;
+
+      for index in 1..self.segments.len() {
+         if let PathSegment::MoveTo(_) = self.segments[index] {
+            subpaths.push(&self.segments[start..index]);
+            start = index;
+         }
+      }
+
+      if start < self.segments.len() {
+         subpaths.push(&self.segments[start..]);
+      }
+
+      subpaths
+   }
+}
+
+impl Default for Path {
+   fn default() -> Path {
+      Path::new()
+   }
+}
+
+
+fn flatten_arc(contour: &mut Vec<FloatPoint>, arc: &Arc, flattener: &Flattener) {
+   let (radius, _, _) = arc.shape();
+
+   let steps = flattener.arc_steps(radius, arc.sweep);
+
+   for i in 1..steps {
+      contour.push(arc.point_at(arc.start + arc.sweep * i as f64 / steps as f64));
+   }
+
+   contour.push(arc.end_point());
+}
+
+
+// Parameters inside 0..1 where the tangent of a quadratic curve is
+// horizontal or vertical, in increasing order
+pub fn quad_extrema(p0: FloatPoint, c: FloatPoint, p1: FloatPoint) -> Vec<f64> {
+   let mut params = Vec::new();
+
+   for &(v0, vc, v1) in &[(p0.x, c.x, p1.x), (p0.y, c.y, p1.y)] {
+      let den = v0 - 2. * vc + v1;
+
+      if den != 0. {
+         push_param(&mut params, (v0 - vc) / den);
+      }
+   }
+
+   params.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+   params
+}
+
+pub fn cubic_extrema(p0: FloatPoint, c1: FloatPoint, c2: FloatPoint, p1: FloatPoint) -> Vec<f64> {
+   let mut params = Vec::new();
+
+   for &(v0, v1, v2, v3) in &[(p0.x, c1.x, c2.x, p1.x), (p0.y, c1.y, c2.y, p1.y)] {
+      let a = v3 - v0 + 3. * (v1 - v2);
+      let b = 2. * (v0 - 2. * v1 + v2);
+      let c = v1 - v0;
+
+      let scale = a.abs().max(b.abs()).max(c.abs());
+
+      if scale == 0. {
+         continue;
+      }
+
+      if a.abs() <= EPSILON * scale {
+         if b != 0. {
+            push_param(&mut params, -c / b);
+         }
+      } else {
+         let disc = b * b - 4. * a * c;
+
+         if disc >= 0. {
+            let root = disc.sqrt();
+
+            push_param(&mut params, (-b + root) / (2. * a));
+            push_param(&mut params, (-b - root) / (2. * a));
+         }
+      }
+   }
+
+   params.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+   params
+}
+
+#[inline]
+fn push_param(params: &mut Vec<f64>, t: f64) {
+   if t > 0. && t < 1. {
+      params.push(t);
+   }
+}
+
+// Control points of the t1..t2 part of a quadratic curve
+pub fn quad_piece(
+   p0: FloatPoint, c: FloatPoint, p1: FloatPoint, t1: f64, t2: f64
+) -> (FloatPoint, FloatPoint, FloatPoint) {
+   (
+      quad_blossom(p0, c, p1, t1, t1),
+      quad_blossom(p0, c, p1, t1, t2),
+      quad_blossom(p0, c, p1, t2, t2),
+   )
+}
+
+// Control points of the t1..t2 part of a cubic curve
+pub fn cubic_piece(
+   p0: FloatPoint, c1: FloatPoint, c2: FloatPoint, p1: FloatPoint, t1: f64, t2: f64
+) -> (FloatPoint, FloatPoint, FloatPoint, FloatPoint) {
+   let points = [p0, c1, c2, p1];
+
+   (
+      cubic_blossom(&points, t1, t1, t1),
+      cubic_blossom(&points, t1, t1, t2),
+      cubic_blossom(&points, t1, t2, t2),
+      cubic_blossom(&points, t2, t2, t2),
+   )
+}
+
+#[inline]
+fn quad_blossom(p0: FloatPoint, c: FloatPoint, p1: FloatPoint, s: f64, t: f64) -> FloatPoint {
+   let u = on_segment(p0, c, 1. - s, s);
+   let v = on_segment(c, p1, 1. - s, s);
+
+   on_segment(u, v, 1. - t, t)
+}
+
+#[inline]
+fn cubic_blossom(points: &[FloatPoint; 4], r: f64, s: f64, t: f64) -> FloatPoint {
+   let u = on_segment(points[0], points[1], 1. - r, r);
+   let v = on_segment(points[1], points[2], 1. - r, r);
+   let w = on_segment(points[2], points[3], 1. - r, r);
+
+   let m = on_segment(u, v, 1. - s, s);
+   let n = on_segment(v, w, 1. - s, s);
+
+   on_segment(m, n, 1. - t, t)
+}
+
+#[inline]
+fn coincident(a: FloatPoint, b: FloatPoint) -> bool {
+   let scale = 1. + a.x.abs().max(a.y.abs());
+
+   (a.x - b.x).abs() <= EPSILON * scale && (a.y - b.y).abs() <= EPSILON * scale
+}
+
+
+#[cfg(test)]
+mod tests {
+   use std::f64::consts::PI;
+
+   use data::FloatPoint;
+   use geom::flatten::Flattener;
+   use geom::transform::Transform;
+
+   use super::*;
+
+   fn close_to(a: FloatPoint, b: FloatPoint) -> bool {
+      (a.x - b.x).abs() < 1e-6 && (a.y - b.y).abs() < 1e-6
+   }
+
+   fn distance_to_polyline(point: FloatPoint, polyline: &[FloatPoint]) -> f64 {
+      let mut min = ::std::f64::MAX;
+
+      for pair in polyline.windows(2) {
+         let d = pair[1] - pair[0];
+         let v = point - pair[0];
+
+         let len_sq = d.x * d.x + d.y * d.y;
+
+         let t = if len_sq > 0. {
+            ((v.x * d.x + v.y * d.y) / len_sq).max(0.).min(1.)
+         } else {
+            0.
+         };
+
+         let nearest = pair[0] + d * t;
+
+         min = min.min((point.x - nearest.x).hypot(point.y - nearest.y));
+      }
+
+      min
+   }
+
+   #[test]
+   fn test_svg_arc() {
+      let path = Path::new()
+         .move_to(FloatPoint::new(0., 0.))
+         .arc_to(5., 5., 0., false, true, FloatPoint::new(10., 0.));
+
+      let arc = match path.segments()[1] {
+         PathSegment::ArcTo(arc) => arc,
+         _ => panic!("Arc expected"),
+      };
+
+      assert!(close_to(arc.center, FloatPoint::new(5., 0.)));
+      assert!((arc.sweep - PI).abs() < 1e-9);
+      assert!(close_to(path.current(), FloatPoint::new(10., 0.)));
+
+      let (min, max) = path.bounds().unwrap();
+
+      assert!(close_to(min, FloatPoint::new(0., -5.)));
+      assert!(close_to(max, FloatPoint::new(10., 0.)));
+   }
+
+   #[test]
+   fn test_reversed() {
+      let path = Path::new()
+         .move_to(FloatPoint::new(0., 0.))
+         .line_to(FloatPoint::new(10., 0.))
+         .cubic_to(FloatPoint::new(20., 5.), FloatPoint::new(0., 15.), FloatPoint::new(10., 20.))
+         .quad_to(FloatPoint::new(-10., 20.), FloatPoint::new(0., 10.))
+         .close();
+
+      let reversed = path.reversed();
+
+      assert_eq!(reversed.segments().len(), 6);
+
+      let flattener = Flattener::new(0.01);
+
+      let forward = path.flatten(&flattener);
+      let backward = reversed.reversed().flatten(&flattener);
+
+      for (a, b) in forward[0].iter().zip(backward[0].iter()) {
+         assert!(close_to(*a, *b));
+      }
+   }
+
+   #[test]
+   fn test_transformed_curves() {
+      let path = Path::new()
+         .move_to(FloatPoint::new(10., 0.))
+         .arc(FloatPoint::new(0., 0.), 10., 4., 0.3, 0., 1.5 * PI)
+         .cubic_to(FloatPoint::new(0., 0.), FloatPoint::new(4., 8.), FloatPoint::new(10., 0.));
+
+      let transform = Transform::rotate(0.7, FloatPoint::new(3., 1.))
+         .then(&Transform::scale(2., 0.5))
+         .then(&Transform::translate(4., -2.));
+
+      let flattener = Flattener::new(0.01);
+
+      let transformed = path.transform(&transform).flatten(&flattener);
+      let expected = path.flatten(&flattener);
+
+      let (rx, ry, _) = match path.transform(&transform).segments()[2] {
+         PathSegment::ArcTo(arc) => arc.shape(),
+         _ => panic!("Arc expected"),
+      };
+
+      assert!(rx >= ry);
+      assert!((rx * ry - 40.).abs() < 1e-9);
+
+      for point in expected[0].iter() {
+         assert!(distance_to_polyline(transform.apply(*point), &transformed[0]) < 0.05);
+      }
+   }
+}
@@ -0,0 +1,115 @@
+use data::FloatPoint;
+
+
+// Affine transform, x' = xx * x + xy * y + x0 and y' = yx * x + yy * y + y0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+   pub xx: f64,
+   pub xy: f64,
+   pub yx: f64,
+   pub yy: f64,
+   pub x0: f64,
+   pub y0: f64,
+}
+
+impl Transform {
+   #[inline]
+   pub fn new(xx: f64, xy: f64, yx: f64, yy: f64, x0: f64, y0: f64) -> Self {
+      Transform {
+         xx: xx,
+         xy: xy,
+         yx: yx,
+         yy: yy,
+         x0: x0,
+         y0: y0,
+      }
+   }
+
+   #[inline]
+   pub fn identity() -> Self {
+      Transform::new(1., 0., 0., 1., 0., 0.)
+   }
+
+   #[inline]
+   pub fn translate(x: f64, y: f64) -> Self {
+      Transform::new(1., 0., 0., 1., x, y)
+   }
+
+   #[inline]
+   pub fn scale(sx: f64, sy: f64) -> Self {
+      Transform::new(sx, 0., 0., sy, 0., 0.)
+   }
+
+   // Rotation by `angle` radians around `center`
+   pub fn rotate(angle: f64, center: FloatPoint) -> Self {
+      let (sin, cos) = angle.sin_cos();
+
+      Transform::new(
+         cos, -sin,
+         sin, cos,
+         center.x - cos * center.x + sin * center.y,
+         center.y - sin * center.x - cos * center.y,
+      )
+   }
+
+   // Applies `self` first and `other` afterwards
+   pub fn then(&self, other: &Transform) -> Self {
+      Transform::new(
+         other.xx * self.xx + other.xy * self.yx,
+         other.xx * self.xy + other.xy * self.yy,
+         other.yx * self.xx + other.yy * self.yx,
+         other.yx * self.xy + other.yy * self.yy,
+         other.xx * self.x0 + other.xy * self.y0 + other.x0,
+         other.yx * self.x0 + other.yy * self.y0 + other.y0,
+      )
+   }
+
+   #[inline]
+   pub fn apply(&self, point: FloatPoint) -> FloatPoint {
+      FloatPoint::new(
+         self.xx * point.x + self.xy * point.y + self.x0,
+         self.yx * point.x + self.yy * point.y + self.y0,
+      )
+   }
+
+   // Transforms a direction, ignoring the translation
+   #[inline]
+   pub fn apply_vector(&self, vector: FloatPoint) -> FloatPoint {
+      FloatPoint::new(
+         self.xx * vector.x + self.xy * vector.y,
+         self.yx * vector.x + self.yy * vector.y,
+      )
+   }
+
+   #[inline]
+   pub fn determinant(&self) -> f64 {
+      self.xx * self.yy - self.xy * self.yx
+   }
+
+   // None for degenerate transforms
+   pub fn invert(&self) -> Option<Transform> {
+      let determinant = self.determinant();
+
+      if determinant == 0. || !determinant.is_finite() {
+         return None;
+      }
+
+      let xx = self.yy / determinant;
+      let xy = -self.xy / determinant;
+      let yx = -self.yx / determinant;
+      let yy = self.xx / determinant;
+
+      Some(Transform::new(
+         xx, xy,
+         yx, yy,
+         -(xx * self.x0 + xy * self.y0),
+         -(yx * self.x0 + yy * self.y0),
+      ))
+   }
+}
+
+impl Default for Transform {
+   fn default() -> Transform {
+      Transform::identity()
+   }
+}
@@ -0,0 +1,576 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::io;
+use std::fmt;
+
+use draw::RGB;
+use frame::Frame;
+use data::FloatPoint;
+use geom::transform::Transform;
+use devel::ppm::read_ppm;
+
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+
+// Premultiplied RGBA pixels of one mipmap level
+#[derive(Debug)]
+struct Level {
+   width: usize,
+   height: usize,
+   pixels: Vec<u8>,
+}
+
+impl Level {
+   // Box filtered half size level, odd sizes repeat their last row or column
+   fn downsample(&self) -> Level {
+      let width = (self.width / 2).max(1);
+      let height = (self.height / 2).max(1);
+
+      let mut pixels = vec![0; width * height * 4];
+
+      for y in 0..height {
+         for x in 0..width {
+            let xs = [(2 * x).min(self.width - 1), (2 * x + 1).min(self.width - 1)];
+            let ys = [(2 * y).min(self.height - 1), (2 * y + 1).min(self.height - 1)];
+
+            for channel in 0..4 {
+               let mut sum = 0;
+
+               for &sy in ys.iter() {
+                  for &sx in xs.iter() {
+                     sum += self.pixels[(sy * self.width + sx) * 4 + channel] as u32;
+                  }
+               }
+
+               pixels[(y * width + x) * 4 + channel] = ((sum + 2) / 4) as u8;
+            }
+         }
+      }
+
+      Level {
+         width: width,
+         height: height,
+         pixels: pixels,
+      }
+   }
+}
+
+
+// Decoded image with its mipmap chain, cheap to clone as the levels are
+// shared
+#[derive(Clone)]
+pub struct Image {
+   pub width: usize,
+   pub height: usize,
+   levels: Arc<Vec<Level>>,
+   id: usize,
+}
+
+impl Image {
+   // `pixels` are RGBA with straight alpha, row by row from the top
+   pub fn new(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+      let mut pixels = pixels;
+
+      for pixel in pixels.chunks_mut(4) {
+         let alpha = pixel[3] as u32;
+
+         for channel in 0..3 {
+            pixel[channel] = ((pixel[channel] as u32 * alpha + 127) / 255) as u8;
+         }
+      }
+
+      Image::from_premultiplied(width, height, pixels)
+   }
+
+   // Same as `new` with the colors already multiplied by alpha
+   pub fn from_premultiplied(width: usize, height: usize, pixels: Vec<u8>) -> Self {
+      assert!(width > 0 && height > 0);
+      assert_eq!(pixels.len(), width * height * 4);
+
+      let mut levels = vec![
+         Level {
+            width: width,
+            height: height,
+            pixels: pixels,
+         }
+      ];
+
+      while {
+         let last = levels.last().unwrap();
+         last.width > 1 || last.height > 1
+      } {
+         let level = levels.last().unwrap().downsample();
+
+         levels.push(level);
+      }
+
+      Image {
+         width: width,
+         height: height,
+         levels: Arc::new(levels),
+         id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+      }
+   }
+
+   // Premultiplied RGBA of the full size pixel at `x`, `y`
+   #[inline]
+   pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+      let level = &self.levels[0];
+
+      let offset = (y * level.width + x) * 4;
+
+      [
+         level.pixels[offset],
+         level.pixels[offset + 1],
+         level.pixels[offset + 2],
+         level.pixels[offset + 3],
+      ]
+   }
+
+   // Identity of the decoded pixels, shared by the clones of the image
+   #[inline]
+   pub fn id(&self) -> usize {
+      self.id
+   }
+
+   pub fn from_ppm(filename: &str) -> io::Result<Self> {
+      let (width, height, pixels) = try!(read_ppm(filename));
+
+      if width == 0 || height == 0 {
+         return Err(io::Error::new(io::ErrorKind::InvalidData, "Empty image"));
+      }
+
+      Ok(Image::new(width, height, pixels))
+   }
+
+   // Copy of the current frame contents
+   pub fn from_frame(frame: &Frame) -> Self {
+      let width = frame.width as usize;
+      let height = frame.height as usize;
+
+      let mut pixels = Vec::with_capacity(width * height * 4);
+
+      for y in 0..height {
+         for x in 0..width {
+            let color = frame.get_pixel(x as i32, y as i32);
+
+            pixels.extend_from_slice(&[color.r, color.g, color.b, 255]);
+         }
+      }
+
+      Image::new(width, height, pixels)
+   }
+
+   #[inline]
+   pub fn levels(&self) -> usize {
+      self.levels.len()
+   }
+
+   // Premultiplied texel of `level`, transparent where the repeat mode leaves
+   // no texel
+   #[inline]
+   fn texel(&self, level: usize, x: i64, y: i64, repeat: RepeatMode) -> [f32; 4] {
+      let level = &self.levels[level];
+
+      let x = match repeat.wrap(x, level.width) {
+         Some(x) => x,
+         None => return [0.; 4],
+      };
+
+      let y = match repeat.wrap(y, level.height) {
+         Some(y) => y,
+         None => return [0.; 4],
+      };
+
+      let i = (y * level.width + x) * 4;
+      let pixels = &level.pixels;
+
+      [pixels[i] as f32, pixels[i + 1] as f32, pixels[i + 2] as f32, pixels[i + 3] as f32]
+   }
+
+   fn nearest(&self, level: usize, u: f64, v: f64, repeat: RepeatMode) -> [f32; 4] {
+      self.texel(level, u.floor() as i64, v.floor() as i64, repeat)
+   }
+
+   fn bilinear(&self, level: usize, u: f64, v: f64, repeat: RepeatMode) -> [f32; 4] {
+      let u = u - 0.5;
+      let v = v - 0.5;
+
+      let x = u.floor();
+      let y = v.floor();
+
+      let fx = (u - x) as f32;
+      let fy = (v - y) as f32;
+
+      let x = x as i64;
+      let y = y as i64;
+
+      let p00 = self.texel(level, x, y, repeat);
+      let p10 = self.texel(level, x + 1, y, repeat);
+      let p01 = self.texel(level, x, y + 1, repeat);
+      let p11 = self.texel(level, x + 1, y + 1, repeat);
+
+      let mut result = [0.; 4];
+
+      for channel in 0..4 {
+         let top = p00[channel] + (p10[channel] - p00[channel]) * fx;
+         let bottom = p01[channel] + (p11[channel] - p01[channel]) * fx;
+
+         result[channel] = top + (bottom - top) * fy;
+      }
+
+      result
+   }
+
+   // Catmull-Rom, clamped so the result stays premultiplied
+   fn bicubic(&self, level: usize, u: f64, v: f64, repeat: RepeatMode) -> [f32; 4] {
+      let u = u - 0.5;
+      let v = v - 0.5;
+
+      let x = u.floor();
+      let y = v.floor();
+
+      let wx = cubic_weights((u - x) as f32);
+      let wy = cubic_weights((v - y) as f32);
+
+      let x = x as i64;
+      let y = y as i64;
+
+      let mut result = [0.; 4];
+
+      for j in 0..4 {
+         for i in 0..4 {
+            let texel = self.texel(level, x + i as i64 - 1, y + j as i64 - 1, repeat);
+            let weight = wx[i] * wy[j];
+
+            for channel in 0..4 {
+               result[channel] += texel[channel] * weight;
+            }
+         }
+      }
+
+      let alpha = result[3].max(0.).min(255.);
+
+      [
+         result[0].max(0.).min(alpha),
+         result[1].max(0.).min(alpha),
+         result[2].max(0.).min(alpha),
+         alpha,
+      ]
+   }
+
+   // Trilinear filtering between the two levels around `lod`
+   fn mipmap(&self, u: f64, v: f64, lod: f64, repeat: RepeatMode) -> [f32; 4] {
+      let max_level = (self.levels.len() - 1) as f64;
+      let lod = lod.max(0.).min(max_level);
+
+      let lower = lod.floor() as usize;
+      let upper = lod.ceil() as usize;
+
+      let sample_level = |level: usize| {
+         let data = &self.levels[level];
+
+         let u = u * data.width as f64 / self.width as f64;
+         let v = v * data.height as f64 / self.height as f64;
+
+         self.bilinear(level, u, v, repeat)
+      };
+
+      let low = sample_level(lower);
+
+      if upper == lower {
+         return low;
+      }
+
+      let high = sample_level(upper);
+      let t = (lod - lower as f64) as f32;
+
+      let mut result = [0.; 4];
+
+      for channel in 0..4 {
+         result[channel] = low[channel] + (high[channel] - low[channel]) * t;
+      }
+
+      result
+   }
+}
+
+impl fmt::Debug for Image {
+   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      write!(f, "(image {}x{})", self.width, self.height)
+   }
+}
+
+
+#[inline]
+fn cubic_weights(t: f32) -> [f32; 4] {
+   let t2 = t * t;
+   let t3 = t2 * t;
+
+   [
+      0.5 * (-t3 + 2. * t2 - t),
+      0.5 * (3. * t3 - 5. * t2 + 2.),
+      0.5 * (-3. * t3 + 4. * t2 + t),
+      0.5 * (t3 - t2),
+   ]
+}
+
+
+// How the image extends past its edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatMode {
+   None,
+   Pad,
+   Repeat,
+   Reflect,
+}
+
+impl RepeatMode {
+   pub fn from_name(name: &str) -> Option<Self> {
+      match name {
+         "none" => Some(RepeatMode::None),
+         "pad" => Some(RepeatMode::Pad),
+         "repeat" => Some(RepeatMode::Repeat),
+         "reflect" => Some(RepeatMode::Reflect),
+         _ => None,
+      }
+   }
+
+   #[inline]
+   fn wrap(&self, i: i64, size: usize) -> Option<usize> {
+      let size = size as i64;
+
+      match *self {
+         RepeatMode::None => {
+            if i >= 0 && i < size {
+               Some(i as usize)
+            } else {
+               None
+            }
+         },
+         RepeatMode::Pad => Some(i.max(0).min(size - 1) as usize),
+         RepeatMode::Repeat => Some(((i % size + size) % size) as usize),
+         RepeatMode::Reflect => {
+            let period = 2 * size;
+            let i = (i % period + period) % period;
+
+            Some(if i < size { i } else { period - 1 - i } as usize)
+         },
+      }
+   }
+}
+
+impl Default for RepeatMode {
+   fn default() -> RepeatMode {
+      RepeatMode::None
+   }
+}
+
+
+// Sampling filter, mipmap uses trilinear filtering when minifying
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFilter {
+   Nearest,
+   Bilinear,
+   Bicubic,
+   Mipmap,
+}
+
+impl ImageFilter {
+   pub fn from_name(name: &str) -> Option<Self> {
+      match name {
+         "nearest" => Some(ImageFilter::Nearest),
+         "bilinear" => Some(ImageFilter::Bilinear),
+         "bicubic" => Some(ImageFilter::Bicubic),
+         "mipmap" => Some(ImageFilter::Mipmap),
+         _ => None,
+      }
+   }
+}
+
+impl Default for ImageFilter {
+   fn default() -> ImageFilter {
+      ImageFilter::Bilinear
+   }
+}
+
+
+// Image used as a fill, `transform` maps image pixels to the coordinates of
+// the filled outlines
+#[derive(Debug, Clone)]
+pub struct ImagePaint {
+   pub image: Image,
+   pub transform: Transform,
+   pub repeat: RepeatMode,
+   pub filter: ImageFilter,
+   inverse: Transform,
+}
+
+impl ImagePaint {
+   // None when the transform can't be inverted, the image having no area
+   // left to sample from
+   pub fn new(image: Image, transform: Transform, repeat: RepeatMode, filter: ImageFilter) -> Option<Self> {
+      let inverse = match transform.invert() {
+         Some(inverse) => inverse,
+         None => return None,
+      };
+
+      Some(ImagePaint {
+         image: image,
+         transform: transform,
+         repeat: repeat,
+         filter: filter,
+         inverse: inverse,
+      })
+   }
+
+   // Same paint with `other` applied after the image transform
+   #[inline]
+   pub fn then(&self, other: &Transform) -> Option<Self> {
+      ImagePaint::new(self.image.clone(), self.transform.then(other), self.repeat, self.filter)
+   }
+
+   // Premultiplied RGBA in 0..255 at `x` and `y`, `pixel_size` being the
+   // size of a frame pixel in the same units
+   pub fn sample(&self, x: f64, y: f64, pixel_size: f64) -> [f32; 4] {
+      let point = self.inverse.apply(FloatPoint::new(x, y));
+
+      match self.filter {
+         ImageFilter::Nearest => self.image.nearest(0, point.x, point.y, self.repeat),
+         ImageFilter::Bilinear => self.image.bilinear(0, point.x, point.y, self.repeat),
+         ImageFilter::Bicubic => self.image.bicubic(0, point.x, point.y, self.repeat),
+         ImageFilter::Mipmap => {
+            let dx = self.inverse.apply_vector(FloatPoint::new(pixel_size, 0.));
+            let dy = self.inverse.apply_vector(FloatPoint::new(0., pixel_size));
+
+            let footprint = (dx.x * dx.x + dx.y * dx.y).max(dy.x * dy.x + dy.y * dy.y).sqrt();
+
+            if footprint <= 1. {
+               self.image.bilinear(0, point.x, point.y, self.repeat)
+            } else {
+               self.image.mipmap(point.x, point.y, footprint.log2(), self.repeat)
+            }
+         },
+      }
+   }
+
+   // Sample composited over the opaque `background`
+   pub fn sample_over(&self, x: f64, y: f64, pixel_size: f64, background: &RGB) -> RGB {
+      let sample = self.sample(x, y, pixel_size);
+
+      let remaining = 1. - sample[3] / 255.;
+
+      RGB::new(
+         (sample[0] + background.r as f32 * remaining).round().min(255.) as u8,
+         (sample[1] + background.g as f32 * remaining).round().min(255.) as u8,
+         (sample[2] + background.b as f32 * remaining).round().min(255.) as u8,
+      )
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use std::env;
+
+   use geom::transform::Transform;
+   use devel::ppm::write_ppm;
+
+   // 2x2 image, red and green on top, blue and transparent white below
+   fn checker() -> Image {
+      Image::new(2, 2, vec![
+         255, 0, 0, 255,   0, 255, 0, 255,
+         0, 0, 255, 255,   255, 255, 255, 0,
+      ])
+   }
+
+   fn paint(filter: ImageFilter, repeat: RepeatMode, transform: Transform) -> ImagePaint {
+      ImagePaint::new(checker(), transform, repeat, filter).unwrap()
+   }
+
+   #[test]
+   fn test_nearest_and_repeat() {
+      let nearest = paint(ImageFilter::Nearest, RepeatMode::None, Transform::identity());
+
+      assert_eq!(nearest.sample(0.5, 0.5, 1.), [255., 0., 0., 255.]);
+      assert_eq!(nearest.sample(1.5, 1.5, 1.), [0., 0., 0., 0.]);
+      assert_eq!(nearest.sample(2.5, 0.5, 1.), [0., 0., 0., 0.]);
+
+      let repeat = paint(ImageFilter::Nearest, RepeatMode::Repeat, Transform::identity());
+
+      assert_eq!(repeat.sample(2.5, 0.5, 1.), [255., 0., 0., 255.]);
+      assert_eq!(repeat.sample(-0.5, -1.5, 1.), [0., 255., 0., 255.]);
+
+      let reflect = paint(ImageFilter::Nearest, RepeatMode::Reflect, Transform::identity());
+
+      assert_eq!(reflect.sample(2.5, 0.5, 1.), [0., 255., 0., 255.]);
+      assert_eq!(reflect.sample(3.5, 2.5, 1.), [0., 0., 255., 255.]);
+
+      let pad = paint(ImageFilter::Nearest, RepeatMode::Pad, Transform::translate(10., 0.));
+
+      assert_eq!(pad.sample(5., 0.5, 1.), [255., 0., 0., 255.]);
+      assert_eq!(pad.sample(20., 0.5, 1.), [0., 255., 0., 255.]);
+   }
+
+   #[test]
+   fn test_bilinear_and_bicubic() {
+      let transform = Transform::scale(10., 10.);
+
+      let bilinear = paint(ImageFilter::Bilinear, RepeatMode::Pad, transform);
+
+      assert_eq!(bilinear.sample(5., 5., 1.), [255., 0., 0., 255.]);
+      assert_eq!(bilinear.sample(10., 5., 1.), [127.5, 127.5, 0., 255.]);
+
+      let bicubic = paint(ImageFilter::Bicubic, RepeatMode::Pad, transform);
+
+      assert_eq!(bicubic.sample(15., 5., 1.), [0., 255., 0., 255.]);
+
+      let middle = bicubic.sample(10., 10., 1.);
+
+      assert!(middle[3] > 190. && middle[3] < 192.);
+      assert!(middle[0] <= middle[3]);
+   }
+
+   #[test]
+   fn test_mipmap() {
+      let image = checker();
+
+      assert_eq!(image.levels(), 2);
+
+      let mipmap = paint(ImageFilter::Mipmap, RepeatMode::Repeat, Transform::scale(0.25, 0.25));
+
+      let sample = mipmap.sample(0.1, 0.1, 1.);
+
+      assert_eq!(sample, [64., 64., 64., 191.]);
+
+      let magnified = paint(ImageFilter::Mipmap, RepeatMode::Repeat, Transform::scale(10., 10.));
+
+      assert_eq!(magnified.sample(5., 5., 1.), [255., 0., 0., 255.]);
+   }
+
+   #[test]
+   fn test_from_ppm() {
+      let path = env::temp_dir().join("polydraw_test_image.ppm");
+      let filename = path.to_str().unwrap();
+
+      let pixels = [10_u8, 20, 30, 255, 40, 50, 60, 255];
+
+      write_ppm(filename, 2, 1, pixels.as_ptr()).unwrap();
+
+      let image = Image::from_ppm(filename).unwrap();
+
+      assert_eq!(image.pixel(1, 0), [40, 50, 60, 255]);
+   }
+
+   #[test]
+   fn test_singular_transform() {
+      assert!(ImagePaint::new(checker(), Transform::scale(0., 2.), RepeatMode::None, ImageFilter::Nearest).is_none());
+
+      let paint = paint(ImageFilter::Nearest, RepeatMode::None, Transform::identity());
+
+      assert!(paint.then(&Transform::scale(3., 0.)).is_none());
+   }
+}
@@ -11,6 +11,7 @@ use super::parser::{FnType, FnIndex, Argument, Function, Value};
 use super::operator::BuiltinFns;
 use super::registry::TypeRegistry;
 use super::execute::{execute_builtin_function, Executor};
+use super::environment::ImageCache;
 
 
 #[derive(PartialEq, Clone, Debug)]
@@ -190,6 +191,11 @@ impl Program {
          consts: consts,
       }
    }
+
+   #[inline]
+   pub fn main_arity(&self) -> usize {
+      self.compiled_fns[self.main_index].template.len()
+   }
 }
 
 pub fn compile_program (
@@ -198,6 +204,9 @@ pub fn compile_program (
    builtin_fns: &BuiltinFns,
    registry: &TypeRegistry,
    freetype: &FreeType,
+   images: &ImageCache,
+   tolerance: f64,
+   subdivisions: i64,
 ) -> Result<Program, String> {
    let defined_indices = try!(map_defined_indices(&functions));
 
@@ -217,6 +226,9 @@ pub fn compile_program (
             &defined_indices,
             registry,
             freetype,
+            images,
+            tolerance,
+            subdivisions,
          ))
       );
 
@@ -293,6 +305,9 @@ fn compile_function(
    defined_indices: &DefinedIndices,
    registry: &TypeRegistry,
    freetype: &FreeType,
+   images: &ImageCache,
+   tolerance: f64,
+   subdivisions: i64,
 ) -> Result<CompiledFn, String> {
 
    let span = defined_indices[&function.name as &str].span;
@@ -320,6 +335,9 @@ fn compile_function(
          defined_indices,
          registry,
          freetype,
+         images,
+         tolerance,
+         subdivisions,
       ));
 
       if span < assignment.names.len() {
@@ -364,6 +382,9 @@ fn compile_value(
    defined_indices: &DefinedIndices,
    registry: &TypeRegistry,
    freetype: &FreeType,
+   images: &ImageCache,
+   tolerance: f64,
+   subdivisions: i64,
 ) -> Result<(CallArg, usize), String> {
    match value {
       &Value::Int(value) => push_const(registry, consts, value),
@@ -400,6 +421,9 @@ fn compile_value(
                defined_indices,
                registry,
                freetype,
+               images,
+               tolerance,
+               subdivisions,
             ));
 
             if call_arg.arg_type != CallArgType::Const
@@ -441,6 +465,9 @@ fn compile_value(
                      consts,
                      registry,
                      freetype,
+                     images,
+                     tolerance,
+                     subdivisions,
                   );
 
                   let fn_ref = FnRef::builtin(fn_index.index);
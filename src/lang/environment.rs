@@ -1,4 +1,10 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
 use sys::ft::FreeType;
+use image::Image;
+use geom::flatten::DEFAULT_TOLERANCE;
+use devel::SUBDIVISIONS;
 
 use super::compiler::{BuiltinIndices, Program, compile_program};
 use super::operator::{BuiltinFns, register_builtin_fns};
@@ -14,6 +20,10 @@ pub struct Environment {
    pub builtin_indices: BuiltinIndices,
    pub builtin_fns: BuiltinFns,
    pub freetype: FreeType,
+   pub images: ImageCache,
+   pub tolerance: f64,
+   pub subdivisions: i64,
+   scale_factor: f64,
 }
 
 impl Environment {
@@ -27,9 +37,35 @@ impl Environment {
          builtin_indices: builtin_indices,
          builtin_fns: builtin_fns,
          freetype: freetype,
+         images: ImageCache::new(),
+         tolerance: DEFAULT_TOLERANCE * SUBDIVISIONS as f64,
+         subdivisions: SUBDIVISIONS,
+         scale_factor: 1.,
       }
    }
 
+   #[inline]
+   pub fn set_scale_factor(&mut self, scale_factor: f64) {
+      self.scale_factor = scale_factor;
+
+      self.update_tolerance();
+   }
+
+   // Script units per logical pixel, the subdivisions of the renderer
+   #[inline]
+   pub fn set_subdivisions(&mut self, subdivisions: i64) {
+      self.subdivisions = subdivisions;
+
+      self.update_tolerance();
+   }
+
+   // Curves are flattened in script units, which are device pixels divided
+   // by the scale factor and multiplied by the subdivisions
+   #[inline]
+   fn update_tolerance(&mut self) {
+      self.tolerance = DEFAULT_TOLERANCE * self.subdivisions as f64 / self.scale_factor;
+   }
+
    pub fn compile_program(&self, source: &str) -> Result<Program, String> {
       let tokens = try!(tokenize(&source));
 
@@ -41,6 +77,9 @@ impl Environment {
          &self.builtin_fns,
          &self.registry,
          &self.freetype,
+         &self.images,
+         self.tolerance,
+         self.subdivisions,
       )
    }
 
@@ -55,7 +94,70 @@ impl Environment {
          &self.builtin_fns,
          &self.registry,
          &self.freetype,
+         &self.images,
+         self.tolerance,
+         self.subdivisions,
       )
    }
 }
 
+
+// Decoded images by file name, so scripts reading an image on every frame
+// only decode it once
+pub struct ImageCache {
+   images: RefCell<HashMap<String, Option<Image>>>,
+}
+
+impl ImageCache {
+   #[inline]
+   pub fn new() -> Self {
+      ImageCache {
+         images: RefCell::new(HashMap::new()),
+      }
+   }
+
+   // None when the file can't be read or decoded, which is remembered too
+   pub fn load(&self, filename: &str) -> Option<Image> {
+      if let Some(image) = self.images.borrow().get(filename) {
+         return image.clone();
+      }
+
+      let image = Image::from_ppm(filename).ok();
+
+      self.images.borrow_mut().insert(filename.to_string(), image.clone());
+
+      image
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use std::env;
+   use std::fs;
+
+   use devel::ppm::write_ppm;
+
+   #[test]
+   fn test_image_cache() {
+      let path = env::temp_dir().join("polydraw_test_cache.ppm");
+      let filename = path.to_str().unwrap();
+
+      let pixels = [10_u8, 20, 30, 255];
+
+      write_ppm(filename, 1, 1, pixels.as_ptr()).unwrap();
+
+      let cache = ImageCache::new();
+
+      assert_eq!(cache.load(filename).unwrap().width, 1);
+
+      // Later loads don't read the file again
+      fs::remove_file(filename).unwrap();
+
+      assert_eq!(cache.load(filename).unwrap().width, 1);
+
+      assert!(cache.load("polydraw_missing.ppm").is_none());
+   }
+}
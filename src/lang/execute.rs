@@ -8,6 +8,7 @@ use super::variant::{Variant, VariantVec};
 use super::operator::{BuiltinFns, TypeFnMap};
 use super::parser::FnType;
 use super::registry::TypeRegistry;
+use super::environment::ImageCache;
 
 
 pub fn execute_program(
@@ -16,6 +17,9 @@ pub fn execute_program(
    builtin_fns: &BuiltinFns,
    registry: &TypeRegistry,
    freetype: &FreeType,
+   images: &ImageCache,
+   tolerance: f64,
+   subdivisions: i64,
 ) -> Vec<Variant> {
    let mut arg_refs = Vec::new();
 
@@ -29,6 +33,9 @@ pub fn execute_program(
       &program.consts,
       registry,
       freetype,
+      images,
+      tolerance,
+      subdivisions,
    );
 
    let fn_ref = FnRef::defined(program.main_index);
@@ -164,6 +171,9 @@ pub struct Executor<'a> {
    pub consts: &'a Vec<Variant>,
    pub registry: &'a TypeRegistry,
    pub freetype: &'a FreeType,
+   pub images: &'a ImageCache,
+   pub tolerance: f64,
+   pub subdivisions: i64,
 }
 
 impl<'a> Executor<'a> {
@@ -173,6 +183,9 @@ impl<'a> Executor<'a> {
       consts: &'a Vec<Variant>,
       registry: &'a TypeRegistry,
       freetype: &'a FreeType,
+      images: &'a ImageCache,
+      tolerance: f64,
+      subdivisions: i64,
    ) -> Self {
       Executor {
          compiled_fns: compiled_fns,
@@ -180,6 +193,9 @@ impl<'a> Executor<'a> {
          consts: consts,
          registry: registry,
          freetype: freetype,
+         images: images,
+         tolerance: tolerance,
+         subdivisions: subdivisions,
       }
    }
 
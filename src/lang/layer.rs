@@ -0,0 +1,63 @@
+use devel::{MaskMode, FilterOp};
+use draw::BlendMode;
+
+use super::variant::Variant;
+
+
+// Drawable `content` with its coverage modulated by the drawable `source`
+#[derive(Debug, Clone)]
+pub struct Masked {
+   pub content: Variant,
+   pub source: Variant,
+   pub mode: MaskMode,
+}
+
+impl Masked {
+   #[inline]
+   pub fn new(content: Variant, source: Variant, mode: MaskMode) -> Self {
+      Masked {
+         content: content,
+         source: source,
+         mode: mode,
+      }
+   }
+}
+
+
+// Drawable `content` composited as a whole with `opacity` and `blend`
+#[derive(Debug, Clone)]
+pub struct Group {
+   pub content: Variant,
+   pub opacity: f64,
+   pub blend: BlendMode,
+}
+
+impl Group {
+   #[inline]
+   pub fn new(content: Variant, opacity: f64, blend: BlendMode) -> Self {
+      Group {
+         content: content,
+         opacity: opacity,
+         blend: blend,
+      }
+   }
+}
+
+
+// Drawable `content` passed through the filter `ops` in order, with lengths
+// in script units
+#[derive(Debug, Clone)]
+pub struct Filtered {
+   pub content: Variant,
+   pub ops: Vec<FilterOp>,
+}
+
+impl Filtered {
+   #[inline]
+   pub fn new(content: Variant, ops: Vec<FilterOp>) -> Self {
+      Filtered {
+         content: content,
+         ops: ops,
+      }
+   }
+}
@@ -8,6 +8,8 @@ mod execute;
 mod environment;
 mod variant;
 mod registry;
+mod layer;
+mod paint;
 
 mod renderer;
 
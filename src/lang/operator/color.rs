@@ -1,4 +1,6 @@
 use draw::RGB;
+use color::{Xyz, Lab, Oklch, hsl_to_rgb, hsv_to_rgb};
+use color::space::to_rgb8;
 
 
 
@@ -19,3 +21,39 @@ fn unequal_rgb_rgb_(a: &RGB, b: &RGB) -> bool {
 }
 wrap_2_arg!(unequal_rgb_rgb, unequal_rgb_rgb_);
 
+
+
+// Hue in degrees, saturation and lightness in percents like CSS
+fn hsl_(h: &f64, s: &f64, l: &f64) -> RGB {
+   to_rgb8(hsl_to_rgb(*h, *s / 100., *l / 100.))
+}
+wrap_3_arg!(hsl, hsl_);
+
+
+fn hsv_(h: &f64, s: &f64, v: &f64) -> RGB {
+   to_rgb8(hsv_to_rgb(*h, *s / 100., *v / 100.))
+}
+wrap_3_arg!(hsv, hsv_);
+
+
+// CIE Lab with the D50 white like CSS, out of gamut colors get clipped
+fn lab_(l: &f64, a: &f64, b: &f64) -> RGB {
+   Lab::new(*l, *a, *b).to_xyz().to_rgb()
+}
+wrap_3_arg!(lab, lab_);
+
+
+fn oklch_(l: &f64, c: &f64, h: &f64) -> RGB {
+   Oklch::new(*l, *c, *h).to_oklab().to_xyz().to_rgb()
+}
+wrap_3_arg!(oklch, oklch_);
+
+
+// Interpolation in OKLab, which keeps the perceived lightness even
+fn mix_(a: &RGB, b: &RGB, t: &f64) -> RGB {
+   let a = Xyz::from_rgb(a).to_oklab();
+   let b = Xyz::from_rgb(b).to_oklab();
+
+   a.mix(&b, t.max(0.).min(1.)).to_xyz().to_rgb()
+}
+wrap_3_arg!(mix, mix_);
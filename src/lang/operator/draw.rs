@@ -1,13 +1,17 @@
 use std::any::TypeId;
 use std::usize;
 
-use draw::RGB;
-use devel::Poly;
+use draw::{RGB, BlendMode};
+use devel::{Poly, MaskMode, FilterOp, Morphology, DropShadow};
 use data::{IntPoint, FloatPoint, Empty};
+use geom::path::Path;
+use geom::flatten::Flattener;
 
 use lang::variant::{Variant, VariantVec};
 use lang::compiler::FnRef;
 use lang::execute::Executor;
+use lang::layer::{Masked, Group, Filtered};
+use lang::paint::{Pattern, PatternFill};
 
 
 
@@ -29,11 +33,310 @@ pub fn solid_fill(
 
    match depth {
       Some(depth_value) => vec![extract_poly_points(executor, list, color, depth_value)],
-      None => vecval!(executor, Empty),
+      None => vec![fill_paths(executor, list, color)],
    }
 }
 
 
+// mask(content, source) or mask(content, source, "luminance"), alpha
+// masking being the default
+pub fn mask(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() < 2 || arguments.len() > 3 {
+      return vecval!(executor, Empty);
+   }
+
+   let mode = if arguments.len() == 3 {
+      match arguments[2].as_ref_checked::<String>().map(|mode| mode.as_str()) {
+         Some("alpha") => MaskMode::Alpha,
+         Some("luminance") => MaskMode::Luminance,
+         _ => return vecval!(executor, Empty),
+      }
+   } else {
+      MaskMode::Alpha
+   };
+
+   vecval!(executor, Masked::new(arguments[0].clone(), arguments[1].clone(), mode))
+}
+
+
+// group(content, opacity) or group(content, opacity, "multiply") with any
+// of the BlendMode names
+pub fn group(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() < 2 || arguments.len() > 3 {
+      return vecval!(executor, Empty);
+   }
+
+   let opacity = match arguments[1].as_ref_checked::<f64>() {
+      Some(opacity) => *opacity,
+      None => return vecval!(executor, Empty),
+   };
+
+   let blend = if arguments.len() == 3 {
+      match arguments[2].as_ref_checked::<String>().and_then(|name| BlendMode::from_name(name)) {
+         Some(blend) => blend,
+         None => return vecval!(executor, Empty),
+      }
+   } else {
+      BlendMode::Normal
+   };
+
+   vecval!(executor, Group::new(arguments[0].clone(), opacity, blend))
+}
+
+
+// Appends `op` to the filters of already filtered content, so nested filter
+// calls build a pipeline
+fn filtered(executor: &Executor, content: &Variant, op: FilterOp) -> Vec<Variant> {
+   let filtered = match content.as_ref_checked::<Filtered>() {
+      Some(filtered) => {
+         let mut ops = filtered.ops.clone();
+
+         ops.push(op);
+
+         Filtered::new(filtered.content.clone(), ops)
+      },
+      None => Filtered::new(content.clone(), vec![op]),
+   };
+
+   vecval!(executor, filtered)
+}
+
+
+// Numbers following the filtered content
+fn filter_numbers(arguments: &[&Variant]) -> Option<Vec<f64>> {
+   if arguments.len() == 0 {
+      return None;
+   }
+
+   numbers(&arguments[1..])
+}
+
+
+fn numbers(arguments: &[&Variant]) -> Option<Vec<f64>> {
+   let mut numbers = Vec::with_capacity(arguments.len());
+
+   for argument in arguments.iter() {
+      match argument.as_ref_checked::<f64>() {
+         Some(number) => numbers.push(*number),
+         None => return None,
+      }
+   }
+
+   Some(numbers)
+}
+
+
+// blur(content, std_dev) or blur(content, std_dev_x, std_dev_y)
+pub fn blur(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   match filter_numbers(arguments) {
+      Some(ref std_dev) if std_dev.len() == 1 => {
+         filtered(executor, arguments[0], FilterOp::Blur(std_dev[0], std_dev[0]))
+      },
+      Some(ref std_dev) if std_dev.len() == 2 => {
+         filtered(executor, arguments[0], FilterOp::Blur(std_dev[0], std_dev[1]))
+      },
+      _ => vecval!(executor, Empty),
+   }
+}
+
+
+// offset(content, dx, dy)
+pub fn offset(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   match filter_numbers(arguments) {
+      Some(ref delta) if delta.len() == 2 => {
+         filtered(executor, arguments[0], FilterOp::Offset(delta[0], delta[1]))
+      },
+      _ => vecval!(executor, Empty),
+   }
+}
+
+
+// color_matrix(content, m0 .. m19) with the 4x5 matrix in row order
+pub fn color_matrix(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   match filter_numbers(arguments) {
+      Some(ref values) if values.len() == 20 => {
+         let mut matrix = [0.; 20];
+
+         matrix.copy_from_slice(values);
+
+         filtered(executor, arguments[0], FilterOp::ColorMatrix(matrix))
+      },
+      _ => vecval!(executor, Empty),
+   }
+}
+
+
+// dilate(content, radius) or dilate(content, radius_x, radius_y)
+pub fn dilate(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   morphology(arguments, executor, Morphology::Dilate)
+}
+
+
+// erode(content, radius) or erode(content, radius_x, radius_y)
+pub fn erode(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   morphology(arguments, executor, Morphology::Erode)
+}
+
+
+fn morphology(arguments: &[&Variant], executor: &Executor, op: Morphology) -> Vec<Variant> {
+   match filter_numbers(arguments) {
+      Some(ref radius) if radius.len() == 1 => {
+         filtered(executor, arguments[0], FilterOp::Morphology(op, radius[0], radius[0]))
+      },
+      Some(ref radius) if radius.len() == 2 => {
+         filtered(executor, arguments[0], FilterOp::Morphology(op, radius[0], radius[1]))
+      },
+      _ => vecval!(executor, Empty),
+   }
+}
+
+
+// drop_shadow(content, dx, dy, std_dev, color) or with an opacity after the
+// color
+pub fn drop_shadow(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() < 5 || arguments.len() > 6 {
+      return vecval!(executor, Empty);
+   }
+
+   let color = match arguments[4].as_ref_checked::<RGB>() {
+      Some(color) => *color,
+      None => return vecval!(executor, Empty),
+   };
+
+   let opacity = if arguments.len() == 6 {
+      match arguments[5].as_ref_checked::<f64>() {
+         Some(opacity) => *opacity,
+         None => return vecval!(executor, Empty),
+      }
+   } else {
+      1.
+   };
+
+   let values = match numbers(&arguments[1..4]) {
+      Some(values) => values,
+      None => return vecval!(executor, Empty),
+   };
+
+   let shadow = DropShadow {
+      dx: values[0],
+      dy: values[1],
+      std_dev: values[2],
+      color: color,
+      opacity: opacity,
+   };
+
+   filtered(executor, arguments[0], FilterOp::DropShadow(shadow))
+}
+
+
+// pattern(content, origin, size) with the tile rectangle in script units
+pub fn pattern(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() != 3 {
+      return vecval!(executor, Empty);
+   }
+
+   let origin = arguments[1].as_ref_checked::<FloatPoint>();
+   let size = arguments[2].as_ref_checked::<FloatPoint>();
+
+   match (origin, size) {
+      (Some(origin), Some(size)) if size.x > 0. && size.y > 0. => {
+         vecval!(executor, Pattern::new(arguments[0].clone(), *origin, *size))
+      },
+      _ => vecval!(executor, Empty),
+   }
+}
+
+
+pub fn fill_path_pattern(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let path = arguments[0].as_ref::<Path>();
+   let pattern = arguments[1].as_ref::<Pattern>();
+
+   vecval!(executor, PatternFill::new(path.clone(), pattern.clone()))
+}
+
+
+pub fn fill_path(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let path = arguments[0].as_ref::<Path>();
+   let color = arguments[1].as_ref::<RGB>();
+
+   vec![path_poly(executor, path, color)]
+}
+
+
+fn path_poly(executor: &Executor, path: &Path, color: &RGB) -> Variant {
+   let poly = Poly::from_path(path, &Flattener::new(executor.tolerance), color.clone());
+
+   if poly.contours.len() == 0 {
+      executor.registry.variant(Empty)
+   } else {
+      executor.registry.variant(poly)
+   }
+}
+
+
+// Lists of paths, possibly nested or mixed with point lists
+fn fill_paths(executor: &Executor, list: &VariantVec, color: &RGB) -> Variant {
+   let mut polys = Vec::new();
+
+   for variant in list.iter() {
+      if let Some(path) = variant.as_ref_checked::<Path>() {
+         polys.push(path_poly(executor, path, color));
+      } else if let Some(inner_list) = variant.as_ref_checked::<VariantVec>() {
+         match drill_points_depth(inner_list) {
+            Some(depth) => polys.push(extract_poly_points(executor, inner_list, color, depth)),
+            None => polys.push(fill_paths(executor, inner_list, color)),
+         }
+      }
+   }
+
+   executor.registry.variant(polys)
+}
+
+
 fn extract_poly_points(executor: &Executor, list: &VariantVec, color: &RGB, depth: usize) -> Variant {
    match depth {
       1 => {
@@ -0,0 +1,103 @@
+use devel::Poly;
+use draw::RGB;
+use data::{FloatPoint, Empty};
+use geom::path::Path;
+use geom::flatten::Flattener;
+use geom::transform::Transform;
+use image::{Image, ImagePaint, RepeatMode, ImageFilter};
+
+use lang::variant::Variant;
+use lang::compiler::FnRef;
+use lang::execute::Executor;
+
+
+pub fn image_str(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let filename = arguments[0].as_ref::<String>();
+
+   match executor.images.load(filename) {
+      Some(image) => vecval!(executor, image),
+      None => vecval!(executor, Empty),
+   }
+}
+
+
+// image_fill(path, image) places the image pixels at the frame pixels from
+// the origin. An origin point, a scale number and repeat or filter names like
+// "repeat" or "bicubic" can follow in that order
+pub fn image_fill(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() < 2 {
+      return vecval!(executor, Empty);
+   }
+
+   let path = match arguments[0].as_ref_checked::<Path>() {
+      Some(path) => path,
+      None => return vecval!(executor, Empty),
+   };
+
+   let image = match arguments[1].as_ref_checked::<Image>() {
+      Some(image) => image,
+      None => return vecval!(executor, Empty),
+   };
+
+   let mut rest = &arguments[2..];
+
+   let mut origin = FloatPoint::new(0., 0.);
+
+   if let Some(point) = rest.first().and_then(|argument| argument.as_ref_checked::<FloatPoint>()) {
+      origin = *point;
+      rest = &rest[1..];
+   }
+
+   let mut scale = 1.;
+
+   if let Some(number) = rest.first().and_then(|argument| argument.as_ref_checked::<f64>()) {
+      scale = *number;
+      rest = &rest[1..];
+   }
+
+   let mut repeat = RepeatMode::default();
+   let mut filter = ImageFilter::default();
+
+   for argument in rest.iter() {
+      let name = match argument.as_ref_checked::<String>() {
+         Some(name) => name,
+         None => return vecval!(executor, Empty),
+      };
+
+      if let Some(mode) = RepeatMode::from_name(name) {
+         repeat = mode;
+      } else if let Some(name_filter) = ImageFilter::from_name(name) {
+         filter = name_filter;
+      } else {
+         return vecval!(executor, Empty);
+      }
+   }
+
+   // Script units are subdivided frame pixels
+   let pixel_scale = scale * executor.subdivisions as f64;
+
+   let transform = Transform::scale(pixel_scale, pixel_scale)
+      .then(&Transform::translate(origin.x, origin.y));
+
+   // A zero scale leaves nothing to sample
+   let paint = match ImagePaint::new(image.clone(), transform, repeat, filter) {
+      Some(paint) => paint,
+      None => return vecval!(executor, Empty),
+   };
+
+   let poly = Poly::from_path(path, &Flattener::new(executor.tolerance), RGB::default());
+
+   if poly.contours.len() == 0 {
+      vecval!(executor, Empty)
+   } else {
+      vecval!(executor, poly.with_paint(paint))
+   }
+}
@@ -0,0 +1,139 @@
+use devel::{Patch, Mesh};
+use draw::RGB;
+use data::{FloatPoint, Empty};
+
+use lang::variant::Variant;
+use lang::compiler::FnRef;
+use lang::execute::Executor;
+
+
+// patch(points..., colors...) takes the 12 boundary points of a Coons patch
+// or the 16 points of a tensor patch, followed by the 4 corner colors, all
+// in the order of the PDF shading types 6 and 7
+pub fn patch(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let count = arguments.len();
+
+   if count != 16 && count != 20 {
+      return vecval!(executor, Empty);
+   }
+
+   let (points, colors) = match (points(&arguments[..count - 4]), colors(&arguments[count - 4..])) {
+      (Some(points), Some(colors)) => (points, colors),
+      _ => return vecval!(executor, Empty),
+   };
+
+   let colors = [colors[0], colors[1], colors[2], colors[3]];
+
+   let mut boundary = [FloatPoint::default(); 12];
+
+   boundary.copy_from_slice(&points[..12]);
+
+   let mut patch = Patch::coons(&boundary, colors);
+
+   if points.len() == 16 {
+      patch.points[1][1] = points[12];
+      patch.points[1][2] = points[13];
+      patch.points[2][2] = points[14];
+      patch.points[2][1] = points[15];
+   }
+
+   vecval!(executor, patch)
+}
+
+
+// mesh(patches...) with meshes among them adding all their patches
+pub fn mesh(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let mut patches = Vec::new();
+
+   for argument in arguments.iter() {
+      if let Some(patch) = argument.as_ref_checked::<Patch>() {
+         patches.push(patch.clone());
+      } else if let Some(mesh) = argument.as_ref_checked::<Mesh>() {
+         patches.extend(mesh.patches.iter().cloned());
+      } else {
+         return vecval!(executor, Empty);
+      }
+   }
+
+   if patches.len() == 0 {
+      return vecval!(executor, Empty);
+   }
+
+   vecval!(executor, Mesh::new(patches))
+}
+
+
+// mesh_grid(columns, rows, points..., colors...) with the grid vertices and
+// then their colors row by row, `columns + 1` of each per row
+pub fn mesh_grid(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   if arguments.len() < 2 {
+      return vecval!(executor, Empty);
+   }
+
+   let size = |argument: &Variant| {
+      argument.as_ref_checked::<f64>().and_then(
+         |number| if *number >= 1. { Some(*number as usize) } else { None }
+      )
+   };
+
+   let (columns, rows) = match (size(arguments[0]), size(arguments[1])) {
+      (Some(columns), Some(rows)) => (columns, rows),
+      _ => return vecval!(executor, Empty),
+   };
+
+   let count = (columns + 1) * (rows + 1);
+
+   if arguments.len() != 2 + 2 * count {
+      return vecval!(executor, Empty);
+   }
+
+   let (points, colors) = match (points(&arguments[2..2 + count]), colors(&arguments[2 + count..])) {
+      (Some(points), Some(colors)) => (points, colors),
+      _ => return vecval!(executor, Empty),
+   };
+
+   match Mesh::grid(columns, rows, &points, &colors) {
+      Some(mesh) => vecval!(executor, mesh),
+      None => vecval!(executor, Empty),
+   }
+}
+
+
+fn points(arguments: &[&Variant]) -> Option<Vec<FloatPoint>> {
+   let mut points = Vec::with_capacity(arguments.len());
+
+   for argument in arguments.iter() {
+      match argument.as_ref_checked::<FloatPoint>() {
+         Some(point) => points.push(*point),
+         None => return None,
+      }
+   }
+
+   Some(points)
+}
+
+
+fn colors(arguments: &[&Variant]) -> Option<Vec<RGB>> {
+   let mut colors = Vec::with_capacity(arguments.len());
+
+   for argument in arguments.iter() {
+      match argument.as_ref_checked::<RGB>() {
+         Some(color) => colors.push(*color),
+         None => return None,
+      }
+   }
+
+   Some(colors)
+}
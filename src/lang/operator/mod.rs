@@ -8,7 +8,10 @@ mod point;
 mod color;
 mod text;
 mod draw;
+mod image;
+mod mesh;
 mod svg;
+mod path;
 mod control;
 
 use std::any::TypeId;
@@ -17,9 +20,11 @@ use std::collections::HashMap;
 use sys::ft::Face;
 use draw::RGB;
 use data::FloatPoint;
+use geom::path::Path;
 
 use super::compiler::{BuiltinIndices, FnRef};
 use super::variant::{Variant, VariantVec};
+use super::paint::Pattern;
 use super::execute::Executor;
 use super::parser::FnIndex;
 
@@ -52,11 +57,20 @@ use self::functional::{
 };
 
 use self::color::{
-   rgb, equal_rgb_rgb, unequal_rgb_rgb,
+   rgb, equal_rgb_rgb, unequal_rgb_rgb, hsl, hsv, lab, oklch, mix,
 };
 
 use self::draw::{
-   solid_fill,
+   solid_fill, fill_path, fill_path_pattern, pattern, mask, group,
+   blur, offset, color_matrix, dilate, erode, drop_shadow,
+};
+
+use self::image::{
+   image_str, image_fill,
+};
+
+use self::mesh::{
+   patch, mesh, mesh_grid,
 };
 
 use self::text::{
@@ -65,6 +79,13 @@ use self::text::{
 
 use self::svg::svg_path;
 
+use self::path::{
+   path_fpt, path_lst, move_to_pth_fpt, line_to_pth_fpt, quad_to_pth_fpt_fpt,
+   cubic_to_pth_fpt_fpt_fpt, arc_pth_fpt_f64, close_pth, reverse_pth,
+   translate_pth_fpt, scale_pth_f64, scale_pth_fpt, rotate_pth_fpt_f64,
+   bounds_pth, flatten_pth, union_pth_pth, intersect_pth_pth, subtract_pth_pth, xor_pth_pth,
+};
+
 use self::control::if_;
 
 
@@ -174,6 +195,8 @@ pub fn register_builtin_fns() -> (BuiltinIndices, BuiltinFns) {
    let tyid_fce = TypeId::of::<Face>();
    let tyid_str = TypeId::of::<String>();
    let tyid_bln = TypeId::of::<bool>();
+   let tyid_pth = TypeId::of::<Path>();
+   let tyid_ptn = TypeId::of::<Pattern>();
 
    register_2_arg(&mut indices, &mut fn_list, "add", (tyid_f64, tyid_f64), add_f64_f64);
 
@@ -309,6 +332,8 @@ pub fn register_builtin_fns() -> (BuiltinIndices, BuiltinFns) {
 
    register_3_arg(&mut indices, &mut fn_list, "rotate", (tyid_lst, tyid_lst, tyid_lst), list_lst_lst_lst);
 
+   register_3_arg(&mut indices, &mut fn_list, "rotate", (tyid_pth, tyid_fpt, tyid_f64), rotate_pth_fpt_f64);
+
    register_3_arg(&mut indices, &mut fn_list, "flip_x", (tyid_fpt, tyid_f64, tyid_f64), flip_x_fpt_f64_f64);
 
    register_3_arg(&mut indices, &mut fn_list, "flip_x", (tyid_lst, tyid_f64, tyid_f64), list_lst_val_val);
@@ -329,12 +354,42 @@ pub fn register_builtin_fns() -> (BuiltinIndices, BuiltinFns) {
 
    register_3_arg(&mut indices, &mut fn_list, "rgb", (tyid_lst, tyid_lst, tyid_lst), list_lst_lst_lst);
 
+   register_3_arg(&mut indices, &mut fn_list, "hsl", (tyid_f64, tyid_f64, tyid_f64), hsl);
+   register_3_arg(&mut indices, &mut fn_list, "hsv", (tyid_f64, tyid_f64, tyid_f64), hsv);
+   register_3_arg(&mut indices, &mut fn_list, "lab", (tyid_f64, tyid_f64, tyid_f64), lab);
+   register_3_arg(&mut indices, &mut fn_list, "oklch", (tyid_f64, tyid_f64, tyid_f64), oklch);
+
+   register_3_arg(&mut indices, &mut fn_list, "mix", (tyid_rgb, tyid_rgb, tyid_f64), mix);
+
    register_1_arg(&mut indices, &mut fn_list, "font_face", tyid_str, font_face);
 
    register_4_arg(&mut indices, &mut fn_list, "text", (tyid_fce, tyid_str, tyid_f64, tyid_fpt), text_fce_str_f64_fpt);
 
    register_2_arg(&mut indices, &mut fn_list, "fill", (tyid_lst, tyid_rgb), solid_fill);
    register_2_arg(&mut indices, &mut fn_list, "fill", (tyid_lst, tyid_lst), list_lst_lst);
+   register_2_arg(&mut indices, &mut fn_list, "fill", (tyid_pth, tyid_rgb), fill_path);
+   register_2_arg(&mut indices, &mut fn_list, "fill", (tyid_pth, tyid_ptn), fill_path_pattern);
+
+   register_n_arg(&mut indices, &mut fn_list, "pattern", pattern);
+
+   register_n_arg(&mut indices, &mut fn_list, "mask", mask);
+
+   register_n_arg(&mut indices, &mut fn_list, "group", group);
+
+   register_n_arg(&mut indices, &mut fn_list, "blur", blur);
+   register_n_arg(&mut indices, &mut fn_list, "offset", offset);
+   register_n_arg(&mut indices, &mut fn_list, "color_matrix", color_matrix);
+   register_n_arg(&mut indices, &mut fn_list, "dilate", dilate);
+   register_n_arg(&mut indices, &mut fn_list, "erode", erode);
+   register_n_arg(&mut indices, &mut fn_list, "drop_shadow", drop_shadow);
+
+   register_1_arg(&mut indices, &mut fn_list, "image", tyid_str, image_str);
+
+   register_n_arg(&mut indices, &mut fn_list, "image_fill", image_fill);
+
+   register_n_arg(&mut indices, &mut fn_list, "patch", patch);
+   register_n_arg(&mut indices, &mut fn_list, "mesh", mesh);
+   register_n_arg(&mut indices, &mut fn_list, "mesh_grid", mesh_grid);
 
    register_2_arg(&mut indices, &mut fn_list, "call", (tyid_lst, tyid_fnp), call_lst_fnp);
    register_2_arg(&mut indices, &mut fn_list, "call", (tyid_lst, tyid_lst), call_lst_lst);
@@ -351,6 +406,42 @@ pub fn register_builtin_fns() -> (BuiltinIndices, BuiltinFns) {
 
    register_1_arg(&mut indices, &mut fn_list, "svg_path", tyid_str, svg_path);
 
+   register_1_arg(&mut indices, &mut fn_list, "path", tyid_fpt, path_fpt);
+   register_1_arg(&mut indices, &mut fn_list, "path", tyid_lst, path_lst);
+
+   register_2_arg(&mut indices, &mut fn_list, "move_to", (tyid_pth, tyid_fpt), move_to_pth_fpt);
+
+   register_2_arg(&mut indices, &mut fn_list, "line_to", (tyid_pth, tyid_fpt), line_to_pth_fpt);
+
+   register_3_arg(&mut indices, &mut fn_list, "quad_to", (tyid_pth, tyid_fpt, tyid_fpt), quad_to_pth_fpt_fpt);
+
+   register_4_arg(
+      &mut indices, &mut fn_list, "cubic_to", (tyid_pth, tyid_fpt, tyid_fpt, tyid_fpt), cubic_to_pth_fpt_fpt_fpt
+   );
+
+   register_3_arg(&mut indices, &mut fn_list, "arc", (tyid_pth, tyid_fpt, tyid_f64), arc_pth_fpt_f64);
+
+   register_1_arg(&mut indices, &mut fn_list, "close", tyid_pth, close_pth);
+
+   register_1_arg(&mut indices, &mut fn_list, "reverse", tyid_pth, reverse_pth);
+
+   register_2_arg(&mut indices, &mut fn_list, "translate", (tyid_pth, tyid_fpt), translate_pth_fpt);
+   register_2_arg(&mut indices, &mut fn_list, "translate", (tyid_lst, tyid_fpt), list_lst_val);
+
+   register_2_arg(&mut indices, &mut fn_list, "scale", (tyid_pth, tyid_f64), scale_pth_f64);
+   register_2_arg(&mut indices, &mut fn_list, "scale", (tyid_pth, tyid_fpt), scale_pth_fpt);
+   register_2_arg(&mut indices, &mut fn_list, "scale", (tyid_lst, tyid_f64), list_lst_val);
+   register_2_arg(&mut indices, &mut fn_list, "scale", (tyid_lst, tyid_fpt), list_lst_val);
+
+   register_1_arg(&mut indices, &mut fn_list, "bounds", tyid_pth, bounds_pth);
+
+   register_1_arg(&mut indices, &mut fn_list, "flatten", tyid_pth, flatten_pth);
+
+   register_2_arg(&mut indices, &mut fn_list, "union", (tyid_pth, tyid_pth), union_pth_pth);
+   register_2_arg(&mut indices, &mut fn_list, "intersect", (tyid_pth, tyid_pth), intersect_pth_pth);
+   register_2_arg(&mut indices, &mut fn_list, "subtract", (tyid_pth, tyid_pth), subtract_pth_pth);
+   register_2_arg(&mut indices, &mut fn_list, "xor", (tyid_pth, tyid_pth), xor_pth_pth);
+
    register_2_arg(&mut indices, &mut fn_list, "repeat", (tyid_i64, tyid_fnp), repeat);
 
    register_n_arg(&mut indices, &mut fn_list, "if", if_);
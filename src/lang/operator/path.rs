@@ -0,0 +1,231 @@
+use data::{IntPoint, FloatPoint, Empty};
+use geom::path::{Path, PathSegment};
+use geom::flatten::Flattener;
+use geom::boolean::{BooleanOp, boolean_path};
+
+use lang::variant::{Variant, VariantVec};
+use lang::compiler::FnRef;
+use lang::execute::Executor;
+
+
+fn path_fpt_(start: &FloatPoint) -> Path {
+   Path::new().move_to(*start)
+}
+wrap_1_arg!(path_fpt, path_fpt_);
+
+
+pub fn path_lst(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let list = arguments[0].as_ref::<VariantVec>();
+
+   let mut path = Path::new();
+
+   push_point_lists(&mut path, list);
+
+   if path.is_empty() {
+      vecval!(executor, Empty)
+   } else {
+      vecval!(executor, path)
+   }
+}
+
+
+// Every list of points becomes a closed polygon subpath
+fn push_point_lists(path: &mut Path, list: &VariantVec) {
+   let mut points = Vec::new();
+
+   for variant in list.iter() {
+      if let Some(point) = variant.as_ref_checked::<FloatPoint>() {
+         points.push(*point);
+      } else if let Some(point) = variant.as_ref_checked::<IntPoint>() {
+         points.push(point.as_float());
+      } else if let Some(inner) = variant.as_ref_checked::<VariantVec>() {
+         push_point_lists(path, inner);
+      }
+   }
+
+   if points.len() < 2 {
+      return;
+   }
+
+   path.push(PathSegment::MoveTo(points[0]));
+
+   for point in points[1..].iter() {
+      path.push(PathSegment::LineTo(*point));
+   }
+
+   path.push(PathSegment::Close);
+}
+
+
+fn move_to_pth_fpt_(path: &Path, p: &FloatPoint) -> Path {
+   path.clone().move_to(*p)
+}
+wrap_2_arg!(move_to_pth_fpt, move_to_pth_fpt_);
+
+
+fn line_to_pth_fpt_(path: &Path, p: &FloatPoint) -> Path {
+   path.clone().line_to(*p)
+}
+wrap_2_arg!(line_to_pth_fpt, line_to_pth_fpt_);
+
+
+fn quad_to_pth_fpt_fpt_(path: &Path, ctrl: &FloatPoint, p: &FloatPoint) -> Path {
+   path.clone().quad_to(*ctrl, *p)
+}
+wrap_3_arg!(quad_to_pth_fpt_fpt, quad_to_pth_fpt_fpt_);
+
+
+fn cubic_to_pth_fpt_fpt_fpt_(
+   path: &Path, ctrl1: &FloatPoint, ctrl2: &FloatPoint, p: &FloatPoint
+) -> Path {
+   path.clone().cubic_to(*ctrl1, *ctrl2, *p)
+}
+wrap_4_arg!(cubic_to_pth_fpt_fpt_fpt, cubic_to_pth_fpt_fpt_fpt_);
+
+
+// Circular arc from the current point around `center`, the sweep in degrees
+fn arc_pth_fpt_f64_(path: &Path, center: &FloatPoint, sweep: &f64) -> Path {
+   let current = path.current();
+
+   let dx = current.x - center.x;
+   let dy = current.y - center.y;
+
+   let radius = dx.hypot(dy);
+
+   path.clone().arc(*center, radius, radius, 0., dy.atan2(dx), sweep.to_radians())
+}
+wrap_3_arg!(arc_pth_fpt_f64, arc_pth_fpt_f64_);
+
+
+fn close_pth_(path: &Path) -> Path {
+   path.clone().close()
+}
+wrap_1_arg!(close_pth, close_pth_);
+
+
+fn reverse_pth_(path: &Path) -> Path {
+   path.reversed()
+}
+wrap_1_arg!(reverse_pth, reverse_pth_);
+
+
+fn translate_pth_fpt_(path: &Path, offset: &FloatPoint) -> Path {
+   path.translate(*offset)
+}
+wrap_2_arg!(translate_pth_fpt, translate_pth_fpt_);
+
+
+fn scale_pth_f64_(path: &Path, factor: &f64) -> Path {
+   path.scale(*factor, *factor)
+}
+wrap_2_arg!(scale_pth_f64, scale_pth_f64_);
+
+
+fn scale_pth_fpt_(path: &Path, factors: &FloatPoint) -> Path {
+   path.scale(factors.x, factors.y)
+}
+wrap_2_arg!(scale_pth_fpt, scale_pth_fpt_);
+
+
+fn rotate_pth_fpt_f64_(path: &Path, origin: &FloatPoint, angle: &f64) -> Path {
+   path.rotate(angle.to_radians(), *origin)
+}
+wrap_3_arg!(rotate_pth_fpt_f64, rotate_pth_fpt_f64_);
+
+
+pub fn bounds_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let path = arguments[0].as_ref::<Path>();
+
+   match path.bounds() {
+      Some((min, max)) => {
+         let corners = vec![executor.registry.variant(min), executor.registry.variant(max)];
+
+         vecval!(executor, corners)
+      },
+      None => vecval!(executor, Empty),
+   }
+}
+
+
+pub fn flatten_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   let path = arguments[0].as_ref::<Path>();
+
+   let mut result = Vec::new();
+
+   for contour in path.flatten(&Flattener::new(executor.tolerance)) {
+      let mut inner = Vec::new();
+
+      for point in contour {
+         inner.push(executor.registry.variant(point));
+      }
+
+      result.push(executor.registry.variant(inner));
+   }
+
+   vecval!(executor, result)
+}
+
+
+// Both paths are combined with the even-odd rule, the parts of the result
+// which follow the operand curves are kept as curves
+fn boolean_paths(
+   op: BooleanOp,
+   arguments: &[&Variant],
+   executor: &Executor,
+) -> Vec<Variant> {
+   let flattener = Flattener::new(executor.tolerance);
+
+   let path = boolean_path(
+      op, arguments[0].as_ref::<Path>(), arguments[1].as_ref::<Path>(), &flattener
+   );
+
+   vecval!(executor, path)
+}
+
+
+pub fn union_pth_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   boolean_paths(BooleanOp::Union, arguments, executor)
+}
+
+
+pub fn intersect_pth_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   boolean_paths(BooleanOp::Intersection, arguments, executor)
+}
+
+
+pub fn subtract_pth_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   boolean_paths(BooleanOp::Difference, arguments, executor)
+}
+
+
+pub fn xor_pth_pth(
+   arguments: &[&Variant],
+   executor: &Executor,
+   _: &FnRef
+) -> Vec<Variant> {
+   boolean_paths(BooleanOp::Xor, arguments, executor)
+}
@@ -1,7 +1,5 @@
-use std::f64::consts::PI;
-
-use devel::SUBDIVISIONS;
 use data::{Empty, FloatPoint};
+use geom::path::Path;
 
 use lang::variant::Variant;
 use lang::execute::Executor;
@@ -466,43 +464,27 @@ fn get_number(tokens: &[Token]) -> Result<(f64, &[Token]), ()> {
 }
 
 
-fn process_path(commands: Vec<(Command, bool)>, steps: usize) -> Vec<Vec<FloatPoint>> {
-   let mut contours = Vec::new();
-
-   let mut active = Vec::new();
-
-   let mut last = FloatPoint::default();
-
-   let mut start = FloatPoint::default();
+fn process_path(commands: Vec<(Command, bool)>) -> Path {
+   let mut path = Path::new();
 
    let mut last_control = FloatPoint::default();
 
    for (command, absolute) in commands {
+      let last = path.current();
+
       match command {
          Command::MoveTo {p} => {
-            let p = to_absolute(p, last, absolute);
+            path = path.move_to(to_absolute(p, last, absolute));
 
-            active.push(p);
-
-            last = p;
-            start = p;
-            last_control = last;
+            last_control = path.current();
          },
          Command::CurveTo {p1, p2, p} => {
             let p1 = to_absolute(p1, last, absolute);
             let p2 = to_absolute(p2, last, absolute);
             let p = to_absolute(p, last, absolute);
 
-            cubic_to(
-               &mut active,
-               last,
-               p1,
-               p2,
-               p,
-               steps
-            );
+            path = path.cubic_to(p1, p2, p);
 
-            last = p;
             last_control = p2;
          },
          Command::SmoothCurveTo {p2, p} => {
@@ -510,74 +492,37 @@ fn process_path(commands: Vec<(Command, bool)>, steps: usize) -> Vec<Vec<FloatPo
             let p2 = to_absolute(p2, last, absolute);
             let p = to_absolute(p, last, absolute);
 
-            cubic_to(
-               &mut active,
-               last,
-               p1,
-               p2,
-               p,
-               steps
-            );
+            path = path.cubic_to(p1, p2, p);
 
-            last = p;
             last_control = p2;
          },
          Command::Quadratic {p1, p} => {
             let p1 = to_absolute(p1, last, absolute);
             let p = to_absolute(p, last, absolute);
 
-            conic_to(
-               &mut active,
-               last,
-               p1,
-               p,
-               steps
-            );
+            path = path.quad_to(p1, p);
 
-            last = p;
             last_control = p1;
          },
          Command::SmoothQuadratic {p} => {
             let p1 = last + (last - last_control);
             let p = to_absolute(p, last, absolute);
 
-            conic_to(
-               &mut active,
-               last,
-               p1,
-               p,
-               steps
-            );
+            path = path.quad_to(p1, p);
 
-            last = p;
             last_control = p1;
          },
          Command::EllipticalArc {rx, ry, rotation, large, sweep, p} => {
-            assert!(rx == ry);
-            assert!(rotation == 0.0);
-
             let p = to_absolute(p, last, absolute);
 
-            arc_to(
-               &mut active,
-               last,
-               p,
-               rx,
-               large,
-               sweep,
-               steps
-            );
-
-            last = p;
-            last_control = last;
+            path = path.arc_to(rx, ry, rotation.to_radians(), large, sweep, p);
+
+            last_control = path.current();
          },
          Command::LineTo {p} => {
-            let p = to_absolute(p, last, absolute);
-
-            active.push(p);
+            path = path.line_to(to_absolute(p, last, absolute));
 
-            last = p;
-            last_control = last;
+            last_control = path.current();
          },
          Command::Horizontal {x} => {
             let p = if absolute {
@@ -586,10 +531,9 @@ fn process_path(commands: Vec<(Command, bool)>, steps: usize) -> Vec<Vec<FloatPo
                FloatPoint::new(last.x + x, last.y)
             };
 
-            active.push(p);
+            path = path.line_to(p);
 
-            last = p;
-            last_control = last;
+            last_control = p;
          },
          Command::Vertical {y} => {
             let p = if absolute {
@@ -598,23 +542,19 @@ fn process_path(commands: Vec<(Command, bool)>, steps: usize) -> Vec<Vec<FloatPo
                FloatPoint::new(last.x, last.y + y)
             };
 
-            active.push(p);
+            path = path.line_to(p);
 
-            last = p;
-            last_control = last;
+            last_control = p;
          },
          Command::ClosePath => {
-            if active.len() > 0 {
-               contours.push(active);
-            }
+            path = path.close();
 
-            active = vec![];
-            last = start;
+            last_control = path.current();
          },
       }
    }
 
-   contours
+   path
 }
 
 
@@ -628,192 +568,20 @@ fn to_absolute(p: FloatPoint, last: FloatPoint, absolute: bool) -> FloatPoint {
 }
 
 
-fn conic_to(
-   contour: &mut Vec<FloatPoint>,
-   from: FloatPoint,
-   ctrl: FloatPoint,
-   to: FloatPoint,
-   steps: usize
-) {
-   for i in 1..steps {
-      let t2 = i as f64 / steps as f64;
-      let t1 = 1. - t2;
-
-      let u = on_segment(from, ctrl, t1, t2);
-      let v = on_segment(ctrl, to, t1, t2);
-
-      let f = on_segment(u, v, t1, t2);
-
-      contour.push(f);
-   }
-
-   contour.push(to);
-}
-
-
-fn cubic_to(
-   contour: &mut Vec<FloatPoint>,
-   from: FloatPoint,
-   ctrl1: FloatPoint,
-   ctrl2: FloatPoint,
-   to: FloatPoint,
-   steps: usize
-) {
-   for i in 1..steps {
-      let t2 = i as f64 / steps as f64;
-      let t1 = 1. - t2;
-
-      let u = on_segment(from, ctrl1, t1, t2);
-      let v = on_segment(ctrl1, ctrl2, t1, t2);
-      let w = on_segment(ctrl2, to, t1, t2);
-
-      let m = on_segment(u, v, t1, t2);
-      let n = on_segment(v, w, t1, t2);
-
-      let f = on_segment(m, n, t1, t2);
-
-      contour.push(f);
-   }
-
-   contour.push(to);
-}
-
-
-#[inline]
-fn on_segment(
-   pt1: FloatPoint,
-   pt2: FloatPoint,
-   t1: f64,
-   t2: f64
-) -> FloatPoint {
-   let x = t1 * pt1.x + t2 * pt2.x;
-   let y = t1 * pt1.y + t2 * pt2.y;
-
-   FloatPoint::new(x, y)
-}
-
-
-fn arc_to(
-   contour: &mut Vec<FloatPoint>,
-   from: FloatPoint,
-   to: FloatPoint,
-   mut radius: f64,
-   large: bool,
-   sweep: bool,
-   steps: usize
-) {
-   let middle = (from + to) / 2;
-
-   let vx = to.x - from.x;
-   let vy = to.y - from.y;
-
-   let len = vx.hypot(vy);
-
-   let half_len = len / 2.;
-
-   if radius < half_len {
-      radius = half_len;
-   }
-
-   let dist = (radius.powi(2) - half_len.powi(2)).sqrt();
-
-   let vp = FloatPoint::new(-vy / len, vx / len) * dist;
-
-   let center = if sweep {
-      middle + vp
-   } else {
-      middle - vp
-   };
-
-   let a1 = angle_of(center, from);
-   let a2 = angle_of(center, to);
-
-   let mut delta = a2 - a1;
-
-   if delta > PI {
-      delta -= 2. * PI;
-   } else if delta < -PI {
-      delta += 2. * PI;
-   }
-
-   if large {
-      if delta > 0. {
-         delta -= 2. * PI;
-      } else {
-         delta += 2. * PI;
-      }
-   }
-
-   let segments = ((steps as f64) * delta.abs() * 6. / PI) as usize;
-
-   let delta_step = delta / segments as f64;
-
-   for i in 1..segments {
-      let angle = a1 + delta_step * i as f64;
-
-      let point = FloatPoint::new(
-         radius * angle.cos() + center.x,
-         radius * angle.sin() + center.y
-      );
-
-      contour.push(point);
-   }
-
-   contour.push(to);
-}
-
-
-fn angle_of(center: FloatPoint, point: FloatPoint) -> f64 {
-   let dx = point.x - center.x;
-   let dy = point.y - center.y;
-
-   let mut angle = (dy / dx).abs().atan();
-
-   if dx < 0. {
-      if dy < 0. {
-         angle += PI;
-      } else {
-         angle = PI - angle;
-      }
-   } else {
-      if dy < 0. {
-         angle = 2. * PI - angle;
-      }
-   }
-
-   angle
-}
-
-
-fn to_value_ptr_points(executor: &Executor, contours: Vec<Vec<FloatPoint>>) -> Vec<Variant> {
-   let mut result = Vec::new();
-
-   for contour in contours {
-      let mut inner = Vec::new();
-
-      for point in contour {
-         inner.push(executor.registry.variant(point * SUBDIVISIONS as f64));
-      }
-
-      result.push(executor.registry.variant(inner));
-   }
-
-   vecval!(executor, result)
-}
-
-
 pub fn svg_path(
    arguments: &[&Variant],
    executor: &Executor,
    _: &FnRef
 ) -> Vec<Variant> {
-   let path = arguments[0].as_ref::<String>();
+   let source = arguments[0].as_ref::<String>();
 
-   if let Some(tokens) = tokenize_svg_path(path) {
+   if let Some(tokens) = tokenize_svg_path(source) {
       if let Some(commands) = parse_svg_path(&tokens) {
-         let contours = process_path(commands, 20);
+         let subdivisions = executor.subdivisions as f64;
+
+         let path = process_path(commands).scale(subdivisions, subdivisions);
 
-         to_value_ptr_points(executor, contours)
+         vecval!(executor, path)
       } else {
          vecval!(executor, Empty)
       }
@@ -1,6 +1,7 @@
 use sys::ft::{Face, TextAlign};
 
 use data::FloatPoint;
+use geom::transform::Transform;
 
 use lang::variant::Variant;
 use lang::compiler::FnRef;
@@ -57,30 +58,14 @@ fn text_fce_str_f64_fpt_(
    let capped_size = if *size <= 0.0 { 0.0000001 } else { *size };
    let scale = capped_size / (2048.0 * 64.0);
 
-   let text_contours = face.text(string, 20, text_align);
+   let transform = Transform::scale(scale, scale)
+      .then(&Transform::translate(origin.x, origin.y));
 
    let mut result = Vec::new();
 
-   for char_contours in text_contours {
-      let mut char_outer = Vec::new();
-
-      for contour in char_contours.iter() {
-         let mut char_inner = Vec::new();
-
-         for point in contour.iter() {
-            let transformed = FloatPoint::new(
-               point.x * scale + origin.x,
-               point.y * scale + origin.y,
-            );
-            char_inner.push(executor.registry.variant(transformed));
-         }
-
-         char_outer.push(executor.registry.variant(char_inner));
-      }
-
-      result.push(executor.registry.variant(char_outer));
+   for path in face.text(string, text_align) {
+      result.push(executor.registry.variant(path.transform(&transform)));
    }
 
    vecval!(executor, result)
 }
-
@@ -0,0 +1,249 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use data::{FloatPoint, IntPoint};
+use geom::path::{Path, PathSegment};
+use geom::transform::Transform;
+use devel::{Poly, Patch, Mesh, FilterOp};
+use draw::RGB;
+
+use super::variant::{Variant, VariantVec};
+use super::layer::{Masked, Group, Filtered};
+
+
+// Drawable `content` repeated with the tile at `origin` of `size`, in script
+// units
+#[derive(Debug, Clone)]
+pub struct Pattern {
+   pub content: Variant,
+   pub origin: FloatPoint,
+   pub size: FloatPoint,
+}
+
+impl Pattern {
+   #[inline]
+   pub fn new(content: Variant, origin: FloatPoint, size: FloatPoint) -> Self {
+      Pattern {
+         content: content,
+         origin: origin,
+         size: size,
+      }
+   }
+
+   // Hash of the tile rectangle and the drawable content, images count by
+   // their identity rather than their pixels
+   pub fn content_hash(&self) -> u64 {
+      let mut state = DefaultHasher::new();
+
+      self.hash_into(&mut state);
+
+      state.finish()
+   }
+
+   fn hash_into(&self, state: &mut DefaultHasher) {
+      hash_point(&self.origin, state);
+      hash_point(&self.size, state);
+      hash_variant(&self.content, state);
+   }
+}
+
+
+// Path filled with a pattern, the tile gets rendered once the scale factor
+// of the frame is known
+#[derive(Debug, Clone)]
+pub struct PatternFill {
+   pub path: Path,
+   pub pattern: Pattern,
+}
+
+impl PatternFill {
+   #[inline]
+   pub fn new(path: Path, pattern: Pattern) -> Self {
+      PatternFill {
+         path: path,
+         pattern: pattern,
+      }
+   }
+}
+
+
+// Hash of a drawable value, with images counted by their identity
+pub fn value_hash(variant: &Variant) -> u64 {
+   let mut state = DefaultHasher::new();
+
+   hash_variant(variant, &mut state);
+
+   state.finish()
+}
+
+// Hashes the values collect_scene draws, anything else only by its type
+fn hash_variant(variant: &Variant, state: &mut DefaultHasher) {
+   variant.type_id().hash(state);
+
+   if let Some(list) = variant.as_ref_checked::<VariantVec>() {
+      list.len().hash(state);
+
+      for variant in list.iter() {
+         hash_variant(variant, state);
+      }
+
+   } else if let Some(point) = variant.as_ref_checked::<IntPoint>() {
+      point.x.hash(state);
+      point.y.hash(state);
+
+   } else if let Some(point) = variant.as_ref_checked::<FloatPoint>() {
+      hash_point(point, state);
+
+   } else if let Some(poly) = variant.as_ref_checked::<Poly>() {
+      hash_poly(poly, state);
+
+   } else if let Some(path) = variant.as_ref_checked::<Path>() {
+      hash_path(path, state);
+
+   } else if let Some(group) = variant.as_ref_checked::<Group>() {
+      hash_values(&[group.opacity], state);
+      (group.blend as u8).hash(state);
+      hash_variant(&group.content, state);
+
+   } else if let Some(masked) = variant.as_ref_checked::<Masked>() {
+      (masked.mode as u8).hash(state);
+      hash_variant(&masked.source, state);
+      hash_variant(&masked.content, state);
+
+   } else if let Some(filtered) = variant.as_ref_checked::<Filtered>() {
+      for op in filtered.ops.iter() {
+         hash_filter_op(op, state);
+      }
+
+      hash_variant(&filtered.content, state);
+
+   } else if let Some(fill) = variant.as_ref_checked::<PatternFill>() {
+      hash_path(&fill.path, state);
+      fill.pattern.hash_into(state);
+
+   } else if let Some(patch) = variant.as_ref_checked::<Patch>() {
+      hash_patch(patch, state);
+
+   } else if let Some(mesh) = variant.as_ref_checked::<Mesh>() {
+      for patch in mesh.patches.iter() {
+         hash_patch(patch, state);
+      }
+   }
+}
+
+fn hash_poly(poly: &Poly, state: &mut DefaultHasher) {
+   for contour in poly.contours.iter() {
+      contour.len().hash(state);
+
+      for point in contour.iter() {
+         point.x.hash(state);
+         point.y.hash(state);
+      }
+   }
+
+   hash_rgb(&poly.color, state);
+
+   if let Some(ref paint) = poly.paint {
+      paint.image.id().hash(state);
+      hash_transform(&paint.transform, state);
+      (paint.repeat as u8).hash(state);
+      (paint.filter as u8).hash(state);
+   }
+}
+
+fn hash_path(path: &Path, state: &mut DefaultHasher) {
+   for segment in path.segments().iter() {
+      match *segment {
+         PathSegment::MoveTo(p) => {
+            0_u8.hash(state);
+            hash_point(&p, state);
+         },
+         PathSegment::LineTo(p) => {
+            1_u8.hash(state);
+            hash_point(&p, state);
+         },
+         PathSegment::QuadTo(c, p) => {
+            2_u8.hash(state);
+            hash_point(&c, state);
+            hash_point(&p, state);
+         },
+         PathSegment::CubicTo(c1, c2, p) => {
+            3_u8.hash(state);
+            hash_point(&c1, state);
+            hash_point(&c2, state);
+            hash_point(&p, state);
+         },
+         PathSegment::ArcTo(ref arc) => {
+            4_u8.hash(state);
+            hash_point(&arc.center, state);
+            hash_point(&arc.x_axis, state);
+            hash_point(&arc.y_axis, state);
+            hash_values(&[arc.start, arc.sweep], state);
+         },
+         PathSegment::Close => {
+            5_u8.hash(state);
+         },
+      }
+   }
+}
+
+fn hash_filter_op(op: &FilterOp, state: &mut DefaultHasher) {
+   match *op {
+      FilterOp::Blur(x, y) => {
+         0_u8.hash(state);
+         hash_values(&[x, y], state);
+      },
+      FilterOp::Offset(dx, dy) => {
+         1_u8.hash(state);
+         hash_values(&[dx, dy], state);
+      },
+      FilterOp::ColorMatrix(ref matrix) => {
+         2_u8.hash(state);
+         hash_values(matrix, state);
+      },
+      FilterOp::Morphology(op, rx, ry) => {
+         3_u8.hash(state);
+         (op as u8).hash(state);
+         hash_values(&[rx, ry], state);
+      },
+      FilterOp::DropShadow(ref shadow) => {
+         4_u8.hash(state);
+         hash_values(&[shadow.dx, shadow.dy, shadow.std_dev, shadow.opacity], state);
+         hash_rgb(&shadow.color, state);
+      },
+   }
+}
+
+fn hash_patch(patch: &Patch, state: &mut DefaultHasher) {
+   for row in patch.points.iter() {
+      for point in row.iter() {
+         hash_point(point, state);
+      }
+   }
+
+   for color in patch.colors.iter() {
+      hash_rgb(color, state);
+   }
+}
+
+#[inline]
+fn hash_point(point: &FloatPoint, state: &mut DefaultHasher) {
+   hash_values(&[point.x, point.y], state);
+}
+
+#[inline]
+fn hash_rgb(color: &RGB, state: &mut DefaultHasher) {
+   (color.r, color.g, color.b).hash(state);
+}
+
+#[inline]
+fn hash_transform(t: &Transform, state: &mut DefaultHasher) {
+   hash_values(&[t.xx, t.xy, t.yx, t.yy, t.x0, t.y0], state);
+}
+
+#[inline]
+fn hash_values(values: &[f64], state: &mut DefaultHasher) {
+   for value in values.iter() {
+      value.to_bits().hash(state);
+   }
+}
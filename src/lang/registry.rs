@@ -4,10 +4,14 @@ use std::fmt;
 
 use super::variant::{Variant, VTable, VType, CloneFn, DropFn, DebugFn};
 use super::compiler::FnRef;
+use super::layer::{Masked, Group, Filtered};
+use super::paint::{Pattern, PatternFill};
 
 use sys::ft::Face;
 use draw::RGB;
-use devel::Poly;
+use devel::{Poly, Patch, Mesh};
+use geom::path::Path;
+use image::Image;
 use data::{IntPoint, FloatPoint, Empty};
 
 
@@ -33,6 +37,15 @@ impl TypeRegistry {
       registry.register::<RGB>(clone_rgb, drop_rgb, debug_rgb);
       registry.register::<Poly>(clone_poly, drop_poly, debug_poly);
       registry.register::<Face>(clone_face, drop_face, debug_face);
+      registry.register::<Path>(clone_path, drop_path, debug_path);
+      registry.register::<Masked>(clone_masked, drop_masked, debug_masked);
+      registry.register::<Group>(clone_group, drop_group, debug_group);
+      registry.register::<Filtered>(clone_filtered, drop_filtered, debug_filtered);
+      registry.register::<Image>(clone_image, drop_image, debug_image);
+      registry.register::<Pattern>(clone_pattern, drop_pattern, debug_pattern);
+      registry.register::<PatternFill>(clone_pattern_fill, drop_pattern_fill, debug_pattern_fill);
+      registry.register::<Patch>(clone_patch, drop_patch, debug_patch);
+      registry.register::<Mesh>(clone_mesh, drop_mesh, debug_mesh);
 
       registry
    }
@@ -93,6 +106,15 @@ drop_func!(drop_float_point, FloatPoint);
 drop_func!(drop_rgb, RGB);
 drop_func!(drop_poly, Poly);
 drop_func!(drop_face, Face);
+drop_func!(drop_path, Path);
+drop_func!(drop_masked, Masked);
+drop_func!(drop_group, Group);
+drop_func!(drop_filtered, Filtered);
+drop_func!(drop_image, Image);
+drop_func!(drop_pattern, Pattern);
+drop_func!(drop_pattern_fill, PatternFill);
+drop_func!(drop_patch, Patch);
+drop_func!(drop_mesh, Mesh);
 
 
 macro_rules! clone_func {
@@ -116,6 +138,15 @@ clone_func!(clone_float_point, FloatPoint);
 clone_func!(clone_rgb, RGB);
 clone_func!(clone_poly, Poly);
 clone_func!(clone_face, Face);
+clone_func!(clone_path, Path);
+clone_func!(clone_masked, Masked);
+clone_func!(clone_group, Group);
+clone_func!(clone_filtered, Filtered);
+clone_func!(clone_image, Image);
+clone_func!(clone_pattern, Pattern);
+clone_func!(clone_pattern_fill, PatternFill);
+clone_func!(clone_patch, Patch);
+clone_func!(clone_mesh, Mesh);
 
 
 macro_rules! debug_func {
@@ -137,3 +168,12 @@ debug_func!(debug_float_point, FloatPoint);
 debug_func!(debug_rgb, RGB);
 debug_func!(debug_poly, Poly);
 debug_func!(debug_face, Face);
+debug_func!(debug_path, Path);
+debug_func!(debug_masked, Masked);
+debug_func!(debug_group, Group);
+debug_func!(debug_filtered, Filtered);
+debug_func!(debug_image, Image);
+debug_func!(debug_pattern, Pattern);
+debug_func!(debug_pattern_fill, PatternFill);
+debug_func!(debug_patch, Patch);
+debug_func!(debug_mesh, Mesh);
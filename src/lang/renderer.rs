@@ -1,20 +1,34 @@
 use std::any::TypeId;
+use std::collections::HashMap;
+use std::mem;
 
 use data::{IntPoint, FloatPoint};
-use devel::{DevelRenderer, Scene, SUBDIVISIONS, Poly};
+use devel::{DevelRenderer, Scene, Poly, MaskSource, Pattern, Patch, Mesh, Sampling};
 use renderer::{Renderer};
 use frame::Frame;
+use clock::FrameClock;
 use draw::RGB;
+use color::Profile;
+use geom::path::Path;
+use geom::flatten::Flattener;
+use geom::transform::Transform;
+use image::ImagePaint;
 
 use super::{Environment, Program};
 use super::variant::{Variant, VariantVec};
+use super::layer::{Masked, Group, Filtered};
+use super::paint::{PatternFill, value_hash};
 
 
+// The program is declared first so its values get dropped while the type
+// registry of the environment is still alive
 pub struct LangRenderer {
+   program: Program,
    renderer: DevelRenderer,
-   frame: i64,
+   tiles: TileCache,
+   scale_factor: f64,
    environment: Environment,
-   program: Program,
+   source: String,
 }
 
 
@@ -32,11 +46,43 @@ impl LangRenderer {
 
       Ok(LangRenderer {
          renderer: DevelRenderer::new(Scene::new()),
-         frame: 0,
+         tiles: TileCache::new(),
+         scale_factor: 1.,
          environment: environment,
          program: program,
+         source: source.to_string(),
       })
    }
+
+   #[inline]
+   pub fn set_workers(&mut self, workers: usize) {
+      self.renderer.set_workers(workers);
+   }
+
+   // Script units follow the sample grid, the script gets recompiled for
+   // the constant curves
+   pub fn set_sampling(&mut self, sampling: Sampling) {
+      self.renderer.set_sampling(sampling);
+
+      self.tiles.set_sampling(sampling);
+
+      self.environment.set_subdivisions(self.renderer.subdivisions());
+
+      self.recompile();
+   }
+
+   // Scripts use sRGB colors, converted to `profile` when rendering
+   #[inline]
+   pub fn set_output_profile(&mut self, profile: Option<Profile>) {
+      self.renderer.set_output_profile(profile);
+   }
+
+   // Constant curves are flattened at compile time with the old tolerance
+   fn recompile(&mut self) {
+      if let Ok(program) = self.environment.compile_program(&self.source) {
+         self.program = program;
+      }
+   }
 }
 
 
@@ -46,43 +92,345 @@ impl Renderer for LangRenderer {
       self.renderer.init(width, height);
    }
 
+   fn scale_factor_changed(&mut self, scale_factor: f64) {
+      self.scale_factor = scale_factor;
+
+      self.environment.set_scale_factor(scale_factor);
+
+      self.recompile();
+   }
+
    #[inline]
-   fn render(&mut self, frame: &mut Frame) {
-      let arguments = vec![
-         self.environment.registry.variant(self.frame),
-         self.environment.registry.variant(frame.width as i64 * SUBDIVISIONS),
-         self.environment.registry.variant(frame.height as i64 * SUBDIVISIONS),
+   fn render(&mut self, frame: &mut Frame, clock: &FrameClock) {
+      // Scripts work in logical pixels, the scene is scaled back before rendering
+      let width = (frame.width as f64 / self.scale_factor) as i64;
+      let height = (frame.height as f64 / self.scale_factor) as i64;
+
+      let subdivisions = self.environment.subdivisions;
+
+      let mut arguments = vec![
+         self.environment.registry.variant(clock.frame as i64),
+         self.environment.registry.variant(width * subdivisions),
+         self.environment.registry.variant(height * subdivisions),
       ];
 
+      // Time based animations declare a fourth `elapsed` argument in seconds
+      if self.program.main_arity() == 4 {
+         arguments.push(self.environment.registry.variant(clock.elapsed));
+      }
+
       let result = self.environment.execute_program(&self.program, arguments);
 
       let mut scene = Scene::new();
 
+      let flattener = Flattener::new(self.environment.tolerance);
 
       for value_ptr in result.iter() {
-         collect_polys(&mut scene, value_ptr);
+         collect_scene(
+            &mut scene, &mut self.tiles, value_ptr, &flattener, self.scale_factor, subdivisions
+         );
       }
 
+      self.tiles.end_frame();
+
       self.renderer.set_scene(scene);
 
-      self.renderer.render(frame);
+      self.renderer.render(frame, clock);
+   }
+}
+
+
+// Pattern tiles and mesh polys rendered for earlier frames, keyed by the
+// content hash of their value and the scale factor
+struct TileCache {
+   renderer: DevelRenderer,
+   tiles: FrameCache<Option<ImagePaint>>,
+   meshes: FrameCache<Option<Poly>>,
+}
+
+impl TileCache {
+   #[inline]
+   fn new() -> Self {
+      TileCache {
+         renderer: DevelRenderer::new(Scene::new()),
+         tiles: FrameCache::new(),
+         meshes: FrameCache::new(),
+      }
+   }
+
+   #[inline]
+   fn key(fill: &PatternFill, scale_factor: f64) -> (u64, u64) {
+      (fill.pattern.content_hash(), scale_factor.to_bits())
+   }
+
+   #[inline]
+   fn get(&mut self, key: (u64, u64)) -> Option<Option<ImagePaint>> {
+      self.tiles.get(key)
+   }
+
+   // Tiles of the other grid are in different units
+   fn set_sampling(&mut self, sampling: Sampling) {
+      self.renderer.set_sampling(sampling);
+
+      self.tiles.clear();
+      self.meshes.clear();
+   }
+
+   fn render(&mut self, key: (u64, u64), pattern: &Pattern) -> Option<ImagePaint> {
+      let paint = pattern.paint(&mut self.renderer);
+
+      self.tiles.insert(key, paint.clone());
+
+      paint
+   }
+
+   #[inline]
+   fn end_frame(&mut self) {
+      self.tiles.end_frame();
+      self.meshes.end_frame();
+   }
+}
+
+
+// Values of the last frame, the ones a frame doesn't use get dropped
+struct FrameCache<T: Clone> {
+   values: HashMap<(u64, u64), T>,
+   used: HashMap<(u64, u64), T>,
+}
+
+impl<T: Clone> FrameCache<T> {
+   #[inline]
+   fn new() -> Self {
+      FrameCache {
+         values: HashMap::new(),
+         used: HashMap::new(),
+      }
+   }
+
+   fn get(&mut self, key: (u64, u64)) -> Option<T> {
+      if let Some(value) = self.used.get(&key) {
+         return Some(value.clone());
+      }
+
+      let value = match self.values.remove(&key) {
+         Some(value) => value,
+         None => return None,
+      };
+
+      self.used.insert(key, value.clone());
+
+      Some(value)
+   }
+
+   #[inline]
+   fn insert(&mut self, key: (u64, u64), value: T) {
+      self.used.insert(key, value);
+   }
+
+   #[inline]
+   fn clear(&mut self) {
+      self.values.clear();
+      self.used.clear();
+   }
 
-      self.frame += 1;
+   #[inline]
+   fn end_frame(&mut self) {
+      self.values = mem::replace(&mut self.used, HashMap::new());
    }
 }
 
 
-fn collect_polys(scene: &mut Scene, variant: &Variant) {
+fn scale_poly(mut poly: Poly, scale_factor: f64) -> Poly {
+   if scale_factor == 1. {
+      return poly;
+   }
+
+   for contour in poly.contours.iter_mut() {
+      for point in contour.iter_mut() {
+         point.x = (point.x as f64 * scale_factor).round() as i64;
+         point.y = (point.y as f64 * scale_factor).round() as i64;
+      }
+   }
+
+   poly.paint = poly.paint.and_then(
+      |paint| paint.then(&Transform::scale(scale_factor, scale_factor))
+   );
+
+   poly
+}
+
+
+// Masked, group and filtered values open a layer around their content,
+// pattern fills render their tile at the frame scale unless an earlier frame
+// did, the rest is drawn in order
+fn collect_scene(
+   scene: &mut Scene,
+   tiles: &mut TileCache,
+   variant: &Variant,
+   flattener: &Flattener,
+   scale_factor: f64,
+   subdivisions: i64
+) {
+   if let Some(group) = variant.as_ref_checked::<Group>() {
+      scene.begin_group(group.opacity, group.blend);
+
+      collect_scene(scene, tiles, &group.content, flattener, scale_factor, subdivisions);
+
+      scene.end_group();
+
+      return;
+   }
+
+   if let Some(filtered) = variant.as_ref_checked::<Filtered>() {
+      // Filter lengths are in script units, same as the subdivided points
+      let factor = scale_factor / subdivisions as f64;
+
+      scene.begin_filter(filtered.ops.iter().map(|op| op.scale(factor)).collect());
+
+      collect_scene(scene, tiles, &filtered.content, flattener, scale_factor, subdivisions);
+
+      scene.end_filter();
+
+      return;
+   }
+
+   if let Some(fill) = variant.as_ref_checked::<PatternFill>() {
+      let poly = scale_poly(Poly::from_path(&fill.path, flattener, RGB::default()), scale_factor);
+
+      if poly.contours.len() == 0 {
+         return;
+      }
+
+      let key = TileCache::key(fill, scale_factor);
+
+      let paint = match tiles.get(key) {
+         Some(paint) => paint,
+         None => {
+            let mut tile = Scene::new();
+
+            collect_scene(&mut tile, tiles, &fill.pattern.content, flattener, 1., subdivisions);
+
+            let ref pattern = fill.pattern;
+
+            let pattern = Pattern::new(
+               tile,
+               pattern.origin.x,
+               pattern.origin.y,
+               pattern.size.x,
+               pattern.size.y,
+               Transform::scale(scale_factor, scale_factor),
+            );
+
+            tiles.render(key, &pattern)
+         }
+      };
+
+      if let Some(paint) = paint {
+         scene.push(Box::new(poly.with_paint(paint)));
+      }
+
+      return;
+   }
+
+   if let Some(patch) = variant.as_ref_checked::<Patch>() {
+      let mesh = || Mesh::new(vec![patch.clone()]);
+
+      push_mesh(scene, tiles, variant, mesh, scale_factor, subdivisions);
+
+      return;
+   }
+
+   if let Some(mesh) = variant.as_ref_checked::<Mesh>() {
+      push_mesh(scene, tiles, variant, || mesh.clone(), scale_factor, subdivisions);
+
+      return;
+   }
+
+   if let Some(masked) = variant.as_ref_checked::<Masked>() {
+      let mut source = Vec::new();
+
+      collect_polys(&mut source, &masked.source, flattener);
+
+      let source = source.into_iter().map(
+         |poly| Box::new(scale_poly(poly, scale_factor))
+      ).collect();
+
+      scene.begin_mask(masked.mode, MaskSource::Polys(source));
+
+      collect_scene(scene, tiles, &masked.content, flattener, scale_factor, subdivisions);
+
+      scene.end_mask();
+
+      return;
+   }
+
    if let Some(list) = variant.as_ref_checked::<VariantVec>() {
-      collect_polys_from_list(scene, list);
+      if list.len() > 0 && !is_point_list(list) {
+         for variant in list.iter() {
+            collect_scene(scene, tiles, variant, flattener, scale_factor, subdivisions);
+         }
+
+         return;
+      }
+   }
+
+   let mut polys = Vec::new();
+
+   collect_polys(&mut polys, variant, flattener);
+
+   for poly in polys.into_iter() {
+      scene.push(Box::new(scale_poly(poly, scale_factor)));
+   }
+}
+
+
+// Mesh colors get evaluated at the frame resolution, unless an earlier frame
+// did for the same mesh
+fn push_mesh<F: FnOnce() -> Mesh>(
+   scene: &mut Scene,
+   tiles: &mut TileCache,
+   variant: &Variant,
+   mesh: F,
+   scale_factor: f64,
+   subdivisions: i64
+) {
+   let key = (value_hash(variant), scale_factor.to_bits());
+
+   let poly = match tiles.meshes.get(key) {
+      Some(poly) => poly,
+      None => {
+         let mut mesh = mesh();
+
+         mesh.transform(&Transform::scale(scale_factor, scale_factor));
+
+         let poly = mesh.poly(subdivisions as f64);
+
+         tiles.meshes.insert(key, poly.clone());
+
+         poly
+      }
+   };
+
+   if let Some(poly) = poly {
+      scene.push(Box::new(poly));
+   }
+}
+
+
+fn collect_polys(polys: &mut Vec<Poly>, variant: &Variant, flattener: &Flattener) {
+   if let Some(list) = variant.as_ref_checked::<VariantVec>() {
+      collect_polys_from_list(polys, list, flattener);
 
    } else if let Some(poly) = variant.as_ref_checked::<Poly>() {
-      scene.push(Box::new(poly.clone()));
+      polys.push(poly.clone());
+
+   } else if let Some(path) = variant.as_ref_checked::<Path>() {
+      polys.push(Poly::from_path(path, flattener, RGB::new(255, 255, 255)));
    }
 }
 
 
-fn collect_polys_from_list(scene: &mut Scene, variants: &Vec<Variant>) {
+fn collect_polys_from_list(polys: &mut Vec<Poly>, variants: &Vec<Variant>, flattener: &Flattener) {
    if variants.len() == 0 {
       return;
    }
@@ -92,16 +440,11 @@ fn collect_polys_from_list(scene: &mut Scene, variants: &Vec<Variant>) {
 
       let poly = Poly::new(vec![points], RGB::new(255, 255, 255));
 
-      scene.push(Box::new(poly));
+      polys.push(poly);
 
    } else {
       for variant in variants.iter() {
-         if let Some(list) = variant.as_ref_checked::<VariantVec>() {
-            collect_polys_from_list(scene, list);
-
-         } else if let Some(poly) = variant.as_ref_checked::<Poly>() {
-            scene.push(Box::new(poly.clone()));
-         }
+         collect_polys(polys, variant, flattener);
       }
    }
 }
@@ -139,3 +482,177 @@ fn collect_poly_points(variants: &Vec<Variant>) -> Vec<IntPoint> {
    points
 }
 
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   use devel::SUBDIVISIONS;
+   use image::{Image, RepeatMode, ImageFilter};
+
+   use lang::registry::TypeRegistry;
+   use lang::paint::Pattern as LangPattern;
+
+   #[test]
+   fn test_tile_cache() {
+      let s = SUBDIVISIONS;
+
+      let mut tile = Scene::new();
+
+      tile.push(Box::new(Poly::new(vec![vec![
+         IntPoint::new(0, 0), IntPoint::new(2 * s, 0), IntPoint::new(2 * s, 2 * s), IntPoint::new(0, 2 * s),
+      ]], RGB::new(255, 0, 0))));
+
+      let size = 4. * s as f64;
+
+      let pattern = Pattern::new(tile, 0., 0., size, size, Transform::identity());
+
+      let mut tiles = TileCache::new();
+
+      let key = (1, 1);
+
+      assert!(tiles.get(key).is_none());
+
+      let paint = tiles.render(key, &pattern).unwrap();
+
+      assert_eq!(paint.image.width, 4);
+
+      // Kept while the frames use it
+      for _ in 0..2 {
+         assert_eq!(tiles.get(key).unwrap().unwrap().image.width, 4);
+
+         tiles.end_frame();
+      }
+
+      tiles.end_frame();
+
+      assert!(tiles.get(key).is_none());
+   }
+
+   #[test]
+   fn test_tile_keys() {
+      let registry = TypeRegistry::new();
+
+      let square = Path::new()
+         .move_to(FloatPoint::new(0., 0.))
+         .line_to(FloatPoint::new(4., 0.))
+         .line_to(FloatPoint::new(4., 4.))
+         .close();
+
+      let fill = |image: &Image| {
+         let paint = ImagePaint::new(
+            image.clone(), Transform::identity(), RepeatMode::Repeat, ImageFilter::Nearest
+         ).unwrap();
+
+         let poly = Poly::new(
+            vec![vec![IntPoint::new(0, 0), IntPoint::new(4, 0), IntPoint::new(4, 4)]], RGB::default()
+         );
+
+         let content = registry.variant(poly.with_paint(paint));
+
+         LangPattern::new(content, FloatPoint::new(0., 0.), FloatPoint::new(4., 4.))
+      };
+
+      let red = Image::new(1, 1, vec![255, 0, 0, 255]);
+      let blue = Image::new(1, 1, vec![0, 0, 255, 255]);
+
+      let key = |pattern: LangPattern, scale_factor: f64| {
+         TileCache::key(&PatternFill::new(square.clone(), pattern), scale_factor)
+      };
+
+      // Images of the same size don't collide, clones of one image do
+      assert!(key(fill(&red), 1.) != key(fill(&blue), 1.));
+      assert_eq!(key(fill(&red), 1.), key(fill(&red.clone()), 1.));
+      assert!(key(fill(&red), 1.) != key(fill(&red), 2.));
+   }
+
+   #[test]
+   fn test_mesh_cache() {
+      let registry = TypeRegistry::new();
+
+      let size = 8. * SUBDIVISIONS as f64;
+
+      let corners = [
+         FloatPoint::new(0., 0.),
+         FloatPoint::new(0., size),
+         FloatPoint::new(size, size),
+         FloatPoint::new(size, 0.),
+      ];
+
+      let patch = |color: RGB| {
+         registry.variant(Patch::quad(&corners, [color, RGB::new(0, 255, 0), RGB::new(0, 0, 255), color]))
+      };
+
+      let flattener = Flattener::new(0.1);
+
+      let mut tiles = TileCache::new();
+
+      let mut render = |tiles: &mut TileCache, variant: &Variant, scale_factor: f64| {
+         let mut scene = Scene::new();
+
+         collect_scene(&mut scene, tiles, variant, &flattener, scale_factor, SUBDIVISIONS);
+
+         tiles.end_frame();
+      };
+
+      let red = patch(RGB::new(255, 0, 0));
+
+      render(&mut tiles, &red, 1.);
+
+      assert_eq!(tiles.meshes.values.len(), 1);
+
+      // Later frames reuse the poly of the same mesh at the same scale
+      let key = (value_hash(&red), 1_f64.to_bits());
+
+      let cached = tiles.meshes.values[&key].clone().unwrap();
+
+      render(&mut tiles, &patch(RGB::new(255, 0, 0)), 1.);
+
+      let reused = tiles.meshes.values[&key].clone().unwrap();
+
+      assert_eq!(cached.paint.unwrap().image.id(), reused.paint.unwrap().image.id());
+
+      render(&mut tiles, &patch(RGB::new(0, 0, 0)), 1.);
+      render(&mut tiles, &red, 2.);
+
+      assert!(tiles.meshes.values.get(&key).is_none());
+   }
+
+   #[test]
+   fn test_sampling_units() {
+      let source = "main >> frame width height
+   $0 = fill! (svg_path! \"M0 0 H10.0625 V4 H0 Z\") (rgb! 255. 255. 255.)
+";
+
+      let mut lang = LangRenderer::new(source).unwrap();
+
+      lang.set_sampling(Sampling::Grid(16));
+
+      let arguments = vec![
+         lang.environment.registry.variant(0_i64),
+         lang.environment.registry.variant(16_i64 * 16),
+         lang.environment.registry.variant(4_i64 * 16),
+      ];
+
+      let result = lang.environment.execute_program(&lang.program, arguments);
+
+      let mut scene = Scene::new();
+
+      let flattener = Flattener::new(lang.environment.tolerance);
+
+      for value_ptr in result.iter() {
+         collect_scene(&mut scene, &mut lang.tiles, value_ptr, &flattener, 1., 16);
+      }
+
+      lang.renderer.set_scene(scene);
+
+      let image = lang.renderer.render_image(16, 4);
+
+      // The sixteenth of a pixel the path reaches into the eleventh column
+      // isn't rounded away
+      let alpha = image.pixel(10, 1)[3];
+
+      assert!((alpha as f32 - 255. / 16.).abs() <= 1., "{}", alpha);
+   }
+}
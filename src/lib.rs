@@ -11,12 +11,28 @@ pub mod error;
 pub mod frame;
 pub mod event;
 pub mod event_loop;
+pub mod clipboard;
+pub mod pointer;
+pub mod monitor;
+pub mod clock;
 pub mod draw;
+pub mod srgb;
+pub mod color;
+pub mod image;
 pub mod raster;
 pub mod devel;
 pub mod lang;
 pub mod data;
+pub mod allocation;
+
+#[doc(hidden)]
+pub mod testing;
 
 pub use application::Application;
 pub use renderer::Renderer;
 pub use frame::Frame;
+pub use event::WindowId;
+pub use clipboard::{Clipboard, Selection};
+pub use pointer::{Pointer, CursorShape, CursorImage};
+pub use monitor::Monitor;
+pub use clock::{FrameClock, FrameStats};
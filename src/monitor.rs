@@ -0,0 +1,100 @@
+pub const BASE_DPI: f64 = 96.;
+
+#[derive(Clone, Debug)]
+pub struct Monitor {
+   pub name: String,
+   pub x: i32,
+   pub y: i32,
+   pub width: u32,
+   pub height: u32,
+   pub width_mm: u32,
+   pub height_mm: u32,
+   pub refresh_rate: f64,
+   pub primary: bool,
+   pub scale_factor: f64,
+}
+
+impl Monitor {
+   #[inline]
+   pub fn contains(&self, x: i32, y: i32) -> bool {
+      x >= self.x && x < self.x + self.width as i32 &&
+      y >= self.y && y < self.y + self.height as i32
+   }
+
+   pub fn physical_dpi(&self) -> Option<f64> {
+      if self.width_mm == 0 {
+         return None;
+      }
+
+      Some(self.width as f64 * 25.4 / self.width_mm as f64)
+   }
+}
+
+pub fn scale_factor_from_dpi(dpi: f64) -> f64 {
+   let scale_factor = (4. * dpi / BASE_DPI).round() / 4.;
+
+   if scale_factor < 1. {
+      1.
+   } else {
+      scale_factor
+   }
+}
+
+pub fn primary_monitor(monitors: &[Monitor]) -> Option<&Monitor> {
+   match monitors.iter().find(|monitor| monitor.primary) {
+      Some(monitor) => Some(monitor),
+      None => monitors.first()
+   }
+}
+
+pub fn monitor_at(monitors: &[Monitor], x: i32, y: i32) -> Option<&Monitor> {
+   match monitors.iter().find(|monitor| monitor.contains(x, y)) {
+      Some(monitor) => Some(monitor),
+      None => primary_monitor(monitors)
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   fn monitor(x: i32, width: u32, width_mm: u32, primary: bool) -> Monitor {
+      Monitor {
+         name: String::new(),
+         x: x,
+         y: 0,
+         width: width,
+         height: 1080,
+         width_mm: width_mm,
+         height_mm: 0,
+         refresh_rate: 60.,
+         primary: primary,
+         scale_factor: 1.,
+      }
+   }
+
+   #[test]
+   fn test_scale_factor_from_dpi() {
+      assert_eq!(scale_factor_from_dpi(96.), 1.);
+      assert_eq!(scale_factor_from_dpi(144.), 1.5);
+      assert_eq!(scale_factor_from_dpi(192.), 2.);
+      assert_eq!(scale_factor_from_dpi(200.), 2.);
+      assert_eq!(scale_factor_from_dpi(72.), 1.);
+   }
+
+   #[test]
+   fn test_physical_dpi() {
+      assert_eq!(monitor(0, 3840, 0, false).physical_dpi(), None);
+      assert_eq!(monitor(0, 3840, 508, false).physical_dpi(), Some(192.));
+   }
+
+   #[test]
+   fn test_monitor_at() {
+      let monitors = vec![monitor(0, 1920, 0, false), monitor(1920, 3840, 0, true)];
+
+      assert_eq!(monitor_at(&monitors, 100, 100).unwrap().x, 0);
+      assert_eq!(monitor_at(&monitors, 2000, 100).unwrap().x, 1920);
+      assert_eq!(monitor_at(&monitors, -100, 100).unwrap().x, 1920);
+   }
+}
@@ -1,50 +1,73 @@
-use error::{RuntimeError, VoidResult};
+use std::rc::Rc;
+
+use error::{RuntimeError, ErrorKind, VoidResult};
 use frame::GPUFrame;
+use event::WindowId;
+use monitor::{Monitor, monitor_at};
 
 use sys::ft;
 
 use super::display::LinuxDisplay;
 use super::window::{LinuxWindow, PollEventsIterator};
 use super::context::{Context, create_context};
+use super::pointer::LinuxPointer;
 
 pub struct LinuxApplication {
    pub display: LinuxDisplay,
-   pub window: LinuxWindow,
-   pub context: Box<Context>,
+   pub windows: Vec<Option<Rc<LinuxWindow>>>,
+   pub contexts: Vec<Option<Box<Context>>>,
+   pub monitors: Vec<Monitor>,
    pub freetype: ft::FreeType,
 }
 
 impl LinuxApplication {
-   pub fn new(
-      display: LinuxDisplay,
+   pub fn new(display: LinuxDisplay) -> Result<Self, RuntimeError> {
+      let freetype = ft::FreeType::new();
+
+      let monitors = display.monitors();
+
+      Ok(LinuxApplication {
+         display: display,
+         windows: Vec::new(),
+         contexts: Vec::new(),
+         monitors: monitors,
+         freetype: freetype,
+      })
+   }
+
+   pub fn create_window(
+      &mut self,
       title: &str,
       x: i32, y: i32,
       width: u32, height: u32
-   ) -> Result<Self, RuntimeError> {
+   ) -> Result<WindowId, RuntimeError> {
 
       let window = try!(LinuxWindow::new(
-         &display, title, x, y, width, height,
+         &self.display, title, x, y, width, height,
       ));
 
       let context = try!(
-         create_context(&display.display, &display.screen_id, &window.window)
+         create_context(&self.display.display, &self.display.screen_id, &window.window)
       );
 
-      let freetype = ft::FreeType::new();
+      let window_id = WindowId(self.windows.len());
 
-      Ok(LinuxApplication {
-         display: display,
-         window: window,
-         context: context,
-         freetype: freetype,
-      })
+      self.windows.push(Some(Rc::new(window)));
+      self.contexts.push(Some(context));
+
+      Ok(window_id)
+   }
+
+   #[inline]
+   pub fn window_count(&self) -> usize {
+      self.windows.len()
    }
 
    #[inline]
    pub fn create_gpu_frame(
-      &self, width: u32, height: u32
+      &self, window_id: WindowId, width: u32, height: u32
    ) -> Result<Box<GPUFrame>, RuntimeError> {
-      self.context.create_gpu_frame(width, height)
+      try!(self.context(window_id)).create_gpu_frame(width, height)
    }
 
    #[inline]
@@ -52,13 +75,83 @@ impl LinuxApplication {
       self.display.screen_size()
    }
 
+   #[inline]
+   pub fn monitors(&self) -> &[Monitor] {
+      &self.monitors
+   }
+
+   pub fn scale_factor(&self, window_id: WindowId) -> Result<f64, RuntimeError> {
+      let window = try!(self.window(window_id));
+
+      let (x, y) = try!(window.window.root_position(self.display.screen.root()));
+
+      Ok(match monitor_at(&self.monitors, x, y) {
+         Some(monitor) => monitor.scale_factor,
+         None => 1.
+      })
+   }
+
    #[inline]
    pub fn poll_events(&self) -> PollEventsIterator {
-      self.window.poll_events()
+      PollEventsIterator::new(&self.display.connection, &self.windows, &self.display.queues)
+   }
+
+   #[inline]
+   pub fn make_current(&self, window_id: WindowId) -> VoidResult {
+      try!(self.context(window_id)).make_current()
+   }
+
+   #[inline]
+   pub fn swap_buffers(&self, window_id: WindowId) -> VoidResult {
+      try!(self.context(window_id)).swap_buffers()
    }
 
    #[inline]
-   pub fn swap_buffers(&self) -> VoidResult {
-      self.context.swap_buffers()
+   pub fn set_swap_interval(&self, window_id: WindowId, interval: i32) -> VoidResult {
+      try!(self.make_current(window_id));
+
+      try!(self.context(window_id)).set_swap_interval(interval)
+   }
+
+   pub fn destroy_window(&mut self, window_id: WindowId) -> VoidResult {
+      try!(self.window(window_id));
+
+      // The GLX window refers to the X window, so the context goes first
+      self.contexts[window_id.0] = None;
+      self.windows[window_id.0] = None;
+
+      self.display.connection.flush();
+
+      Ok(())
    }
+
+   #[inline]
+   pub fn pointer(&self, window_id: WindowId) -> Result<LinuxPointer, RuntimeError> {
+      let window = try!(self.window(window_id));
+
+      Ok(LinuxPointer::new(window.clone()))
+   }
+
+   #[inline]
+   fn window(&self, window_id: WindowId) -> Result<&Rc<LinuxWindow>, RuntimeError> {
+      match self.windows.get(window_id.0) {
+         Some(&Some(ref window)) => Ok(window),
+         _ => Err(unknown_window(window_id))
+      }
+   }
+
+   #[inline]
+   fn context(&self, window_id: WindowId) -> Result<&Box<Context>, RuntimeError> {
+      match self.contexts.get(window_id.0) {
+         Some(&Some(ref context)) => Ok(context),
+         _ => Err(unknown_window(window_id))
+      }
+   }
+}
+
+fn unknown_window(window_id: WindowId) -> RuntimeError {
+   RuntimeError::new(
+      ErrorKind::InvalidInput,
+      format!("Unknown window: {}", window_id.0)
+   )
 }
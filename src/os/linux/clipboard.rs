@@ -0,0 +1,585 @@
+use std::rc::Rc;
+use std::cmp::min;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use error::{RuntimeError, ErrorKind, VoidResult};
+use clipboard::Selection;
+
+use sys::xcb;
+use sys::xcb::ffi;
+
+use super::display::{LinuxDisplay, EventQueues, is_selection_event};
+
+const INCR_CHUNK_SIZE: usize = 0x40000;
+const PROPERTY_READ_SIZE: usize = 0x100000;
+
+const SELECTION_TIMEOUT_MS: u64 = 2000;
+
+pub struct ClipboardAtoms {
+   pub clipboard: ffi::xcb_atom_t,
+   pub targets: ffi::xcb_atom_t,
+   pub utf8_string: ffi::xcb_atom_t,
+   pub text: ffi::xcb_atom_t,
+   pub incr: ffi::xcb_atom_t,
+   pub property: ffi::xcb_atom_t,
+}
+
+pub struct IncrTransfer {
+   pub requestor: ffi::xcb_window_t,
+   pub property: ffi::xcb_atom_t,
+   pub target: ffi::xcb_atom_t,
+   pub data: Vec<u8>,
+   pub offset: usize,
+}
+
+// Runs on the connection of the application display, selection events the
+// windows read are passed over through the display queues
+pub struct LinuxClipboard {
+   pub window: xcb::Window,
+   pub connection: Rc<xcb::Connection>,
+   pub queues: Rc<EventQueues>,
+   pub atoms: ClipboardAtoms,
+   pub clipboard_text: Option<String>,
+   pub primary_text: Option<String>,
+   pub transfers: Vec<IncrTransfer>,
+   pub chunk_size: usize,
+}
+
+impl LinuxClipboard {
+   pub fn new(display: &LinuxDisplay) -> Result<Self, RuntimeError> {
+      let window = try!(xcb::Window::create_hidden(
+         &display.connection, &display.screen
+      ));
+
+      let atoms = try!(Self::init_atoms(&display.connection));
+
+      let chunk_size = min(
+         INCR_CHUNK_SIZE, display.connection.maximum_request_length() / 2
+      );
+
+      Ok(LinuxClipboard {
+         window: window,
+         connection: display.connection.clone(),
+         queues: display.queues.clone(),
+         atoms: atoms,
+         clipboard_text: None,
+         primary_text: None,
+         transfers: Vec::new(),
+         chunk_size: chunk_size,
+      })
+   }
+
+   pub fn init_atoms(connection: &xcb::Connection) -> Result<ClipboardAtoms, RuntimeError> {
+      Ok(ClipboardAtoms {
+         clipboard: try!(intern_atom(connection, "CLIPBOARD")),
+         targets: try!(intern_atom(connection, "TARGETS")),
+         utf8_string: try!(intern_atom(connection, "UTF8_STRING")),
+         text: try!(intern_atom(connection, "TEXT")),
+         incr: try!(intern_atom(connection, "INCR")),
+         property: try!(intern_atom(connection, "POLYDRAW_SELECTION")),
+      })
+   }
+
+   pub fn set_text(&mut self, selection: Selection, text: &str) -> VoidResult {
+      let selection_atom = self.selection_atom(selection);
+
+      let window = self.window.window_id.id;
+
+      try!(self.connection.set_selection_owner(window, selection_atom));
+
+      if try!(self.connection.selection_owner(selection_atom)) != window {
+         return Err(RuntimeError::new(
+            ErrorKind::XCB,
+            "Acquiring selection ownership failed".to_string()
+         ));
+      }
+
+      match selection {
+         Selection::Clipboard => self.clipboard_text = Some(text.to_string()),
+         Selection::Primary => self.primary_text = Some(text.to_string()),
+      }
+
+      Ok(())
+   }
+
+   pub fn text(&mut self, selection: Selection) -> Result<Option<String>, RuntimeError> {
+      try!(self.process_events());
+
+      let selection_atom = self.selection_atom(selection);
+
+      let owner = try!(self.connection.selection_owner(selection_atom));
+
+      if owner == self.window.window_id.id {
+         return Ok(self.owned_text(selection_atom).map(|text| text.clone()));
+      }
+
+      if owner == ffi::XCB_ATOM_NONE {
+         return Ok(None);
+      }
+
+      let utf8_string = self.atoms.utf8_string;
+
+      let data = match try!(self.convert_selection(selection_atom, utf8_string)) {
+         Some(data) => Some(data),
+         None => try!(self.convert_selection(selection_atom, ffi::XCB_ATOM_STRING)),
+      };
+
+      Ok(data.map(|data| String::from_utf8_lossy(&data).into_owned()))
+   }
+
+   pub fn process_events(&mut self) -> VoidResult {
+      while let Some(event) = try!(self.poll_event()) {
+         try!(self.handle_event(event));
+      }
+
+      self.connection.flush();
+
+      Ok(())
+   }
+
+   fn convert_selection(
+      &mut self, selection: ffi::xcb_atom_t, target: ffi::xcb_atom_t
+   ) -> Result<Option<Vec<u8>>, RuntimeError> {
+      let window = self.window.window_id.id;
+      let property = self.atoms.property;
+
+      self.connection.delete_property(window, property);
+      self.connection.convert_selection(window, selection, target, property);
+      self.connection.flush();
+
+      let deadline = deadline();
+
+      loop {
+         let event = try!(self.next_event(deadline));
+
+         match event.event_type() {
+            Some(xcb::EventType::SelectionNotify) => {
+               let notify: xcb::SelectionNotifyEvent = event.into();
+
+               if notify.requestor() != window || notify.selection() != selection {
+                  continue;
+               }
+
+               if notify.property() == ffi::XCB_ATOM_NONE {
+                  return Ok(None);
+               }
+
+               break;
+            },
+            _ => try!(self.handle_event(event))
+         }
+      }
+
+      let (property_type, data) = try!(self.read_property());
+
+      if property_type != self.atoms.incr {
+         return Ok(Some(data));
+      }
+
+      self.receive_incr()
+   }
+
+   fn receive_incr(&mut self) -> Result<Option<Vec<u8>>, RuntimeError> {
+      let window = self.window.window_id.id;
+      let property = self.atoms.property;
+
+      let mut data = Vec::new();
+
+      loop {
+         let deadline = deadline();
+
+         loop {
+            let event = try!(self.next_event(deadline));
+
+            match event.event_type() {
+               Some(xcb::EventType::PropertyNotify) => {
+                  let notify: xcb::PropertyNotifyEvent = event.into();
+
+                  if notify.window() == window && notify.atom() == property &&
+                     !notify.is_deleted() {
+                     break;
+                  }
+
+                  try!(self.handle_property_notify(notify));
+               },
+               _ => try!(self.handle_event(event))
+            }
+         }
+
+         let (_, chunk) = try!(self.read_property());
+
+         if chunk.is_empty() {
+            return Ok(Some(data));
+         }
+
+         data.extend_from_slice(&chunk);
+      }
+   }
+
+   fn read_property(&self) -> Result<(ffi::xcb_atom_t, Vec<u8>), RuntimeError> {
+      let window = self.window.window_id.id;
+      let property = self.atoms.property;
+
+      let mut data = Vec::new();
+      let mut property_type;
+
+      loop {
+         let reply = try!(self.connection.get_property(
+            window, property, false, data.len(), PROPERTY_READ_SIZE
+         ));
+
+         property_type = reply.property_type();
+
+         data.extend_from_slice(reply.value());
+
+         if reply.bytes_after() == 0 {
+            break;
+         }
+      }
+
+      self.connection.delete_property(window, property);
+      self.connection.flush();
+
+      Ok((property_type, data))
+   }
+
+   // Next selection event, the window events read on the way are queued for
+   // the windows
+   fn poll_event(&self) -> Result<Option<xcb::Event>, RuntimeError> {
+      let queued = self.queues.selections.borrow_mut().pop_front();
+
+      if queued.is_some() {
+         return Ok(queued);
+      }
+
+      for result in self.connection.poll_event_iter() {
+         let event = try!(result);
+
+         if is_selection_event(&event) {
+            return Ok(Some(event));
+         }
+
+         self.queues.windows.borrow_mut().push_back(event);
+      }
+
+      Ok(None)
+   }
+
+   fn next_event(&self, deadline: Instant) -> Result<xcb::Event, RuntimeError> {
+      loop {
+         if let Some(event) = try!(self.poll_event()) {
+            return Ok(event);
+         }
+
+         if Instant::now() > deadline {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               "Timed out waiting for selection owner".to_string()
+            ));
+         }
+
+         thread::sleep(Duration::from_millis(1));
+      }
+   }
+
+   fn handle_event(&mut self, event: xcb::Event) -> VoidResult {
+      match event.event_type() {
+         Some(xcb::EventType::SelectionRequest) => {
+            self.handle_selection_request(event.into())
+         },
+         Some(xcb::EventType::SelectionClear) => {
+            self.handle_selection_clear(event.into());
+            Ok(())
+         },
+         Some(xcb::EventType::PropertyNotify) => {
+            self.handle_property_notify(event.into())
+         },
+         _ => Ok(())
+      }
+   }
+
+   fn handle_selection_request(&mut self, request: xcb::SelectionRequestEvent) -> VoidResult {
+      let target = request.target();
+
+      // Obsolete clients may leave the property unset
+      let property = if request.property() == ffi::XCB_ATOM_NONE {
+         target
+      } else {
+         request.property()
+      };
+
+      let data = match self.owned_text(request.selection()) {
+         Some(text) => Some(text.clone().into_bytes()),
+         None => None
+      };
+
+      let accepted = match data {
+         None => false,
+         Some(data) => {
+            let result = if target == self.atoms.targets {
+               self.send_targets(request.requestor(), property)
+            } else if target == self.atoms.utf8_string ||
+               target == self.atoms.text ||
+               target == ffi::XCB_ATOM_STRING {
+               let property_type = if target == self.atoms.text {
+                  self.atoms.utf8_string
+               } else {
+                  target
+               };
+
+               self.send_text(request.requestor(), property, property_type, data)
+            } else {
+               Ok(false)
+            };
+
+            // The requestor might be already gone, refusing is all we can do
+            result.unwrap_or(false)
+         }
+      };
+
+      self.connection.send_selection_notify(
+         request.requestor(),
+         request.selection(),
+         target,
+         if accepted { property } else { ffi::XCB_ATOM_NONE },
+         request.time()
+      );
+
+      self.connection.flush();
+
+      Ok(())
+   }
+
+   fn send_targets(
+      &self, requestor: ffi::xcb_window_t, property: ffi::xcb_atom_t
+   ) -> Result<bool, RuntimeError> {
+      let targets = [
+         self.atoms.targets,
+         self.atoms.utf8_string,
+         self.atoms.text,
+         ffi::XCB_ATOM_STRING,
+      ];
+
+      let mut data = Vec::with_capacity(4 * targets.len());
+
+      for target in targets.iter() {
+         data.extend_from_slice(&target.to_ne_bytes());
+      }
+
+      try!(self.connection.change_property(
+         requestor, property, ffi::XCB_ATOM_ATOM, 32, &data
+      ));
+
+      Ok(true)
+   }
+
+   fn send_text(
+      &mut self,
+      requestor: ffi::xcb_window_t,
+      property: ffi::xcb_atom_t,
+      property_type: ffi::xcb_atom_t,
+      data: Vec<u8>
+   ) -> Result<bool, RuntimeError> {
+      if data.len() <= self.chunk_size {
+         try!(self.connection.change_property(
+            requestor, property, property_type, 8, &data
+         ));
+
+         return Ok(true);
+      }
+
+      try!(self.connection.select_events(
+         requestor, ffi::XCB_EVENT_MASK_PROPERTY_CHANGE
+      ));
+
+      let size = data.len() as u32;
+
+      try!(self.connection.change_property(
+         requestor, property, self.atoms.incr, 32, &size.to_ne_bytes()
+      ));
+
+      self.transfers.push(IncrTransfer {
+         requestor: requestor,
+         property: property,
+         target: property_type,
+         data: data,
+         offset: 0,
+      });
+
+      Ok(true)
+   }
+
+   fn handle_selection_clear(&mut self, clear: xcb::SelectionClearEvent) {
+      if clear.owner() != self.window.window_id.id {
+         return;
+      }
+
+      if clear.selection() == self.atoms.clipboard {
+         self.clipboard_text = None;
+      } else if clear.selection() == ffi::XCB_ATOM_PRIMARY {
+         self.primary_text = None;
+      }
+   }
+
+   fn handle_property_notify(&mut self, notify: xcb::PropertyNotifyEvent) -> VoidResult {
+      if !notify.is_deleted() {
+         return Ok(());
+      }
+
+      let index = match self.transfers.iter().position(
+         |transfer| transfer.requestor == notify.window() &&
+                    transfer.property == notify.atom()
+      ) {
+         Some(index) => index,
+         None => return Ok(())
+      };
+
+      let finished = {
+         let transfer = &mut self.transfers[index];
+
+         let end = min(transfer.offset + self.chunk_size, transfer.data.len());
+
+         let result = self.connection.change_property(
+            transfer.requestor,
+            transfer.property,
+            transfer.target,
+            8,
+            &transfer.data[transfer.offset..end]
+         );
+
+         // An empty chunk marks the end of the transfer
+         let finished = result.is_err() || transfer.offset == end;
+
+         transfer.offset = end;
+
+         finished
+      };
+
+      if finished {
+         let transfer = self.transfers.remove(index);
+
+         let _ = self.connection.select_events(
+            transfer.requestor, ffi::XCB_EVENT_MASK_NO_EVENT
+         );
+      }
+
+      self.connection.flush();
+
+      Ok(())
+   }
+
+   #[inline]
+   fn selection_atom(&self, selection: Selection) -> ffi::xcb_atom_t {
+      match selection {
+         Selection::Clipboard => self.atoms.clipboard,
+         Selection::Primary => ffi::XCB_ATOM_PRIMARY,
+      }
+   }
+
+   #[inline]
+   fn owned_text(&self, selection: ffi::xcb_atom_t) -> Option<&String> {
+      if selection == self.atoms.clipboard {
+         self.clipboard_text.as_ref()
+      } else if selection == ffi::XCB_ATOM_PRIMARY {
+         self.primary_text.as_ref()
+      } else {
+         None
+      }
+   }
+}
+
+#[inline]
+fn deadline() -> Instant {
+   Instant::now() + Duration::from_millis(SELECTION_TIMEOUT_MS)
+}
+
+fn intern_atom(connection: &xcb::Connection, name: &str) -> Result<ffi::xcb_atom_t, RuntimeError> {
+   let cookie = connection.intern_atom(name, false);
+   let reply = connection.intern_atom_reply(&cookie);
+
+   if reply.xcb_reply.is_null() {
+      return Err(RuntimeError::new(
+         ErrorKind::XCB,
+         format!("Interning atom {} failed", name)
+      ));
+   }
+
+   Ok(reply.atom().xcb_atom)
+}
+
+
+#[cfg(test)]
+mod tests {
+   use std::thread;
+   use std::sync::mpsc;
+   use std::time::{Duration, Instant};
+
+   use clipboard::Selection;
+
+   use super::*;
+
+   fn serve(selection: Selection, text: String) -> mpsc::Sender<()> {
+      let (ready_sender, ready_receiver) = mpsc::channel();
+      let (stop_sender, stop_receiver) = mpsc::channel();
+
+      thread::spawn(move || {
+         let display = LinuxDisplay::new().unwrap();
+
+         let mut owner = LinuxClipboard::new(&display).unwrap();
+         owner.set_text(selection, &text).unwrap();
+
+         ready_sender.send(()).unwrap();
+
+         let deadline = Instant::now() + Duration::from_secs(10);
+
+         while stop_receiver.try_recv().is_err() && Instant::now() < deadline {
+            owner.process_events().unwrap();
+            thread::sleep(Duration::from_millis(1));
+         }
+      });
+
+      ready_receiver.recv().unwrap();
+
+      stop_sender
+   }
+
+   // Skipped without an X server to talk to
+   fn x_display() -> Option<LinuxDisplay> {
+      LinuxDisplay::new().ok()
+   }
+
+   #[test]
+   fn test_clipboard_roundtrip() {
+      let display = match x_display() {
+         Some(display) => display,
+         None => return,
+      };
+
+      let text = "polydraw ✓".to_string();
+      let stop = serve(Selection::Clipboard, text.clone());
+
+      let mut reader = LinuxClipboard::new(&display).unwrap();
+      assert_eq!(reader.text(Selection::Clipboard).unwrap(), Some(text));
+
+      stop.send(()).unwrap();
+   }
+
+   #[test]
+   fn test_primary_incr_roundtrip() {
+      let display = match x_display() {
+         Some(display) => display,
+         None => return,
+      };
+
+      let text: String = (0..3 * INCR_CHUNK_SIZE).map(
+         |i| (b'a' + (i % 26) as u8) as char
+      ).collect();
+      let stop = serve(Selection::Primary, text.clone());
+
+      let mut reader = LinuxClipboard::new(&display).unwrap();
+      assert_eq!(reader.text(Selection::Primary).unwrap(), Some(text));
+
+      stop.send(()).unwrap();
+   }
+}
@@ -59,10 +59,28 @@ impl Context for EglContext {
       gl::frame::create_gpu_frame(width, height)
    }
 
+   #[inline]
+   fn make_current(&self) -> VoidResult {
+      self.display.make_current(&self.surface, &self.surface, &self.context)
+   }
+
    #[inline]
    fn swap_buffers(&self) -> VoidResult {
       self.display.swap_buffers(&self.surface)
    }
+
+   #[inline]
+   fn set_swap_interval(&self, interval: i32) -> VoidResult {
+      self.display.swap_interval(interval)
+   }
+}
+
+impl Drop for EglContext {
+   fn drop(&mut self) {
+      let _ = self.display.release_current();
+      let _ = self.display.destroy_surface(&self.surface);
+      let _ = self.display.destroy_context(&self.context);
+   }
 }
 
 impl EglContext {
@@ -108,9 +126,6 @@ impl EglContext {
          context
       ));
 
-      // TODO
-      // try!(display.swap_interval(0));
-
       Ok(surface)
    }
 
@@ -63,10 +63,28 @@ impl Context for GlxContext {
       gl::frame::create_gpu_frame(width, height)
    }
 
+   #[inline]
+   fn make_current(&self) -> VoidResult {
+      glx::make_current(&self.display, self.rendering_area, &self.context)
+   }
+
    #[inline]
    fn swap_buffers(&self) -> VoidResult {
       glx::swap_buffers(&self.display, self.rendering_area)
    }
+
+   #[inline]
+   fn set_swap_interval(&self, interval: i32) -> VoidResult {
+      glx::swap_interval(&self.display, self.rendering_area, interval)
+   }
+}
+
+impl Drop for GlxContext {
+   fn drop(&mut self) {
+      let _ = glx::release_current(&self.display);
+      glx::destroy_rendering_area(&self.display, self.rendering_area);
+      glx::destroy_context(&self.display, &self.context);
+   }
 }
 
 impl GlxContext {
@@ -81,9 +99,6 @@ impl GlxContext {
 
       try!(glx::make_current(&display, rendering_area, &context));
 
-      // TODO
-      // try!(glx::swap_interval(display, 0));
-
       Ok(rendering_area)
    }
 
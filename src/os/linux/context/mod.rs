@@ -17,8 +17,12 @@ pub trait Context {
       window: &xcb::Window
    ) -> Result<Self, RuntimeError> where Self: Sized;
 
+   fn make_current(&self) -> VoidResult;
+
    fn swap_buffers(&self) -> VoidResult;
 
+   fn set_swap_interval(&self, interval: i32) -> VoidResult;
+
    fn create_gpu_frame(&self, width: u32, height: u32) -> Result<Box<GPUFrame>, RuntimeError>;
 }
 
@@ -1,15 +1,54 @@
 use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 
 use error::RuntimeError;
+use monitor::{Monitor, scale_factor_from_dpi};
 
 use sys::x11;
 use sys::xcb;
+use sys::xcb::{ffi, randr};
+
+const RESOURCE_MANAGER_SIZE: usize = 0x100000;
 
 pub struct LinuxDisplay {
    pub display: x11::Display,
    pub connection: Rc<xcb::Connection>,
    pub screen: xcb::Screen,
    pub screen_id: x11::ScreenID,
+   pub queues: Rc<EventQueues>,
+}
+
+
+// Windows and clipboard share the connection. Events read by the side they
+// are not meant for wait here until the other side polls
+pub struct EventQueues {
+   pub windows: RefCell<VecDeque<xcb::Event>>,
+   pub selections: RefCell<VecDeque<xcb::Event>>,
+}
+
+impl EventQueues {
+   #[inline]
+   pub fn new() -> Self {
+      EventQueues {
+         windows: RefCell::new(VecDeque::new()),
+         selections: RefCell::new(VecDeque::new()),
+      }
+   }
+}
+
+
+// Selection transfers go to the clipboard, the windows don't select
+// property changes
+#[inline]
+pub fn is_selection_event(event: &xcb::Event) -> bool {
+   match event.event_type() {
+      Some(xcb::EventType::SelectionRequest) |
+      Some(xcb::EventType::SelectionClear) |
+      Some(xcb::EventType::SelectionNotify) |
+      Some(xcb::EventType::PropertyNotify) => true,
+      _ => false
+   }
 }
 
 impl LinuxDisplay {
@@ -27,6 +66,7 @@ impl LinuxDisplay {
          connection: connection,
          screen: screen,
          screen_id: screen_id,
+         queues: Rc::new(EventQueues::new()),
       })
    }
 
@@ -46,4 +86,121 @@ impl LinuxDisplay {
          self.screen.height_in_pixels()
       )
    }
+
+   pub fn monitors(&self) -> Vec<Monitor> {
+      let xft_dpi = self.xft_dpi();
+
+      let outputs = match randr::outputs(&self.connection, self.screen.root()) {
+         Ok(outputs) => outputs,
+         Err(_) => Vec::new()
+      };
+
+      let mut monitors: Vec<Monitor> = outputs.into_iter().map(|output| Monitor {
+         name: output.name,
+         x: output.x,
+         y: output.y,
+         width: output.width,
+         height: output.height,
+         width_mm: output.width_mm,
+         height_mm: output.height_mm,
+         refresh_rate: output.refresh_rate,
+         primary: output.primary,
+         scale_factor: 1.,
+      }).collect();
+
+      // Without RandR the root screen is the only monitor we know about
+      if monitors.is_empty() {
+         let (width, height) = self.screen_size();
+         let (width_mm, height_mm) = unsafe {(
+            (*self.screen.ptr).width_in_millimeters as u32,
+            (*self.screen.ptr).height_in_millimeters as u32,
+         )};
+
+         monitors.push(Monitor {
+            name: "default".to_string(),
+            x: 0,
+            y: 0,
+            width: width,
+            height: height,
+            width_mm: width_mm,
+            height_mm: height_mm,
+            refresh_rate: 0.,
+            primary: true,
+            scale_factor: 1.,
+         });
+      }
+
+      for monitor in monitors.iter_mut() {
+         let dpi = match xft_dpi {
+            Some(dpi) => Some(dpi),
+            None => monitor.physical_dpi()
+         };
+
+         if let Some(dpi) = dpi {
+            monitor.scale_factor = scale_factor_from_dpi(dpi);
+         }
+      }
+
+      monitors
+   }
+
+   pub fn xft_dpi(&self) -> Option<f64> {
+      let reply = match self.connection.get_property(
+         self.screen.root(), ffi::XCB_ATOM_RESOURCE_MANAGER, false, 0, RESOURCE_MANAGER_SIZE
+      ) {
+         Ok(reply) => reply,
+         Err(_) => return None
+      };
+
+      parse_xft_dpi(&String::from_utf8_lossy(reply.value()))
+   }
+}
+
+pub fn parse_xft_dpi(resources: &str) -> Option<f64> {
+   for line in resources.lines() {
+      let mut parts = line.splitn(2, ':');
+
+      let name = match parts.next() {
+         Some(name) => name.trim(),
+         None => continue
+      };
+
+      if name != "Xft.dpi" {
+         continue;
+      }
+
+      match parts.next() {
+         Some(value) => match value.trim().parse::<f64>() {
+            Ok(dpi) if dpi > 0. => return Some(dpi),
+            _ => return None
+         },
+         None => return None
+      }
+   }
+
+   None
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn test_parse_xft_dpi() {
+      assert_eq!(parse_xft_dpi(""), None);
+      assert_eq!(parse_xft_dpi("Xft.antialias:\t1\nXft.hinting:\t1\n"), None);
+
+      assert_eq!(parse_xft_dpi("Xft.dpi:\t192\n"), Some(192.));
+      assert_eq!(parse_xft_dpi("  Xft.dpi  :   144.5  "), Some(144.5));
+
+      assert_eq!(parse_xft_dpi("Xft.dpi:\tlarge\n"), None);
+      assert_eq!(parse_xft_dpi("Xft.dpi:\t0\n"), None);
+      assert_eq!(parse_xft_dpi("Xft.dpi\n"), None);
+
+      assert_eq!(
+         parse_xft_dpi("Xcursor.size:\t24\nXft.dpiscale:\t2\nXft.dpi:\t120\nXft.rgba:\trgb\n"),
+         Some(120.)
+      );
+   }
 }
@@ -2,3 +2,5 @@ pub mod display;
 pub mod window;
 pub mod application;
 pub mod context;
+pub mod clipboard;
+pub mod pointer;
@@ -0,0 +1,65 @@
+use std::rc::Rc;
+
+use error::VoidResult;
+use pointer::{CursorShape, CursorImage};
+
+use sys::xcb;
+use sys::xcb::cursor;
+
+use super::window::LinuxWindow;
+
+#[derive(Clone)]
+pub struct LinuxPointer {
+   pub window: Rc<LinuxWindow>,
+}
+
+impl LinuxPointer {
+   pub fn new(window: Rc<LinuxWindow>) -> Self {
+      LinuxPointer {
+         window: window,
+      }
+   }
+
+   pub fn set_cursor(&self, shape: CursorShape) -> VoidResult {
+      let glyph = match shape {
+         CursorShape::Arrow => cursor::XC_LEFT_PTR,
+         CursorShape::Crosshair => cursor::XC_CROSSHAIR,
+         CursorShape::Hand => cursor::XC_HAND2,
+         CursorShape::Text => cursor::XC_XTERM,
+         CursorShape::ResizeHorizontal => cursor::XC_SB_H_DOUBLE_ARROW,
+         CursorShape::ResizeVertical => cursor::XC_SB_V_DOUBLE_ARROW,
+         CursorShape::ResizeDiagonalNwse => cursor::XC_TOP_LEFT_CORNER,
+         CursorShape::ResizeDiagonalNesw => cursor::XC_TOP_RIGHT_CORNER,
+         CursorShape::Move => cursor::XC_FLEUR,
+         CursorShape::Wait => cursor::XC_WATCH,
+         CursorShape::NotAllowed => cursor::XC_X_CURSOR,
+      };
+
+      let cursor = try!(xcb::Cursor::from_glyph(&self.window.window.connection, glyph));
+
+      // The server keeps the cursor alive for as long as the window uses it
+      self.window.window.set_cursor(&cursor)
+   }
+
+   pub fn set_custom_cursor(&self, image: &CursorImage) -> VoidResult {
+      let cursor = try!(xcb::Cursor::from_rgba(
+         &self.window.window.connection,
+         self.window.window.window_id.id,
+         image.width, image.height,
+         image.hot_x, image.hot_y,
+         &image.pixels
+      ));
+
+      self.window.window.set_cursor(&cursor)
+   }
+
+   #[inline]
+   pub fn grab(&self, confine: bool) -> VoidResult {
+      self.window.window.grab_pointer(confine)
+   }
+
+   #[inline]
+   pub fn ungrab(&self) {
+      self.window.window.ungrab_pointer()
+   }
+}
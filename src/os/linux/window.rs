@@ -1,12 +1,12 @@
 use std::rc::Rc;
 
-use error::RuntimeError;
+use error::{RuntimeError, VoidResult};
 
 use sys::xcb;
 
-use event::Event;
+use event::{Event, WindowId};
 
-use super::display::LinuxDisplay;
+use super::display::{LinuxDisplay, EventQueues, is_selection_event};
 
 pub struct XcbAtoms {
    pub protocols_atom: xcb::Atom,
@@ -66,49 +66,78 @@ impl LinuxWindow {
    }
 
    #[inline]
-   pub fn poll_events(&self) -> PollEventsIterator {
-      PollEventsIterator::new(&self.window, &self.atoms)
+   pub fn unmap(&self) -> VoidResult {
+      self.window.unmap()
    }
 }
 
 pub struct PollEventsIterator<'a> {
    xcb_iterator: xcb::EventIterator,
-   atoms: &'a XcbAtoms,
+   windows: &'a [Option<Rc<LinuxWindow>>],
+   queues: &'a EventQueues,
 }
 
 impl<'a> PollEventsIterator<'a> {
    #[inline]
-   pub fn new(window: &xcb::Window, atoms: &'a XcbAtoms) -> Self {
+   pub fn new(
+      connection: &xcb::Connection,
+      windows: &'a [Option<Rc<LinuxWindow>>],
+      queues: &'a EventQueues
+   ) -> Self {
       PollEventsIterator {
-         xcb_iterator: window.connection.poll_event_iter(),
-         atoms: atoms
+         xcb_iterator: connection.poll_event_iter(),
+         windows: windows,
+         queues: queues,
+      }
+   }
+
+   #[inline]
+   fn find_window(&self, xcb_event: &xcb::Event) -> Option<(WindowId, &'a LinuxWindow)> {
+      let xcb_window_id = match xcb_event.window_id() {
+         Some(xcb_window_id) => xcb_window_id,
+         None => return None
+      };
+
+      for (index, slot) in self.windows.iter().enumerate() {
+         if let Some(ref window) = *slot {
+            if window.window.window_id.id == xcb_window_id {
+               return Some((WindowId(index), &**window));
+            }
+         }
       }
+
+      None
    }
 
    #[inline]
-   fn convert(&mut self, xcb_event: xcb::Event) -> Option<Event> {
+   fn convert(&mut self, xcb_event: xcb::Event) -> Option<(WindowId, Event)> {
+      let (window_id, window) = match self.find_window(&xcb_event) {
+         Some(found) => found,
+         None => return self.next()
+      };
+
       match xcb_event.event_type() {
          None => {},
          Some(event_type) => match event_type {
             xcb::EventType::ClientMessage => {
                if xcb_event.is_close_event(
-                     &self.atoms.protocols_atom,
-                     &self.atoms.delete_window_atom
+                     &window.atoms.protocols_atom,
+                     &window.atoms.delete_window_atom
                ) {
-                  return Some(Event::Quit);
+                  return Some((window_id, Event::Quit));
                }
             },
 
             xcb::EventType::ConfigureNotify => {
                let resized: xcb::ResizedEvent = xcb_event.into();
                let (width, height) = resized.size();
-               return Some(Event::Resized(width, height));
+               return Some((window_id, Event::Resized(width, height)));
             },
 
             xcb::EventType::MotionNotify => {
                let mouse_moved: xcb::MouseMovedEvent = xcb_event.into();
                let (x, y) = mouse_moved.position();
-               return Some(Event::MouseMoved(x, y));
+               return Some((window_id, Event::MouseMoved(x, y)));
             },
 
             xcb::EventType::ButtonPress => {
@@ -116,16 +145,16 @@ impl<'a> PollEventsIterator<'a> {
                match pressed.button() {
                   Some(button) => match button {
                      xcb::MouseButton::Left => {
-                        return Some(Event::MouseLeftButtonPressed);
+                        return Some((window_id, Event::MouseLeftButtonPressed));
                      },
                      xcb::MouseButton::Middle => {
-                        return Some(Event::MouseMiddleButtonPressed);
+                        return Some((window_id, Event::MouseMiddleButtonPressed));
                      },
                      xcb::MouseButton::Right => {
-                        return Some(Event::MouseRightButtonPressed);
+                        return Some((window_id, Event::MouseRightButtonPressed));
                      },
                      xcb::MouseButton::Extra(n) => {
-                        return Some(Event::MouseExtraButtonPressed(n));
+                        return Some((window_id, Event::MouseExtraButtonPressed(n)));
                      },
                   },
                   None => {}
@@ -137,16 +166,16 @@ impl<'a> PollEventsIterator<'a> {
                match released.button() {
                   Some(button) => match button {
                      xcb::MouseButton::Left => {
-                        return Some(Event::MouseLeftButtonReleased);
+                        return Some((window_id, Event::MouseLeftButtonReleased));
                      },
                      xcb::MouseButton::Middle => {
-                        return Some(Event::MouseMiddleButtonReleased);
+                        return Some((window_id, Event::MouseMiddleButtonReleased));
                      },
                      xcb::MouseButton::Right => {
-                        return Some(Event::MouseRightButtonReleased);
+                        return Some((window_id, Event::MouseRightButtonReleased));
                      },
                      xcb::MouseButton::Extra(n) => {
-                        return Some(Event::MouseExtraButtonReleased(n));
+                        return Some((window_id, Event::MouseExtraButtonReleased(n)));
                      },
                   },
                   None => {}
@@ -162,16 +191,28 @@ impl<'a> PollEventsIterator<'a> {
 }
 
 impl<'a> Iterator for PollEventsIterator<'a> {
-   type Item = Event;
+   type Item = (WindowId, Event);
 
    #[inline]
-   fn next(&mut self) -> Option<Event> {
+   fn next(&mut self) -> Option<(WindowId, Event)> {
+      let queued = self.queues.windows.borrow_mut().pop_front();
+
+      if let Some(xcb_event) = queued {
+         return self.convert(xcb_event);
+      }
+
       match self.xcb_iterator.next() {
          None => None,
          Some(result) => {
             match result {
                Err(e) => panic!(e.description),
                Ok(xcb_event) => {
+                  if is_selection_event(&xcb_event) {
+                     self.queues.selections.borrow_mut().push_back(xcb_event);
+
+                     return self.next();
+                  }
+
                   self.convert(xcb_event)
                }
             }
@@ -1,47 +1,68 @@
-use error::{RuntimeError, VoidResult};
+use error::{RuntimeError, ErrorKind, VoidResult};
 use frame::GPUFrame;
+use event::{Event, WindowId};
+use monitor::Monitor;
 
 use sys::wgl;
 use sys::ft;
 
 use super::display::WindowsDisplay;
-use super::window::{WindowsWindow, PollEventsIterator};
+use super::window::WindowsWindow;
 use super::wgl_context::WglContext;
+use super::pointer::WindowsPointer;
 
 pub struct WindowsApplication {
    pub display: WindowsDisplay,
-   pub window: WindowsWindow,
-   pub wgl: WglContext,
+   pub windows: Vec<Option<WindowsWindow>>,
+   pub contexts: Vec<Option<WglContext>>,
+   pub monitors: Vec<Monitor>,
    pub freetype: ft::FreeType,
 }
 
 impl WindowsApplication {
-   pub fn new(
-      display: WindowsDisplay,
+   pub fn new(display: WindowsDisplay) -> Result<Self, RuntimeError> {
+      let freetype = ft::FreeType::new();
+
+      let monitors = display.monitors();
+
+      Ok(WindowsApplication {
+         display: display,
+         windows: Vec::new(),
+         contexts: Vec::new(),
+         monitors: monitors,
+         freetype: freetype,
+      })
+   }
+
+   pub fn create_window(
+      &mut self,
       title: &str,
       x: i32, y: i32,
       width: u32, height: u32
-   ) -> Result<Self, RuntimeError> {
+   ) -> Result<WindowId, RuntimeError> {
 
       let window = try!(WindowsWindow::new(title, x, y, width, height));
 
       let wgl = try!(WglContext::new(&window.device_context));
 
-      let freetype = ft::FreeType::new();
+      let window_id = WindowId(self.windows.len());
 
-      Ok(WindowsApplication {
-         display: display,
-         window: window,
-         wgl: wgl,
-         freetype: freetype,
-      })
+      self.windows.push(Some(window));
+      self.contexts.push(Some(wgl));
+
+      Ok(window_id)
+   }
+
+   #[inline]
+   pub fn window_count(&self) -> usize {
+      self.windows.len()
    }
 
    #[inline]
    pub fn create_gpu_frame(
-      &self, width: u32, height: u32
+      &self, window_id: WindowId, width: u32, height: u32
    ) -> Result<Box<GPUFrame>, RuntimeError> {
-      self.wgl.create_gpu_frame(width, height)
+      try!(self.context(window_id)).create_gpu_frame(width, height)
    }
 
    #[inline]
@@ -49,14 +70,107 @@ impl WindowsApplication {
       self.display.screen_size()
    }
 
+   #[inline]
+   pub fn monitors(&self) -> &[Monitor] {
+      &self.monitors
+   }
+
+   #[inline]
+   pub fn scale_factor(&self, window_id: WindowId) -> Result<f64, RuntimeError> {
+      try!(self.window(window_id));
+
+      Ok(1.)
+   }
+
    #[inline]
    pub fn poll_events(&self) -> PollEventsIterator {
-      self.window.poll_events()
+      PollEventsIterator {
+         windows: &self.windows,
+         index: 0,
+      }
    }
 
    #[inline]
-   pub fn swap_buffers(&self) -> VoidResult {
-      wgl::swap_buffers(&self.window.device_context);
+   pub fn make_current(&self, window_id: WindowId) -> VoidResult {
+      let window = try!(self.window(window_id));
+
+      try!(self.context(window_id)).make_current(&window.device_context)
+   }
+
+   #[inline]
+   pub fn swap_buffers(&self, window_id: WindowId) -> VoidResult {
+      let window = try!(self.window(window_id));
+
+      wgl::swap_buffers(&window.device_context);
       Ok(())
    }
+
+   #[inline]
+   pub fn set_swap_interval(&self, window_id: WindowId, interval: i32) -> VoidResult {
+      try!(self.make_current(window_id));
+
+      try!(self.context(window_id)).set_swap_interval(interval)
+   }
+
+   pub fn destroy_window(&mut self, window_id: WindowId) -> VoidResult {
+      try!(self.window(window_id)).window.hide();
+
+      self.contexts[window_id.0] = None;
+      self.windows[window_id.0] = None;
+
+      Ok(())
+   }
+
+   #[inline]
+   pub fn pointer(&self, window_id: WindowId) -> Result<WindowsPointer, RuntimeError> {
+      try!(self.window(window_id));
+
+      Ok(WindowsPointer)
+   }
+
+   #[inline]
+   fn window(&self, window_id: WindowId) -> Result<&WindowsWindow, RuntimeError> {
+      match self.windows.get(window_id.0) {
+         Some(&Some(ref window)) => Ok(window),
+         _ => Err(unknown_window(window_id))
+      }
+   }
+
+   #[inline]
+   fn context(&self, window_id: WindowId) -> Result<&WglContext, RuntimeError> {
+      match self.contexts.get(window_id.0) {
+         Some(&Some(ref context)) => Ok(context),
+         _ => Err(unknown_window(window_id))
+      }
+   }
+}
+
+fn unknown_window(window_id: WindowId) -> RuntimeError {
+   RuntimeError::new(
+      ErrorKind::InvalidInput,
+      format!("Unknown window: {}", window_id.0)
+   )
+}
+
+pub struct PollEventsIterator<'a> {
+   windows: &'a [Option<WindowsWindow>],
+   index: usize,
+}
+
+impl<'a> Iterator for PollEventsIterator<'a> {
+   type Item = (WindowId, Event);
+
+   fn next(&mut self) -> Option<(WindowId, Event)> {
+      while self.index < self.windows.len() {
+         if let Some(ref window) = self.windows[self.index] {
+            if let Some(event) = window.poll_events().next() {
+               return Some((WindowId(self.index), event));
+            }
+         }
+
+         self.index += 1;
+      }
+
+      None
+   }
 }
@@ -0,0 +1,32 @@
+use error::{RuntimeError, ErrorKind, VoidResult};
+use clipboard::Selection;
+
+use super::display::WindowsDisplay;
+
+pub struct WindowsClipboard;
+
+impl WindowsClipboard {
+   pub fn new(_: &WindowsDisplay) -> Result<Self, RuntimeError> {
+      Ok(WindowsClipboard)
+   }
+
+   pub fn text(&mut self, _: Selection) -> Result<Option<String>, RuntimeError> {
+      Err(unsupported())
+   }
+
+   pub fn set_text(&mut self, _: Selection, _: &str) -> VoidResult {
+      Err(unsupported())
+   }
+
+   #[inline]
+   pub fn process_events(&mut self) -> VoidResult {
+      Ok(())
+   }
+}
+
+fn unsupported() -> RuntimeError {
+   RuntimeError::new(
+      ErrorKind::Win32,
+      "Clipboard is not supported on Windows yet".to_string()
+   )
+}
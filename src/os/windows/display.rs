@@ -1,4 +1,5 @@
 use error::RuntimeError;
+use monitor::Monitor;
 
 use sys::win32;
 
@@ -13,4 +14,21 @@ impl WindowsDisplay {
    pub fn screen_size(&self) -> (u32, u32) {
       win32::DeviceMode::enumerate().screen_size()
    }
+
+   pub fn monitors(&self) -> Vec<Monitor> {
+      let (width, height) = self.screen_size();
+
+      vec![Monitor {
+         name: "default".to_string(),
+         x: 0,
+         y: 0,
+         width: width,
+         height: height,
+         width_mm: 0,
+         height_mm: 0,
+         refresh_rate: 0.,
+         primary: true,
+         scale_factor: 1.,
+      }]
+   }
 }
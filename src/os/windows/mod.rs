@@ -3,3 +3,5 @@ pub mod window;
 pub mod application;
 pub mod wnd_proc;
 pub mod wgl_context;
+pub mod clipboard;
+pub mod pointer;
@@ -0,0 +1,30 @@
+use error::{RuntimeError, ErrorKind, VoidResult};
+use pointer::{CursorShape, CursorImage};
+
+#[derive(Clone)]
+pub struct WindowsPointer;
+
+impl WindowsPointer {
+   pub fn set_cursor(&self, _: CursorShape) -> VoidResult {
+      Err(unsupported())
+   }
+
+   pub fn set_custom_cursor(&self, _: &CursorImage) -> VoidResult {
+      Err(unsupported())
+   }
+
+   pub fn grab(&self, _: bool) -> VoidResult {
+      Err(unsupported())
+   }
+
+   #[inline]
+   pub fn ungrab(&self) {
+   }
+}
+
+fn unsupported() -> RuntimeError {
+   RuntimeError::new(
+      ErrorKind::Win32,
+      "Cursor control is not supported on Windows yet".to_string()
+   )
+}
@@ -41,6 +41,16 @@ impl WglContext {
       Ok(())
    }
 
+   #[inline]
+   pub fn make_current(&self, device_context: &win32::DeviceContext) -> VoidResult {
+      self.context.make_current(device_context)
+   }
+
+   #[inline]
+   pub fn set_swap_interval(&self, interval: i32) -> VoidResult {
+      wgl::swap_interval(interval)
+   }
+
    #[inline]
    pub fn create_gpu_frame(
       &self, width: u32, height: u32
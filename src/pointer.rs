@@ -0,0 +1,84 @@
+#[cfg(target_os = "windows")]
+pub use os::windows::pointer::WindowsPointer as OsPointer;
+#[cfg(target_os = "linux")]
+pub use os::linux::pointer::LinuxPointer as OsPointer;
+
+use error::VoidResult;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CursorShape {
+   Arrow,
+   Crosshair,
+   Hand,
+   Text,
+   ResizeHorizontal,
+   ResizeVertical,
+   ResizeDiagonalNwse,
+   ResizeDiagonalNesw,
+   Move,
+   Wait,
+   NotAllowed,
+}
+
+#[derive(Clone, Debug)]
+pub struct CursorImage {
+   pub width: u32,
+   pub height: u32,
+   pub hot_x: u32,
+   pub hot_y: u32,
+   pub pixels: Vec<u8>,
+}
+
+impl CursorImage {
+   pub fn new(width: u32, height: u32, hot_x: u32, hot_y: u32, pixels: Vec<u8>) -> Self {
+      CursorImage {
+         width: width,
+         height: height,
+         hot_x: hot_x,
+         hot_y: hot_y,
+         pixels: pixels,
+      }
+   }
+
+   pub fn transparent(width: u32, height: u32) -> Self {
+      CursorImage::new(width, height, 0, 0, vec![0; (4 * width * height) as usize])
+   }
+}
+
+#[derive(Clone)]
+pub struct Pointer {
+   pub os_pointer: OsPointer,
+}
+
+impl Pointer {
+   pub fn new(os_pointer: OsPointer) -> Self {
+      Pointer {
+         os_pointer: os_pointer,
+      }
+   }
+
+   #[inline]
+   pub fn set_cursor(&self, shape: CursorShape) -> VoidResult {
+      self.os_pointer.set_cursor(shape)
+   }
+
+   #[inline]
+   pub fn set_custom_cursor(&self, image: &CursorImage) -> VoidResult {
+      self.os_pointer.set_custom_cursor(image)
+   }
+
+   #[inline]
+   pub fn hide_cursor(&self) -> VoidResult {
+      self.os_pointer.set_custom_cursor(&CursorImage::transparent(1, 1))
+   }
+
+   #[inline]
+   pub fn grab(&self, confine: bool) -> VoidResult {
+      self.os_pointer.grab(confine)
+   }
+
+   #[inline]
+   pub fn ungrab(&self) {
+      self.os_pointer.ungrab()
+   }
+}
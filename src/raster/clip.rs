@@ -0,0 +1,153 @@
+use geom::path::Path;
+use geom::flatten::Flattener;
+use geom::boolean::{BooleanOp, boolean_path};
+
+use super::scene::Scene;
+
+
+// Clip region of a group of polys, already intersected with the enclosing
+// clips. Clipped paths get flattened with the same flattener for the
+// polygon intersection
+#[derive(Debug, Clone)]
+pub struct Clip {
+   pub path: Path,
+   pub flattener: Flattener,
+}
+
+impl Clip {
+   #[inline]
+   pub fn new(path: Path, flattener: Flattener) -> Self {
+      Clip {
+         path: path,
+         flattener: flattener,
+      }
+   }
+
+   // Outline of `path` inside the clip. Clipping is done on the geometry and
+   // keeps the curves of both outlines apart from the chords cut at their
+   // crossings, so the clipped edges get the same analytic coverage as any
+   // other edge
+   #[inline]
+   pub fn apply(&self, path: &Path) -> Path {
+      boolean_path(BooleanOp::Intersection, path, &self.path, &self.flattener)
+   }
+}
+
+
+impl Scene {
+   // Paths pushed until the matching pop_clip are clipped by `path`. Nested
+   // clips intersect with the enclosing ones
+   pub fn push_clip(&mut self, path: &Path, flattener: &Flattener) {
+      let path = match self.clips.last() {
+         Some(clip) => clip.apply(path),
+         None => path.clone(),
+      };
+
+      self.clips.push(Clip::new(path, *flattener));
+   }
+
+   #[inline]
+   pub fn pop_clip(&mut self) {
+      self.clips.pop();
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use std::f64::consts::PI;
+
+   use data::FloatPoint;
+   use draw::RGB;
+   use geom::path::Path;
+   use geom::flatten::Flattener;
+
+   use raster::scene::Scene;
+   use raster::edge::EdgeType;
+   use raster::rasterizer::Rasterizer;
+   use raster::rasterizer::tests::rect;
+   use testing::pixel_frame;
+
+   #[test]
+   fn test_nested_clips() {
+      let flattener = Flattener::new(0.2);
+
+      let mut scene = Scene::new();
+
+      scene.push_clip(&rect(10., 10., 60., 60.), &flattener);
+      scene.push_clip(&rect(30., 30., 90., 90.), &flattener);
+
+      scene.push_path(&rect(0., 0., 100., 100.), RGB::new(255, 0, 0));
+
+      scene.pop_clip();
+      scene.pop_clip();
+
+      scene.push_path(&rect(70., 0., 100., 20.), RGB::new(0, 0, 255));
+
+      assert_eq!(scene.polys.len(), 2);
+      assert_eq!(scene.clips.len(), 0);
+
+      let ref poly = scene.polys[0];
+
+      for edge in scene.edges[poly.start..poly.end].iter() {
+         let ref segment = scene.segments[edge.segment];
+
+         for point in [scene.points[segment.p1], scene.points[segment.p2]].iter() {
+            assert!(point.x >= 30 && point.x <= 60 && point.y >= 30 && point.y <= 60);
+         }
+      }
+
+      // Nothing is left of paths outside the clip
+      scene.push_clip(&rect(0., 0., 10., 10.), &flattener);
+      scene.push_path(&rect(20., 20., 30., 30.), RGB::new(0, 255, 0));
+
+      assert_eq!(scene.polys.len(), 2);
+   }
+   #[test]
+   fn test_curved_clip() {
+      let center = FloatPoint::new(2000., 2000.);
+
+      let circle = Path::new()
+         .move_to(FloatPoint::new(3500., 2000.))
+         .arc(center, 1500., 1500., 0., 0., 2. * PI)
+         .close();
+
+      let mut scene = Scene::new();
+
+      scene.push_clip(&circle, &Flattener::new(20.));
+      scene.push_path(&rect(0., 0., 4000., 4000.), RGB::new(255, 0, 0));
+      scene.pop_clip();
+
+      // The clip outline is put back as arcs instead of its flattened chords
+      assert!(scene.edges.iter().all(|edge| match edge.edge_type {
+         EdgeType::ETR | EdgeType::ETL | EdgeType::EBR | EdgeType::EBL => true,
+         _ => false,
+      }));
+
+      let mut rasterizer = Rasterizer::new();
+      let mut frame = pixel_frame(40, 40);
+
+      rasterizer.render(&scene, &mut frame, 100);
+
+      assert_eq!(frame.get_pixel(20, 20), RGB::new(255, 0, 0));
+      assert_eq!(frame.get_pixel(2, 2), RGB::new(0, 0, 0));
+
+      // The edge crosses row 23 at x = 5.41, the uncovered part of the pixel
+      // shows the background instead of the clipped poly
+      assert_eq!(frame.get_pixel(4, 23), RGB::new(0, 0, 0));
+      assert_eq!(frame.get_pixel(6, 23), RGB::new(255, 0, 0));
+
+      let edge = frame.get_pixel(5, 23);
+
+      assert!(edge.r > 60 && edge.r < 240, "{:?}", edge);
+      assert_eq!((edge.g, edge.b), (0, 0));
+
+      // Same on the right side of the circle
+      assert_eq!(frame.get_pixel(35, 23), RGB::new(0, 0, 0));
+      assert_eq!(frame.get_pixel(33, 23), RGB::new(255, 0, 0));
+
+      let edge = frame.get_pixel(34, 23);
+
+      assert!(edge.r > 60 && edge.r < 240, "{:?}", edge);
+   }
+}
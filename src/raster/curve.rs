@@ -0,0 +1,228 @@
+use geom::point::Point;
+
+
+const SOLVE_ITERATIONS: usize = 64;
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct Curve {
+   pub c1: usize,
+   pub c2: usize,
+}
+
+impl Curve {
+   #[inline]
+   pub fn new(c1: usize, c2: usize) -> Self {
+      Curve {
+         c1: c1,
+         c2: c2,
+      }
+   }
+
+   #[inline]
+   pub fn quadratic(c: usize) -> Self {
+      Curve::new(c, c)
+   }
+}
+
+impl Default for Curve {
+   fn default() -> Curve {
+      Curve::new(0, 0)
+   }
+}
+
+
+// Power basis form of a monotonic curve piece, relative to its first point
+#[derive(Debug, Clone, Copy)]
+pub struct CurvePoly {
+   pub origin: Point,
+   pub x: [f64; 4],
+   pub y: [f64; 4],
+}
+
+impl CurvePoly {
+   pub fn quadratic(p1: &Point, c: &Point, p2: &Point) -> Self {
+      let (cx, cy) = relative(c, p1);
+      let (x2, y2) = relative(p2, p1);
+
+      CurvePoly {
+         origin: *p1,
+         x: [0., 2. * cx, x2 - 2. * cx, 0.],
+         y: [0., 2. * cy, y2 - 2. * cy, 0.],
+      }
+   }
+
+   pub fn cubic(p1: &Point, c1: &Point, c2: &Point, p2: &Point) -> Self {
+      let (c1x, c1y) = relative(c1, p1);
+      let (c2x, c2y) = relative(c2, p1);
+      let (x2, y2) = relative(p2, p1);
+
+      CurvePoly {
+         origin: *p1,
+         x: [0., 3. * c1x, 3. * (c2x - 2. * c1x), x2 + 3. * (c1x - c2x)],
+         y: [0., 3. * c1y, 3. * (c2y - 2. * c1y), y2 + 3. * (c1y - c2y)],
+      }
+   }
+
+   #[inline]
+   pub fn x_at(&self, t: f64) -> f64 {
+      eval(&self.x, t)
+   }
+
+   #[inline]
+   pub fn y_at(&self, t: f64) -> f64 {
+      eval(&self.y, t)
+   }
+
+   #[inline]
+   pub fn t_at_x(&self, x: i64) -> f64 {
+      solve(&self.x, (x - self.origin.x) as f64)
+   }
+
+   #[inline]
+   pub fn t_at_y(&self, y: i64) -> f64 {
+      solve(&self.y, (y - self.origin.y) as f64)
+   }
+
+   pub fn t_at(&self, point: &Point) -> f64 {
+      if self.x_at(1.).abs() > self.y_at(1.).abs() {
+         self.t_at_x(point.x)
+      } else {
+         self.t_at_y(point.y)
+      }
+   }
+
+   #[inline]
+   pub fn x_at_y(&self, y: i64) -> i64 {
+      self.origin.x + self.x_at(self.t_at_y(y)).round() as i64
+   }
+
+   #[inline]
+   pub fn y_at_x(&self, x: i64) -> i64 {
+      self.origin.y + self.y_at(self.t_at_x(x)).round() as i64
+   }
+
+   // Doubled area between the curve and its chord in the t1..t2 range,
+   // signed the same way as the trapezoid terms in Rasterizer::double_area
+   pub fn double_segment_area(&self, t1: f64, t2: f64) -> f64 {
+      let mut integral = [0.; 6];
+
+      for i in 0..4 {
+         for j in 1..4 {
+            integral[i + j - 1] += self.y[i] * self.x[j] * j as f64;
+         }
+      }
+
+      let curve = 2. * (antiderivative(&integral, t2) - antiderivative(&integral, t1));
+
+      let chord = (self.x_at(t2) - self.x_at(t1)) * (self.y_at(t1) + self.y_at(t2));
+
+      curve - chord
+   }
+}
+
+#[inline]
+fn relative(point: &Point, origin: &Point) -> (f64, f64) {
+   ((point.x - origin.x) as f64, (point.y - origin.y) as f64)
+}
+
+#[inline]
+fn eval(coeffs: &[f64; 4], t: f64) -> f64 {
+   ((coeffs[3] * t + coeffs[2]) * t + coeffs[1]) * t + coeffs[0]
+}
+
+#[inline]
+fn derivative(coeffs: &[f64; 4], t: f64) -> f64 {
+   (3. * coeffs[3] * t + 2. * coeffs[2]) * t + coeffs[1]
+}
+
+fn antiderivative(coeffs: &[f64; 6], t: f64) -> f64 {
+   let mut value = 0.;
+
+   for k in (0..6).rev() {
+      value = (value + coeffs[k] / (k + 1) as f64) * t;
+   }
+
+   value
+}
+
+// Safeguarded Newton iteration, the polynomial is monotonic on 0..1
+fn solve(coeffs: &[f64; 4], value: f64) -> f64 {
+   let end = eval(coeffs, 1.);
+
+   if end == 0. {
+      return 0.;
+   }
+
+   let increasing = end > 0.;
+
+   let mut lo = 0.;
+   let mut hi = 1.;
+
+   let mut t = (value / end).max(0.).min(1.);
+
+   for _ in 0..SOLVE_ITERATIONS {
+      let diff = eval(coeffs, t) - value;
+
+      if diff == 0. {
+         return t;
+      }
+
+      if (diff < 0.) == increasing {
+         lo = t;
+      } else {
+         hi = t;
+      }
+
+      if hi - lo < 1e-15 {
+         break;
+      }
+
+      let slope = derivative(coeffs, t);
+
+      let next = if slope != 0. { t - diff / slope } else { lo };
+
+      t = if next > lo && next < hi {
+         next
+      } else {
+         0.5 * (lo + hi)
+      };
+   }
+
+   t
+}
+
+
+#[cfg(test)]
+mod tests {
+   use geom::point::Point;
+
+   use super::*;
+
+   #[test]
+   fn test_quadratic_solve() {
+      let poly = CurvePoly::quadratic(
+         &Point::new(0, 0), &Point::new(1000, 0), &Point::new(2000, 4000)
+      );
+
+      assert_eq!(poly.x_at_y(1000), 1000);
+      assert_eq!(poly.x_at_y(2250), 1500);
+      assert_eq!(poly.y_at_x(500), 250);
+      assert_eq!(poly.t_at(&Point::new(2000, 4000)), 1.);
+   }
+
+   #[test]
+   fn test_segment_area() {
+      let quadratic = CurvePoly::quadratic(
+         &Point::new(0, 0), &Point::new(3, 0), &Point::new(6, 12)
+      );
+
+      let cubic = CurvePoly::cubic(
+         &Point::new(0, 0), &Point::new(2, 0), &Point::new(4, 4), &Point::new(6, 12)
+      );
+
+      assert!((quadratic.double_segment_area(0., 1.) + 24.).abs() < 1e-9);
+      assert!((cubic.double_segment_area(0., 1.) + 24.).abs() < 1e-9);
+      assert!((cubic.double_segment_area(1., 0.) - 24.).abs() < 1e-9);
+   }
+}
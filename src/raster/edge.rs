@@ -24,6 +24,21 @@ pub enum EdgeType {
    ATL, // anti-clockwise arc top-left
    ABR, // anti-clockwise arc bottom-right
    ABL, // anti-clockwise arc bottom-left
+
+   QTR, // quadratic curve top-right
+   QTL, // quadratic curve top-left
+   QBR, // quadratic curve bottom-right
+   QBL, // quadratic curve bottom-left
+
+   BTR, // cubic curve top-right
+   BTL, // cubic curve top-left
+   BBR, // cubic curve bottom-right
+   BBL, // cubic curve bottom-left
+
+   ETR, // elliptical arc top-right
+   ETL, // elliptical arc top-left
+   EBR, // elliptical arc bottom-right
+   EBL, // elliptical arc bottom-left
 }
 
 impl EdgeType {
@@ -31,7 +46,34 @@ impl EdgeType {
    pub fn reversed(&self) -> bool {
       match *self {
          EdgeType::LBR | EdgeType::LBL | EdgeType::LHL | EdgeType::LVB |
-         EdgeType::CBR | EdgeType::CBL | EdgeType::ABR | EdgeType::ABL => {
+         EdgeType::CBR | EdgeType::CBL | EdgeType::ABR | EdgeType::ABL |
+         EdgeType::QBR | EdgeType::QBL | EdgeType::BBR | EdgeType::BBL |
+         EdgeType::EBR | EdgeType::EBL => {
+            true
+         },
+         _ => {
+            false
+         }
+      }
+   }
+
+   #[inline]
+   pub fn curved(&self) -> bool {
+      match *self {
+         EdgeType::QTR | EdgeType::QTL | EdgeType::QBR | EdgeType::QBL |
+         EdgeType::BTR | EdgeType::BTL | EdgeType::BBR | EdgeType::BBL => {
+            true
+         },
+         _ => {
+            false
+         }
+      }
+   }
+
+   #[inline]
+   pub fn elliptical(&self) -> bool {
+      match *self {
+         EdgeType::ETR | EdgeType::ETL | EdgeType::EBR | EdgeType::EBL => {
             true
          },
          _ => {
@@ -52,6 +94,8 @@ pub struct EdgeSrc {
    pub edge_type: EdgeType,
    pub segment: usize,
    pub circle: usize,
+   pub curve: usize,
+   pub ellipse: usize,
 }
 
 impl EdgeSrc {
@@ -61,6 +105,34 @@ impl EdgeSrc {
          edge_type: edge_type,
          segment: segment,
          circle: circle,
+         curve: usize::MAX,
+         ellipse: usize::MAX,
+      }
+   }
+
+   #[inline]
+   pub fn new_curve(edge_type: EdgeType, segment: usize, curve: usize) -> Self {
+      debug_assert!(edge_type.curved());
+
+      EdgeSrc {
+         edge_type: edge_type,
+         segment: segment,
+         circle: usize::MAX,
+         curve: curve,
+         ellipse: usize::MAX,
+      }
+   }
+
+   #[inline]
+   pub fn new_ellipse(edge_type: EdgeType, segment: usize, ellipse: usize) -> Self {
+      debug_assert!(edge_type.elliptical());
+
+      EdgeSrc {
+         edge_type: edge_type,
+         segment: segment,
+         circle: usize::MAX,
+         curve: usize::MAX,
+         ellipse: ellipse,
       }
    }
 
@@ -81,6 +153,8 @@ pub struct Edge {
    pub edge_type: EdgeType,
    pub segment: usize,
    pub circle: usize,
+   pub curve: usize,
+   pub ellipse: usize,
    pub p1: Point,
    pub p2: Point,
 }
@@ -92,6 +166,8 @@ impl Edge {
          edge_type: edge_type,
          segment: segment,
          circle: circle,
+         curve: usize::MAX,
+         ellipse: usize::MAX,
          p1: p1,
          p2: p2,
       }
@@ -0,0 +1,287 @@
+use std::f64::consts::PI;
+
+use geom::point::Point;
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ellipse {
+   pub center: usize,
+   pub rx: i64,
+   pub ry: i64,
+   pub rotation: f64,
+}
+
+impl Ellipse {
+   #[inline]
+   pub fn new(center: usize, rx: i64, ry: i64, rotation: f64) -> Self {
+      Ellipse {
+         center: center,
+         rx: rx,
+         ry: ry,
+         rotation: rotation,
+      }
+   }
+
+   #[inline]
+   pub fn axis_aligned(center: usize, rx: i64, ry: i64) -> Self {
+      Ellipse::new(center, rx, ry, 0.)
+   }
+
+   // Parametric angles of the horizontal and vertical tangents, where
+   // arcs have to be split to get x and y monotonic edges
+   pub fn split_angles(&self) -> [f64; 4] {
+      let (sin, cos) = self.rotation.sin_cos();
+
+      let rx = self.rx as f64;
+      let ry = self.ry as f64;
+
+      let x_extremum = normalize((-ry * sin).atan2(rx * cos));
+      let y_extremum = normalize((ry * cos).atan2(rx * sin));
+
+      let mut angles = [
+         x_extremum, normalize(x_extremum + PI),
+         y_extremum, normalize(y_extremum + PI),
+      ];
+
+      angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+      angles
+   }
+}
+
+impl Default for Ellipse {
+   fn default() -> Ellipse {
+      Ellipse::new(0, 0, 0, 0.)
+   }
+}
+
+
+// Monotonic elliptical arc between two points, parametrized by the angle
+// before stretching and rotation
+#[derive(Debug, Clone, Copy)]
+pub struct EllipseArc {
+   pub center: Point,
+   pub rx: f64,
+   pub ry: f64,
+   pub sin: f64,
+   pub cos: f64,
+   pub start: f64,
+   pub end: f64,
+}
+
+impl EllipseArc {
+   pub fn new(ellipse: &Ellipse, center: &Point, p1: &Point, p2: &Point) -> Self {
+      let (sin, cos) = ellipse.rotation.sin_cos();
+
+      let mut arc = EllipseArc {
+         center: *center,
+         rx: ellipse.rx as f64,
+         ry: ellipse.ry as f64,
+         sin: sin,
+         cos: cos,
+         start: 0.,
+         end: 0.,
+      };
+
+      arc.start = arc.raw_angle(p1);
+      arc.end = nearest(arc.raw_angle(p2), arc.start);
+
+      arc
+   }
+
+   #[inline]
+   pub fn x_at(&self, angle: f64) -> f64 {
+      let (sin, cos) = angle.sin_cos();
+      self.rx * cos * self.cos - self.ry * sin * self.sin
+   }
+
+   #[inline]
+   pub fn y_at(&self, angle: f64) -> f64 {
+      let (sin, cos) = angle.sin_cos();
+      self.rx * cos * self.sin + self.ry * sin * self.cos
+   }
+
+   #[inline]
+   pub fn angle_at(&self, point: &Point) -> f64 {
+      nearest(self.raw_angle(point), 0.5 * (self.start + self.end))
+   }
+
+   pub fn angle_at_x(&self, x: i64) -> f64 {
+      self.solve(self.rx * self.cos, -self.ry * self.sin, (x - self.center.x) as f64)
+   }
+
+   pub fn angle_at_y(&self, y: i64) -> f64 {
+      self.solve(self.rx * self.sin, self.ry * self.cos, (y - self.center.y) as f64)
+   }
+
+   #[inline]
+   pub fn x_at_y(&self, y: i64) -> i64 {
+      self.center.x + self.x_at(self.angle_at_y(y)).round() as i64
+   }
+
+   #[inline]
+   pub fn y_at_x(&self, x: i64) -> i64 {
+      self.center.y + self.y_at(self.angle_at_x(x)).round() as i64
+   }
+
+   // Doubled area between the arc and its chord in the a1..a2 angle range,
+   // signed the same way as the trapezoid terms in Rasterizer::double_area
+   pub fn double_segment_area(&self, a1: f64, a2: f64) -> f64 {
+      let arc = 2. * (self.antiderivative(a2) - self.antiderivative(a1));
+
+      let chord = (self.x_at(a2) - self.x_at(a1)) * (self.y_at(a1) + self.y_at(a2));
+
+      arc - chord
+   }
+
+   // Integral of y dx, with x = C cos + D sin and y = A cos + B sin
+   fn antiderivative(&self, angle: f64) -> f64 {
+      let a = self.rx * self.sin;
+      let b = self.ry * self.cos;
+      let c = self.rx * self.cos;
+      let d = -self.ry * self.sin;
+
+      let (sin, cos) = angle.sin_cos();
+      let sin_2 = 2. * sin * cos;
+
+      (b * d - a * c) * sin * sin / 2. +
+      a * d * (angle / 2. + sin_2 / 4.) -
+      b * c * (angle / 2. - sin_2 / 4.)
+   }
+
+   fn raw_angle(&self, point: &Point) -> f64 {
+      let dx = (point.x - self.center.x) as f64;
+      let dy = (point.y - self.center.y) as f64;
+
+      let u = dx * self.cos + dy * self.sin;
+      let v = dy * self.cos - dx * self.sin;
+
+      (v / self.ry).atan2(u / self.rx)
+   }
+
+   // Solves a cos(angle) + b sin(angle) = value inside the arc range
+   fn solve(&self, a: f64, b: f64, value: f64) -> f64 {
+      let amplitude = a.hypot(b);
+      let phase = b.atan2(a);
+
+      let offset = (value / amplitude).max(-1.).min(1.).acos();
+
+      let mid = 0.5 * (self.start + self.end);
+
+      let first = nearest(phase + offset, mid);
+      let second = nearest(phase - offset, mid);
+
+      if self.distance(first) <= self.distance(second) {
+         first
+      } else {
+         second
+      }
+   }
+
+   #[inline]
+   fn distance(&self, angle: f64) -> f64 {
+      let (low, high) = if self.start < self.end {
+         (self.start, self.end)
+      } else {
+         (self.end, self.start)
+      };
+
+      if angle < low {
+         low - angle
+      } else if angle > high {
+         angle - high
+      } else {
+         0.
+      }
+   }
+}
+
+#[inline]
+fn normalize(angle: f64) -> f64 {
+   let angle = angle % (2. * PI);
+
+   if angle < 0. {
+      angle + 2. * PI
+   } else {
+      angle
+   }
+}
+
+#[inline]
+fn nearest(angle: f64, target: f64) -> f64 {
+   angle - (2. * PI) * ((angle - target) / (2. * PI)).round()
+}
+
+
+#[cfg(test)]
+mod tests {
+   use std::f64::consts::PI;
+
+   use geom::point::Point;
+
+   use super::*;
+
+   #[test]
+   fn test_rotated_intersections() {
+      let center = Point::new(12000, 0);
+
+      let aligned = EllipseArc::new(
+         &Ellipse::axis_aligned(0, 12000, 8000), &center, &Point::new(0, 0), &Point::new(12000, 8000)
+      );
+
+      let rotated = EllipseArc::new(
+         &Ellipse::new(0, 8000, 12000, 0.5 * PI), &center, &Point::new(0, 0), &Point::new(12000, 8000)
+      );
+
+      for arc in &[aligned, rotated] {
+         assert_eq!(arc.x_at_y(4000), 1608);
+         assert_eq!(arc.y_at_x(6000), 6928);
+         assert_eq!(arc.x_at_y(8000), 12000);
+      }
+   }
+
+   #[test]
+   fn test_segment_area() {
+      let arc = EllipseArc::new(
+         &Ellipse::new(0, 3, 2, 0.7), &Point::new(0, 0), &Point::new(3, 0), &Point::new(0, 2)
+      );
+
+      let quarter = arc.double_segment_area(0., 0.5 * PI);
+
+      assert!((quarter.abs() - 2. * (1.5 * PI - 3.)).abs() < 1e-9);
+      assert!((arc.double_segment_area(0.5 * PI, 0.) + quarter).abs() < 1e-9);
+   }
+
+   #[test]
+   fn test_split_angles() {
+      let angles = Ellipse::axis_aligned(0, 5, 3).split_angles();
+
+      for (angle, expected) in angles.iter().zip(&[0., 0.5 * PI, PI, 1.5 * PI]) {
+         assert!((angle - expected).abs() < 1e-9);
+      }
+   }
+
+   #[test]
+   fn test_arbitrary_rotation() {
+      let ellipse = Ellipse::new(0, 10000, 4000, 0.4);
+      let angles = ellipse.split_angles();
+
+      let center = Point::new(0, 0);
+      let probe = EllipseArc::new(&ellipse, &center, &center, &center);
+
+      let point_at = |angle: f64| {
+         Point::new(probe.x_at(angle).round() as i64, probe.y_at(angle).round() as i64)
+      };
+
+      let arc = EllipseArc::new(&ellipse, &center, &point_at(angles[0]), &point_at(angles[1]));
+
+      let mid = 0.5 * (angles[0] + angles[1]);
+
+      let x = probe.x_at(mid).round() as i64;
+      let y = probe.y_at(mid).round() as i64;
+
+      assert!((arc.x_at_y(y) - x).abs() <= 1);
+      assert!((arc.y_at_x(x) - y).abs() <= 1);
+      assert!((arc.angle_at(&Point::new(x, y)) - mid).abs() < 1e-3);
+   }
+}
@@ -0,0 +1,104 @@
+use std::usize;
+
+use draw::BlendMode;
+
+use super::scene::Scene;
+
+
+// Polys from `start` to `end` composited with a single opacity and blend
+// mode. Raster polys never overlap, so the backdrop of a group is the
+// rasterizer background and nested groups multiply their opacities
+#[derive(Debug, Clone)]
+pub struct Group {
+   pub opacity: f64,
+   pub blend: BlendMode,
+   pub start: usize,
+   pub end: usize,
+}
+
+impl Group {
+   #[inline]
+   pub fn new(opacity: f64, blend: BlendMode, start: usize, end: usize) -> Self {
+      Group {
+         opacity: opacity,
+         blend: blend,
+         start: start,
+         end: end,
+      }
+   }
+}
+
+
+impl Scene {
+   // Polys pushed until the matching end_group belong to the group
+   #[inline]
+   pub fn begin_group(&mut self, opacity: f64, blend: BlendMode) {
+      let start = self.polys.len();
+
+      self.groups.push(Group::new(opacity, blend, start, usize::MAX));
+   }
+
+   #[inline]
+   pub fn end_group(&mut self) {
+      let end = self.polys.len();
+
+      if let Some(group) = self.groups.iter_mut().rev().find(|group| group.end == usize::MAX) {
+         group.end = end;
+      }
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use draw::{RGB, BlendMode};
+
+   use raster::scene::Scene;
+   use raster::rasterizer::Rasterizer;
+   use raster::rasterizer::tests::rect;
+   use testing::pixel_frame;
+
+   #[test]
+   fn test_render_twice() {
+      let mut scene = Scene::new();
+
+      scene.push_path(&rect(0., 0., 1000., 1000.), RGB::new(0, 0, 255));
+
+      scene.begin_group(0.5, BlendMode::Multiply);
+      scene.push_path(&rect(1000., 0., 2000., 1000.), RGB::new(100, 255, 0));
+
+      scene.begin_group(0.5, BlendMode::Screen);
+      scene.push_path(&rect(2000., 0., 3000., 1000.), RGB::new(0, 255, 0));
+      scene.end_group();
+
+      scene.end_group();
+
+      let background = RGB::new(200, 100, 0);
+
+      let mut rasterizer = Rasterizer::new();
+
+      rasterizer.background = background;
+
+      let mut frame = pixel_frame(30, 10);
+
+      let expected = [
+         RGB::new(0, 0, 255),
+         BlendMode::Multiply.mix(&background, &RGB::new(100, 255, 0), 0.5),
+         BlendMode::Multiply.mix(&background, &RGB::new(0, 255, 0), 0.25),
+      ];
+
+      // The group pixels of the first frame don't become the backdrop of
+      // the second one
+      for _ in 0..2 {
+         rasterizer.render(&scene, &mut frame, 100);
+
+         for x in 0..30 {
+            for y in 0..10 {
+               assert_eq!(frame.get_pixel(x, y), expected[x as usize / 10]);
+            }
+         }
+      }
+
+      assert_eq!(expected[1], RGB::new(139, 100, 0));
+   }
+}
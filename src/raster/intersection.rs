@@ -4,7 +4,7 @@ use std::{i64, usize};
 use geom::point::Point;
 use num::NumberOps;
 
-use super::rasterizer::Rasterizer;
+use super::rasterizer::{Rasterizer, grow};
 use super::edge::{Edge, EdgeType};
 use super::scene::Scene;
 
@@ -39,6 +39,8 @@ impl Default for IntersectionRef {
 
 
 pub trait RasterizerIntersection {
+   fn reserve_intersections(&mut self, scene: &Scene);
+
    fn reset_intersections(&mut self, scene: &Scene);
 
    fn intersect_edges(&mut self, scene: &Scene);
@@ -52,6 +54,28 @@ pub trait RasterizerIntersection {
 
 
 impl RasterizerIntersection for Rasterizer {
+   // Every segment crossing a pixel row or column boundary gets one
+   // intersection per crossing, counted the way the intersect functions do
+   fn reserve_intersections(&mut self, scene: &Scene) {
+      let mut vert_len = 0;
+      let mut hori_len = 0;
+
+      for segment in &scene.segments {
+         let ref p1 = scene.points[segment.p1];
+         let ref p2 = scene.points[segment.p2];
+
+         vert_len += crossings(min(p1.x, p2.x), max(p1.x, p2.x), self.div_per_column);
+         hori_len += crossings(min(p1.y, p2.y), max(p1.y, p2.y), self.div_per_pixel);
+      }
+
+      let segments_len = scene.segments.len();
+
+      grow(&mut self.vert_intersections_ref, segments_len);
+      grow(&mut self.hori_intersections_ref, segments_len);
+      grow(&mut self.vert_intersections, vert_len);
+      grow(&mut self.hori_intersections, hori_len);
+   }
+
    fn reset_intersections(&mut self, scene: &Scene) {
       for i in 0..scene.segments.len() {
          self.vert_intersections_ref[i].start = usize::MAX;
@@ -60,6 +84,8 @@ impl RasterizerIntersection for Rasterizer {
    }
 
    fn intersect_edges(&mut self, scene: &Scene) {
+      self.reserve_intersections(scene);
+
       self.reset_intersections(scene);
 
       let mut vert_prev_end = 0;
@@ -86,7 +112,7 @@ impl RasterizerIntersection for Rasterizer {
                hori_ref.start = hori_prev_end;
 
                let (vert_end, x_first_px) = v_multi_intersect_fast(
-                  p1, p2, self.div_per_pixel, vert_ref.start, &mut self.vert_intersections
+                  p1, p2, self.div_per_column, vert_ref.start, &mut self.vert_intersections
                );
 
                let (hori_end, y_first_px) = h_multi_intersect_fast(
@@ -158,11 +184,11 @@ impl RasterizerIntersection for Rasterizer {
 
                debug_assert!(x1 <= x2);
 
-               let start = 1 + x1 / self.div_per_pixel;
-               let end = 1 + (x2 - 1) / self.div_per_pixel;
+               let start = 1 + x1 / self.div_per_column;
+               let end = 1 + (x2 - 1) / self.div_per_column;
 
                for x_px in start..end {
-                  let x = x_px * self.div_per_pixel;
+                  let x = x_px * self.div_per_column;
                   let dx = center.x - x;
 
                   debug_assert!(radius > dx.abs());
@@ -183,6 +209,83 @@ impl RasterizerIntersection for Rasterizer {
                vert_ref.end = vert_prev_end;
                vert_ref.first_px = start;
             },
+            EdgeType::QTR | EdgeType::QTL | EdgeType::QBR | EdgeType::QBL |
+            EdgeType::BTR | EdgeType::BTL | EdgeType::BBR | EdgeType::BBL => {
+
+               let segment_index = edge.segment;
+
+               let ref mut vert_ref = self.vert_intersections_ref[segment_index];
+               if vert_ref.start != usize::MAX {
+                  continue;
+               }
+
+               let ref mut hori_ref = self.hori_intersections_ref[segment_index];
+
+               let ref segment = scene.segments[segment_index];
+               let ref p1 = scene.points[segment.p1];
+               let ref p2 = scene.points[segment.p2];
+
+               hori_ref.start = hori_prev_end;
+               vert_ref.start = vert_prev_end;
+
+               let curve = scene.curve_poly(edge.edge_type, segment_index, edge.curve);
+
+               let (hori_end, y_first_px) = h_multi_intersect_monotonic(
+                  p1, p2, self.div_per_pixel, hori_ref.start, &mut self.hori_intersections,
+                  |y| curve.x_at_y(y)
+               );
+
+               let (vert_end, x_first_px) = v_multi_intersect_monotonic(
+                  p1, p2, self.div_per_column, vert_ref.start, &mut self.vert_intersections,
+                  |x| curve.y_at_x(x)
+               );
+
+               hori_prev_end = hori_end;
+               hori_ref.end = hori_end;
+               hori_ref.first_px = y_first_px;
+
+               vert_prev_end = vert_end;
+               vert_ref.end = vert_end;
+               vert_ref.first_px = x_first_px;
+            },
+            EdgeType::ETR | EdgeType::ETL | EdgeType::EBR | EdgeType::EBL => {
+
+               let segment_index = edge.segment;
+
+               let ref mut vert_ref = self.vert_intersections_ref[segment_index];
+               if vert_ref.start != usize::MAX {
+                  continue;
+               }
+
+               let ref mut hori_ref = self.hori_intersections_ref[segment_index];
+
+               let ref segment = scene.segments[segment_index];
+               let ref p1 = scene.points[segment.p1];
+               let ref p2 = scene.points[segment.p2];
+
+               hori_ref.start = hori_prev_end;
+               vert_ref.start = vert_prev_end;
+
+               let arc = scene.ellipse_arc(segment_index, edge.ellipse);
+
+               let (hori_end, y_first_px) = h_multi_intersect_monotonic(
+                  p1, p2, self.div_per_pixel, hori_ref.start, &mut self.hori_intersections,
+                  |y| arc.x_at_y(y)
+               );
+
+               let (vert_end, x_first_px) = v_multi_intersect_monotonic(
+                  p1, p2, self.div_per_column, vert_ref.start, &mut self.vert_intersections,
+                  |x| arc.y_at_x(x)
+               );
+
+               hori_prev_end = hori_end;
+               hori_ref.end = hori_end;
+               hori_ref.first_px = y_first_px;
+
+               vert_prev_end = vert_end;
+               vert_ref.end = vert_end;
+               vert_ref.first_px = x_first_px;
+            },
             _ => {}
          }
       }
@@ -205,24 +308,50 @@ impl RasterizerIntersection for Rasterizer {
 
                let ref vert_ref = self.vert_intersections_ref[edge.segment];
 
-               let mut prev_y = i64::MIN;
+               // Falling edges cross the lines in decreasing order
+               let rising = (p2.y - p1.y) * (p2.x - p1.x) >= 0;
+
+               let mut prev_y = None;
                for i in vert_ref.start..vert_ref.end {
                   let y = self.vert_intersections[i];
                   debug_assert!(min_y <= y);
                   debug_assert!(max_y >= y);
-                  debug_assert!(prev_y < y);
-                  prev_y = y;
+                  debug_assert!(prev_y.map_or(true, |prev| if rising { prev <= y } else { prev >= y }));
+                  prev_y = Some(y);
                }
 
                let ref hori_ref = self.hori_intersections_ref[edge.segment];
 
-               let mut prev_x = i64::MIN;
+               let mut prev_x = None;
                for i in hori_ref.start..hori_ref.end {
                   let x = self.hori_intersections[i];
                   debug_assert!(min_x <= x);
                   debug_assert!(max_x >= x);
-                  debug_assert!(prev_x < x);
-                  prev_x = x;
+                  debug_assert!(prev_x.map_or(true, |prev| if rising { prev <= x } else { prev >= x }));
+                  prev_x = Some(x);
+               }
+            },
+            EdgeType::QTR | EdgeType::QTL | EdgeType::QBR | EdgeType::QBL |
+            EdgeType::BTR | EdgeType::BTL | EdgeType::BBR | EdgeType::BBL |
+            EdgeType::ETR | EdgeType::ETL | EdgeType::EBR | EdgeType::EBL => {
+               let ref segment = scene.segments[edge.segment];
+               let ref p1 = scene.points[segment.p1];
+               let ref p2 = scene.points[segment.p2];
+
+               let ref vert_ref = self.vert_intersections_ref[edge.segment];
+
+               for i in vert_ref.start..vert_ref.end {
+                  let y = self.vert_intersections[i];
+                  debug_assert!(p1.y <= y);
+                  debug_assert!(p2.y >= y);
+               }
+
+               let ref hori_ref = self.hori_intersections_ref[edge.segment];
+
+               for i in hori_ref.start..hori_ref.end {
+                  let x = self.hori_intersections[i];
+                  debug_assert!(min(p1.x, p2.x) <= x);
+                  debug_assert!(max(p1.x, p2.x) >= x);
                }
             },
             _ => {}
@@ -264,6 +393,12 @@ impl RasterizerIntersection for Rasterizer {
 }
 
 
+#[inline]
+fn crossings(start: i64, end: i64, step: i64) -> usize {
+   max(0, (1 + (end - 1) / step) - (1 + start / step)) as usize
+}
+
+
 fn h_multi_intersect_fast(p1: &Point, p2: &Point, step_y: i64, mut vec_start: usize, inters: &mut Vec<i64>) -> (usize, i64) {
    let (p1, p2) = if p1.y > p2.y {
       (p2, p1)
@@ -379,3 +514,40 @@ fn v_multi_intersect_fast(p1: &Point, p2: &Point, step_x: i64, mut vec_start: us
    (vec_start, start)
 }
 
+
+fn h_multi_intersect_monotonic<F>(p1: &Point, p2: &Point, step_y: i64, mut vec_start: usize, inters: &mut Vec<i64>, x_at_y: F) -> (usize, i64) where F: Fn(i64) -> i64 {
+   debug_assert!(p1.y <= p2.y);
+
+   let min_x = min(p1.x, p2.x);
+   let max_x = max(p1.x, p2.x);
+
+   let start = 1 + p1.y / step_y;
+   let end = 1 + (p2.y - 1) / step_y;
+
+   for y_px in start..end {
+      let x = x_at_y(y_px * step_y);
+
+      inters[vec_start] = min(max(x, min_x), max_x);
+      vec_start += 1;
+   }
+
+   (vec_start, start)
+}
+
+
+fn v_multi_intersect_monotonic<F>(p1: &Point, p2: &Point, step_x: i64, mut vec_start: usize, inters: &mut Vec<i64>, y_at_x: F) -> (usize, i64) where F: Fn(i64) -> i64 {
+   let min_x = min(p1.x, p2.x);
+   let max_x = max(p1.x, p2.x);
+
+   let start = 1 + min_x / step_x;
+   let end = 1 + (max_x - 1) / step_x;
+
+   for x_px in start..end {
+      let y = y_at_x(x_px * step_x);
+
+      inters[vec_start] = min(max(y, p1.y), p2.y);
+      vec_start += 1;
+   }
+
+   (vec_start, start)
+}
@@ -1,4 +1,6 @@
 pub mod circle;
+pub mod curve;
+pub mod ellipse;
 pub mod segment;
 pub mod edge;
 pub mod intersection;
@@ -6,10 +8,17 @@ pub mod poly;
 pub mod rasterizer;
 pub mod scene;
 pub mod pool;
+pub mod path;
+pub mod clip;
+pub mod group;
 
 pub use self::scene::Scene;
 pub use self::circle::Circle;
+pub use self::curve::Curve;
+pub use self::ellipse::Ellipse;
 pub use self::segment::Segment;
 pub use self::edge::{EdgeType, EdgeSrc};
 pub use self::poly::Poly;
-pub use self::rasterizer::{Rasterizer, create_default_vec};
+pub use self::clip::Clip;
+pub use self::group::Group;
+pub use self::rasterizer::{Rasterizer, MemoryUsage, create_default_vec};
@@ -0,0 +1,262 @@
+use std::cmp::Ordering;
+
+use data::FloatPoint;
+use geom::point::Point;
+use geom::path::{PathSegment, Arc, quad_extrema, quad_piece, cubic_extrema, cubic_piece};
+
+use super::edge::EdgeType;
+
+
+// Part of a path segment which is monotonic in both x and y
+#[derive(Debug, Clone, Copy)]
+pub enum Piece {
+   Line(FloatPoint, FloatPoint),
+   Quad(FloatPoint, FloatPoint, FloatPoint),
+   Cubic(FloatPoint, FloatPoint, FloatPoint, FloatPoint),
+   Arc(Arc),
+}
+
+impl Piece {
+   pub fn end(&self) -> FloatPoint {
+      match *self {
+         Piece::Line(_, p) | Piece::Quad(_, _, p) | Piece::Cubic(_, _, _, p) => p,
+         Piece::Arc(ref arc) => arc.end_point(),
+      }
+   }
+}
+
+
+// Splits the segments at horizontal and vertical tangents and closes the
+// subpath with a line
+pub fn monotonic_pieces(subpath: &[PathSegment]) -> (FloatPoint, Vec<Piece>) {
+   let mut pieces = Vec::new();
+
+   let mut first = FloatPoint::default();
+   let mut last = FloatPoint::default();
+
+   for segment in subpath.iter() {
+      match *segment {
+         PathSegment::MoveTo(p) => {
+            first = p;
+         },
+         PathSegment::LineTo(p) => {
+            pieces.push(Piece::Line(last, p));
+         },
+         PathSegment::QuadTo(c, p) => {
+            let mut t1 = 0.;
+
+            for t2 in quad_extrema(last, c, p).into_iter().chain(Some(1.)) {
+               let (q0, q1, q2) = quad_piece(last, c, p, t1, t2);
+
+               pieces.push(Piece::Quad(q0, q1, q2));
+
+               t1 = t2;
+            }
+         },
+         PathSegment::CubicTo(c1, c2, p) => {
+            let mut t1 = 0.;
+
+            for t2 in cubic_extrema(last, c1, c2, p).into_iter().chain(Some(1.)) {
+               let (q0, q1, q2, q3) = cubic_piece(last, c1, c2, p, t1, t2);
+
+               pieces.push(Piece::Cubic(q0, q1, q2, q3));
+
+               t1 = t2;
+            }
+         },
+         PathSegment::ArcTo(ref arc) => {
+            let mut a1 = arc.start;
+
+            for a2 in arc.extrema().into_iter().chain(Some(arc.start + arc.sweep)) {
+               pieces.push(Piece::Arc(Arc::new(arc.center, arc.x_axis, arc.y_axis, a1, a2 - a1)));
+
+               a1 = a2;
+            }
+         },
+         PathSegment::Close => {},
+      }
+
+      last = segment.end().unwrap_or(first);
+   }
+
+   if pieces.len() > 0 {
+      pieces.push(Piece::Line(last, first));
+   }
+
+   (first, pieces)
+}
+
+
+// Doubled area of the polygon through the piece end points, positive for the
+// orientation Rasterizer::double_area expects
+pub fn double_area(pieces: &[Piece]) -> f64 {
+   let mut area = 0.;
+
+   let mut last = match pieces.last() {
+      Some(piece) => piece.end(),
+      None => return 0.,
+   };
+
+   for piece in pieces.iter() {
+      let end = piece.end();
+
+      area += (end.x - last.x) * (end.y + last.y);
+
+      last = end;
+   }
+
+   area
+}
+
+
+#[inline]
+pub fn to_point(point: FloatPoint) -> Point {
+   Point::new(point.x.round() as i64, point.y.round() as i64)
+}
+
+
+#[inline]
+pub fn directed(a: &Point, b: &Point, types: [EdgeType; 4]) -> EdgeType {
+   match (b.y > a.y, b.x > a.x) {
+      (true, true) => types[0],
+      (true, false) => types[1],
+      (false, true) => types[2],
+      (false, false) => types[3],
+   }
+}
+
+
+// Whether the closed outline through the pieces turns back at most once in
+// each of x and y, which makes it a poly the rasterizer can slice as is
+pub fn is_monotonic(pieces: &[Piece]) -> bool {
+   let mut last = match pieces.last() {
+      Some(piece) => piece.end(),
+      None => return true,
+   };
+
+   let mut directions = Vec::with_capacity(pieces.len());
+
+   for piece in pieces.iter() {
+      let end = piece.end();
+
+      directions.push((end.x - last.x, end.y - last.y));
+
+      last = end;
+   }
+
+   turns(directions.iter().map(|d| d.0)) <= 2 && turns(directions.iter().map(|d| d.1)) <= 2
+}
+
+// Sign changes of the non zero deltas, counted around the closed outline
+fn turns<I: Iterator<Item = f64>>(deltas: I) -> usize {
+   let signs: Vec<bool> = deltas.filter(|d| *d != 0.).map(|d| d > 0.).collect();
+
+   match signs.last() {
+      Some(&last) => {
+         let mut previous = last;
+
+         signs.iter().filter(|&&sign| {
+            let turn = sign != previous;
+
+            previous = sign;
+
+            turn
+         }).count()
+      },
+      None => 0,
+   }
+}
+
+
+// Splits contours which neither cross nor overlap, like the boolean
+// results, into trapezoids with horizontal top and bottom sides. The area
+// between the edges is filled with the even-odd rule. Trapezoids of the
+// same pair of edges in consecutive bands are joined
+pub fn trapezoids(contours: &[Vec<FloatPoint>]) -> Vec<[FloatPoint; 4]> {
+   let mut edges = Vec::new();
+   let mut ys = Vec::new();
+
+   for contour in contours.iter() {
+      let mut last = match contour.last() {
+         Some(&point) => point,
+         None => continue,
+      };
+
+      for &point in contour.iter() {
+         if point.y < last.y {
+            edges.push((point, last));
+         } else if point.y > last.y {
+            edges.push((last, point));
+         }
+
+         ys.push(point.y);
+
+         last = point;
+      }
+   }
+
+   ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+   ys.dedup();
+
+   let mut result: Vec<[FloatPoint; 4]> = Vec::new();
+
+   // Edge pairs of the previous band with their trapezoid
+   let mut open: Vec<(usize, usize, usize)> = Vec::new();
+
+   for band in ys.windows(2) {
+      let (y1, y2) = (band[0], band[1]);
+
+      let mut crossing: Vec<(usize, f64, f64)> = edges.iter().enumerate()
+         .filter(|&(_, edge)| edge.0.y <= y1 && edge.1.y >= y2)
+         .map(|(i, edge)| (i, x_at(edge, y1), x_at(edge, y2)))
+         .collect();
+
+      crossing.sort_by(|a, b| (a.1 + a.2).partial_cmp(&(b.1 + b.2)).unwrap_or(Ordering::Equal));
+
+      let mut next = Vec::with_capacity(crossing.len() / 2);
+
+      for pair in crossing.chunks(2) {
+         if pair.len() < 2 {
+            break;
+         }
+
+         let (left, right) = (pair[0], pair[1]);
+
+         let bottom_left = FloatPoint::new(left.2, y2);
+         let bottom_right = FloatPoint::new(right.2, y2);
+
+         let index = match open.iter().find(|o| o.0 == left.0 && o.1 == right.0) {
+            Some(&(_, _, index)) => {
+               result[index][2] = bottom_right;
+               result[index][3] = bottom_left;
+               index
+            },
+            None => {
+               result.push([
+                  FloatPoint::new(left.1, y1), FloatPoint::new(right.1, y1), bottom_right, bottom_left
+               ]);
+               result.len() - 1
+            },
+         };
+
+         next.push((left.0, right.0, index));
+      }
+
+      open = next;
+   }
+
+   result
+}
+
+#[inline]
+fn x_at(edge: &(FloatPoint, FloatPoint), y: f64) -> f64 {
+   let (top, bottom) = *edge;
+
+   if y <= top.y {
+      top.x
+   } else if y >= bottom.y {
+      bottom.x
+   } else {
+      top.x + (bottom.x - top.x) * (y - top.y) / (bottom.y - top.y)
+   }
+}
@@ -4,6 +4,7 @@ pub struct Poly {
    pub start: usize,
    pub end: usize,
    pub color: usize,
+   pub paint: Option<usize>,
 }
 
 impl Poly {
@@ -13,6 +14,7 @@ impl Poly {
          start: start,
          end: end,
          color: color,
+         paint: None,
       }
    }
 }
@@ -67,7 +67,7 @@ impl RasterizerPool for Rasterizer {
       for edge_index in poly_start..poly_end {
          let edge = pool[edge_index];
 
-         if edge.edge_type.reversed() != (edge.p1 > edge.p2) {
+         if edge.p1 != edge.p2 && edge.edge_type.reversed() != (edge.p1 > edge.p2) {
             panic!("Wrong edge points ordering");
          }
 
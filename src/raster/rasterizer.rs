@@ -1,9 +1,11 @@
-use std::cmp::{Ordering, min};
+use std::cmp::{Ordering, min, max};
 use std::iter::repeat;
+use std::mem;
 use std::i64;
 
 use frame::Frame;
-use draw::RGB;
+use draw::{RGB, BlendMode, Blending, Subpixel, SUBPIXEL_FILTER};
+use srgb::SrgbTables;
 use num::NumberOps;
 use geom::point::Point;
 
@@ -41,6 +43,8 @@ pub struct Rasterizer {
 
    pub lower_min_x: Vec<i64>,
    pub lower_max_x: Vec<i64>,
+   pub lower_full_min_x: Vec<i64>,
+   pub lower_full_max_x: Vec<i64>,
 
    pub lower_active: Vec<usize>,
    pub lower_active_start: usize,
@@ -53,82 +57,111 @@ pub struct Rasterizer {
    pub final_active: Vec<usize>,
    pub final_active_full: usize,
 
+   pub poly_opacity: Vec<f64>,
+   pub poly_blend: Vec<BlendMode>,
+   pub has_groups: bool,
+
    pub div_per_pixel: i64,
+   pub div_per_column: i64,
    pub double_pixel_area: i64,
-}
-
-impl Rasterizer {
-   pub fn new() -> Self {
-      let vert_intersections_ref = create_default_vec(65536);
-      let hori_intersections_ref = create_default_vec(65536);
-      let vert_intersections = create_default_vec(65536);
-      let hori_intersections = create_default_vec(65536);
-
-      let poly_to_pool = create_default_vec(65536);
-
-      let upper_edges = create_default_vec(65536);
-      let upper_edges_len = create_default_vec(65536);
 
-      let upper_min_y = create_default_vec(65536);
-      let upper_max_y = create_default_vec(65536);
-      let upper_active = create_default_vec(65536);
+   pub blending: Blending,
+   pub srgb: SrgbTables,
 
-      let lower_edges = create_default_vec(65536);
-      let lower_edges_len = create_default_vec(65536);
+   // Shown by the parts of the pixels no poly covers, pixels no poly reaches
+   // are left as they are
+   pub background: RGB,
 
-      let lower_min_x = create_default_vec(65536);
-      let lower_max_x = create_default_vec(65536);
-      let lower_active = create_default_vec(65536);
-
-      let final_edges = create_default_vec(65536);
-      let final_edges_len = create_default_vec(65536);
-
-      let final_active = create_default_vec(65536);
+   // Columns are a third of a pixel wide. When `div_per_pixel` is not a
+   // multiple of 3 the scene is rendered scaled by 3 from `subpixel_scene`
+   pub subpixel: Subpixel,
+   pub subpixel_row: Vec<RGB>,
+   pub subpixel_scene: Scene,
+}
 
+impl Rasterizer {
+   pub fn new() -> Self {
       Rasterizer {
-         vert_intersections_ref: vert_intersections_ref,
-         hori_intersections_ref: hori_intersections_ref,
-         vert_intersections: vert_intersections,
-         hori_intersections: hori_intersections,
+         vert_intersections_ref: Vec::new(),
+         hori_intersections_ref: Vec::new(),
+         vert_intersections: Vec::new(),
+         hori_intersections: Vec::new(),
 
          polys_len: 0,
-         poly_to_pool: poly_to_pool,
+         poly_to_pool: Vec::new(),
 
-         upper_edges: upper_edges,
-         upper_edges_len: upper_edges_len,
+         upper_edges: Vec::new(),
+         upper_edges_len: Vec::new(),
 
-         upper_min_y: upper_min_y,
-         upper_max_y: upper_max_y,
+         upper_min_y: Vec::new(),
+         upper_max_y: Vec::new(),
 
-         upper_active: upper_active,
+         upper_active: Vec::new(),
          upper_active_start: 0,
          upper_active_end: 0,
 
-         lower_edges: lower_edges,
-         lower_edges_len: lower_edges_len,
+         lower_edges: Vec::new(),
+         lower_edges_len: Vec::new(),
 
-         lower_min_x: lower_min_x,
-         lower_max_x: lower_max_x,
+         lower_min_x: Vec::new(),
+         lower_max_x: Vec::new(),
+         lower_full_min_x: Vec::new(),
+         lower_full_max_x: Vec::new(),
 
-         lower_active: lower_active,
+         lower_active: Vec::new(),
          lower_active_start: 0,
          lower_active_end: 0,
          lower_active_full: 0,
 
-         final_edges: final_edges,
-         final_edges_len: final_edges_len,
+         final_edges: Vec::new(),
+         final_edges_len: Vec::new(),
 
-         final_active: final_active,
+         final_active: Vec::new(),
          final_active_full: 0,
 
+         poly_opacity: Vec::new(),
+         poly_blend: Vec::new(),
+         has_groups: false,
+
          div_per_pixel: 0,
+         div_per_column: 0,
          double_pixel_area: 0,
+
+         blending: Blending::default(),
+         srgb: SrgbTables::new(),
+
+         background: RGB::default(),
+
+         subpixel: Subpixel::default(),
+         subpixel_row: Vec::new(),
+         subpixel_scene: Scene::new(),
       }
    }
 
    pub fn render(&mut self, scene: &Scene, frame: &mut Frame, div_per_pixel: i64) {
+      // Subpixel rendering slices the pixels in three columns, which needs
+      // a whole number of divisions per column
+      if self.subpixel != Subpixel::None && div_per_pixel % 3 != 0 {
+         let mut scaled = mem::replace(&mut self.subpixel_scene, Scene::new());
+
+         scaled.scale_from(scene, 3);
+
+         self.render_scene(&scaled, frame, 3 * div_per_pixel);
+
+         self.subpixel_scene = scaled;
+      } else {
+         self.render_scene(scene, frame, div_per_pixel);
+      }
+   }
+
+   fn render_scene(&mut self, scene: &Scene, frame: &mut Frame, div_per_pixel: i64) {
+      let subpixel = self.subpixel != Subpixel::None;
+
+      let columns_per_pixel = if subpixel { 3 } else { 1 };
+
       self.div_per_pixel = div_per_pixel;
-      self.double_pixel_area = div_per_pixel * div_per_pixel * 2;
+      self.div_per_column = div_per_pixel / columns_per_pixel;
+      self.double_pixel_area = self.div_per_column * div_per_pixel * 2;
 
       self.transfer_scene(scene);
 
@@ -146,8 +179,8 @@ impl Rasterizer {
 
       debug_check!(self.check_upper_min_max_y(min_y, max_y));
 
-      let x_start = self.to_px(min_x);
-      let x_end = self.to_px(max_x - 1) + 1;
+      let x_start = min_x / self.div_per_column;
+      let x_end = (max_x - 1) / self.div_per_column + 1;
       let y_start = self.to_px(min_y);
       let y_end = self.to_px(max_y - 1) + 1;
 
@@ -173,15 +206,19 @@ impl Rasterizer {
 
          debug_check!(self.check_lower_initial_bounds(y_slice));
 
-         self.update_lower_min_max_x();
+         self.update_lower_min_max_x(y_world, y_slice);
 
          debug_check!(self.check_lower_min_max_x(min_x, max_x));
 
+         if subpixel {
+            self.start_subpixel_row(frame.width as usize);
+         }
+
          let mut x = x_start;
 
          while x < x_end {
-            let x_world = self.from_px(x);
-            let x_slice = x_world + self.div_per_pixel;
+            let x_world = x * self.div_per_column;
+            let x_slice = x_world + self.div_per_column;
 
             self.final_active_full = 0;
 
@@ -191,17 +228,32 @@ impl Rasterizer {
 
             debug_check!(self.check_lower_pool());
 
-            match self.can_advance_stripe(x_end) {
+            match self.can_advance_stripe(x_world, x_end) {
                Some(x_delta) => {
                   let poly_index = self.lower_active[self.lower_active_start];
 
-                  let x_delta_world = self.from_px(x_delta);
+                  let x_delta_world = x_delta * self.div_per_column;
                   self.v_slice_poly(poly_index, x_delta_world, x_delta);
 
                   let ref color = scene.colors[scene.polys[poly_index].color];
 
-                  for fill_x in x..x_delta {
-                     frame.put_pixel(fill_x as i32, y as i32, &color);
+                  if self.has_groups {
+                     for fill_x in x..x_delta {
+                        let color = self.fill_color(scene, poly_index, fill_x, y);
+                        let color = self.poly_color(poly_index, &color);
+
+                        self.put_column(frame, fill_x, y, &color, subpixel);
+                     }
+                  } else if scene.polys[poly_index].paint.is_some() {
+                     for fill_x in x..x_delta {
+                        let color = self.fill_color(scene, poly_index, fill_x, y);
+
+                        self.put_column(frame, fill_x, y, &color, subpixel);
+                     }
+                  } else {
+                     for fill_x in x..x_delta {
+                        self.put_column(frame, fill_x, y, color, subpixel);
+                     }
                   }
 
                   x = x_delta;
@@ -216,15 +268,80 @@ impl Rasterizer {
                   debug_check!(self.check_final_bounds(x_slice));
 
                   if self.final_active_full != 0 {
-                     let color = self.active_color(scene);
+                     let color = self.active_color(scene, x, y);
 
-                     frame.put_pixel(x as i32, y as i32, &color);
+                     self.put_column(frame, x, y, &color, subpixel);
                   }
 
                   x += 1;
                }
             }
          }
+
+         if subpixel {
+            self.end_subpixel_row(frame, y, x_start / 3, (x_end + 2) / 3);
+         }
+      }
+   }
+
+   #[inline]
+   fn put_column(&mut self, frame: &mut Frame, x: i64, y: i64, color: &RGB, subpixel: bool) {
+      if !subpixel {
+         frame.put_pixel(x as i32, y as i32, color);
+      } else if x >= 0 && (x as usize) < self.subpixel_row.len() {
+         self.subpixel_row[x as usize] = *color;
+      }
+   }
+
+   // Columns no poly covers show the background like the uncovered parts of
+   // the pixels
+   fn start_subpixel_row(&mut self, width: usize) {
+      self.subpixel_row.clear();
+      self.subpixel_row.resize(3 * width, self.background);
+   }
+
+   // Each channel of the pixels from `x_start` to `x_end` takes its value
+   // from the columns around the third it is lit by
+   fn end_subpixel_row(&mut self, frame: &mut Frame, y: i64, x_start: i64, x_end: i64) {
+      let linear = self.blending == Blending::Linear;
+
+      let columns = self.subpixel_row.len() as i64;
+      let filter_sum: u32 = SUBPIXEL_FILTER.iter().sum();
+
+      let thirds = self.subpixel.thirds();
+
+      for x in max(x_start - 1, 0)..min(x_end + 1, columns / 3) {
+         let mut channels = [0; 3];
+
+         for channel in 0..3 {
+            let center = 3 * x + thirds[channel] as i64;
+
+            let mut sum = 0;
+
+            for (tap, weight) in SUBPIXEL_FILTER.iter().enumerate() {
+               let column = max(0, min(columns - 1, center + tap as i64 - 2));
+
+               let ref color = self.subpixel_row[column as usize];
+
+               let value = match channel {
+                  0 => color.r,
+                  1 => color.g,
+                  _ => color.b,
+               };
+
+               sum += weight * if linear { self.srgb.linear(value) as u32 } else { value as u32 };
+            }
+
+            let value = (sum + filter_sum / 2) / filter_sum;
+
+            channels[channel] = if linear {
+               self.srgb.srgb_dithered(value as u16, x as usize, y as usize)
+            } else {
+               value as u8
+            };
+         }
+
+         frame.put_pixel(x as i32, y as i32, &RGB::new(channels[0], channels[1], channels[2]));
       }
    }
 
@@ -241,6 +358,8 @@ impl Rasterizer {
    pub fn transfer_scene(&mut self, scene: &Scene) {
       self.polys_len = scene.polys.len();
 
+      self.reserve_polys(scene);
+
       let mut pool_index = 0;
       for i in 0..self.polys_len {
          let ref poly = &scene.polys[i];
@@ -252,6 +371,8 @@ impl Rasterizer {
             edge_ref.edge_type = edge.edge_type;
             edge_ref.segment = edge.segment;
             edge_ref.circle = edge.circle;
+            edge_ref.curve = edge.curve;
+            edge_ref.ellipse = edge.ellipse;
 
             let ref segment = scene.segments[edge.segment];
             let (ref p1, ref p2) = if edge.reversed() {
@@ -271,10 +392,118 @@ impl Rasterizer {
          pool_index += 4;
 
          self.upper_active[i] = i;
+
+         self.poly_opacity[i] = 1.;
+         self.poly_blend[i] = BlendMode::Normal;
       }
 
       self.upper_active_start = 0;
       self.upper_active_end = 0;
+
+      self.transfer_groups(scene);
+   }
+
+   // Grows the poly buffers and the edge pools to fit the scene, keeping
+   // their size for the next frames
+   fn reserve_polys(&mut self, scene: &Scene) {
+      let polys_len = self.polys_len;
+
+      let mut pool_len = 0;
+      for poly in &scene.polys {
+         pool_len += poly.end - poly.start + 4;
+      }
+
+      grow(&mut self.poly_to_pool, polys_len);
+
+      grow(&mut self.upper_edges, pool_len);
+      grow(&mut self.upper_edges_len, polys_len);
+      grow(&mut self.upper_min_y, polys_len);
+      grow(&mut self.upper_max_y, polys_len);
+      grow(&mut self.upper_active, polys_len);
+
+      grow(&mut self.lower_edges, pool_len);
+      grow(&mut self.lower_edges_len, polys_len);
+      grow(&mut self.lower_min_x, polys_len);
+      grow(&mut self.lower_max_x, polys_len);
+      grow(&mut self.lower_full_min_x, polys_len);
+      grow(&mut self.lower_full_max_x, polys_len);
+      grow(&mut self.lower_active, polys_len);
+
+      grow(&mut self.final_edges, pool_len);
+      grow(&mut self.final_edges_len, polys_len);
+      grow(&mut self.final_active, polys_len);
+
+      grow(&mut self.poly_opacity, polys_len);
+      grow(&mut self.poly_blend, polys_len);
+   }
+
+   // Bytes taken by the working buffers, which only grow
+   pub fn memory_usage(&self) -> MemoryUsage {
+      MemoryUsage {
+         intersections:
+            bytes(&self.vert_intersections_ref) + bytes(&self.hori_intersections_ref) +
+            bytes(&self.vert_intersections) + bytes(&self.hori_intersections),
+         edges:
+            bytes(&self.upper_edges) + bytes(&self.lower_edges) + bytes(&self.final_edges),
+         polys:
+            bytes(&self.poly_to_pool) +
+            bytes(&self.upper_edges_len) + bytes(&self.lower_edges_len) + bytes(&self.final_edges_len) +
+            bytes(&self.upper_min_y) + bytes(&self.upper_max_y) +
+            bytes(&self.lower_min_x) + bytes(&self.lower_max_x) +
+            bytes(&self.lower_full_min_x) + bytes(&self.lower_full_max_x) +
+            bytes(&self.upper_active) + bytes(&self.lower_active) + bytes(&self.final_active) +
+            bytes(&self.poly_opacity) + bytes(&self.poly_blend),
+      }
+   }
+
+   // Nested groups multiply their opacities, the outermost blend mode applies
+   fn transfer_groups(&mut self, scene: &Scene) {
+      self.has_groups = scene.groups.len() > 0;
+
+      for group in scene.groups.iter().rev() {
+         let end = min(group.end, self.polys_len);
+
+         for i in group.start..end {
+            self.poly_opacity[i] *= group.opacity;
+            self.poly_blend[i] = group.blend;
+         }
+      }
+   }
+
+   // Polys don't overlap, so the only thing under a group poly is the
+   // background, never the previous frame
+   #[inline]
+   fn poly_color(&self, poly_index: usize, color: &RGB) -> RGB {
+      let opacity = self.poly_opacity[poly_index];
+      let blend = self.poly_blend[poly_index];
+
+      if opacity >= 1. && blend == BlendMode::Normal {
+         *color
+      } else {
+         blend.mix(&self.background, color, opacity)
+      }
+   }
+
+   // Solid color of the poly, or its image sampled at the center of column
+   // `x` of pixel row `y` over that color
+   #[inline]
+   fn fill_color(&self, scene: &Scene, poly_index: usize, x: i64, y: i64) -> RGB {
+      let ref poly = scene.polys[poly_index];
+      let ref color = scene.colors[poly.color];
+
+      match poly.paint {
+         Some(paint) => {
+            let pixel_size = self.div_per_pixel as f64;
+
+            scene.paints[paint].sample_over(
+               (x as f64 + 0.5) * self.div_per_column as f64,
+               (y as f64 + 0.5) * pixel_size,
+               pixel_size,
+               color
+            )
+         },
+         None => *color,
+      }
    }
 
    fn min_max_x_y(&self, scene: &Scene) -> (i64, i64, i64, i64) {
@@ -291,8 +520,8 @@ impl Rasterizer {
          let s_min_x = min(p1.x, p2.x);
          let s_min_y = min(p1.y, p2.y);
 
-         let s_max_x = min(p1.x, p2.x);
-         let s_max_y = min(p1.y, p2.y);
+         let s_max_x = max(p1.x, p2.x);
+         let s_max_y = max(p1.y, p2.y);
 
          if s_min_x < min_x {
             min_x = s_min_x;
@@ -404,7 +633,7 @@ impl Rasterizer {
       }
    }
 
-   fn update_lower_min_max_x(&mut self) {
+   fn update_lower_min_max_x(&mut self, y_world: i64, y_slice: i64) {
       for active_index in 0..self.lower_active_full {
          let poly_index = self.lower_active[active_index];
 
@@ -413,12 +642,26 @@ impl Rasterizer {
 
          let mut poly_min_x = i64::MAX;
          let mut poly_max_x = i64::MIN;
+         let mut left_y = i64::MAX;
+         let mut left_i = poly_start;
+
+         let mut top = (i64::MAX, i64::MIN);
+         let mut bottom = (i64::MAX, i64::MIN);
 
          for edge_i in poly_start..poly_end {
             let ref edge = self.lower_edges[edge_i];
 
-            if edge.p1.x < poly_min_x  {
+            if edge.p1.y == edge.p2.y && (edge.p1.y == y_slice || edge.p1.y == y_world) {
+               let span = if edge.p1.y == y_slice { &mut top } else { &mut bottom };
+
+               span.0 = min(span.0, min(edge.p1.x, edge.p2.x));
+               span.1 = max(span.1, max(edge.p1.x, edge.p2.x));
+            }
+
+            if edge.p1.x < poly_min_x || (edge.p1.x == poly_min_x && edge.p1.y < left_y) {
                poly_min_x = edge.p1.x;
+               left_y = edge.p1.y;
+               left_i = edge_i;
             }
 
             if edge.p1.x > poly_max_x {
@@ -426,8 +669,16 @@ impl Rasterizer {
             }
          }
 
+         // Stripes keep the start of the poly, lowest rather than leftmost
+         // when the left side leans back, vertical slicing starts leftmost
+         self.lower_edges[poly_start..poly_end].rotate_left(left_i - poly_start);
+
          self.lower_min_x[poly_index] = poly_min_x;
          self.lower_max_x[poly_index] = poly_max_x;
+
+         // Where the poly takes the whole height of the stripe
+         self.lower_full_min_x[poly_index] = max(top.0, bottom.0);
+         self.lower_full_max_x[poly_index] = min(top.1, bottom.1);
       }
 
       self.sort_lower_active();
@@ -764,7 +1015,9 @@ impl Rasterizer {
 
          match edge.edge_type {
             EdgeType::LTR | EdgeType::LTL | EdgeType::LVT | EdgeType::CTR |
-            EdgeType::CTL | EdgeType::ATR | EdgeType::ATL => {
+            EdgeType::CTL | EdgeType::ATR | EdgeType::ATL | EdgeType::QTR |
+            EdgeType::QTL | EdgeType::BTR | EdgeType::BTL | EdgeType::ETR |
+            EdgeType::ETL => {
                let y2 = edge.p2.y;
                if y2 < y {
                   self.lower_edges[lower_i] = edge;
@@ -812,7 +1065,9 @@ impl Rasterizer {
 
          match edge.edge_type {
             EdgeType::LBR | EdgeType::LBL | EdgeType::LVB | EdgeType::CBR |
-            EdgeType::CBL | EdgeType::ABR | EdgeType::ABL => {
+            EdgeType::CBL | EdgeType::ABR | EdgeType::ABL | EdgeType::QBR |
+            EdgeType::QBL | EdgeType::BBR | EdgeType::BBL | EdgeType::EBR |
+            EdgeType::EBL => {
                let y2 = edge.p2.y;
                if y2 > y {
                   self.upper_edges[upper_i] = edge;
@@ -905,7 +1160,9 @@ impl Rasterizer {
 
          match edge.edge_type {
             EdgeType::LTR | EdgeType::LBR | EdgeType::LHR | EdgeType::CTR |
-            EdgeType::CBR | EdgeType::ATR | EdgeType::ABR => {
+            EdgeType::CBR | EdgeType::ATR | EdgeType::ABR | EdgeType::QTR |
+            EdgeType::QBR | EdgeType::BTR | EdgeType::BBR | EdgeType::ETR |
+            EdgeType::EBR => {
                let x2 = edge.p2.x;
                if x2 < x {
                   self.final_edges[final_i] = edge;
@@ -955,7 +1212,9 @@ impl Rasterizer {
 
          match edge.edge_type {
             EdgeType::LTL | EdgeType::LBL | EdgeType::LHL | EdgeType::CTL |
-            EdgeType::CBL | EdgeType::ATL | EdgeType::ABL => {
+            EdgeType::CBL | EdgeType::ATL | EdgeType::ABL | EdgeType::QTL |
+            EdgeType::QBL | EdgeType::BTL | EdgeType::BBL | EdgeType::ETL |
+            EdgeType::EBL => {
                let x2 = edge.p2.x;
                if x2 > x {
                   self.lower_edges[lower_i] = edge;
@@ -1021,7 +1280,7 @@ impl Rasterizer {
    }
 
    #[inline]
-   fn can_advance_stripe(&self, x_end: i64) -> Option<i64> {
+   fn can_advance_stripe(&self, x_world: i64, x_end: i64) -> Option<i64> {
       if self.final_active_full != 0 {
          return None;
       }
@@ -1032,52 +1291,86 @@ impl Rasterizer {
          return None;
       }
 
-      if self.lower_active_end == self.lower_active_full {
-         return Some(x_end);
+      let x_delta = if self.lower_active_end == self.lower_active_full {
+         x_end
+      } else {
+         self.lower_min_x[self.lower_active[self.lower_active_end]] / self.div_per_column
+      };
+
+      // Polys don't have to tile the scene, only the columns fully inside
+      // the active one are filled with its color
+      let poly_index = self.lower_active[self.lower_active_start];
+
+      if self.lower_full_min_x[poly_index] > x_world {
+         return None;
       }
 
-      let poly_index = self.lower_active[self.lower_active_end];
+      let x_delta = min(x_delta, self.lower_full_max_x[poly_index] / self.div_per_column);
 
-      Some(self.lower_min_x[poly_index] / self.div_per_pixel)
+      if x_delta * self.div_per_column <= x_world {
+         return None;
+      }
+
+      Some(x_delta)
    }
 
+   // Area weighted average of the colors of the polys covering the pixel and
+   // of the background over the rest, in linear light unless blending on the
+   // encoded bytes
    #[inline]
-   pub fn active_color(&self, scene: &Scene) -> RGB {
+   pub fn active_color(&self, scene: &Scene, x: i64, y: i64) -> RGB {
+      let linear = self.blending == Blending::Linear;
+
+      let channel = |value: u8| if linear {
+         self.srgb.linear(value) as i64
+      } else {
+         value as i64
+      };
+
       let mut r: i64 = 0;
       let mut g: i64 = 0;
       let mut b: i64 = 0;
 
       let mut total_area: i64 = 0;
 
-      for active_index in 0..self.final_active_full - 1 {
+      for active_index in 0..self.final_active_full {
          let poly_index = self.final_active[active_index];
 
          let area = self.double_area(poly_index, scene);
 
-         let ref color = scene.colors[scene.polys[poly_index].color];
+         let color = self.poly_color(poly_index, &self.fill_color(scene, poly_index, x, y));
 
-         r += (color.r as i64) * area;
-         g += (color.g as i64) * area;
-         b += (color.b as i64) * area;
+         r += channel(color.r) * area;
+         g += channel(color.g) * area;
+         b += channel(color.b) * area;
 
          total_area += area;
       }
 
-      let poly_index = self.final_active[self.final_active_full - 1];
+      // Polys are not required to tile the scene, clipped ones never do
+      let uncovered = max(0, self.double_pixel_area - total_area);
 
-      let area = self.double_pixel_area - total_area;
+      r += channel(self.background.r) * uncovered;
+      g += channel(self.background.g) * uncovered;
+      b += channel(self.background.b) * uncovered;
 
-      let ref color = scene.colors[scene.polys[poly_index].color];
+      total_area += uncovered;
 
-      r += (color.r as i64) * area;
-      g += (color.g as i64) * area;
-      b += (color.b as i64) * area;
+      r /= total_area;
+      g /= total_area;
+      b /= total_area;
 
-      r /= self.double_pixel_area;
-      g /= self.double_pixel_area;
-      b /= self.double_pixel_area;
+      if linear {
+         let (x, y) = (x as usize, y as usize);
 
-      RGB::new(r as u8, g as u8, b as u8)
+         RGB::new(
+            self.srgb.srgb_dithered(r.max(0).min(65535) as u16, x, y),
+            self.srgb.srgb_dithered(g.max(0).min(65535) as u16, x, y),
+            self.srgb.srgb_dithered(b.max(0).min(65535) as u16, x, y),
+         )
+      } else {
+         RGB::new(r as u8, g as u8, b as u8)
+      }
    }
 
    #[inline]
@@ -1094,12 +1387,11 @@ impl Rasterizer {
             EdgeType::LHR | EdgeType::LHL => {
                area += (edge.p2.x - edge.p1.x) * 2 * edge.p1.y;
             },
-            EdgeType::LTR | EdgeType::LBR | EdgeType::CTR => {
+            EdgeType::LTR | EdgeType::LBR | EdgeType::LTL | EdgeType::LBL => {
                area += (edge.p2.x - edge.p1.x) * (edge.p1.y + edge.p2.y);
             },
-            EdgeType::CBR | EdgeType::ATR | EdgeType::ABR |
-            EdgeType::LTL | EdgeType::LBL | EdgeType::CTL |
-            EdgeType::CBL | EdgeType::ATL | EdgeType::ABL => {
+            EdgeType::CTR | EdgeType::CBR | EdgeType::ATR | EdgeType::ABR |
+            EdgeType::CTL | EdgeType::CBL | EdgeType::ATL | EdgeType::ABL => {
                let dx = edge.p2.x - edge.p1.x;
                let dy = edge.p2.y - edge.p1.y;
 
@@ -1119,6 +1411,27 @@ impl Rasterizer {
                   _ => area -= segment_area
                }
             },
+            EdgeType::QTR | EdgeType::QTL | EdgeType::QBR | EdgeType::QBL |
+            EdgeType::BTR | EdgeType::BTL | EdgeType::BBR | EdgeType::BBL => {
+               area += (edge.p2.x - edge.p1.x) * (edge.p1.y + edge.p2.y);
+
+               let curve = scene.curve_poly(edge.edge_type, edge.segment, edge.curve);
+
+               let t1 = curve.t_at(&edge.p1);
+               let t2 = curve.t_at(&edge.p2);
+
+               area += curve.double_segment_area(t1, t2).round() as i64;
+            },
+            EdgeType::ETR | EdgeType::ETL | EdgeType::EBR | EdgeType::EBL => {
+               area += (edge.p2.x - edge.p1.x) * (edge.p1.y + edge.p2.y);
+
+               let arc = scene.ellipse_arc(edge.segment, edge.ellipse);
+
+               let a1 = arc.angle_at(&edge.p1);
+               let a2 = arc.angle_at(&edge.p2);
+
+               area += arc.double_segment_area(a1, a2).round() as i64;
+            },
             _ => {}
          }
       }
@@ -1129,7 +1442,249 @@ impl Rasterizer {
    }
 }
 
+// Bytes held by the rasterizer buffers, split by what they are sized from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryUsage {
+   pub intersections: usize,
+   pub edges: usize,
+   pub polys: usize,
+}
+
+impl MemoryUsage {
+   #[inline]
+   pub fn total(&self) -> usize {
+      self.intersections + self.edges + self.polys
+   }
+}
+
+#[inline]
+pub fn grow<T>(vec: &mut Vec<T>, len: usize) where T: Default + Clone {
+   if vec.len() < len {
+      vec.resize(len, T::default());
+   }
+}
+
+#[inline]
+fn bytes<T>(vec: &Vec<T>) -> usize {
+   vec.capacity() * mem::size_of::<T>()
+}
+
 pub fn create_default_vec<T>(capacity: usize) -> Vec<T> where T: Default + Clone {
    repeat(T::default()).take(capacity).collect()
 }
 
+
+
+// Also used by the render tests of the other raster modules
+#[cfg(test)]
+pub mod tests {
+   use super::*;
+
+   use data::FloatPoint;
+   use geom::path::Path;
+   use testing::pixel_frame;
+
+   pub fn rect(x1: f64, y1: f64, x2: f64, y2: f64) -> Path {
+      Path::new()
+         .move_to(FloatPoint::new(x1, y1))
+         .line_to(FloatPoint::new(x2, y1))
+         .line_to(FloatPoint::new(x2, y2))
+         .line_to(FloatPoint::new(x1, y2))
+         .close()
+   }
+
+   fn color(x: usize, y: usize) -> RGB {
+      RGB::new(x as u8, y as u8, (x ^ y) as u8)
+   }
+
+   // A pixel sized square of its own color for every pixel
+   fn grid_scene(size: usize) -> Scene {
+      let mut scene = Scene::new();
+
+      for y in 0..size {
+         for x in 0..size {
+            let (x1, y1) = (10. * x as f64, 10. * y as f64);
+
+            scene.push_path(&rect(x1, y1, x1 + 10., y1 + 10.), color(x, y));
+         }
+      }
+
+      scene
+   }
+
+   #[test]
+   fn test_large_scene() {
+      let size = 150;
+
+      let scene = grid_scene(size);
+
+      assert!(scene.edges.len() > 65536);
+
+      let mut rasterizer = Rasterizer::new();
+
+      let mut frame = pixel_frame(size as u32, size as u32);
+
+      rasterizer.render(&scene, &mut frame, 10);
+
+      for y in 0..size {
+         for x in 0..size {
+            assert_eq!(frame.get_pixel(x as i32, y as i32), color(x, y));
+         }
+      }
+   }
+
+   #[test]
+   fn test_memory_usage() {
+      let mut rasterizer = Rasterizer::new();
+
+      assert_eq!(rasterizer.memory_usage().total(), 0);
+
+      let mut frame = pixel_frame(40, 40);
+
+      rasterizer.render(&grid_scene(2), &mut frame, 10);
+
+      let small = rasterizer.memory_usage();
+
+      assert!(small.total() > 0);
+      // Several megabytes with the former fixed 65536 entry buffers
+      assert!(small.total() < 16384, "{:?}", small);
+
+      rasterizer.render(&grid_scene(40), &mut frame, 10);
+
+      let large = rasterizer.memory_usage();
+
+      assert!(large.edges > small.edges);
+      assert!(large.intersections > small.intersections);
+      assert!(large.polys > small.polys);
+
+      // The buffers are kept for the next frames
+      rasterizer.render(&grid_scene(2), &mut frame, 10);
+
+      assert_eq!(rasterizer.memory_usage(), large);
+
+      assert_eq!(frame.get_pixel(1, 1), color(1, 1));
+   }
+
+   #[test]
+   fn test_split_paths() {
+      let mut scene = Scene::new();
+
+      // Concave U, open at the top
+      let mut u = Path::new().move_to(FloatPoint::new(0., 0.));
+
+      let corners = [(30., 0.), (30., 70.), (70., 70.), (70., 0.), (100., 0.), (100., 100.), (0., 100.)];
+
+      for &(x, y) in corners.iter() {
+         u = u.line_to(FloatPoint::new(x, y));
+      }
+
+      scene.push_path(&u.close(), RGB::new(255, 0, 0));
+
+      // Square with a square hole
+      let mut o = rect(100., 0., 200., 100.);
+
+      o.extend(&rect(130., 30., 170., 70.));
+
+      scene.push_path(&o, RGB::new(0, 255, 0));
+
+      let mut rasterizer = Rasterizer::new();
+
+      let mut frame = pixel_frame(20, 10);
+
+      rasterizer.render(&scene, &mut frame, 10);
+
+      assert_eq!(frame.get_pixel(1, 2), RGB::new(255, 0, 0));
+      assert_eq!(frame.get_pixel(8, 2), RGB::new(255, 0, 0));
+      assert_eq!(frame.get_pixel(5, 8), RGB::new(255, 0, 0));
+      assert_eq!(frame.get_pixel(5, 2), RGB::default());
+
+      assert_eq!(frame.get_pixel(11, 1), RGB::new(0, 255, 0));
+      assert_eq!(frame.get_pixel(18, 8), RGB::new(0, 255, 0));
+      assert_eq!(frame.get_pixel(15, 5), RGB::default());
+   }
+
+   #[test]
+   fn test_linear_blending() {
+      let mut scene = Scene::new();
+
+      // White over the left half of the first pixel
+      scene.push_path(&rect(0., 0., 5., 10.), RGB::new(255, 255, 255));
+
+      let mut rasterizer = Rasterizer::new();
+
+      let mut frame = pixel_frame(2, 1);
+
+      rasterizer.render(&scene, &mut frame, 10);
+
+      let encoded = frame.get_pixel(0, 0);
+
+      assert!(encoded.r >= 127 && encoded.r <= 128, "{:?}", encoded);
+
+      rasterizer.blending = Blending::Linear;
+
+      rasterizer.render(&scene, &mut frame, 10);
+
+      // Half the light encodes to 187.5, dithered to either side
+      let linear = frame.get_pixel(0, 0);
+
+      assert!(linear.r >= 187 && linear.r <= 188, "{:?}", linear);
+      assert_eq!(linear.r, linear.g);
+      assert_eq!(frame.get_pixel(1, 0), RGB::default());
+   }
+
+   #[test]
+   fn test_subpixel() {
+      let render = |rasterizer: &mut Rasterizer, frame: &mut Frame, div_per_pixel: i64| {
+         let d = div_per_pixel as f64;
+
+         let mut scene = Scene::new();
+
+         // White over the first third of the first pixel
+         scene.push_path(&rect(0., 0., d / 3., d), RGB::new(255, 255, 255));
+
+         rasterizer.render(&scene, frame, div_per_pixel);
+      };
+
+      let mut rasterizer = Rasterizer::new();
+
+      rasterizer.background = RGB::new(0, 0, 60);
+
+      let mut frame = pixel_frame(3, 1);
+
+      // Red left over from a previous frame
+      let mut scene = Scene::new();
+
+      scene.push_path(&rect(0., 0., 90., 30.), RGB::new(255, 0, 0));
+
+      rasterizer.render(&scene, &mut frame, 30);
+
+      rasterizer.subpixel = Subpixel::Rgb;
+
+      render(&mut rasterizer, &mut frame, 30);
+
+      let lit = frame.get_pixel(0, 0);
+
+      assert!(lit.r > lit.g && lit.g > lit.b, "{:?}", lit);
+
+      // Past the reach of the filter the background shows, not the last frame
+      assert_eq!(frame.get_pixel(1, 0), RGB::new(0, 0, 60));
+
+      rasterizer.subpixel = Subpixel::Bgr;
+
+      render(&mut rasterizer, &mut frame, 30);
+
+      let lit = frame.get_pixel(0, 0);
+
+      assert!(lit.b > lit.g && lit.g > lit.r, "{:?}", lit);
+
+      // Divisions that columns can't split get the scene scaled by 3
+      for &div_per_pixel in [10, 1000].iter() {
+         render(&mut rasterizer, &mut frame, div_per_pixel);
+
+         let scaled = frame.get_pixel(0, 0);
+
+         assert!(scaled.b > scaled.g && scaled.g > scaled.r, "{} {:?}", div_per_pixel, scaled);
+         assert_eq!(frame.get_pixel(1, 0), RGB::new(0, 0, 60));
+      }
+   }
+}
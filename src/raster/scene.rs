@@ -1,17 +1,353 @@
+use std::usize;
+
+use data::FloatPoint;
 use geom::point::Point;
+use geom::path::Path;
+use geom::transform::Transform;
+use geom::flatten::{Flattener, DEFAULT_TOLERANCE};
+use geom::boolean::union;
 use draw::RGB;
+use image::ImagePaint;
 
 use super::segment::Segment;
 use super::circle::Circle;
+use super::curve::{Curve, CurvePoly};
+use super::ellipse::{Ellipse, EllipseArc};
 use super::poly::Poly;
-use super::edge::EdgeSrc;
+use super::edge::{EdgeType, EdgeSrc};
+use super::clip::Clip;
+use super::group::Group;
+use super::path::{Piece, monotonic_pieces, is_monotonic, trapezoids, double_area, to_point, directed};
 
 
 pub struct Scene {
    pub points: Vec<Point>,
    pub segments: Vec<Segment>,
    pub circles: Vec<Circle>,
+   pub curves: Vec<Curve>,
+   pub ellipses: Vec<Ellipse>,
    pub edges: Vec<EdgeSrc>,
    pub polys: Vec<Poly>,
    pub colors: Vec<RGB>,
+   pub clips: Vec<Clip>,
+   pub groups: Vec<Group>,
+   pub paints: Vec<ImagePaint>,
+}
+
+impl Scene {
+   pub fn new() -> Self {
+      Scene {
+         points: Vec::new(),
+         segments: Vec::new(),
+         circles: Vec::new(),
+         curves: Vec::new(),
+         ellipses: Vec::new(),
+         edges: Vec::new(),
+         polys: Vec::new(),
+         colors: Vec::new(),
+         clips: Vec::new(),
+         groups: Vec::new(),
+         paints: Vec::new(),
+      }
+   }
+
+   // A path of a single x and y monotonic subpath is appended as one poly
+   // of monotonic edges. Any other path, like a concave outline or one with
+   // holes, is flattened, filled with the even-odd rule and split into
+   // trapezoids. The flattener of the current clip is used, the default
+   // tolerance without one
+   pub fn push_path(&mut self, path: &Path, color: RGB) {
+      self.push_path_paint(path, color, None);
+   }
+
+   // Fills the path with the image composited over `color`, the paint
+   // transform maps image pixels to scene units
+   pub fn push_image_path(&mut self, path: &Path, paint: ImagePaint, color: RGB) {
+      let paint_index = self.paints.len();
+
+      self.paints.push(paint);
+
+      self.push_path_paint(path, color, Some(paint_index));
+   }
+
+   // Turns `self` into `scene` with all lengths multiplied by `factor`,
+   // keeping the buffers of the earlier copies
+   pub fn scale_from(&mut self, scene: &Scene, factor: i64) {
+      let f = factor as f64;
+
+      self.points.clear();
+      self.points.extend(scene.points.iter().map(|p| Point::new(p.x * factor, p.y * factor)));
+
+      self.circles.clear();
+      self.circles.extend(scene.circles.iter().map(|c| Circle::new(c.center, c.radius * factor)));
+
+      self.ellipses.clear();
+      self.ellipses.extend(scene.ellipses.iter().map(
+         |e| Ellipse::new(e.center, e.rx * factor, e.ry * factor, e.rotation)
+      ));
+
+      self.paints.clear();
+      self.paints.extend(scene.paints.iter().map(
+         |paint| paint.then(&Transform::scale(f, f)).unwrap()
+      ));
+
+      self.segments.clone_from(&scene.segments);
+      self.curves.clone_from(&scene.curves);
+      self.edges.clone_from(&scene.edges);
+      self.polys.clone_from(&scene.polys);
+      self.colors.clone_from(&scene.colors);
+      self.groups.clone_from(&scene.groups);
+      self.clips.clear();
+   }
+
+   fn push_path_paint(&mut self, path: &Path, color: RGB, paint: Option<usize>) {
+      let clipped;
+
+      let path = match self.clips.last() {
+         Some(clip) => {
+            clipped = clip.apply(path);
+            &clipped
+         },
+         None => path,
+      };
+
+      let color_index = self.colors.len();
+
+      self.colors.push(color);
+
+      let subpaths = path.subpaths();
+
+      if subpaths.len() == 1 {
+         let (first, pieces) = monotonic_pieces(subpaths[0]);
+
+         if is_monotonic(&pieces) {
+            let (first, pieces) = if double_area(&pieces) < 0. {
+               let mut reversed = Path::new();
+
+               for segment in subpaths[0].iter() {
+                  reversed.push(*segment);
+               }
+
+               monotonic_pieces(reversed.reversed().segments())
+            } else {
+               (first, pieces)
+            };
+
+            self.push_poly(first, &pieces, color_index, paint);
+
+            return;
+         }
+      }
+
+      let flattener = match self.clips.last() {
+         Some(clip) => clip.flattener,
+         None => Flattener::new(DEFAULT_TOLERANCE),
+      };
+
+      let contours: Vec<Vec<FloatPoint>> = path.flatten(&flattener).into_iter()
+         .filter(|contour| contour.len() > 2)
+         .collect();
+
+      for trapezoid in trapezoids(&union(&contours, &[])).iter() {
+         let mut pieces: Vec<Piece> = (0..4)
+            .map(|i| Piece::Line(trapezoid[i], trapezoid[(i + 1) % 4]))
+            .collect();
+
+         if double_area(&pieces) < 0. {
+            pieces = (0..4).rev()
+               .map(|i| Piece::Line(trapezoid[(i + 1) % 4], trapezoid[i]))
+               .collect();
+         }
+
+         self.push_poly(pieces[3].end(), &pieces, color_index, paint);
+      }
+   }
+
+   fn push_poly(
+      &mut self, first: FloatPoint, pieces: &[Piece], color_index: usize, paint: Option<usize>
+   ) {
+      let start = self.edges.len();
+
+      self.push_pieces(first, pieces);
+
+      let end = self.edges.len();
+
+      if end - start < 3 {
+         self.edges.truncate(start);
+         return;
+      }
+
+      // Slicing starts from the top-left vertex
+      let top_left = (start..end).min_by_key(|&i| self.edge_start(i)).unwrap();
+
+      self.edges[start..end].rotate_left(top_left - start);
+
+      let mut poly = Poly::new(start, end, color_index);
+
+      poly.paint = paint;
+
+      self.polys.push(poly);
+   }
+
+   fn push_pieces(&mut self, first: FloatPoint, pieces: &[Piece]) {
+      let first = self.push_point(first);
+
+      let mut current = first;
+
+      for piece in pieces.iter() {
+         let end_point = to_point(piece.end());
+
+         if end_point == self.points[current] {
+            continue;
+         }
+
+         let end = if end_point == self.points[first] {
+            first
+         } else {
+            self.push_point(piece.end())
+         };
+
+         self.push_edge(piece, current, end);
+
+         current = end;
+      }
+   }
+
+   fn push_edge(&mut self, piece: &Piece, start: usize, end: usize) {
+      let a = self.points[start];
+      let b = self.points[end];
+
+      if a.x == b.x || a.y == b.y {
+         let edge_type = if a.y == b.y {
+            if b.x > a.x { EdgeType::LHR } else { EdgeType::LHL }
+         } else {
+            if b.y > a.y { EdgeType::LVT } else { EdgeType::LVB }
+         };
+
+         let segment = self.push_segment(edge_type, start, end);
+
+         self.edges.push(EdgeSrc::new(edge_type, segment, usize::MAX));
+
+         return;
+      }
+
+      match *piece {
+         Piece::Line(..) => {
+            let edge_type = directed(&a, &b, [EdgeType::LTR, EdgeType::LTL, EdgeType::LBR, EdgeType::LBL]);
+
+            let segment = self.push_segment(edge_type, start, end);
+
+            self.edges.push(EdgeSrc::new(edge_type, segment, usize::MAX));
+         },
+         Piece::Quad(_, c, _) => {
+            let edge_type = directed(&a, &b, [EdgeType::QTR, EdgeType::QTL, EdgeType::QBR, EdgeType::QBL]);
+
+            let segment = self.push_segment(edge_type, start, end);
+
+            let c = self.push_point(c);
+
+            self.curves.push(Curve::quadratic(c));
+
+            let curve = self.curves.len() - 1;
+
+            self.edges.push(EdgeSrc::new_curve(edge_type, segment, curve));
+         },
+         Piece::Cubic(_, c1, c2, _) => {
+            let edge_type = directed(&a, &b, [EdgeType::BTR, EdgeType::BTL, EdgeType::BBR, EdgeType::BBL]);
+
+            let segment = self.push_segment(edge_type, start, end);
+
+            let c1 = self.push_point(c1);
+            let c2 = self.push_point(c2);
+
+            // Segments of reversed edges are stored from the end point
+            self.curves.push(if edge_type.reversed() {
+               Curve::new(c2, c1)
+            } else {
+               Curve::new(c1, c2)
+            });
+
+            let curve = self.curves.len() - 1;
+
+            self.edges.push(EdgeSrc::new_curve(edge_type, segment, curve));
+         },
+         Piece::Arc(ref arc) => {
+            let edge_type = directed(&a, &b, [EdgeType::ETR, EdgeType::ETL, EdgeType::EBR, EdgeType::EBL]);
+
+            let segment = self.push_segment(edge_type, start, end);
+
+            let (rx, ry, rotation) = arc.shape();
+
+            let center = self.push_point(arc.center);
+
+            self.ellipses.push(
+               Ellipse::new(center, rx.round() as i64, ry.round().max(1.) as i64, rotation)
+            );
+
+            let ellipse = self.ellipses.len() - 1;
+
+            self.edges.push(EdgeSrc::new_ellipse(edge_type, segment, ellipse));
+         },
+      }
+   }
+
+   #[inline]
+   fn edge_start(&self, edge_index: usize) -> (i64, i64) {
+      let ref edge = self.edges[edge_index];
+      let ref segment = self.segments[edge.segment];
+
+      let ref point = if edge.reversed() {
+         self.points[segment.p2]
+      } else {
+         self.points[segment.p1]
+      };
+
+      (point.y, point.x)
+   }
+
+   #[inline]
+   fn push_point(&mut self, point: FloatPoint) -> usize {
+      self.points.push(to_point(point));
+
+      self.points.len() - 1
+   }
+
+   #[inline]
+   fn push_segment(&mut self, edge_type: EdgeType, start: usize, end: usize) -> usize {
+      self.segments.push(if edge_type.reversed() {
+         Segment::new(end, start)
+      } else {
+         Segment::new(start, end)
+      });
+
+      self.segments.len() - 1
+   }
+
+   pub fn curve_poly(&self, edge_type: EdgeType, segment: usize, curve: usize) -> CurvePoly {
+      let ref segment = self.segments[segment];
+      let ref curve = self.curves[curve];
+
+      let ref p1 = self.points[segment.p1];
+      let ref p2 = self.points[segment.p2];
+
+      match edge_type {
+         EdgeType::QTR | EdgeType::QTL | EdgeType::QBR | EdgeType::QBL => {
+            CurvePoly::quadratic(p1, &self.points[curve.c1], p2)
+         },
+         _ => {
+            CurvePoly::cubic(p1, &self.points[curve.c1], &self.points[curve.c2], p2)
+         }
+      }
+   }
+   pub fn ellipse_arc(&self, segment: usize, ellipse: usize) -> EllipseArc {
+      let ref segment = self.segments[segment];
+      let ref ellipse = self.ellipses[ellipse];
+
+      EllipseArc::new(
+         ellipse,
+         &self.points[ellipse.center],
+         &self.points[segment.p1],
+         &self.points[segment.p2]
+      )
+   }
 }
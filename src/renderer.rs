@@ -1,13 +1,18 @@
 use frame::Frame;
+use clock::FrameClock;
 
 #[allow(unused_variables)]
 pub trait Renderer {
-   fn render(&mut self, &mut Frame);
+   fn render(&mut self, &mut Frame, &FrameClock);
 
    fn init(&mut self, width: u32, height: u32) {}
 
    fn resized(&mut self, width: u32, height: u32) {}
 
+   // Sizes and mouse positions stay in physical pixels like the frame, a
+   // renderer working in logical pixels divides them by `scale_factor`
+   fn scale_factor_changed(&mut self, scale_factor: f64) {}
+
    fn mouse_moved(&mut self, x: i32, y: i32) {}
 
    fn mouse_left_button_pressed(&mut self) {}
@@ -26,7 +31,7 @@ pub trait Renderer {
 pub struct NullRenderer;
 
 impl Renderer for NullRenderer {
-   fn render(&mut self, frame: &mut Frame) {
+   fn render(&mut self, frame: &mut Frame, _: &FrameClock) {
       frame.clear();
    }
 }
@@ -0,0 +1,140 @@
+// sRGB transfer functions and the tables the rasterizers use to blend in
+// linear light. Linear values are 16-bit, encoded values keep 8 fractional
+// bits so they can get dithered when quantized to the 8-bit output
+
+// 4x4 ordered dither matrix
+const BAYER: [[u16; 4]; 4] = [
+   [0, 8, 2, 10],
+   [12, 4, 14, 6],
+   [3, 11, 1, 9],
+   [15, 7, 13, 5],
+];
+
+
+#[inline]
+pub fn srgb_to_linear(value: f64) -> f64 {
+   if value <= 0.04045 {
+      value / 12.92
+   } else {
+      ((value + 0.055) / 1.055).powf(2.4)
+   }
+}
+
+
+#[inline]
+pub fn linear_to_srgb(value: f64) -> f64 {
+   if value <= 0.0031308 {
+      value * 12.92
+   } else {
+      1.055 * value.powf(1. / 2.4) - 0.055
+   }
+}
+
+
+pub struct SrgbTables {
+   to_linear: Vec<u16>,
+   to_srgb: Vec<u16>,
+}
+
+impl SrgbTables {
+   pub fn new() -> Self {
+      let to_linear = (0..256).map(
+         |value| (srgb_to_linear(value as f64 / 255.) * 65535.).round() as u16
+      ).collect();
+
+      let to_srgb = (0..65536).map(
+         |value| (linear_to_srgb(value as f64 / 65535.) * 255. * 256.).round() as u16
+      ).collect();
+
+      SrgbTables {
+         to_linear: to_linear,
+         to_srgb: to_srgb,
+      }
+   }
+
+   #[inline]
+   pub fn linear(&self, value: u8) -> u16 {
+      unsafe {
+         *self.to_linear.get_unchecked(value as usize)
+      }
+   }
+
+   #[inline]
+   pub fn srgb(&self, linear: u16) -> u8 {
+      let encoded = unsafe {
+         *self.to_srgb.get_unchecked(linear as usize)
+      };
+
+      ((encoded as u32 + 128) >> 8) as u8
+   }
+
+   // Encoded with the ordered dither threshold of pixel `x`, `y`, values
+   // that encode exactly stay the same
+   #[inline]
+   pub fn srgb_dithered(&self, linear: u16, x: usize, y: usize) -> u8 {
+      let encoded = unsafe {
+         *self.to_srgb.get_unchecked(linear as usize)
+      };
+
+      let threshold = BAYER[y & 3][x & 3] * 16 + 8;
+
+      ((encoded as u32 + threshold as u32) >> 8) as u8
+   }
+}
+
+impl Default for SrgbTables {
+   #[inline]
+   fn default() -> SrgbTables {
+      SrgbTables::new()
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   #[test]
+   fn test_round_trip() {
+      let tables = SrgbTables::new();
+
+      for value in 0..256 {
+         let linear = tables.linear(value as u8);
+
+         assert_eq!(tables.srgb(linear), value as u8);
+
+         for y in 0..4 {
+            for x in 0..4 {
+               assert_eq!(tables.srgb_dithered(linear, x, y), value as u8);
+            }
+         }
+      }
+   }
+
+   #[test]
+   fn test_half_coverage() {
+      let tables = SrgbTables::new();
+
+      // White over black at half coverage is lighter than the byte average
+      let half = ((tables.linear(255) as u32 + tables.linear(0) as u32) / 2) as u16;
+
+      assert_eq!(tables.srgb(half), 188);
+
+      // Dithering between two levels keeps the average
+      let linear = ((tables.linear(100) as u32 + tables.linear(101) as u32) / 2) as u16;
+
+      let mut sum = 0;
+
+      for y in 0..4 {
+         for x in 0..4 {
+            let value = tables.srgb_dithered(linear, x, y);
+
+            assert!(value == 100 || value == 101);
+
+            sum += value as u32;
+         }
+      }
+
+      assert!(sum > 100 * 16 && sum < 101 * 16);
+   }
+}
@@ -148,6 +148,8 @@ static mut eglMakeCurrentPtr:                    FnPtr = NULL_PTR;
 static mut eglQueryContextPtr:                   FnPtr = NULL_PTR;
 static mut eglSwapBuffersPtr:                    FnPtr = NULL_PTR;
 static mut eglSwapIntervalPtr:                   FnPtr = NULL_PTR;
+static mut eglDestroySurfacePtr:                 FnPtr = NULL_PTR;
+static mut eglDestroyContextPtr:                 FnPtr = NULL_PTR;
 
 #[inline]
 pub unsafe fn eglGetError() -> EGLint {
@@ -219,6 +221,16 @@ pub unsafe fn eglSwapInterval(display: EGLDisplay, interval: EGLint) -> EGLBoole
    mem::transmute::<_, extern "system" fn(EGLDisplay, EGLint) -> EGLBoolean>(eglSwapIntervalPtr)(display, interval)
 }
 
+#[inline]
+pub unsafe fn eglDestroySurface(display: EGLDisplay, surface: EGLSurface) -> EGLBoolean {
+   mem::transmute::<_, extern "system" fn(EGLDisplay, EGLSurface) -> EGLBoolean>(eglDestroySurfacePtr)(display, surface)
+}
+
+#[inline]
+pub unsafe fn eglDestroyContext(display: EGLDisplay, ctx: EGLContext) -> EGLBoolean {
+   mem::transmute::<_, extern "system" fn(EGLDisplay, EGLContext) -> EGLBoolean>(eglDestroyContextPtr)(display, ctx)
+}
+
 pub unsafe fn load_functions(loader: &FnPtrLoader) -> bool {
    eglGetErrorPtr = loader.load("eglGetError");
    eglBindAPIPtr = loader.load("eglBindAPI");
@@ -234,6 +246,8 @@ pub unsafe fn load_functions(loader: &FnPtrLoader) -> bool {
    eglQueryContextPtr = loader.load("eglQueryContext");
    eglSwapBuffersPtr = loader.load("eglSwapBuffers");
    eglSwapIntervalPtr = loader.load("eglSwapInterval");
+   eglDestroySurfacePtr = loader.load("eglDestroySurface");
+   eglDestroyContextPtr = loader.load("eglDestroyContext");
 
    true
 }
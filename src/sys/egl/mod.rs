@@ -522,6 +522,47 @@ impl Display {
       }
    }
 
+   pub fn release_current(&self) -> VoidResult {
+
+      let released = unsafe {
+         ffi::eglMakeCurrent(
+            self.ptr,
+            ffi::EGL_NO_SURFACE,
+            ffi::EGL_NO_SURFACE,
+            ffi::EGL_NO_CONTEXT
+         )
+      };
+
+      match released {
+         ffi::EGL_FALSE => egl_error("eglMakeCurrent (release) failed"),
+         _ => egl_result(())
+      }
+   }
+
+   pub fn destroy_surface(&self, surface: &Surface) -> VoidResult {
+
+      let result = unsafe {
+         ffi::eglDestroySurface(self.ptr, surface.ptr)
+      };
+
+      match result {
+         ffi::EGL_FALSE => egl_error("eglDestroySurface failed"),
+         _ => egl_result(())
+      }
+   }
+
+   pub fn destroy_context(&self, context: &Context) -> VoidResult {
+
+      let result = unsafe {
+         ffi::eglDestroyContext(self.ptr, context.ptr)
+      };
+
+      match result {
+         ffi::EGL_FALSE => egl_error("eglDestroyContext failed"),
+         _ => egl_result(())
+      }
+   }
+
    pub fn swap_interval(&self, interval: ffi::c_int) -> VoidResult {
 
       let result = unsafe {
@@ -7,6 +7,7 @@ use std::ptr;
 use std::ffi::CString;
 
 use data::FloatPoint;
+use geom::path::{Path, PathSegment};
 
 use super::DynLibrary;
 use super::utils::fn_ptr::FnPtrLibrary;
@@ -94,9 +95,7 @@ impl Face {
       }
    }
 
-   pub fn text(
-      &self, string: &str, steps: usize, align: TextAlign
-   ) -> Vec<Vec<Vec<FloatPoint>>> {
+   pub fn text(&self, string: &str, align: TextAlign) -> Vec<Path> {
 
       let funcs = ffi::FT_Outline_Funcs {
          move_to: Some(move_to),
@@ -114,14 +113,14 @@ impl Face {
       let mut previous_index = 0;
 
       for ch in string.chars() {
-         let mut points: Box<CharPoints> = Box::new(CharPoints::new(steps));
+         let mut points: Box<CharPoints> = Box::new(CharPoints::new());
 
          unsafe {
-            ffi::FT_Load_Char(self.ft_face, ch as u32, ffi::FT_LOAD_DEFAULT);
+            ffi::FT_Load_Char(self.ft_face, ch as ffi::FT_ULong, ffi::FT_LOAD_DEFAULT);
 
             let slot = (*self.ft_face).glyph as ffi::FT_GlyphSlot;
 
-            let current_index = ffi::FT_Get_Char_Index(self.ft_face, ch as u32);
+            let current_index = ffi::FT_Get_Char_Index(self.ft_face, ch as ffi::FT_ULong);
 
             if previous_index != 0 {
                let mut delta = ffi::FT_Vector::default();
@@ -143,10 +142,10 @@ impl Face {
                &mut *points as *mut _ as *mut c_void
             );
 
-            let contours = points.with_offset(offset);
+            points.path.push(PathSegment::Close);
 
-            if contours.len() > 0 {
-               result.push(contours);
+            if !points.path.is_empty() {
+               result.push(points.path.translate(FloatPoint::new(offset, 0.0)));
             }
 
             offset += (*slot).metrics.horiAdvance as f64;
@@ -157,8 +156,8 @@ impl Face {
 
       match align {
          TextAlign::Left => {},
-         TextAlign::Center => offset_points(&mut result, -offset / 2.0),
-         TextAlign::Right => offset_points(&mut result, -offset),
+         TextAlign::Center => offset_paths(&mut result, -offset / 2.0),
+         TextAlign::Right => offset_paths(&mut result, -offset),
       }
 
       result
@@ -166,13 +165,9 @@ impl Face {
 }
 
 
-fn offset_points(points: &mut Vec<Vec<Vec<FloatPoint>>>, offset: f64) {
-   for contours in points.iter_mut() {
-      for contour in contours.iter_mut() {
-         for point in contour.iter_mut() {
-            point.x += offset;
-         }
-      }
+fn offset_paths(paths: &mut Vec<Path>, offset: f64) {
+   for path in paths.iter_mut() {
+      *path = path.translate(FloatPoint::new(offset, 0.0));
    }
 }
 
@@ -259,110 +254,35 @@ unsafe fn pt(ft_vec: *const ffi::FT_Vector) -> FloatPoint {
 
 #[derive(Debug)]
 struct CharPoints {
-   points: Vec<Vec<FloatPoint>>,
-   steps: usize,
+   path: Path,
 }
 
 impl CharPoints {
-   pub fn new(steps: usize) -> Self {
+   pub fn new() -> Self {
       CharPoints {
-         points: Vec::new(),
-         steps: steps,
+         path: Path::new(),
       }
    }
 
+   // Outline contours are implicitly closed
    #[inline]
    pub fn move_to(&mut self, to: FloatPoint) {
-      self.points.push(Vec::new());
-
-      self.push(to);
+      self.path.push(PathSegment::Close);
+      self.path.push(PathSegment::MoveTo(to));
    }
 
    #[inline]
    pub fn line_to(&mut self, to: FloatPoint) {
-      self.push(to);
+      self.path.push(PathSegment::LineTo(to));
    }
 
    #[inline]
    pub fn conic_to(&mut self, ctrl: FloatPoint, to: FloatPoint) {
-      let from = self.last();
-
-      for i in 1..self.steps {
-         let t2 = i as f64 / self.steps as f64;
-         let t1 = 1. - t2;
-
-         let u = on_segment(from, ctrl, t1, t2);
-         let v = on_segment(ctrl, to, t1, t2);
-
-         let f = on_segment(u, v, t1, t2);
-
-         self.push(f);
-      }
-
-      self.push(to);
+      self.path.push(PathSegment::QuadTo(ctrl, to));
    }
 
    #[inline]
    pub fn cubic_to(&mut self, ctrl1: FloatPoint, ctrl2: FloatPoint, to: FloatPoint) {
-      let from = self.last();
-
-      for i in 1..self.steps {
-         let t2 = i as f64 / self.steps as f64;
-         let t1 = 1. - t2;
-
-         let u = on_segment(from, ctrl1, t1, t2);
-         let v = on_segment(ctrl1, ctrl2, t1, t2);
-         let w = on_segment(ctrl2, to, t1, t2);
-
-         let m = on_segment(u, v, t1, t2);
-         let n = on_segment(v, w, t1, t2);
-
-         let f = on_segment(m, n, t1, t2);
-
-         self.push(f);
-      }
-
-      self.push(to);
-   }
-
-   #[inline]
-   fn push(&mut self, point: FloatPoint) {
-      if let Some(last) = self.points.last_mut() {
-         last.push(point);
-      }
-   }
-
-   #[inline]
-   fn last(&mut self) -> FloatPoint {
-      *self.points.last().unwrap().last().unwrap()
+      self.path.push(PathSegment::CubicTo(ctrl1, ctrl2, to));
    }
-
-   #[inline]
-   fn with_offset(&self, offset: f64) -> Vec<Vec<FloatPoint>> {
-      let mut outer = Vec::new();
-
-      for contour in &self.points {
-         let mut inner = Vec::new();
-
-         for point in contour {
-            let x = point.x + offset;
-            let y = point.y;
-
-            inner.push(FloatPoint::new(x, y));
-         }
-
-         outer.push(inner);
-      }
-
-      outer
-   }
-}
-
-
-#[inline]
-fn on_segment(pt1: FloatPoint, pt2: FloatPoint, t1: f64, t2: f64) -> FloatPoint {
-   let x = t1 * pt1.x + t2 * pt2.x;
-   let y = t1 * pt1.y + t2 * pt2.y;
-
-   FloatPoint::new(x, y)
 }
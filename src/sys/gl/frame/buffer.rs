@@ -57,6 +57,19 @@ impl GPUFrame for BufferFrame {
       }
    }
 
+   #[inline]
+   fn get_pixel(&self, x: i32, y: i32, width: u32, height: u32) -> RGB {
+      if x >= width as i32 || y >= height as i32 || x < 0 || y < 0 {
+         return RGB::default();
+      }
+
+      let i = 4 * (x + (height as i32 - y - 1) * width as i32) as isize;
+      let p = self.buffer.ptr as *const u8;
+      unsafe {
+         RGB::new(*p.offset(i), *p.offset(i+1), *p.offset(i+2))
+      }
+   }
+
    #[inline]
    fn ptr_mut(&mut self) -> *mut u8 {
       self.buffer.ptr as *mut u8
@@ -40,6 +40,16 @@ impl GPUFrame for DrawPixelsFrame {
       self.data[i + 2] = color.b;
    }
 
+   #[inline]
+   fn get_pixel(&self, x: i32, y: i32, width: u32, height: u32) -> RGB {
+      if x >= width as i32 || y >= height as i32 || x < 0 || y < 0 {
+         return RGB::default();
+      }
+
+      let i = 4 * (x + (height as i32 - y - 1) * width as i32) as usize;
+      RGB::new(self.data[i], self.data[i + 1], self.data[i + 2])
+   }
+
    #[inline]
    fn ptr_mut(&mut self) -> *mut u8 {
       self.data.as_mut_ptr()
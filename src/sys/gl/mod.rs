@@ -40,6 +40,10 @@ pub fn initialize<T: FnPtrLoader>(loader: &T) -> VoidResult {
 
    try!(reset_pixelstore_alignment());
 
+   // Frames hold the sRGB encoded output of the rasterizers, presenting them
+   // must not encode again. ES contexts lack the capability and never do
+   let _ = disable_framebuffer_srgb();
+
    Ok(())
 }
 
@@ -136,6 +140,15 @@ pub fn enable_framebuffer_srgb() -> VoidResult {
    gl_result("glEnable(GL_FRAMEBUFFER_SRGB)", ())
 }
 
+#[inline]
+pub fn disable_framebuffer_srgb() -> VoidResult {
+   unsafe {
+      ffi::glDisable(ffi::GL_FRAMEBUFFER_SRGB);
+   }
+
+   gl_result("glDisable(GL_FRAMEBUFFER_SRGB)", ())
+}
+
 #[inline]
 pub fn viewport(width: u32, height: u32) -> VoidResult {
    unsafe {
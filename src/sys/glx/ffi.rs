@@ -2,9 +2,10 @@
 #![allow(non_snake_case)]
 
 use std::mem;
+use std::ffi::CString;
 
 pub use libc::{
-   c_char, c_uchar, c_int, c_void,
+   c_char, c_uchar, c_int, c_uint, c_void,
 };
 
 use super::GLXNativeWindowType;
@@ -88,7 +89,11 @@ static mut glXSwapBuffersPtr:                                 FnPtr = NULL_PTR;
 static mut glXDestroyContextPtr:                              FnPtr = NULL_PTR;
 static mut glXMakeCurrentPtr:                                 FnPtr = NULL_PTR;
 static mut glXCreateWindowPtr:                                FnPtr = NULL_PTR;
+static mut glXDestroyWindowPtr:                               FnPtr = NULL_PTR;
 static mut glXMakeContextCurrentPtr:                          FnPtr = NULL_PTR;
+static mut glXSwapIntervalEXTPtr:                             FnPtr = NULL_PTR;
+static mut glXSwapIntervalMESAPtr:                            FnPtr = NULL_PTR;
+static mut glXSwapIntervalSGIPtr:                             FnPtr = NULL_PTR;
 
 #[inline]
 pub unsafe fn glXQueryVersion(display: *mut Display, major: *mut c_int, minor: *mut c_int) -> c_int {
@@ -145,11 +150,46 @@ pub unsafe fn glXCreateWindow(display: *mut Display, config: GLXFBConfig, window
    mem::transmute::<_, extern "system" fn(*mut Display, GLXFBConfig, GLXNativeWindowType, *const c_int) -> GLXWindow>(glXCreateWindowPtr)(display, config, window, attrib_list)
 }
 
+#[inline]
+pub unsafe fn glXDestroyWindow(display: *mut Display, window: GLXWindow) {
+   mem::transmute::<_, extern "system" fn(*mut Display, GLXWindow) -> ()>(glXDestroyWindowPtr)(display, window)
+}
+
 #[inline]
 pub unsafe fn glXMakeContextCurrent(display: *mut Display, draw: GLXDrawable, read: GLXDrawable, ctx: GLXContext) -> Bool {
    mem::transmute::<_, extern "system" fn(*mut Display, GLXDrawable, GLXDrawable, GLXContext) -> Bool>(glXMakeContextCurrentPtr)(display, draw, read, ctx)
 }
 
+#[inline]
+pub unsafe fn glXSwapIntervalEXT(display: *mut Display, drawable: GLXDrawable, interval: c_int) {
+   mem::transmute::<_, extern "system" fn(*mut Display, GLXDrawable, c_int)>(glXSwapIntervalEXTPtr)(display, drawable, interval)
+}
+
+#[inline]
+pub unsafe fn glXSwapIntervalMESA(interval: c_uint) -> c_int {
+   mem::transmute::<_, extern "system" fn(c_uint) -> c_int>(glXSwapIntervalMESAPtr)(interval)
+}
+
+#[inline]
+pub unsafe fn glXSwapIntervalSGI(interval: c_int) -> c_int {
+   mem::transmute::<_, extern "system" fn(c_int) -> c_int>(glXSwapIntervalSGIPtr)(interval)
+}
+
+#[inline]
+pub unsafe fn has_swap_interval_ext() -> bool {
+   glXSwapIntervalEXTPtr != NULL_PTR
+}
+
+#[inline]
+pub unsafe fn has_swap_interval_mesa() -> bool {
+   glXSwapIntervalMESAPtr != NULL_PTR
+}
+
+#[inline]
+pub unsafe fn has_swap_interval_sgi() -> bool {
+   glXSwapIntervalSGIPtr != NULL_PTR
+}
+
 pub unsafe fn load_functions(loader: &FnPtrLoader) -> bool {
    glXQueryVersionPtr = loader.load("glXQueryVersion");
    glXGetProcAddressPtr = loader.load("glXGetProcAddress");
@@ -162,7 +202,21 @@ pub unsafe fn load_functions(loader: &FnPtrLoader) -> bool {
    glXDestroyContextPtr = loader.load("glXDestroyContext");
    glXMakeCurrentPtr = loader.load("glXMakeCurrent");
    glXCreateWindowPtr = loader.load("glXCreateWindow");
+   glXDestroyWindowPtr = loader.load("glXDestroyWindow");
    glXMakeContextCurrentPtr = loader.load("glXMakeContextCurrent");
 
+   if glXGetProcAddressPtr != NULL_PTR {
+      glXSwapIntervalEXTPtr = proc_address("glXSwapIntervalEXT");
+      glXSwapIntervalMESAPtr = proc_address("glXSwapIntervalMESA");
+      glXSwapIntervalSGIPtr = proc_address("glXSwapIntervalSGI");
+   }
+
    true
 }
+
+#[inline]
+unsafe fn proc_address(name: &str) -> FnPtr {
+   let cname = CString::new(name).unwrap();
+
+   glXGetProcAddress(cname.as_ptr())
+}
@@ -243,6 +243,34 @@ pub fn make_current(
    Ok(())
 }
 
+pub fn release_current(display: &Display) -> VoidResult {
+
+   let released = unsafe {
+      ffi::glXMakeContextCurrent(display.ptr, 0, 0, ptr::null_mut())
+   };
+
+   if released == 0 {
+      return Err(RuntimeError::new(
+         ErrorKind::GLX,
+         "glXMakeContextCurrent (release) failed".to_string()
+      ));
+   }
+
+   Ok(())
+}
+
+pub fn destroy_rendering_area(display: &Display, rendering_area: ffi::GLXWindow) {
+   unsafe {
+      ffi::glXDestroyWindow(display.ptr, rendering_area)
+   };
+}
+
+pub fn destroy_context(display: &Display, context: &Context) {
+   unsafe {
+      ffi::glXDestroyContext(display.ptr, context.ptr)
+   };
+}
+
 pub fn swap_buffers(
    display: &Display,
    rendering_area: ffi::GLXWindow,
@@ -254,3 +282,32 @@ pub fn swap_buffers(
 
    Ok(())
 }
+
+pub fn swap_interval(
+   display: &Display,
+   rendering_area: ffi::GLXWindow,
+   interval: c_int,
+) -> VoidResult {
+
+   unsafe {
+      if ffi::has_swap_interval_ext() {
+         ffi::glXSwapIntervalEXT(display.ptr, rendering_area, interval);
+         return Ok(());
+      }
+
+      if ffi::has_swap_interval_mesa() {
+         if ffi::glXSwapIntervalMESA(interval as ffi::c_uint) == 0 {
+            return Ok(());
+         }
+      } else if ffi::has_swap_interval_sgi() && interval > 0 {
+         if ffi::glXSwapIntervalSGI(interval) == 0 {
+            return Ok(());
+         }
+      }
+   }
+
+   Err(RuntimeError::new(
+      ErrorKind::GLX,
+      "Setting GLX swap interval failed".to_string()
+   ))
+}
@@ -113,6 +113,21 @@ impl Context {
       })
    }
 
+   pub fn make_current(&self, device_context: &win32::DeviceContext) -> VoidResult {
+      let result = unsafe {
+         ffi::wglMakeCurrent(device_context.hdc, self.rc)
+      };
+
+      if result != ffi::TRUE {
+         return Err(RuntimeError::new(
+            ErrorKind::WGL,
+            "wglMakeCurrent failed".to_string()
+         ));
+      }
+
+      Ok(())
+   }
+
    #[inline]
    pub fn current() -> Result<Self, RuntimeError> {
       let rc = unsafe { ffi::wglGetCurrentContext() };
@@ -146,6 +146,7 @@ pub const PM_NOREMOVE:                 c_uint = 0;
 pub const PM_REMOVE :                  c_uint = 1;
 pub const PM_NOYIELD:                  c_uint = 2;
 
+pub const SW_HIDE:                      c_int = 0;
 pub const SW_SHOWNORMAL:                c_int = 1;
 pub const SW_SHOWMINIMIZED:             c_int = 2;
 pub const SW_MAXIMIZE:                  c_int = 3;
@@ -111,6 +111,13 @@ impl Window {
       };
    }
 
+   #[inline]
+   pub fn hide(&self) {
+      unsafe {
+         ffi::ShowWindow(self.hwnd, ffi::SW_HIDE)
+      };
+   }
+
    #[inline]
    pub fn position(&self, x: i32, y: i32) {
       unsafe {
@@ -1,5 +1,6 @@
 use super::ffi;
 
+#[derive(Clone, Copy, PartialEq)]
 pub struct Atom {
    pub xcb_atom: ffi::xcb_atom_t
 }
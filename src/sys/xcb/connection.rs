@@ -1,7 +1,9 @@
 use std::ptr;
+use std::mem;
+use std::slice;
 use std::ffi::CString;
 
-use error::{RuntimeError, ErrorKind};
+use error::{RuntimeError, ErrorKind, VoidResult};
 
 use super::super::x11::ScreenID;
 
@@ -134,5 +136,238 @@ impl Connection {
 
       None
    }
+
+   pub fn maximum_request_length(&self) -> usize {
+      let length = unsafe {
+         ffi::xcb_get_maximum_request_length(self.ptr)
+      };
+
+      4 * length as usize
+   }
+
+   pub fn select_events(&self, window: ffi::xcb_window_t, eventmask: ffi::c_uint) -> VoidResult {
+      let valuelist = [eventmask, 0];
+
+      let cookie = unsafe {
+         ffi::xcb_change_window_attributes_checked(
+            self.ptr,
+            window,
+            ffi::XCB_CW_EVENT_MASK,
+            valuelist.as_ptr()
+         )
+      };
+
+      match self.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Selecting window events failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(())
+   }
+
+   pub fn change_property(
+      &self,
+      window: ffi::xcb_window_t,
+      property: ffi::xcb_atom_t,
+      property_type: ffi::xcb_atom_t,
+      format: ffi::c_uchar,
+      data: &[u8]
+   ) -> VoidResult {
+      let data_len = data.len() / (format as usize / 8);
+
+      let cookie = unsafe {
+         ffi::xcb_change_property_checked(
+            self.ptr,
+            ffi::XCB_PROP_MODE_REPLACE,
+            window,
+            property,
+            property_type,
+            format,
+            data_len as ffi::c_uint,
+            data.as_ptr() as *const _
+         )
+      };
+
+      match self.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Changing property failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(())
+   }
+
+   pub fn delete_property(&self, window: ffi::xcb_window_t, property: ffi::xcb_atom_t) {
+      unsafe {
+         ffi::xcb_delete_property(self.ptr, window, property);
+      }
+   }
+
+   pub fn get_property(
+      &self,
+      window: ffi::xcb_window_t,
+      property: ffi::xcb_atom_t,
+      delete: bool,
+      offset: usize,
+      length: usize
+   ) -> Result<GetPropertyReply, RuntimeError> {
+      let xcb_reply = unsafe {
+         let cookie = ffi::xcb_get_property(
+            self.ptr,
+            delete as ffi::c_uchar,
+            window,
+            property,
+            ffi::XCB_ATOM_ANY,
+            (offset / 4) as ffi::c_uint,
+            (length / 4) as ffi::c_uint
+         );
+
+         ffi::xcb_get_property_reply(self.ptr, cookie, ptr::null_mut())
+      };
+
+      if xcb_reply.is_null() {
+         return Err(RuntimeError::new(
+            ErrorKind::XCB,
+            "Getting property failed".to_string()
+         ));
+      }
+
+      Ok(GetPropertyReply {
+         xcb_reply: xcb_reply
+      })
+   }
+
+   pub fn set_selection_owner(
+      &self, owner: ffi::xcb_window_t, selection: ffi::xcb_atom_t
+   ) -> VoidResult {
+      let cookie = unsafe {
+         ffi::xcb_set_selection_owner_checked(
+            self.ptr, owner, selection, ffi::XCB_CURRENT_TIME
+         )
+      };
+
+      match self.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Setting selection owner failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(())
+   }
+
+   pub fn selection_owner(
+      &self, selection: ffi::xcb_atom_t
+   ) -> Result<ffi::xcb_window_t, RuntimeError> {
+      let xcb_reply = unsafe {
+         let cookie = ffi::xcb_get_selection_owner(self.ptr, selection);
+
+         ffi::xcb_get_selection_owner_reply(self.ptr, cookie, ptr::null_mut())
+      };
+
+      if xcb_reply.is_null() {
+         return Err(RuntimeError::new(
+            ErrorKind::XCB,
+            "Getting selection owner failed".to_string()
+         ));
+      }
+
+      let owner = unsafe { (*xcb_reply).owner };
+
+      unsafe {
+         ffi::free(xcb_reply as *mut _);
+      }
+
+      Ok(owner)
+   }
+
+   pub fn convert_selection(
+      &self,
+      requestor: ffi::xcb_window_t,
+      selection: ffi::xcb_atom_t,
+      target: ffi::xcb_atom_t,
+      property: ffi::xcb_atom_t
+   ) {
+      unsafe {
+         ffi::xcb_convert_selection(
+            self.ptr, requestor, selection, target, property, ffi::XCB_CURRENT_TIME
+         );
+      }
+   }
+
+   pub fn send_selection_notify(
+      &self,
+      requestor: ffi::xcb_window_t,
+      selection: ffi::xcb_atom_t,
+      target: ffi::xcb_atom_t,
+      property: ffi::xcb_atom_t,
+      time: ffi::xcb_timestamp_t
+   ) {
+      let mut event: ffi::xcb_selection_notify_event_t = Default::default();
+
+      event.response_type = ffi::XCB_SELECTION_NOTIFY;
+      event.time = time;
+      event.requestor = requestor;
+      event.selection = selection;
+      event.target = target;
+      event.property = property;
+
+      // X11 events sent over the wire are always 32 bytes long
+      let mut raw = [0 as ffi::c_char; 32];
+
+      unsafe {
+         ptr::copy_nonoverlapping(
+            &event as *const _ as *const ffi::c_char,
+            raw.as_mut_ptr(),
+            mem::size_of::<ffi::xcb_selection_notify_event_t>()
+         );
+
+         ffi::xcb_send_event(
+            self.ptr, 0, requestor, ffi::XCB_EVENT_MASK_NO_EVENT, raw.as_ptr()
+         );
+      }
+   }
+}
+
+pub struct GetPropertyReply {
+   pub xcb_reply: *mut ffi::xcb_get_property_reply_t
+}
+
+impl GetPropertyReply {
+   pub fn property_type(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.xcb_reply)._type }
+   }
+
+   pub fn bytes_after(&self) -> usize {
+      unsafe { (*self.xcb_reply).bytes_after as usize }
+   }
+
+   pub fn value(&self) -> &[u8] {
+      unsafe {
+         let length = ffi::xcb_get_property_value_length(self.xcb_reply);
+         let value = ffi::xcb_get_property_value(self.xcb_reply);
+
+         slice::from_raw_parts(value as *const u8, length as usize)
+      }
+   }
 }
 
+impl Drop for GetPropertyReply {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::free(self.xcb_reply as *mut _);
+      }
+   }
+}
@@ -0,0 +1,310 @@
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+use std::ffi::CString;
+
+use error::{RuntimeError, ErrorKind};
+
+use super::ffi;
+use super::connection::Connection;
+use super::XID;
+
+pub const XC_X_CURSOR: u16 = 0;
+pub const XC_CROSSHAIR: u16 = 34;
+pub const XC_FLEUR: u16 = 52;
+pub const XC_HAND2: u16 = 60;
+pub const XC_LEFT_PTR: u16 = 68;
+pub const XC_SB_H_DOUBLE_ARROW: u16 = 108;
+pub const XC_SB_V_DOUBLE_ARROW: u16 = 116;
+pub const XC_TOP_LEFT_CORNER: u16 = 134;
+pub const XC_TOP_RIGHT_CORNER: u16 = 136;
+pub const XC_WATCH: u16 = 150;
+pub const XC_XTERM: u16 = 152;
+
+
+pub struct Cursor {
+   pub connection: Rc<Connection>,
+   pub cursor_id: XID,
+}
+
+impl Cursor {
+   pub fn from_glyph(connection: &Rc<Connection>, glyph: u16) -> Result<Self, RuntimeError> {
+      let font_id = try!(connection.generate_id());
+
+      let c_name = try!(CString::new("cursor"));
+
+      let cookie = unsafe {
+         ffi::xcb_open_font_checked(
+            connection.ptr,
+            font_id.id,
+            6,
+            c_name.as_ptr()
+         )
+      };
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Opening cursor font failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      let cursor_id = try!(connection.generate_id());
+
+      let cookie = unsafe {
+         ffi::xcb_create_glyph_cursor_checked(
+            connection.ptr,
+            cursor_id.id,
+            font_id.id,
+            font_id.id,
+            glyph,
+            glyph + 1,
+            0, 0, 0,
+            0xffff, 0xffff, 0xffff
+         )
+      };
+
+      unsafe {
+         ffi::xcb_close_font(connection.ptr, font_id.id);
+      }
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Creating glyph cursor failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(Cursor {
+         connection: connection.clone(),
+         cursor_id: cursor_id,
+      })
+   }
+
+   pub fn from_rgba(
+      connection: &Rc<Connection>,
+      drawable: ffi::xcb_window_t,
+      width: u32,
+      height: u32,
+      hot_x: u32,
+      hot_y: u32,
+      pixels: &[u8],
+   ) -> Result<Self, RuntimeError> {
+      if width == 0 || height == 0 || pixels.len() != (4 * width * height) as usize {
+         return Err(RuntimeError::new(
+            ErrorKind::InvalidInput,
+            format!("Cursor image data does not match {}x{} RGBA", width, height)
+         ));
+      }
+
+      let format = try!(argb32_format(connection));
+
+      let pixmap_id = try!(connection.generate_id());
+
+      let cookie = unsafe {
+         ffi::xcb_create_pixmap_checked(
+            connection.ptr,
+            32,
+            pixmap_id.id,
+            drawable,
+            width as ffi::c_ushort,
+            height as ffi::c_ushort
+         )
+      };
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Creating cursor pixmap failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      let result = Self::from_pixmap(
+         connection, pixmap_id.id, format, width, height, hot_x, hot_y, pixels
+      );
+
+      unsafe {
+         ffi::xcb_free_pixmap(connection.ptr, pixmap_id.id);
+      }
+
+      result
+   }
+
+   fn from_pixmap(
+      connection: &Rc<Connection>,
+      pixmap: ffi::c_uint,
+      format: ffi::c_uint,
+      width: u32,
+      height: u32,
+      hot_x: u32,
+      hot_y: u32,
+      pixels: &[u8],
+   ) -> Result<Self, RuntimeError> {
+      let gc_id = try!(connection.generate_id());
+
+      unsafe {
+         ffi::xcb_create_gc(connection.ptr, gc_id.id, pixmap, 0, ptr::null());
+      }
+
+      let data = premultiplied_bgra(pixels);
+
+      let cookie = unsafe {
+         ffi::xcb_put_image_checked(
+            connection.ptr,
+            ffi::XCB_IMAGE_FORMAT_Z_PIXMAP,
+            pixmap,
+            gc_id.id,
+            width as ffi::c_ushort,
+            height as ffi::c_ushort,
+            0, 0,
+            0,
+            32,
+            data.len() as ffi::c_uint,
+            data.as_ptr()
+         )
+      };
+
+      unsafe {
+         ffi::xcb_free_gc(connection.ptr, gc_id.id);
+      }
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Uploading cursor image failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      let picture_id = try!(connection.generate_id());
+
+      let cookie = unsafe {
+         ffi::xcb_render_create_picture_checked(
+            connection.ptr, picture_id.id, pixmap, format, 0, ptr::null()
+         )
+      };
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Creating cursor picture failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      let cursor_id = try!(connection.generate_id());
+
+      let cookie = unsafe {
+         ffi::xcb_render_create_cursor_checked(
+            connection.ptr,
+            cursor_id.id,
+            picture_id.id,
+            hot_x as ffi::c_ushort,
+            hot_y as ffi::c_ushort
+         )
+      };
+
+      unsafe {
+         ffi::xcb_render_free_picture(connection.ptr, picture_id.id);
+      }
+
+      match connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Creating RGBA cursor failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(Cursor {
+         connection: connection.clone(),
+         cursor_id: cursor_id,
+      })
+   }
+}
+
+impl Drop for Cursor {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::xcb_free_cursor(
+            self.connection.ptr, self.cursor_id.id
+         );
+      }
+   }
+}
+
+fn argb32_format(connection: &Connection) -> Result<ffi::c_uint, RuntimeError> {
+   let reply = unsafe {
+      let cookie = ffi::xcb_render_query_pict_formats(connection.ptr);
+
+      ffi::xcb_render_query_pict_formats_reply(connection.ptr, cookie, ptr::null_mut())
+   };
+
+   if reply.is_null() {
+      return Err(RuntimeError::new(
+         ErrorKind::XCB,
+         "Querying render picture formats failed".to_string()
+      ));
+   }
+
+   let formats = unsafe {
+      slice::from_raw_parts(
+         ffi::xcb_render_query_pict_formats_formats(reply),
+         ffi::xcb_render_query_pict_formats_formats_length(reply) as usize
+      )
+   };
+
+   let found = formats.iter().find(|format| {
+      format._type == ffi::XCB_RENDER_PICT_TYPE_DIRECT &&
+      format.depth == 32 &&
+      format.direct.alpha_shift == 24 && format.direct.alpha_mask == 0xff &&
+      format.direct.red_shift == 16 && format.direct.red_mask == 0xff &&
+      format.direct.green_shift == 8 && format.direct.green_mask == 0xff &&
+      format.direct.blue_shift == 0 && format.direct.blue_mask == 0xff
+   }).map(|format| format.id);
+
+   unsafe {
+      ffi::free(reply as *mut _);
+   }
+
+   match found {
+      Some(id) => Ok(id),
+      None => Err(RuntimeError::new(
+         ErrorKind::XCB,
+         "No ARGB32 render picture format available".to_string()
+      ))
+   }
+}
+
+fn premultiplied_bgra(pixels: &[u8]) -> Vec<u8> {
+   let mut data = Vec::with_capacity(pixels.len());
+
+   for rgba in pixels.chunks(4) {
+      let alpha = rgba[3] as u32;
+
+      let argb =
+         (alpha << 24) |
+         ((rgba[0] as u32 * alpha / 255) << 16) |
+         ((rgba[1] as u32 * alpha / 255) << 8) |
+         (rgba[2] as u32 * alpha / 255);
+
+      data.extend_from_slice(&argb.to_ne_bytes());
+   }
+
+   data
+}
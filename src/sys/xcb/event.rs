@@ -21,6 +21,10 @@ pub enum EventType {
    Expose,
    ClientMessage,
    ConfigureNotify,
+   PropertyNotify,
+   SelectionClear,
+   SelectionRequest,
+   SelectionNotify,
 }
 
 impl EventType {
@@ -39,6 +43,10 @@ impl EventType {
          ffi::XCB_EXPOSE => EventType::Expose,
          ffi::XCB_CLIENT_MESSAGE => EventType::ClientMessage,
          ffi::XCB_CONFIGURE_NOTIFY => EventType::ConfigureNotify,
+         ffi::XCB_PROPERTY_NOTIFY => EventType::PropertyNotify,
+         ffi::XCB_SELECTION_CLEAR => EventType::SelectionClear,
+         ffi::XCB_SELECTION_REQUEST => EventType::SelectionRequest,
+         ffi::XCB_SELECTION_NOTIFY => EventType::SelectionNotify,
          _ => return None
       })
    }
@@ -60,6 +68,10 @@ impl fmt::Display for EventType {
          EventType::Expose => "Expose",
          EventType::ClientMessage => "ClientMessage",
          EventType::ConfigureNotify => "ConfigureNotify",
+         EventType::PropertyNotify => "PropertyNotify",
+         EventType::SelectionClear => "SelectionClear",
+         EventType::SelectionRequest => "SelectionRequest",
+         EventType::SelectionNotify => "SelectionNotify",
       };
 
       write!(f, "{}", type_str)
@@ -85,6 +97,31 @@ impl Event {
       )
    }
 
+   pub fn window_id(&self) -> Option<ffi::xcb_window_t> {
+      let event_type = match self.event_type() {
+         Some(event_type) => event_type,
+         None => return None
+      };
+
+      unsafe {
+         Some(match event_type {
+            EventType::ClientMessage => {
+               (*(self.ptr as *mut ffi::xcb_client_message_event_t)).window
+            },
+            EventType::ConfigureNotify => {
+               (*(self.ptr as *mut ffi::xcb_configure_notify_event_t)).window
+            },
+            EventType::MotionNotify => {
+               (*(self.ptr as *mut ffi::xcb_motion_notify_event_t)).event
+            },
+            EventType::ButtonPress | EventType::ButtonRelease => {
+               (*(self.ptr as *mut ffi::xcb_button_press_event_t)).event
+            },
+            _ => return None
+         })
+      }
+   }
+
    pub fn is_close_event(
       &self,
       protocols_atom: &Atom,
@@ -223,6 +260,166 @@ impl Drop for ButtonPressedEvent {
 
 pub type ButtonReleasedEvent = ButtonPressedEvent;
 
+impl Into<PropertyNotifyEvent> for Event {
+   fn into(mut self) -> PropertyNotifyEvent {
+      let ptr = self.ptr as *mut ffi::xcb_property_notify_event_t;
+
+      self.ptr = ptr::null_mut();
+
+      PropertyNotifyEvent {
+         ptr: ptr
+      }
+   }
+}
+
+impl Into<SelectionClearEvent> for Event {
+   fn into(mut self) -> SelectionClearEvent {
+      let ptr = self.ptr as *mut ffi::xcb_selection_clear_event_t;
+
+      self.ptr = ptr::null_mut();
+
+      SelectionClearEvent {
+         ptr: ptr
+      }
+   }
+}
+
+impl Into<SelectionRequestEvent> for Event {
+   fn into(mut self) -> SelectionRequestEvent {
+      let ptr = self.ptr as *mut ffi::xcb_selection_request_event_t;
+
+      self.ptr = ptr::null_mut();
+
+      SelectionRequestEvent {
+         ptr: ptr
+      }
+   }
+}
+
+impl Into<SelectionNotifyEvent> for Event {
+   fn into(mut self) -> SelectionNotifyEvent {
+      let ptr = self.ptr as *mut ffi::xcb_selection_notify_event_t;
+
+      self.ptr = ptr::null_mut();
+
+      SelectionNotifyEvent {
+         ptr: ptr
+      }
+   }
+}
+
+pub struct PropertyNotifyEvent {
+   pub ptr: *mut ffi::xcb_property_notify_event_t
+}
+
+impl PropertyNotifyEvent {
+   pub fn window(&self) -> ffi::xcb_window_t {
+      unsafe { (*self.ptr).window }
+   }
+
+   pub fn atom(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).atom }
+   }
+
+   pub fn is_deleted(&self) -> bool {
+      unsafe { (*self.ptr).state == ffi::XCB_PROPERTY_DELETE }
+   }
+}
+
+impl Drop for PropertyNotifyEvent {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::free(self.ptr as *mut _);
+      }
+   }
+}
+
+pub struct SelectionClearEvent {
+   pub ptr: *mut ffi::xcb_selection_clear_event_t
+}
+
+impl SelectionClearEvent {
+   pub fn owner(&self) -> ffi::xcb_window_t {
+      unsafe { (*self.ptr).owner }
+   }
+
+   pub fn selection(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).selection }
+   }
+}
+
+impl Drop for SelectionClearEvent {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::free(self.ptr as *mut _);
+      }
+   }
+}
+
+pub struct SelectionRequestEvent {
+   pub ptr: *mut ffi::xcb_selection_request_event_t
+}
+
+impl SelectionRequestEvent {
+   pub fn time(&self) -> ffi::xcb_timestamp_t {
+      unsafe { (*self.ptr).time }
+   }
+
+   pub fn owner(&self) -> ffi::xcb_window_t {
+      unsafe { (*self.ptr).owner }
+   }
+
+   pub fn requestor(&self) -> ffi::xcb_window_t {
+      unsafe { (*self.ptr).requestor }
+   }
+
+   pub fn selection(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).selection }
+   }
+
+   pub fn target(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).target }
+   }
+
+   pub fn property(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).property }
+   }
+}
+
+impl Drop for SelectionRequestEvent {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::free(self.ptr as *mut _);
+      }
+   }
+}
+
+pub struct SelectionNotifyEvent {
+   pub ptr: *mut ffi::xcb_selection_notify_event_t
+}
+
+impl SelectionNotifyEvent {
+   pub fn requestor(&self) -> ffi::xcb_window_t {
+      unsafe { (*self.ptr).requestor }
+   }
+
+   pub fn selection(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).selection }
+   }
+
+   pub fn property(&self) -> ffi::xcb_atom_t {
+      unsafe { (*self.ptr).property }
+   }
+}
+
+impl Drop for SelectionNotifyEvent {
+   fn drop (&mut self) {
+      unsafe {
+         ffi::free(self.ptr as *mut _);
+      }
+   }
+}
+
 pub struct EventIterator {
    ptr: *mut ffi::xcb_connection_t,
    started: bool,
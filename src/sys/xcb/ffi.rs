@@ -77,6 +77,30 @@ pub const XCB_PROP_MODE_REPLACE:                c_uchar = 0;
 pub const XCB_PROP_MODE_PREPEND:                c_uchar = 1;
 pub const XCB_PROP_MODE_APPEND:                 c_uchar = 2;
 
+pub const XCB_PROPERTY_NEW_VALUE:               c_uchar = 0;
+pub const XCB_PROPERTY_DELETE:                  c_uchar = 1;
+
+pub const XCB_CURRENT_TIME:                      c_uint = 0;
+
+pub const XCB_NONE:                              c_uint = 0;
+
+pub const XCB_IMAGE_FORMAT_Z_PIXMAP:            c_uchar = 2;
+
+pub const XCB_GRAB_MODE_ASYNC:                  c_uchar = 1;
+
+pub const XCB_GRAB_STATUS_SUCCESS:              c_uchar = 0;
+pub const XCB_GRAB_STATUS_ALREADY_GRABBED:      c_uchar = 1;
+pub const XCB_GRAB_STATUS_INVALID_TIME:         c_uchar = 2;
+pub const XCB_GRAB_STATUS_NOT_VIEWABLE:         c_uchar = 3;
+pub const XCB_GRAB_STATUS_FROZEN:               c_uchar = 4;
+
+pub const XCB_RENDER_PICT_TYPE_DIRECT:          c_uchar = 1;
+
+pub const XCB_RANDR_CONNECTION_CONNECTED:       c_uchar = 0;
+
+pub const XCB_RANDR_MODE_FLAG_INTERLACE:         c_uint = 16;
+pub const XCB_RANDR_MODE_FLAG_DOUBLE_SCAN:       c_uint = 32;
+
 pub const XCB_KEY_PRESS:                        c_uchar = 2;
 pub const XCB_KEY_RELEASE:                      c_uchar = 3;
 pub const XCB_BUTTON_PRESS:                     c_uchar = 4;
@@ -484,6 +508,431 @@ impl Default for xcb_query_pointer_cookie_t {
    fn default() -> Self { unsafe { mem::zeroed() } }
 }
 
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_property_notify_event_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub window: xcb_window_t,
+   pub atom: xcb_atom_t,
+   pub time: xcb_timestamp_t,
+   pub state: c_uchar,
+   pub pad1: [c_uchar; 3usize],
+}
+impl Clone for xcb_property_notify_event_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_property_notify_event_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_selection_clear_event_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub time: xcb_timestamp_t,
+   pub owner: xcb_window_t,
+   pub selection: xcb_atom_t,
+}
+impl Clone for xcb_selection_clear_event_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_selection_clear_event_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_selection_request_event_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub time: xcb_timestamp_t,
+   pub owner: xcb_window_t,
+   pub requestor: xcb_window_t,
+   pub selection: xcb_atom_t,
+   pub target: xcb_atom_t,
+   pub property: xcb_atom_t,
+}
+impl Clone for xcb_selection_request_event_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_selection_request_event_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_selection_notify_event_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub time: xcb_timestamp_t,
+   pub requestor: xcb_window_t,
+   pub selection: xcb_atom_t,
+   pub target: xcb_atom_t,
+   pub property: xcb_atom_t,
+}
+impl Clone for xcb_selection_notify_event_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_selection_notify_event_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_get_property_reply_t {
+   pub response_type: c_uchar,
+   pub format: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub _type: xcb_atom_t,
+   pub bytes_after: c_uint,
+   pub value_len: c_uint,
+   pub pad0: [c_uchar; 12usize],
+}
+impl Clone for xcb_get_property_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_get_property_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_get_property_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_get_property_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_get_property_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_get_selection_owner_reply_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub owner: xcb_window_t,
+}
+impl Clone for xcb_get_selection_owner_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_get_selection_owner_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_get_selection_owner_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_get_selection_owner_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_get_selection_owner_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_grab_pointer_reply_t {
+   pub response_type: c_uchar,
+   pub status: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+}
+impl Clone for xcb_grab_pointer_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_grab_pointer_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_grab_pointer_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_grab_pointer_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_grab_pointer_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_render_directformat_t {
+   pub red_shift: c_ushort,
+   pub red_mask: c_ushort,
+   pub green_shift: c_ushort,
+   pub green_mask: c_ushort,
+   pub blue_shift: c_ushort,
+   pub blue_mask: c_ushort,
+   pub alpha_shift: c_ushort,
+   pub alpha_mask: c_ushort,
+}
+impl Clone for xcb_render_directformat_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_render_directformat_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_render_pictforminfo_t {
+   pub id: c_uint,
+   pub _type: c_uchar,
+   pub depth: c_uchar,
+   pub pad0: [c_uchar; 2usize],
+   pub direct: xcb_render_directformat_t,
+   pub colormap: xcb_colormap_t,
+}
+impl Clone for xcb_render_pictforminfo_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_render_pictforminfo_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_render_query_pict_formats_reply_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub num_formats: c_uint,
+   pub num_screens: c_uint,
+   pub num_depths: c_uint,
+   pub num_visuals: c_uint,
+   pub num_subpixel: c_uint,
+   pub pad1: [c_uchar; 4usize],
+}
+impl Clone for xcb_render_query_pict_formats_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_render_query_pict_formats_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_render_query_pict_formats_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_render_query_pict_formats_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_render_query_pict_formats_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_translate_coordinates_reply_t {
+   pub response_type: c_uchar,
+   pub same_screen: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub child: xcb_window_t,
+   pub dst_x: c_short,
+   pub dst_y: c_short,
+}
+impl Clone for xcb_translate_coordinates_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_translate_coordinates_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_translate_coordinates_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_translate_coordinates_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_translate_coordinates_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+pub type xcb_randr_output_t = c_uint;
+pub type xcb_randr_crtc_t = c_uint;
+pub type xcb_randr_mode_t = c_uint;
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_mode_info_t {
+   pub id: c_uint,
+   pub width: c_ushort,
+   pub height: c_ushort,
+   pub dot_clock: c_uint,
+   pub hsync_start: c_ushort,
+   pub hsync_end: c_ushort,
+   pub htotal: c_ushort,
+   pub hskew: c_ushort,
+   pub vsync_start: c_ushort,
+   pub vsync_end: c_ushort,
+   pub vtotal: c_ushort,
+   pub name_len: c_ushort,
+   pub mode_flags: c_uint,
+}
+impl Clone for xcb_randr_mode_info_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_mode_info_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_screen_resources_current_reply_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub timestamp: xcb_timestamp_t,
+   pub config_timestamp: xcb_timestamp_t,
+   pub num_crtcs: c_ushort,
+   pub num_outputs: c_ushort,
+   pub num_modes: c_ushort,
+   pub names_len: c_ushort,
+   pub pad1: [c_uchar; 8usize],
+}
+impl Clone for xcb_randr_get_screen_resources_current_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_screen_resources_current_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_screen_resources_current_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_randr_get_screen_resources_current_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_screen_resources_current_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_output_info_reply_t {
+   pub response_type: c_uchar,
+   pub status: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub timestamp: xcb_timestamp_t,
+   pub crtc: xcb_randr_crtc_t,
+   pub mm_width: c_uint,
+   pub mm_height: c_uint,
+   pub connection: c_uchar,
+   pub subpixel_order: c_uchar,
+   pub num_crtcs: c_ushort,
+   pub num_modes: c_ushort,
+   pub num_preferred: c_ushort,
+   pub num_clones: c_ushort,
+   pub name_len: c_ushort,
+}
+impl Clone for xcb_randr_get_output_info_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_output_info_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_output_info_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_randr_get_output_info_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_output_info_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_crtc_info_reply_t {
+   pub response_type: c_uchar,
+   pub status: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub timestamp: xcb_timestamp_t,
+   pub x: c_short,
+   pub y: c_short,
+   pub width: c_ushort,
+   pub height: c_ushort,
+   pub mode: xcb_randr_mode_t,
+   pub rotation: c_ushort,
+   pub rotations: c_ushort,
+   pub num_outputs: c_ushort,
+   pub num_possible_outputs: c_ushort,
+}
+impl Clone for xcb_randr_get_crtc_info_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_crtc_info_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_crtc_info_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_randr_get_crtc_info_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_crtc_info_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_output_primary_reply_t {
+   pub response_type: c_uchar,
+   pub pad0: c_uchar,
+   pub sequence: c_ushort,
+   pub length: c_uint,
+   pub output: xcb_randr_output_t,
+}
+impl Clone for xcb_randr_get_output_primary_reply_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_output_primary_reply_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
+#[repr(C)]
+#[derive(Copy)]
+pub struct xcb_randr_get_output_primary_cookie_t {
+   pub sequence: c_uint,
+}
+impl Clone for xcb_randr_get_output_primary_cookie_t {
+   fn clone(&self) -> Self { *self }
+}
+impl Default for xcb_randr_get_output_primary_cookie_t {
+   fn default() -> Self { unsafe { mem::zeroed() } }
+}
+
 #[link(name="xcb")]
 extern "C" {
    pub fn xcb_get_setup(
@@ -528,6 +977,11 @@ extern "C" {
       window: xcb_window_t
    ) -> xcb_void_cookie_t;
 
+   pub fn xcb_unmap_window_checked(
+      c: *mut xcb_connection_t,
+      window: xcb_window_t
+   ) -> xcb_void_cookie_t;
+
    pub fn xcb_configure_window_checked(
       c: *mut xcb_connection_t,
       window: xcb_window_t,
@@ -586,6 +1040,193 @@ extern "C" {
       e: *mut *mut xcb_generic_error_t
    ) -> *mut xcb_query_pointer_reply_t;
 
+   pub fn xcb_change_window_attributes_checked(
+      c: *mut xcb_connection_t,
+      window: xcb_window_t,
+      value_mask: c_uint,
+      value_list: *const c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_delete_property(
+      c: *mut xcb_connection_t,
+      window: xcb_window_t,
+      property: xcb_atom_t
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_get_property(
+      c: *mut xcb_connection_t,
+      _delete: c_uchar,
+      window: xcb_window_t,
+      property: xcb_atom_t,
+      _type: xcb_atom_t,
+      long_offset: c_uint,
+      long_length: c_uint
+   ) -> xcb_get_property_cookie_t;
+
+   pub fn xcb_get_property_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_get_property_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_get_property_reply_t;
+
+   pub fn xcb_get_property_value(
+      R: *const xcb_get_property_reply_t
+   ) -> *mut c_void;
+
+   pub fn xcb_get_property_value_length(
+      R: *const xcb_get_property_reply_t
+   ) -> c_int;
+
+   pub fn xcb_set_selection_owner_checked(
+      c: *mut xcb_connection_t,
+      owner: xcb_window_t,
+      selection: xcb_atom_t,
+      time: xcb_timestamp_t
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_get_selection_owner(
+      c: *mut xcb_connection_t,
+      selection: xcb_atom_t
+   ) -> xcb_get_selection_owner_cookie_t;
+
+   pub fn xcb_get_selection_owner_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_get_selection_owner_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_get_selection_owner_reply_t;
+
+   pub fn xcb_convert_selection(
+      c: *mut xcb_connection_t,
+      requestor: xcb_window_t,
+      selection: xcb_atom_t,
+      target: xcb_atom_t,
+      property: xcb_atom_t,
+      time: xcb_timestamp_t
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_send_event(
+      c: *mut xcb_connection_t,
+      propagate: c_uchar,
+      destination: xcb_window_t,
+      event_mask: c_uint,
+      event: *const c_char
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_get_maximum_request_length(
+      c: *mut xcb_connection_t
+   ) -> c_uint;
+
+   pub fn xcb_open_font_checked(
+      c: *mut xcb_connection_t,
+      fid: c_uint,
+      name_len: c_ushort,
+      name: *const c_char
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_close_font(
+      c: *mut xcb_connection_t,
+      font: c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_create_glyph_cursor_checked(
+      c: *mut xcb_connection_t,
+      cid: c_uint,
+      source_font: c_uint,
+      mask_font: c_uint,
+      source_char: c_ushort,
+      mask_char: c_ushort,
+      fore_red: c_ushort,
+      fore_green: c_ushort,
+      fore_blue: c_ushort,
+      back_red: c_ushort,
+      back_green: c_ushort,
+      back_blue: c_ushort
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_free_cursor(
+      c: *mut xcb_connection_t,
+      cursor: c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_create_pixmap_checked(
+      c: *mut xcb_connection_t,
+      depth: c_uchar,
+      pid: c_uint,
+      drawable: c_uint,
+      width: c_ushort,
+      height: c_ushort
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_free_pixmap(
+      c: *mut xcb_connection_t,
+      pixmap: c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_create_gc(
+      c: *mut xcb_connection_t,
+      cid: c_uint,
+      drawable: c_uint,
+      value_mask: c_uint,
+      value_list: *const c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_free_gc(
+      c: *mut xcb_connection_t,
+      gc: c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_put_image_checked(
+      c: *mut xcb_connection_t,
+      format: c_uchar,
+      drawable: c_uint,
+      gc: c_uint,
+      width: c_ushort,
+      height: c_ushort,
+      dst_x: c_short,
+      dst_y: c_short,
+      left_pad: c_uchar,
+      depth: c_uchar,
+      data_len: c_uint,
+      data: *const c_uchar
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_grab_pointer(
+      c: *mut xcb_connection_t,
+      owner_events: c_uchar,
+      grab_window: xcb_window_t,
+      event_mask: c_ushort,
+      pointer_mode: c_uchar,
+      keyboard_mode: c_uchar,
+      confine_to: xcb_window_t,
+      cursor: c_uint,
+      time: xcb_timestamp_t
+   ) -> xcb_grab_pointer_cookie_t;
+
+   pub fn xcb_grab_pointer_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_grab_pointer_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_grab_pointer_reply_t;
+
+   pub fn xcb_ungrab_pointer(
+      c: *mut xcb_connection_t,
+      time: xcb_timestamp_t
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_translate_coordinates(
+      c: *mut xcb_connection_t,
+      src_window: xcb_window_t,
+      dst_window: xcb_window_t,
+      src_x: c_short,
+      src_y: c_short
+   ) -> xcb_translate_coordinates_cookie_t;
+
+   pub fn xcb_translate_coordinates_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_translate_coordinates_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_translate_coordinates_reply_t;
+
    pub fn xcb_connection_has_error(
       c: *mut xcb_connection_t
    ) -> c_int;
@@ -595,3 +1236,119 @@ extern "C" {
       cookie: xcb_void_cookie_t
    ) -> *mut xcb_generic_error_t;
 }
+
+#[link(name="xcb-render")]
+extern "C" {
+   pub fn xcb_render_query_pict_formats(
+      c: *mut xcb_connection_t
+   ) -> xcb_render_query_pict_formats_cookie_t;
+
+   pub fn xcb_render_query_pict_formats_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_render_query_pict_formats_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_render_query_pict_formats_reply_t;
+
+   pub fn xcb_render_query_pict_formats_formats(
+      R: *const xcb_render_query_pict_formats_reply_t
+   ) -> *mut xcb_render_pictforminfo_t;
+
+   pub fn xcb_render_query_pict_formats_formats_length(
+      R: *const xcb_render_query_pict_formats_reply_t
+   ) -> c_int;
+
+   pub fn xcb_render_create_picture_checked(
+      c: *mut xcb_connection_t,
+      pid: c_uint,
+      drawable: c_uint,
+      format: c_uint,
+      value_mask: c_uint,
+      value_list: *const c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_render_free_picture(
+      c: *mut xcb_connection_t,
+      picture: c_uint
+   ) -> xcb_void_cookie_t;
+
+   pub fn xcb_render_create_cursor_checked(
+      c: *mut xcb_connection_t,
+      cid: c_uint,
+      source: c_uint,
+      x: c_ushort,
+      y: c_ushort
+   ) -> xcb_void_cookie_t;
+}
+
+#[link(name="xcb-randr")]
+extern "C" {
+   pub fn xcb_randr_get_screen_resources_current(
+      c: *mut xcb_connection_t,
+      window: xcb_window_t
+   ) -> xcb_randr_get_screen_resources_current_cookie_t;
+
+   pub fn xcb_randr_get_screen_resources_current_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_randr_get_screen_resources_current_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_randr_get_screen_resources_current_reply_t;
+
+   pub fn xcb_randr_get_screen_resources_current_outputs(
+      R: *const xcb_randr_get_screen_resources_current_reply_t
+   ) -> *mut xcb_randr_output_t;
+
+   pub fn xcb_randr_get_screen_resources_current_outputs_length(
+      R: *const xcb_randr_get_screen_resources_current_reply_t
+   ) -> c_int;
+
+   pub fn xcb_randr_get_screen_resources_current_modes(
+      R: *const xcb_randr_get_screen_resources_current_reply_t
+   ) -> *mut xcb_randr_mode_info_t;
+
+   pub fn xcb_randr_get_screen_resources_current_modes_length(
+      R: *const xcb_randr_get_screen_resources_current_reply_t
+   ) -> c_int;
+
+   pub fn xcb_randr_get_output_info(
+      c: *mut xcb_connection_t,
+      output: xcb_randr_output_t,
+      config_timestamp: xcb_timestamp_t
+   ) -> xcb_randr_get_output_info_cookie_t;
+
+   pub fn xcb_randr_get_output_info_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_randr_get_output_info_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_randr_get_output_info_reply_t;
+
+   pub fn xcb_randr_get_output_info_name(
+      R: *const xcb_randr_get_output_info_reply_t
+   ) -> *mut c_uchar;
+
+   pub fn xcb_randr_get_output_info_name_length(
+      R: *const xcb_randr_get_output_info_reply_t
+   ) -> c_int;
+
+   pub fn xcb_randr_get_crtc_info(
+      c: *mut xcb_connection_t,
+      crtc: xcb_randr_crtc_t,
+      config_timestamp: xcb_timestamp_t
+   ) -> xcb_randr_get_crtc_info_cookie_t;
+
+   pub fn xcb_randr_get_crtc_info_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_randr_get_crtc_info_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_randr_get_crtc_info_reply_t;
+
+   pub fn xcb_randr_get_output_primary(
+      c: *mut xcb_connection_t,
+      window: xcb_window_t
+   ) -> xcb_randr_get_output_primary_cookie_t;
+
+   pub fn xcb_randr_get_output_primary_reply(
+      c: *mut xcb_connection_t,
+      cookie: xcb_randr_get_output_primary_cookie_t,
+      e: *mut *mut xcb_generic_error_t
+   ) -> *mut xcb_randr_get_output_primary_reply_t;
+}
@@ -6,14 +6,19 @@ pub mod connection;
 pub mod screen;
 pub mod event;
 pub mod window;
+pub mod cursor;
+pub mod randr;
 
 pub use self::connection::Connection;
 pub use self::screen::Screen;
 pub use self::window::Window;
 pub use self::atom::Atom;
+pub use self::cursor::Cursor;
+pub use self::connection::GetPropertyReply;
 pub use self::event::{
    Event, EventType, EventIterator, ResizedEvent, MouseMovedEvent,
-   ButtonPressedEvent, ButtonReleasedEvent, MouseButton,
+   ButtonPressedEvent, ButtonReleasedEvent, MouseButton, PropertyNotifyEvent,
+   SelectionClearEvent, SelectionRequestEvent, SelectionNotifyEvent,
 };
 
 #[derive(PartialEq)]
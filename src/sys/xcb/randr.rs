@@ -0,0 +1,195 @@
+use std::ptr;
+use std::slice;
+
+use error::{RuntimeError, ErrorKind};
+
+use super::ffi;
+use super::connection::Connection;
+
+
+#[derive(Clone, Debug)]
+pub struct Output {
+   pub name: String,
+   pub x: i32,
+   pub y: i32,
+   pub width: u32,
+   pub height: u32,
+   pub width_mm: u32,
+   pub height_mm: u32,
+   pub refresh_rate: f64,
+   pub primary: bool,
+}
+
+pub fn outputs(
+   connection: &Connection, root: ffi::xcb_window_t
+) -> Result<Vec<Output>, RuntimeError> {
+   let resources = unsafe {
+      let cookie = ffi::xcb_randr_get_screen_resources_current(connection.ptr, root);
+
+      ffi::xcb_randr_get_screen_resources_current_reply(
+         connection.ptr, cookie, ptr::null_mut()
+      )
+   };
+
+   if resources.is_null() {
+      return Err(RuntimeError::new(
+         ErrorKind::XCB,
+         "Getting RandR screen resources failed".to_string()
+      ));
+   }
+
+   let primary = primary_output(connection, root);
+
+   let (output_ids, modes) = unsafe {(
+      slice::from_raw_parts(
+         ffi::xcb_randr_get_screen_resources_current_outputs(resources),
+         ffi::xcb_randr_get_screen_resources_current_outputs_length(resources) as usize
+      ),
+      slice::from_raw_parts(
+         ffi::xcb_randr_get_screen_resources_current_modes(resources),
+         ffi::xcb_randr_get_screen_resources_current_modes_length(resources) as usize
+      )
+   )};
+
+   let config_timestamp = unsafe { (*resources).config_timestamp };
+
+   let mut outputs = Vec::new();
+
+   for output_id in output_ids {
+      match output(connection, *output_id, config_timestamp, modes) {
+         Some(mut output) => {
+            output.primary = primary == Some(*output_id);
+            outputs.push(output);
+         },
+         None => {}
+      }
+   }
+
+   unsafe {
+      ffi::free(resources as *mut _);
+   }
+
+   Ok(outputs)
+}
+
+fn output(
+   connection: &Connection,
+   output_id: ffi::xcb_randr_output_t,
+   config_timestamp: ffi::xcb_timestamp_t,
+   modes: &[ffi::xcb_randr_mode_info_t]
+) -> Option<Output> {
+   let output_info = unsafe {
+      let cookie = ffi::xcb_randr_get_output_info(
+         connection.ptr, output_id, config_timestamp
+      );
+
+      ffi::xcb_randr_get_output_info_reply(connection.ptr, cookie, ptr::null_mut())
+   };
+
+   if output_info.is_null() {
+      return None;
+   }
+
+   let (connected, crtc, width_mm, height_mm, name) = unsafe {
+      let name = slice::from_raw_parts(
+         ffi::xcb_randr_get_output_info_name(output_info),
+         ffi::xcb_randr_get_output_info_name_length(output_info) as usize
+      );
+
+      (
+         (*output_info).connection == ffi::XCB_RANDR_CONNECTION_CONNECTED,
+         (*output_info).crtc,
+         (*output_info).mm_width,
+         (*output_info).mm_height,
+         String::from_utf8_lossy(name).into_owned()
+      )
+   };
+
+   unsafe {
+      ffi::free(output_info as *mut _);
+   }
+
+   // Disconnected or disabled outputs are not monitors
+   if !connected || crtc == ffi::XCB_NONE {
+      return None;
+   }
+
+   let crtc_info = unsafe {
+      let cookie = ffi::xcb_randr_get_crtc_info(connection.ptr, crtc, config_timestamp);
+
+      ffi::xcb_randr_get_crtc_info_reply(connection.ptr, cookie, ptr::null_mut())
+   };
+
+   if crtc_info.is_null() {
+      return None;
+   }
+
+   let crtc_info_copy = unsafe { *crtc_info };
+
+   unsafe {
+      ffi::free(crtc_info as *mut _);
+   }
+
+   let refresh_rate = match modes.iter().find(|mode| mode.id == crtc_info_copy.mode) {
+      Some(mode) => refresh_rate(mode),
+      None => 0.
+   };
+
+   Some(Output {
+      name: name,
+      x: crtc_info_copy.x as i32,
+      y: crtc_info_copy.y as i32,
+      width: crtc_info_copy.width as u32,
+      height: crtc_info_copy.height as u32,
+      width_mm: width_mm,
+      height_mm: height_mm,
+      refresh_rate: refresh_rate,
+      primary: false,
+   })
+}
+
+fn primary_output(
+   connection: &Connection, root: ffi::xcb_window_t
+) -> Option<ffi::xcb_randr_output_t> {
+   let reply = unsafe {
+      let cookie = ffi::xcb_randr_get_output_primary(connection.ptr, root);
+
+      ffi::xcb_randr_get_output_primary_reply(connection.ptr, cookie, ptr::null_mut())
+   };
+
+   if reply.is_null() {
+      return None;
+   }
+
+   let output = unsafe { (*reply).output };
+
+   unsafe {
+      ffi::free(reply as *mut _);
+   }
+
+   if output == ffi::XCB_NONE {
+      None
+   } else {
+      Some(output)
+   }
+}
+
+fn refresh_rate(mode: &ffi::xcb_randr_mode_info_t) -> f64 {
+   let mut vtotal = mode.vtotal as f64;
+
+   if mode.mode_flags & ffi::XCB_RANDR_MODE_FLAG_DOUBLE_SCAN != 0 {
+      vtotal *= 2.;
+   }
+
+   if mode.mode_flags & ffi::XCB_RANDR_MODE_FLAG_INTERLACE != 0 {
+      vtotal /= 2.;
+   }
+
+   let total = mode.htotal as f64 * vtotal;
+
+   if total == 0. {
+      return 0.;
+   }
+
+   mode.dot_clock as f64 / total
+}
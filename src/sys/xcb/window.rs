@@ -9,6 +9,7 @@ use super::connection::Connection;
 use super::XID;
 use super::screen::Screen;
 use super::atom::Atom;
+use super::cursor::Cursor;
 
 
 pub struct Window {
@@ -23,11 +24,6 @@ impl Window {
       width: u32,
       height: u32,
    ) -> Result<Self, RuntimeError> {
-      let window_id = match connection.generate_id() {
-         Ok(window_id) => window_id,
-         Err(e) => return Err(e)
-      };
-
       let eventmask =
          ffi::XCB_EVENT_MASK_STRUCTURE_NOTIFY |
          ffi::XCB_EVENT_MASK_KEY_PRESS |
@@ -35,6 +31,36 @@ impl Window {
          ffi::XCB_EVENT_MASK_BUTTON_RELEASE |
          ffi::XCB_EVENT_MASK_BUTTON_MOTION |
          ffi::XCB_EVENT_MASK_POINTER_MOTION;
+
+      Self::create_with_class(
+         connection, screen, ffi::XCB_WINDOW_CLASS_INPUT_OUTPUT,
+         width, height, eventmask
+      )
+   }
+
+   pub fn create_hidden(
+      connection: &Rc<Connection>,
+      screen: &Screen,
+   ) -> Result<Self, RuntimeError> {
+      Self::create_with_class(
+         connection, screen, ffi::XCB_WINDOW_CLASS_INPUT_ONLY,
+         1, 1, ffi::XCB_EVENT_MASK_PROPERTY_CHANGE
+      )
+   }
+
+   fn create_with_class(
+      connection: &Rc<Connection>,
+      screen: &Screen,
+      class: ffi::c_uint,
+      width: u32,
+      height: u32,
+      eventmask: ffi::c_uint,
+   ) -> Result<Self, RuntimeError> {
+      let window_id = match connection.generate_id() {
+         Ok(window_id) => window_id,
+         Err(e) => return Err(e)
+      };
+
       let valuelist = [eventmask, 0];
       let valuemask = ffi::XCB_CW_EVENT_MASK;
 
@@ -47,7 +73,7 @@ impl Window {
             0, 0,
             width as ffi::c_ushort, height as ffi::c_ushort,
             0,
-            ffi::XCB_WINDOW_CLASS_INPUT_OUTPUT as u16,
+            class as u16,
             screen.root_visual(),
             valuemask,
             valuelist.as_ptr()
@@ -88,6 +114,24 @@ impl Window {
       Ok(())
    }
 
+   pub fn unmap(&self) -> VoidResult {
+      let cookie = unsafe {
+         ffi::xcb_unmap_window_checked(self.connection.ptr, self.window_id.id)
+      };
+
+      match self.connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Unmapping XCB window failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(())
+   }
+
    pub fn position(&self, x: i32, y: i32) -> VoidResult {
       let value_mask = ffi::XCB_CONFIG_WINDOW_X | ffi::XCB_CONFIG_WINDOW_Y;
       let value_list = [x as ffi::c_uint, y as ffi::c_uint, 0];
@@ -179,6 +223,123 @@ impl Window {
       Ok(())
    }
 
+   pub fn set_cursor(&self, cursor: &Cursor) -> VoidResult {
+      let valuelist = [cursor.cursor_id.id, 0];
+
+      let cookie = unsafe {
+         ffi::xcb_change_window_attributes_checked(
+            self.connection.ptr,
+            self.window_id.id,
+            ffi::XCB_CW_CURSOR,
+            valuelist.as_ptr()
+         )
+      };
+
+      match self.connection.error_check(cookie) {
+         Some(error_code) => {
+            return Err(RuntimeError::new(
+               ErrorKind::XCB,
+               format!("Setting window cursor failed: {}", error_code)
+            ));
+         },
+         None => {}
+      }
+
+      Ok(())
+   }
+
+   pub fn grab_pointer(&self, confine: bool) -> VoidResult {
+      let eventmask =
+         ffi::XCB_EVENT_MASK_BUTTON_PRESS |
+         ffi::XCB_EVENT_MASK_BUTTON_RELEASE |
+         ffi::XCB_EVENT_MASK_BUTTON_MOTION |
+         ffi::XCB_EVENT_MASK_POINTER_MOTION;
+
+      let confine_to = if confine {
+         self.window_id.id
+      } else {
+         ffi::XCB_NONE
+      };
+
+      let xcb_reply = unsafe {
+         let cookie = ffi::xcb_grab_pointer(
+            self.connection.ptr,
+            1,
+            self.window_id.id,
+            eventmask as ffi::c_ushort,
+            ffi::XCB_GRAB_MODE_ASYNC,
+            ffi::XCB_GRAB_MODE_ASYNC,
+            confine_to,
+            ffi::XCB_NONE,
+            ffi::XCB_CURRENT_TIME
+         );
+
+         ffi::xcb_grab_pointer_reply(self.connection.ptr, cookie, ptr::null_mut())
+      };
+
+      if xcb_reply.is_null() {
+         return Err(RuntimeError::new(
+            ErrorKind::XCB,
+            "Grabbing pointer failed".to_string()
+         ));
+      }
+
+      let status = unsafe { (*xcb_reply).status };
+
+      unsafe {
+         ffi::free(xcb_reply as *mut _);
+      }
+
+      let reason = match status {
+         ffi::XCB_GRAB_STATUS_SUCCESS => return Ok(()),
+         ffi::XCB_GRAB_STATUS_ALREADY_GRABBED => "already grabbed",
+         ffi::XCB_GRAB_STATUS_INVALID_TIME => "invalid time",
+         ffi::XCB_GRAB_STATUS_NOT_VIEWABLE => "window not viewable",
+         ffi::XCB_GRAB_STATUS_FROZEN => "pointer frozen",
+         _ => "unknown status",
+      };
+
+      Err(RuntimeError::new(
+         ErrorKind::XCB,
+         format!("Grabbing pointer failed: {}", reason)
+      ))
+   }
+
+   pub fn ungrab_pointer(&self) {
+      unsafe {
+         ffi::xcb_ungrab_pointer(self.connection.ptr, ffi::XCB_CURRENT_TIME);
+      }
+
+      self.connection.flush();
+   }
+
+   pub fn root_position(&self, root: ffi::xcb_window_t) -> Result<(i32, i32), RuntimeError> {
+      let xcb_reply = unsafe {
+         let cookie = ffi::xcb_translate_coordinates(
+            self.connection.ptr, self.window_id.id, root, 0, 0
+         );
+
+         ffi::xcb_translate_coordinates_reply(self.connection.ptr, cookie, ptr::null_mut())
+      };
+
+      if xcb_reply.is_null() {
+         return Err(RuntimeError::new(
+            ErrorKind::XCB,
+            "Translating window coordinates failed".to_string()
+         ));
+      }
+
+      let position = unsafe {
+         ((*xcb_reply).dst_x as i32, (*xcb_reply).dst_y as i32)
+      };
+
+      unsafe {
+         ffi::free(xcb_reply as *mut _);
+      }
+
+      Ok(position)
+   }
+
    pub fn query_pointer(&self) -> QueryPointerCookie {
       let xcb_cookie = unsafe {
          ffi::xcb_query_pointer(
@@ -0,0 +1,63 @@
+use error::{RuntimeError, VoidResult};
+use draw::RGB;
+use frame::{Frame, GPUFrame};
+
+
+// In memory frame for the unit and integration tests, RGBA bytes row by row
+// from the top like the GL frames take them through `ptr_mut`
+pub struct PixelFrame {
+   pixels: Vec<u8>,
+}
+
+impl GPUFrame for PixelFrame {
+   fn new(width: u32, height: u32) -> Result<Self, RuntimeError> {
+      Ok(PixelFrame {
+         pixels: vec![0; (width * height * 4) as usize],
+      })
+   }
+
+   fn clear(&mut self) {
+   }
+
+   fn put_pixel(&mut self, x: i32, y: i32, color: &RGB, width: u32, height: u32) {
+      if x >= 0 && y >= 0 && x < width as i32 && y < height as i32 {
+         let i = 4 * (x + y * width as i32) as usize;
+
+         self.pixels[i] = color.r;
+         self.pixels[i + 1] = color.g;
+         self.pixels[i + 2] = color.b;
+      }
+   }
+
+   fn get_pixel(&self, x: i32, y: i32, width: u32, height: u32) -> RGB {
+      if x >= 0 && y >= 0 && x < width as i32 && y < height as i32 {
+         let i = 4 * (x + y * width as i32) as usize;
+
+         RGB::new(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+      } else {
+         RGB::default()
+      }
+   }
+
+   fn ptr_mut(&mut self) -> *mut u8 {
+      self.pixels.as_mut_ptr()
+   }
+
+   fn resize(&mut self, width: u32, height: u32) -> VoidResult {
+      self.pixels.resize((width * height * 4) as usize, 0);
+
+      Ok(())
+   }
+
+   fn pre_render(&mut self) -> VoidResult {
+      Ok(())
+   }
+
+   fn post_render(&mut self, _: u32, _: u32) -> VoidResult {
+      Ok(())
+   }
+}
+
+pub fn pixel_frame(width: u32, height: u32) -> Frame {
+   Frame::new(width, height, Box::new(PixelFrame::new(width, height).unwrap())).unwrap()
+}
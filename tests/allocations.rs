@@ -0,0 +1,100 @@
+extern crate polydraw;
+
+use std::time::Instant;
+
+use polydraw::{FrameClock, Renderer};
+use polydraw::draw::{RGB, BlendMode, Blending};
+use polydraw::data::IntPoint;
+use polydraw::devel::{DevelRenderer, Scene, Poly, FilterOp, MaskMode, MaskSource, SUBDIVISIONS};
+use polydraw::image::{Image, ImagePaint, RepeatMode, ImageFilter};
+use polydraw::geom::transform::Transform;
+use polydraw::color::{Profile, RgbSpace};
+use polydraw::allocation::{CountingAllocator, allocation_count};
+use polydraw::testing::pixel_frame;
+
+
+// A single test in this binary, the count covers every thread of the process
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 120;
+
+
+fn square(x: i64, y: i64, size: i64) -> Box<Poly> {
+   let s = SUBDIVISIONS;
+
+   let contour = vec![
+      IntPoint::new(x * s, y * s),
+      IntPoint::new((x + size) * s, y * s + 1),
+      IntPoint::new((x + size) * s - 2, (y + size) * s),
+      IntPoint::new(x * s, (y + size) * s - 1),
+   ];
+
+   Box::new(Poly::new(vec![contour], RGB::new((x * 7) as u8, (y * 5) as u8, 200)))
+}
+
+
+fn scene() -> Scene {
+   let mut scene = Scene::new();
+
+   for i in 0..1000 {
+      scene.push(square(i % 150, (i * 7) % 110, 3 + i % 9));
+   }
+
+   let checker = Image::new(2, 2, vec![
+      255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 255
+   ]);
+
+   let paint = ImagePaint::new(
+      checker, Transform::scale(8., 8.), RepeatMode::Repeat, ImageFilter::Bilinear
+   ).unwrap();
+
+   scene.push(Box::new(square(10, 10, 40).with_paint(paint)));
+
+   scene.begin_group(0.5, BlendMode::Multiply);
+   scene.push(square(30, 20, 50));
+
+   scene.begin_mask(MaskMode::Alpha, MaskSource::Polys(vec![square(40, 30, 20)]));
+   scene.push(square(35, 25, 40));
+   scene.end_mask();
+
+   scene.end_group();
+
+   scene.begin_filter(vec![FilterOp::Blur(1.5, 1.5), FilterOp::Blur(4., 3.)]);
+   scene.push(square(90, 40, 30));
+   scene.end_filter();
+
+   scene
+}
+
+
+#[test]
+fn test_steady_state_render() {
+   let mut renderer = DevelRenderer::new(scene());
+
+   renderer.set_blending(Blending::Linear);
+   renderer.set_output_profile(Some(Profile::from_space(RgbSpace::DisplayP3)));
+
+   let mut frame = pixel_frame(WIDTH, HEIGHT);
+
+   let clock = FrameClock::new(Instant::now());
+
+   renderer.init(WIDTH, HEIGHT);
+
+   // The first frames size the layers, arenas and scratch lines
+   for _ in 0..3 {
+      renderer.render(&mut frame, &clock);
+   }
+
+   let before = allocation_count();
+
+   for _ in 0..20 {
+      renderer.render(&mut frame, &clock);
+   }
+
+   assert_eq!(allocation_count() - before, 0);
+
+   assert!(before > 0);
+}